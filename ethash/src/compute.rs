@@ -21,14 +21,17 @@
 
 use primal::is_prime;
 use std::cell::Cell;
+use std::cmp;
 use std::mem;
 use std::ptr;
 use sha3;
 use std::slice;
 use std::path::{Path, PathBuf};
 use std::io::{self, Read, Write};
-use std::fs::{self, File};
+use std::fs::{self, File, OpenOptions};
+use std::thread;
 
+use memmap::{Mmap, Protection};
 use parking_lot::Mutex;
 
 pub const ETHASH_EPOCH_LENGTH: u64 = 30000;
@@ -160,6 +163,78 @@ impl Light {
 	}
 }
 
+/// Full dataset structure, memory-mapped from a file in the cache directory.
+pub struct Full {
+	full_size: usize,
+	dataset: Mmap,
+}
+
+/// Full dataset structure
+impl Full {
+	/// Load the full dataset for the light cache's block number from the cache directory,
+	/// generating it with `threads` threads first if it's not there.
+	pub fn new(light: &Light, threads: usize) -> io::Result<Full> {
+		let (path, deprecated) = {
+			let seed_compute = light.seed_compute.lock();
+			let path = Full::file_path(&light.cache_dir, seed_compute.get_seedhash(light.block_number));
+			let deprecated = match light.block_number >= ETHASH_EPOCH_LENGTH * 2 {
+				true => Some(Full::file_path(
+					&light.cache_dir,
+					seed_compute.get_seedhash(light.block_number - ETHASH_EPOCH_LENGTH * 2)
+				)),
+				false => None,
+			};
+			(path, deprecated)
+		};
+		let full_size = get_data_size(light.block_number);
+
+		match Full::from_file(&path, full_size) {
+			Ok(full) => return Ok(full),
+			Err(e) => debug!(target: "ethash", "Full dataset not found at {:?}: {}", &path, e),
+		}
+
+		if let Some(deprecated) = deprecated {
+			if deprecated.exists() {
+				debug!(target: "ethash", "removing: {:?}", &deprecated);
+				fs::remove_file(deprecated)?;
+			}
+		}
+
+		full_new(light, full_size, &path, threads)?;
+		Full::from_file(&path, full_size)
+	}
+
+	/// Calculate the boundary data
+	/// `header_hash` - The header hash to pack into the mix
+	/// `nonce` - The nonce to pack into the mix
+	pub fn compute(&self, header_hash: &H256, nonce: u64) -> ProofOfWork {
+		full_compute(self, header_hash, nonce)
+	}
+
+	pub fn file_path<T: AsRef<Path>>(cache_dir: T, seed_hash: H256) -> PathBuf {
+		let mut cache_dir = cache_dir.as_ref().to_path_buf();
+		cache_dir.push(format!("full-{}", to_hex(&seed_hash)));
+		cache_dir
+	}
+
+	fn from_file(path: &Path, full_size: usize) -> io::Result<Full> {
+		let file = File::open(path)?;
+		if file.metadata()?.len() != full_size as u64 {
+			return Err(io::Error::new(io::ErrorKind::Other, "Dataset file size mismatch"));
+		}
+		let dataset = Mmap::open(&file, Protection::Read)?;
+		Ok(Full {
+			full_size: full_size,
+			dataset: dataset,
+		})
+	}
+
+	#[inline]
+	fn nodes(&self) -> &[Node] {
+		unsafe { slice::from_raw_parts(self.dataset.ptr() as *const Node, self.full_size / NODE_BYTES) }
+	}
+}
+
 pub struct SeedHashCompute {
 	prev_epoch: Cell<u64>,
 	prev_seedhash: Cell<H256>,
@@ -265,10 +340,20 @@ pub fn quick_get_difficulty(header_hash: &H256, nonce: u64, mix_hash: &H256) ->
 /// `nonce` - The nonce to pack into the mix
 pub fn light_compute(light: &Light, header_hash: &H256, nonce: u64) -> ProofOfWork {
 	let full_size = get_data_size(light.block_number);
-	hash_compute(light, full_size, header_hash, nonce)
+	let cache: &[Node] = &light.cache;  // deref once for better performance
+	hash_compute(full_size, header_hash, nonce, |index| calculate_dag_item(index, cache))
 }
 
-fn hash_compute(light: &Light, full_size: usize, header_hash: &H256, nonce: u64) -> ProofOfWork {
+/// Calculate the boundary data from the full dataset
+/// `full` - The full dataset
+/// `header_hash` - The header hash to pack into the mix
+/// `nonce` - The nonce to pack into the mix
+pub fn full_compute(full: &Full, header_hash: &H256, nonce: u64) -> ProofOfWork {
+	let nodes = full.nodes();
+	hash_compute(full.full_size, header_hash, nonce, |index| unsafe { nodes.get_unchecked(index as usize).clone() })
+}
+
+fn hash_compute<F>(full_size: usize, header_hash: &H256, nonce: u64, dag_item: F) -> ProofOfWork where F: Fn(u32) -> Node {
 	if full_size % MIX_WORDS != 0 {
 		panic!("Unaligned full size");
 	}
@@ -287,12 +372,11 @@ fn hash_compute(light: &Light, full_size: usize, header_hash: &H256, nonce: u64)
 
 		let page_size = 4 * MIX_WORDS;
 		let num_full_pages = (full_size / page_size) as u32;
-		let cache: &[Node] = &light.cache;  // deref once for better performance
 
 		for i in 0..(ETHASH_ACCESSES as u32) {
 			let index = fnv_hash(f_mix.get_unchecked(0).as_words().get_unchecked(0) ^ i, *mix.get_unchecked(0).as_words().get_unchecked((i as usize) % MIX_WORDS)) % num_full_pages;
 			for n in 0..MIX_NODES {
-				let tmp_node = calculate_dag_item(index * MIX_NODES as u32 + n as u32, cache);
+				let tmp_node = dag_item(index * MIX_NODES as u32 + n as u32);
 				for w in 0..NODE_WORDS {
 					*mix.get_unchecked_mut(n).as_words_mut().get_unchecked_mut(w) = fnv_hash(*mix.get_unchecked(n).as_words().get_unchecked(w), *tmp_node.as_words().get_unchecked(w));
 				}
@@ -379,6 +463,63 @@ fn light_new<T: AsRef<Path>>(cache_dir: T, block_number: u64) -> Light {
 	}
 }
 
+// part of the dataset generated by a single thread.
+struct DatasetChunk {
+	nodes: *mut Node,
+	start: usize,
+	end: usize,
+	cache: *const Node,
+	cache_len: usize,
+}
+
+// the dataset and the cache outlive the generating threads, which write to disjoint nodes.
+unsafe impl Send for DatasetChunk {}
+
+impl DatasetChunk {
+	fn generate(self) {
+		unsafe {
+			let cache = slice::from_raw_parts(self.cache, self.cache_len);
+			for i in self.start..self.end {
+				ptr::write(self.nodes.offset(i as isize), calculate_dag_item(i as u32, cache));
+			}
+		}
+	}
+}
+
+fn full_new(light: &Light, full_size: usize, path: &Path, threads: usize) -> io::Result<()> {
+	assert!(full_size % NODE_BYTES == 0, "Unaligned full size");
+	let num_nodes = full_size / NODE_BYTES;
+
+	// generate into a temporary file, so that a partial dataset is never loaded.
+	let tmp_path = path.with_extension("partial");
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir)?;
+	}
+	let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(&tmp_path)?;
+	file.set_len(full_size as u64)?;
+	let mut dataset = Mmap::open(&file, Protection::ReadWrite)?;
+
+	let nodes = dataset.mut_ptr() as *mut Node;
+	let per_thread = (num_nodes + threads - 1) / cmp::max(threads, 1);
+	let handles: Vec<_> = (0..cmp::max(threads, 1)).map(|t| {
+		let chunk = DatasetChunk {
+			nodes: nodes,
+			start: cmp::min(t * per_thread, num_nodes),
+			end: cmp::min((t + 1) * per_thread, num_nodes),
+			cache: light.cache.as_ptr(),
+			cache_len: light.cache.len(),
+		};
+		thread::spawn(move || chunk.generate())
+	}).collect();
+
+	for handle in handles {
+		handle.join().map_err(|_| io::Error::new(io::ErrorKind::Other, "Dataset generation failed"))?;
+	}
+
+	dataset.flush()?;
+	fs::rename(&tmp_path, path)
+}
+
 static CHARS: &'static [u8] = b"0123456789abcdef";
 fn to_hex(bytes: &[u8]) -> String {
 	let mut v = Vec::with_capacity(bytes.len() * 2);
@@ -439,6 +580,26 @@ fn test_light_compute() {
 	assert_eq!(result.value[..], boundary[..]);
 }
 
+#[test]
+fn test_full_compute() {
+	let path = ::std::env::temp_dir().join("ethash-test-full");
+	let light = Light::new(&::std::env::temp_dir(), 0);
+	// a small dataset instead of the gigabyte one, generated by multiple threads.
+	let full_size = 64 * ETHASH_MIX_BYTES;
+	full_new(&light, full_size, &path, 3).unwrap();
+	let full = Full::from_file(&path, full_size).unwrap();
+
+	let hash = [0x42u8; 32];
+	let cache: &[Node] = &light.cache;
+	for nonce in 0..4 {
+		let expected = hash_compute(full_size, &hash, nonce, |index| calculate_dag_item(index, cache));
+		let result = full_compute(&full, &hash, nonce);
+		assert_eq!(result.mix_hash[..], expected.mix_hash[..]);
+		assert_eq!(result.value[..], expected.value[..]);
+	}
+	fs::remove_file(&path).unwrap();
+}
+
 #[test]
 fn test_seed_compute_once() {
 	let seed_compute = SeedHashCompute::new();
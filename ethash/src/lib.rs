@@ -19,15 +19,16 @@
 extern crate primal;
 extern crate sha3;
 extern crate parking_lot;
+extern crate memmap;
 
 #[macro_use]
 extern crate log;
 mod compute;
 
+use std::io;
 use std::mem;
 use std::path::{Path, PathBuf};
-use compute::Light;
-pub use compute::{ETHASH_EPOCH_LENGTH, H256, ProofOfWork, SeedHashCompute, quick_get_difficulty, slow_get_seedhash};
+pub use compute::{ETHASH_EPOCH_LENGTH, H256, ProofOfWork, SeedHashCompute, Light, Full, quick_get_difficulty, slow_get_seedhash};
 
 use std::sync::Arc;
 use parking_lot::Mutex;
@@ -42,6 +43,9 @@ struct LightCache {
 /// Light/Full cache manager.
 pub struct EthashManager {
 	cache: Mutex<LightCache>,
+	full: Mutex<Option<(u64, Arc<Full>)>>,
+	// held while the full dataset is generated, outside of the other locks.
+	full_generation: Mutex<()>,
 	cache_dir: PathBuf,
 }
 
@@ -56,6 +60,8 @@ impl EthashManager {
 				prev_epoch: None,
 				prev: None,
 			}),
+			full: Mutex::new(None),
+			full_generation: Mutex::new(()),
 		}
 	}
 
@@ -65,51 +71,85 @@ impl EthashManager {
 	/// `header_hash` - The header hash to pack into the mix
 	/// `nonce` - The nonce to pack into the mix
 	pub fn compute_light(&self, block_number: u64, header_hash: &H256, nonce: u64) -> ProofOfWork {
+		self.light(block_number).compute(header_hash, nonce)
+	}
+
+	/// Get the full dataset for mining
+	/// `block_number` - Block number to mine
+	/// `threads` - Number of threads generating the dataset if it's not in the cache directory yet
+	pub fn full(&self, block_number: u64, threads: usize) -> io::Result<Arc<Full>> {
 		let epoch = block_number / ETHASH_EPOCH_LENGTH;
-		let light = {
-			let mut lights = self.cache.lock();
-			let light = match lights.recent_epoch.clone() {
-				Some(ref e) if *e == epoch => lights.recent.clone(),
-				_ => match lights.prev_epoch.clone() {
-					Some(e) if e == epoch => {
-						// don't swap if recent is newer.
-						if lights.recent_epoch > lights.prev_epoch {
-							None
-						} else {
-							// swap
-							let t = lights.prev_epoch;
-							lights.prev_epoch = lights.recent_epoch;
-							lights.recent_epoch = t;
-							let t = lights.prev.clone();
-							lights.prev = lights.recent.clone();
-							lights.recent = t;
-							lights.recent.clone()
-						}
+		let current = |full: &Option<(u64, Arc<Full>)>| match *full {
+			Some((e, ref f)) if e == epoch => Some(f.clone()),
+			_ => None,
+		};
+
+		if let Some(f) = current(&*self.full.lock()) {
+			return Ok(f);
+		}
+
+		// only one thread generates, the others wait for its dataset.
+		let _generation = self.full_generation.lock();
+		{
+			let mut full = self.full.lock();
+			if let Some(f) = current(&*full) {
+				return Ok(f);
+			}
+			// drop the previous dataset before generating the next one.
+			*full = None;
+		}
+
+		let light = self.light(block_number);
+		let f = Arc::new(Full::new(&*light, threads)?);
+		*self.full.lock() = Some((epoch, f.clone()));
+		Ok(f)
+	}
+
+	/// Get the light cache
+	/// `block_number` - Block number to check
+	pub fn light(&self, block_number: u64) -> Arc<Light> {
+		let epoch = block_number / ETHASH_EPOCH_LENGTH;
+		let mut lights = self.cache.lock();
+		let light = match lights.recent_epoch.clone() {
+			Some(ref e) if *e == epoch => lights.recent.clone(),
+			_ => match lights.prev_epoch.clone() {
+				Some(e) if e == epoch => {
+					// don't swap if recent is newer.
+					if lights.recent_epoch > lights.prev_epoch {
+						None
+					} else {
+						// swap
+						let t = lights.prev_epoch;
+						lights.prev_epoch = lights.recent_epoch;
+						lights.recent_epoch = t;
+						let t = lights.prev.clone();
+						lights.prev = lights.recent.clone();
+						lights.recent = t;
+						lights.recent.clone()
 					}
-					_ => None,
-				},
-			};
-			match light {
-				None => {
-					let light = match Light::from_file(&self.cache_dir, block_number) {
-						Ok(light) => Arc::new(light),
-						Err(e) => {
-							debug!("Light cache file not found for {}:{}", block_number, e);
-							let light = Light::new(&self.cache_dir, block_number);
-							if let Err(e) = light.to_file() {
-								warn!("Light cache file write error: {}", e);
-							}
-							Arc::new(light)
-						}
-					};
-					lights.prev_epoch = mem::replace(&mut lights.recent_epoch, Some(epoch));
-					lights.prev = mem::replace(&mut lights.recent, Some(light.clone()));
-					light
 				}
-				Some(light) => light,
-			}
+				_ => None,
+			},
 		};
-		light.compute(header_hash, nonce)
+		match light {
+			None => {
+				let light = match Light::from_file(&self.cache_dir, block_number) {
+					Ok(light) => Arc::new(light),
+					Err(e) => {
+						debug!("Light cache file not found for {}:{}", block_number, e);
+						let light = Light::new(&self.cache_dir, block_number);
+						if let Err(e) = light.to_file() {
+							warn!("Light cache file write error: {}", e);
+						}
+						Arc::new(light)
+					}
+				};
+				lights.prev_epoch = mem::replace(&mut lights.recent_epoch, Some(epoch));
+				lights.prev = mem::replace(&mut lights.recent, Some(light.clone()));
+				light
+			}
+			Some(light) => light,
+		}
 	}
 }
 
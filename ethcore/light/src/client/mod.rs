@@ -480,3 +480,69 @@ impl LightChainClient for Client {
 		Client::report(self)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::{Client, Config};
+
+	use std::sync::Arc;
+	use cache::Cache;
+	use ethcore::ethstore::ethkey::{sign, Secret};
+	use ethcore::header::{Header, Seal};
+	use ethcore::ids::BlockId;
+	use ethcore::spec::Spec;
+	use io::IoChannel;
+	use rlp::encode;
+	use time::Duration;
+	use util::{H64, H256, H520, Hashable, Mutex};
+
+	fn clique_header(parent: &Header, signer: &str, difficulty: u64) -> Header {
+		let mut header = Header::new();
+		header.set_parent_hash(parent.hash());
+		header.set_number(parent.number() + 1);
+		header.set_timestamp(parent.timestamp() + 1);
+		header.set_gas_limit(*parent.gas_limit());
+		header.set_difficulty(difficulty.into());
+		header.set_seal(vec![encode(&H256::default()).into_vec(), encode(&H64::default()).into_vec()]);
+		header.set_extra_data(vec![0; 32]);
+
+		let secret: Secret = signer.sha3().into();
+		let signature = sign(&secret, &header.rlp_sha3(Seal::With)).unwrap();
+		header.extra_data_mut().extend_from_slice(&*H520::from(signature));
+		header
+	}
+
+	fn import(client: &Client, header: Header) {
+		client.import_header(header).unwrap();
+		client.flush_queue();
+		client.import_verified();
+	}
+
+	#[test]
+	fn imports_clique_headers_without_signer_state() {
+		let db = Arc::new(::util::kvdb::in_memory(0));
+		let new_client = || {
+			let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::hours(6))));
+			Client::new(Config::default(), db.clone(), None, &Spec::new_test_clique(), IoChannel::disconnected(), cache).unwrap()
+		};
+
+		// "1" and "0" take turns sealing.
+		let genesis = Spec::new_test_clique().genesis_header();
+		let first = clique_header(&genesis, "1", 2);
+		let second = clique_header(&first, "0", 2);
+		let third = clique_header(&second, "1", 2);
+
+		{
+			let client = new_client();
+			import(&client, first);
+			import(&client, second.clone());
+			assert_eq!(client.block_header(BlockId::Number(2)).unwrap().hash(), second.hash());
+		}
+
+		// after a restart the engine doesn't know the signers at the parent.
+		let client = new_client();
+		import(&client, third.clone());
+		assert_eq!(client.chain_info().best_block_number, 3);
+		assert_eq!(client.block_header(BlockId::Number(3)).unwrap().hash(), third.hash());
+	}
+}
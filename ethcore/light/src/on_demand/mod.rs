@@ -18,7 +18,7 @@
 //! The request service is implemented using Futures. Higher level request handlers
 //! will take the raw data received here and extract meaningful results from it.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 use std::sync::Arc;
 
@@ -71,6 +71,8 @@ struct Pending {
 	required_capabilities: Capabilities,
 	responses: Vec<Response>,
 	sender: oneshot::Sender<Vec<Response>>,
+	// peers which didn't know the transaction the next unanswered request asks the index of.
+	not_found_by: HashSet<PeerId>,
 }
 
 impl Pending {
@@ -116,6 +118,7 @@ impl Pending {
 	{
 		match self.requests.supply_response(&cache, response) {
 			Ok(response) => {
+				self.not_found_by.clear();
 				let idx = self.responses.len();
 				self.update_header_refs(idx, &response);
 				self.responses.push(response);
@@ -139,6 +142,25 @@ impl Pending {
 		self.requests.fill_unanswered();
 	}
 
+	// whether the next unanswered request is for the index of a transaction.
+	fn awaits_transaction_index(&self) -> bool {
+		match self.requests.get(self.requests.num_answered()) {
+			Some(&CheckedRequest::TransactionIndex(_, _)) => true,
+			_ => false,
+		}
+	}
+
+	// if none of the given peers know the transaction the next request asks the index of,
+	// send the responses gathered so far and consume self.
+	fn try_complete_not_found(self, peers: &HashMap<PeerId, Peer>) -> Option<Self> {
+		if !self.not_found_by.is_empty() && peers.keys().all(|p| self.not_found_by.contains(p)) {
+			let _ = self.sender.send(self.responses);
+			None
+		} else {
+			Some(self)
+		}
+	}
+
 	// update the cached network requests.
 	fn update_net_requests(&mut self) {
 		use request::IncompleteRequest;
@@ -187,6 +209,7 @@ fn guess_capabilities(requests: &[CheckedRequest]) -> Capabilities {
 				caps.serve_headers = true,
 			CheckedRequest::HeaderByHash(_, _) =>
 				caps.serve_headers = true,
+			CheckedRequest::TransactionIndex(_, _) => {}, // hashes yield no info.
 			CheckedRequest::Body(ref req, _) => if let Ok(ref hdr) = req.0.as_ref() {
 				update_since(&mut caps.serve_chain_since, hdr.number());
 			},
@@ -196,6 +219,9 @@ fn guess_capabilities(requests: &[CheckedRequest]) -> Capabilities {
 			CheckedRequest::Account(ref req, _) => if let Ok(ref hdr) = req.header.as_ref() {
 				update_since(&mut caps.serve_state_since, hdr.number());
 			},
+			CheckedRequest::Storage(ref req, _) => if let Ok(ref hdr) = req.header.as_ref() {
+				update_since(&mut caps.serve_state_since, hdr.number());
+			},
 			CheckedRequest::Code(ref req, _) => if let Ok(ref hdr) = req.header.as_ref() {
 				update_since(&mut caps.serve_state_since, hdr.number());
 			},
@@ -212,6 +238,7 @@ fn guess_capabilities(requests: &[CheckedRequest]) -> Capabilities {
 /// from a vector of responses.
 pub struct OnResponses<T: request::RequestAdapter> {
 	receiver: Receiver<Vec<Response>>,
+	num_requests: usize,
 	_marker: PhantomData<T>,
 }
 
@@ -220,7 +247,10 @@ impl<T: request::RequestAdapter> Future for OnResponses<T> {
 	type Error = Canceled;
 
 	fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-		self.receiver.poll().map(|async| async.map(T::extract_from))
+		match self.receiver.poll()? {
+			Async::Ready(ref responses) if responses.len() < self.num_requests => Err(Canceled),
+			async => Ok(async.map(T::extract_from)),
+		}
 	}
 }
 
@@ -261,7 +291,9 @@ impl OnDemand {
 	/// Submit a vector of requests to be processed together.
 	///
 	/// Fails if back-references are not coherent.
-	/// The returned vector of responses will correspond to the requests exactly.
+	/// The returned vector of responses will correspond to the requests exactly,
+	/// unless no connected peer knows a transaction whose index is requested: then
+	/// only the responses to the requests before that one are returned.
 	pub fn request_raw(&self, ctx: &BasicContext, requests: Vec<Request>)
 		-> Result<Receiver<Vec<Response>>, basic_request::NoSuchOutput>
 	{
@@ -307,6 +339,7 @@ impl OnDemand {
 			required_capabilities: capabilities,
 			responses: responses,
 			sender: sender,
+			not_found_by: HashSet::new(),
 		});
 
 		Ok(receiver)
@@ -315,11 +348,15 @@ impl OnDemand {
 	/// Submit a strongly-typed batch of requests.
 	///
 	/// Fails if back-reference are not coherent.
+	/// Resolves to `Canceled` if no connected peer knows a transaction whose index is requested.
 	pub fn request<T>(&self, ctx: &BasicContext, requests: T) -> Result<OnResponses<T>, basic_request::NoSuchOutput>
 		where T: request::RequestAdapter
 	{
-		self.request_raw(ctx, requests.make_requests()).map(|recv| OnResponses {
+		let requests = requests.make_requests();
+		let num_requests = requests.len();
+		self.request_raw(ctx, requests).map(|recv| OnResponses {
 			receiver: recv,
+			num_requests: num_requests,
 			_marker: PhantomData,
 		})
 	}
@@ -368,11 +405,12 @@ impl OnDemand {
 				false => Some(pending),
 				true => None,
 			})
+			.filter_map(|pending| pending.try_complete_not_found(&*peers))
 			.filter_map(|pending| {
 				for (peer_id, peer) in peers.iter() { // .shuffle?
 					// TODO: see which requests can be answered by the cache?
 
-					if !peer.can_fulfill(&pending.required_capabilities) {
+					if !peer.can_fulfill(&pending.required_capabilities) || pending.not_found_by.contains(peer_id) {
 						continue
 					}
 
@@ -468,6 +506,12 @@ impl Handler for OnDemand {
 			}
 		}
 
+		// peers answer nothing when they don't know the transaction asked about.
+		// it's given up on once no connected peer does.
+		if responses.is_empty() && pending.awaits_transaction_index() {
+			pending.not_found_by.insert(ctx.peer());
+		}
+
 		pending.fill_unanswered();
 		self.submit_pending(ctx.as_basic(), pending);
 	}
@@ -19,6 +19,7 @@
 use std::sync::Arc;
 
 use ethcore::basic_account::BasicAccount;
+use ethcore::client::CallAnalytics;
 use ethcore::encoded;
 use ethcore::engines::Engine;
 use ethcore::env_info::EnvInfo;
@@ -43,12 +44,16 @@ pub enum Request {
 	HeaderProof(HeaderProof),
 	/// A request for a header by hash.
 	HeaderByHash(HeaderByHash),
+	/// A request for the index of a transaction.
+	TransactionIndex(TransactionIndex),
 	/// A request for block receipts.
 	Receipts(BlockReceipts),
 	/// A request for a block body.
 	Body(Body),
 	/// A request for an account.
 	Account(Account),
+	/// A request for a storage item of an account.
+	Storage(Storage),
 	/// A request for a contract's code.
 	Code(Code),
 	/// A request for proof of execution.
@@ -128,9 +133,11 @@ macro_rules! impl_single {
 // implement traits for each kind of request.
 impl_single!(HeaderProof, HeaderProof, (H256, U256));
 impl_single!(HeaderByHash, HeaderByHash, encoded::Header);
+impl_single!(TransactionIndex, TransactionIndex, net_request::TransactionIndexResponse);
 impl_single!(Receipts, BlockReceipts, Vec<Receipt>);
 impl_single!(Body, Body, encoded::Block);
 impl_single!(Account, Account, Option<BasicAccount>);
+impl_single!(Storage, Storage, H256);
 impl_single!(Code, Code, Bytes);
 impl_single!(Execution, TransactionProof, super::ExecutionResult);
 
@@ -236,9 +243,11 @@ impl From<encoded::Header> for HeaderRef {
 pub enum CheckedRequest {
 	HeaderProof(HeaderProof, net_request::IncompleteHeaderProofRequest),
 	HeaderByHash(HeaderByHash, net_request::IncompleteHeadersRequest),
+	TransactionIndex(TransactionIndex, net_request::IncompleteTransactionIndexRequest),
 	Receipts(BlockReceipts, net_request::IncompleteReceiptsRequest),
 	Body(Body, net_request::IncompleteBodyRequest),
 	Account(Account, net_request::IncompleteAccountRequest),
+	Storage(Storage, net_request::IncompleteStorageRequest),
 	Code(Code, net_request::IncompleteCodeRequest),
 	Execution(TransactionProof, net_request::IncompleteExecutionRequest),
 }
@@ -261,6 +270,12 @@ impl From<Request> for CheckedRequest {
 				};
 				CheckedRequest::HeaderProof(req, net_req)
 			}
+			Request::TransactionIndex(req) => {
+				let net_req = net_request::IncompleteTransactionIndexRequest {
+					hash: req.0.clone(),
+				};
+				CheckedRequest::TransactionIndex(req, net_req)
+			}
 			Request::Body(req) =>  {
 				let net_req = net_request::IncompleteBodyRequest {
 					hash: req.0.field(),
@@ -280,6 +295,14 @@ impl From<Request> for CheckedRequest {
 				};
 				CheckedRequest::Account(req, net_req)
 			}
+			Request::Storage(req) => {
+				let net_req = net_request::IncompleteStorageRequest {
+					block_hash: req.header.field(),
+					address_hash: ::util::Hashable::sha3(&req.address).into(),
+					key_hash: ::util::Hashable::sha3(&req.key).into(),
+				};
+				CheckedRequest::Storage(req, net_req)
+			}
 			Request::Code(req) => {
 				let net_req = net_request::IncompleteCodeRequest {
 					block_hash: req.header.field(),
@@ -311,9 +334,11 @@ impl CheckedRequest {
 		match self {
 			CheckedRequest::HeaderProof(_, req) => NetRequest::HeaderProof(req),
 			CheckedRequest::HeaderByHash(_, req) => NetRequest::Headers(req),
+			CheckedRequest::TransactionIndex(_, req) => NetRequest::TransactionIndex(req),
 			CheckedRequest::Receipts(_, req) => NetRequest::Receipts(req),
 			CheckedRequest::Body(_, req) => NetRequest::Body(req),
 			CheckedRequest::Account(_, req) => NetRequest::Account(req),
+			CheckedRequest::Storage(_, req) => NetRequest::Storage(req),
 			CheckedRequest::Code(_, req) => NetRequest::Code(req),
 			CheckedRequest::Execution(_, req) => NetRequest::Execution(req),
 		}
@@ -328,6 +353,7 @@ impl CheckedRequest {
 			CheckedRequest::Receipts(ref x, _) => x.0.needs_header(),
 			CheckedRequest::Body(ref x, _) => x.0.needs_header(),
 			CheckedRequest::Account(ref x, _) => x.header.needs_header(),
+			CheckedRequest::Storage(ref x, _) => x.header.needs_header(),
 			CheckedRequest::Code(ref x, _) => x.header.needs_header(),
 			CheckedRequest::Execution(ref x, _) => x.header.needs_header(),
 			_ => None,
@@ -342,6 +368,7 @@ impl CheckedRequest {
 			CheckedRequest::Receipts(ref mut x, _) => x.0 = HeaderRef::Stored(header),
 			CheckedRequest::Body(ref mut x, _) => x.0 = HeaderRef::Stored(header),
 			CheckedRequest::Account(ref mut x, _) => x.header = HeaderRef::Stored(header),
+			CheckedRequest::Storage(ref mut x, _) => x.header = HeaderRef::Stored(header),
 			CheckedRequest::Code(ref mut x, _) => x.header = HeaderRef::Stored(header),
 			CheckedRequest::Execution(ref mut x, _) => x.header = HeaderRef::Stored(header),
 			_ => {},
@@ -421,6 +448,14 @@ impl CheckedRequest {
 						Response::Body(encoded::Block::new(stream.out()))
 					})
 			}
+			CheckedRequest::Storage(ref check, _) => {
+				// empty storage -> every item is zero.
+				if check.storage_root.as_ref().map_or(false, |&r| r == SHA3_NULL_RLP) {
+					Some(Response::Storage(H256::new()))
+				} else {
+					None
+				}
+			}
 			CheckedRequest::Code(_, ref req) => {
 				if req.code_hash.as_ref().map_or(false, |&h| h == SHA3_EMPTY) {
 					Some(Response::Code(Vec::new()))
@@ -438,9 +473,11 @@ macro_rules! match_me {
 		match $me {
 			CheckedRequest::HeaderProof($check, $req) => $e,
 			CheckedRequest::HeaderByHash($check, $req) => $e,
+			CheckedRequest::TransactionIndex($check, $req) => $e,
 			CheckedRequest::Receipts($check, $req) => $e,
 			CheckedRequest::Body($check, $req) => $e,
 			CheckedRequest::Account($check, $req) => $e,
+			CheckedRequest::Storage($check, $req) => $e,
 			CheckedRequest::Code($check, $req) => $e,
 			CheckedRequest::Execution($check, $req) => $e,
 		}
@@ -465,9 +502,19 @@ impl IncompleteRequest for CheckedRequest {
 					_ => Ok(()),
 				}
 			}
+			CheckedRequest::TransactionIndex(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Receipts(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Body(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Account(_, ref req) => req.check_outputs(f),
+			CheckedRequest::Storage(ref check, ref req) => {
+				req.check_outputs(&mut f)?;
+
+				// the storage root used for verification must be a hash as well.
+				match check.storage_root {
+					Field::BackReference(r, idx) => f(r, idx, OutputKind::Hash),
+					_ => Ok(()),
+				}
+			}
 			CheckedRequest::Code(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Execution(_, ref req) => req.check_outputs(f),
 		}
@@ -478,6 +525,15 @@ impl IncompleteRequest for CheckedRequest {
 	}
 
 	fn fill<F>(&mut self, f: F) where F: Fn(usize, usize) -> Result<Output, net_request::NoSuchOutput> {
+		// the storage root isn't part of the network request, so fill it separately.
+		if let CheckedRequest::Storage(ref mut check, _) = *self {
+			if let Field::BackReference(req, idx) = check.storage_root {
+				if let Ok(Output::Hash(root)) = f(req, idx) {
+					check.storage_root = Field::Scalar(root);
+				}
+			}
+		}
+
 		match_me!(*self, (_, ref mut req) => req.fill(f))
 	}
 
@@ -485,9 +541,11 @@ impl IncompleteRequest for CheckedRequest {
 		match self {
 			CheckedRequest::HeaderProof(_, req) => req.complete().map(CompleteRequest::HeaderProof),
 			CheckedRequest::HeaderByHash(_, req) => req.complete().map(CompleteRequest::Headers),
+			CheckedRequest::TransactionIndex(_, req) => req.complete().map(CompleteRequest::TransactionIndex),
 			CheckedRequest::Receipts(_, req) => req.complete().map(CompleteRequest::Receipts),
 			CheckedRequest::Body(_, req) => req.complete().map(CompleteRequest::Body),
 			CheckedRequest::Account(_, req) => req.complete().map(CompleteRequest::Account),
+			CheckedRequest::Storage(_, req) => req.complete().map(CompleteRequest::Storage),
 			CheckedRequest::Code(_, req) => req.complete().map(CompleteRequest::Code),
 			CheckedRequest::Execution(_, req) => req.complete().map(CompleteRequest::Execution),
 		}
@@ -526,6 +584,9 @@ impl net_request::CheckedRequest for CheckedRequest {
 			CheckedRequest::HeaderByHash(ref prover, _) =>
 				expect!((&NetResponse::Headers(ref res), &CompleteRequest::Headers(ref req)) =>
 					prover.check_response(cache, &req.start, &res.headers).map(Response::HeaderByHash)),
+			CheckedRequest::TransactionIndex(ref prover, _) =>
+				expect!((&NetResponse::TransactionIndex(ref res), _) =>
+					prover.check_response(cache, res).map(Response::TransactionIndex)),
 			CheckedRequest::Receipts(ref prover, _) =>
 				expect!((&NetResponse::Receipts(ref res), _) =>
 					prover.check_response(cache, &res.receipts).map(Response::Receipts)),
@@ -535,6 +596,9 @@ impl net_request::CheckedRequest for CheckedRequest {
 			CheckedRequest::Account(ref prover, _) =>
 				expect!((&NetResponse::Account(ref res), _) =>
 					prover.check_response(cache, &res.proof).map(Response::Account)),
+			CheckedRequest::Storage(ref prover, _) =>
+				expect!((&NetResponse::Storage(ref res), _) =>
+					prover.check_response(cache, &res.proof).map(Response::Storage)),
 			CheckedRequest::Code(ref prover, _) =>
 				expect!((&NetResponse::Code(ref res), &CompleteRequest::Code(ref req)) =>
 					prover.check_response(cache, &req.code_hash, &res.code).map(Response::Code)),
@@ -553,6 +617,8 @@ pub enum Response {
 	HeaderProof((H256, U256)),
 	/// Response to a header-by-hash request.
 	HeaderByHash(encoded::Header),
+	/// Response to a transaction-index request.
+	TransactionIndex(net_request::TransactionIndexResponse),
 	/// Response to a receipts request.
 	Receipts(Vec<Receipt>),
 	/// Response to a block body request.
@@ -560,6 +626,8 @@ pub enum Response {
 	/// Response to an Account request.
 	// TODO: `unwrap_or(engine_defaults)`
 	Account(Option<BasicAccount>),
+	/// Response to a storage request.
+	Storage(H256),
 	/// Response to a request for code.
 	Code(Vec<u8>),
 	/// Response to a request for proved execution.
@@ -570,6 +638,7 @@ impl net_request::ResponseLike for Response {
 	fn fill_outputs<F>(&self, mut f: F) where F: FnMut(usize, Output) {
 		match *self {
 			Response::HeaderProof((ref hash, _)) => f(0, Output::Hash(*hash)),
+			Response::TransactionIndex(ref res) => res.fill_outputs(f),
 			Response::Account(None) => {
 				f(0, Output::Hash(SHA3_EMPTY)); // code hash
 				f(1, Output::Hash(SHA3_NULL_RLP)); // storage root.
@@ -578,6 +647,7 @@ impl net_request::ResponseLike for Response {
 				f(0, Output::Hash(acc.code_hash));
 				f(1, Output::Hash(acc.storage_root));
 			}
+			Response::Storage(ref value) => f(0, Output::Hash(*value)),
 			_ => {}
 		}
 	}
@@ -606,6 +676,8 @@ pub enum Error {
 	WrongTrieRoot(H256, H256),
 	/// Wrong response kind.
 	WrongKind,
+	/// Storage root not yet known.
+	UnresolvedStorageRoot,
 }
 
 impl From<::rlp::DecoderError> for Error {
@@ -699,6 +771,24 @@ impl HeaderByHash {
 	}
 }
 
+/// Request for a transaction index.
+/// The response can't be verified on its own: callers should check the
+/// transaction at the given index of the block body, which is itself verified
+/// against the header's transactions root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionIndex(pub Field<H256>);
+
+impl TransactionIndex {
+	/// Check a response for the transaction index.
+	pub fn check_response(
+		&self,
+		_: &Mutex<::cache::Cache>,
+		res: &net_request::TransactionIndexResponse,
+	) -> Result<net_request::TransactionIndexResponse, Error> {
+		Ok(res.clone())
+	}
+}
+
 /// Request for a block, with header for verification.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Body(pub HeaderRef);
@@ -783,6 +873,42 @@ impl Account {
 	}
 }
 
+/// Request for an item of an account's storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Storage {
+	/// Header for verification.
+	pub header: HeaderRef,
+	/// Address of the account.
+	pub address: Address,
+	/// Storage key requested.
+	pub key: H256,
+	/// Root of the account's storage trie, which the proof is checked against.
+	/// Should refer to the output of an `Account` request for the same address
+	/// and header, so that it is itself proven against the header's state root.
+	pub storage_root: Field<H256>,
+}
+
+impl Storage {
+	/// Check a response with a storage proof against the account's storage root.
+	pub fn check_response(&self, _: &Mutex<::cache::Cache>, proof: &[Bytes]) -> Result<H256, Error> {
+		let storage_root = *self.storage_root.as_ref().ok_or(Error::UnresolvedStorageRoot)?;
+		if storage_root == SHA3_NULL_RLP {
+			return Ok(H256::new());
+		}
+
+		let mut db = MemoryDB::new();
+		for node in proof { db.insert(&node[..]); }
+
+		match TrieDB::new(&db, &storage_root).and_then(|t| t.get(&self.key.sha3()))? {
+			Some(val) => {
+				let value: U256 = UntrustedRlp::new(&val).as_val()?;
+				Ok(value.into())
+			}
+			None => Ok(H256::new()),
+		}
+	}
+}
+
 /// Request for account code.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Code {
@@ -821,6 +947,8 @@ pub struct TransactionProof {
 	pub env_info: EnvInfo,
 	/// Consensus engine.
 	pub engine: Arc<Engine>,
+	/// Traces and state diff to produce while checking the proof.
+	pub analytics: CallAnalytics,
 }
 
 impl TransactionProof {
@@ -831,12 +959,13 @@ impl TransactionProof {
 		let mut env_info = self.env_info.clone();
 		env_info.gas_limit = self.tx.gas.clone();
 
-		let proved_execution = state::check_proof(
+		let proved_execution = state::check_proof_with_analytics(
 			state_items,
 			root,
 			&self.tx,
 			&*self.engine,
 			&self.env_info,
+			self.analytics,
 		);
 
 		match proved_execution {
@@ -850,7 +979,7 @@ impl TransactionProof {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use util::{MemoryDB, Address, Mutex, H256};
+	use util::{MemoryDB, Address, Mutex, H256, U256};
 	use util::trie::{Trie, TrieMut, SecTrieDB, SecTrieDBMut};
 	use util::trie::recorder::Recorder;
 
@@ -995,6 +1124,43 @@ mod tests {
 		assert!(req.check_response(&cache, &proof[..]).is_ok());
 	}
 
+	#[test]
+	fn check_storage_proof() {
+		let mut root = H256::default();
+		let mut db = MemoryDB::new();
+		let header = Header::new();
+
+		let key = H256::random();
+		{
+			let mut trie = SecTrieDBMut::new(&mut db, &mut root);
+			for _ in 0..100 {
+				trie.insert(&*H256::random(), &::rlp::encode(&U256::from(1_000u64))).unwrap();
+			}
+
+			trie.insert(&*key, &::rlp::encode(&U256::from(69u64))).unwrap();
+		}
+
+		let proof = {
+			let trie = SecTrieDB::new(&db, &root).unwrap();
+			let mut recorder = Recorder::new();
+
+			trie.get_with(&*key, &mut recorder).unwrap().unwrap();
+
+			recorder.drain().into_iter().map(|r| r.data).collect::<Vec<_>>()
+		};
+
+		let req = Storage {
+			header: encoded::Header::new(::rlp::encode(&header).into_vec()).into(),
+			address: Address::random(),
+			key: key,
+			storage_root: root.into(),
+		};
+
+		let cache = Mutex::new(make_cache());
+		assert_eq!(req.check_response(&cache, &proof[..]).unwrap(), H256::from(69u64));
+		assert!(req.check_response(&cache, &proof[1..]).is_err());
+	}
+
 	#[test]
 	fn check_code() {
 		let code = vec![1u8; 256];
@@ -440,6 +440,102 @@ fn back_references() {
 	assert!(recv.wait().is_ok());
 }
 
+#[test]
+fn transaction_index_back_references() {
+	let harness = Harness::create();
+
+	let peer_id = 10101;
+	let req_id = ReqId(14426);
+
+	harness.inject_peer(peer_id, Peer {
+		status: dummy_status(),
+		capabilities: dummy_capabilities(),
+	});
+
+	let header = Header::default();
+	let encoded = encoded::Header::new(header.rlp(Seal::With));
+	let tx_hash = H256::random();
+	let hash_ref = ::request::Field::back_ref(0, 1);
+
+	let recv = harness.service.request_raw(
+		&Context::NoOp,
+		vec![
+			request::TransactionIndex(tx_hash.into()).into(),
+			request::HeaderByHash(hash_ref.clone()).into(),
+			request::BlockReceipts(HeaderRef::Unresolved(1, hash_ref)).into(),
+		]
+	).unwrap();
+
+	assert_eq!(harness.service.pending.read().len(), 1);
+
+	harness.service.dispatch_pending(&Context::RequestFrom(peer_id, req_id));
+
+	assert_eq!(harness.service.pending.read().len(), 0);
+
+	harness.service.on_responses(
+		&Context::WithPeer(peer_id),
+		req_id,
+		&[
+			Response::TransactionIndex(basic_request::TransactionIndexResponse {
+				num: header.number(),
+				hash: header.hash(),
+				index: 0,
+			}),
+			Response::Headers(basic_request::HeadersResponse { headers: vec![encoded] }),
+			Response::Receipts(basic_request::ReceiptsResponse { receipts: vec![] }),
+		]
+	);
+
+	assert!(recv.wait().is_ok());
+}
+
+#[test]
+fn transaction_not_found() {
+	let harness = Harness::create();
+
+	let peer_ids = (10101, 12345);
+	let req_ids = (ReqId(14426), ReqId(555));
+
+	for peer_id in &[peer_ids.0, peer_ids.1] {
+		harness.inject_peer(*peer_id, Peer {
+			status: dummy_status(),
+			capabilities: dummy_capabilities(),
+		});
+	}
+
+	let tx_hash = H256::random();
+	let recv = harness.service.request_raw(
+		&Context::NoOp,
+		vec![
+			request::TransactionIndex(tx_hash.into()).into(),
+			request::HeaderByHash(::request::Field::back_ref(0, 1)).into(),
+		]
+	).unwrap();
+
+	assert_eq!(harness.service.pending.read().len(), 1);
+
+	harness.service.dispatch_pending(&Context::RequestFrom(peer_ids.0, req_ids.0));
+	assert_eq!(harness.service.pending.read().len(), 0);
+
+	// the first peer doesn't know the transaction.
+	harness.service.on_responses(&Context::WithPeer(peer_ids.0), req_ids.0, &[]);
+	assert_eq!(harness.service.pending.read().len(), 1);
+
+	// so it isn't asked again.
+	harness.service.dispatch_pending(&Context::RequestFrom(peer_ids.0, req_ids.1));
+	assert_eq!(harness.service.pending.read().len(), 1);
+
+	harness.service.dispatch_pending(&Context::RequestFrom(peer_ids.1, req_ids.1));
+	assert_eq!(harness.service.pending.read().len(), 0);
+
+	// neither does the second, so the request is given up on.
+	harness.service.on_responses(&Context::WithPeer(peer_ids.1), req_ids.1, &[]);
+	harness.service.dispatch_pending(&Context::NoOp);
+	assert!(harness.service.pending.read().is_empty());
+
+	assert!(recv.wait().unwrap().is_empty());
+}
+
 #[test]
 #[should_panic]
 fn bad_back_reference() {
@@ -101,6 +101,10 @@ pub trait Provider: Send + Sync {
 	/// Get a block header by id.
 	fn block_header(&self, id: BlockId) -> Option<encoded::Header>;
 
+	/// Get a transaction index by hash.
+	fn transaction_index(&self, req: request::CompleteTransactionIndexRequest)
+		-> Option<request::TransactionIndexResponse>;
+
 	/// Fulfill a block body request.
 	fn block_body(&self, req: request::CompleteBodyRequest) -> Option<request::BodyResponse>;
 
@@ -146,6 +150,18 @@ impl<T: ProvingBlockChainClient + ?Sized> Provider for T {
 		BlockChainClient::block_header(self, id)
 	}
 
+	fn transaction_index(&self, req: request::CompleteTransactionIndexRequest)
+		-> Option<request::TransactionIndexResponse>
+	{
+		use ethcore::ids::TransactionId;
+
+		self.transaction(TransactionId::Hash(req.hash)).map(|tx| ::request::TransactionIndexResponse {
+			num: tx.block_number,
+			hash: tx.block_hash,
+			index: tx.transaction_index as u64,
+		})
+	}
+
 	fn block_body(&self, req: request::CompleteBodyRequest) -> Option<request::BodyResponse> {
 		BlockChainClient::block_body(self, BlockId::Hash(req.hash))
 			.map(|body| ::request::BodyResponse { body: body })
@@ -301,6 +317,12 @@ impl<L: AsLightClient + Send + Sync> Provider for LightProvider<L> {
 		self.client.as_light_client().block_header(id)
 	}
 
+	fn transaction_index(&self, _req: request::CompleteTransactionIndexRequest)
+		-> Option<request::TransactionIndexResponse>
+	{
+		None
+	}
+
 	fn block_body(&self, _req: request::CompleteBodyRequest) -> Option<request::BodyResponse> {
 		None
 	}
@@ -27,6 +27,8 @@ const SERVICE_TRANSACTION_ABI: &'static str = include_str!("res/service_transact
 const SECRETSTORE_ACL_STORAGE_ABI: &'static str = include_str!("res/secretstore_acl_storage.json");
 const VALIDATOR_SET_ABI: &'static str = include_str!("res/validator_set.json");
 const VALIDATOR_REPORT_ABI: &'static str = include_str!("res/validator_report.json");
+const BLOCK_REWARD_ABI: &'static str = include_str!("res/block_reward.json");
+const TX_ACL_ABI: &'static str = include_str!("res/tx_acl.json");
 
 const TEST_VALIDATOR_SET_ABI: &'static str = include_str!("res/test_validator_set.json");
 
@@ -51,6 +53,8 @@ fn main() {
 	build_file("SecretStoreAclStorage", SECRETSTORE_ACL_STORAGE_ABI, "secretstore_acl_storage.rs");
 	build_file("ValidatorSet", VALIDATOR_SET_ABI, "validator_set.rs");
 	build_file("ValidatorReport", VALIDATOR_REPORT_ABI, "validator_report.rs");
+	build_file("BlockReward", BLOCK_REWARD_ABI, "block_reward.rs");
+	build_file("TransactAcl", TX_ACL_ABI, "tx_acl.rs");
 
 	build_test_contracts();
 }
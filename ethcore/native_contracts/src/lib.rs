@@ -29,6 +29,8 @@ mod service_transaction;
 mod secretstore_acl_storage;
 mod validator_set;
 mod validator_report;
+mod block_reward;
+mod tx_acl;
 
 pub mod test_contracts;
 
@@ -38,3 +40,5 @@ pub use self::service_transaction::ServiceTransactionChecker;
 pub use self::secretstore_acl_storage::SecretStoreAclStorage;
 pub use self::validator_set::ValidatorSet;
 pub use self::validator_report::ValidatorReport;
+pub use self::block_reward::BlockReward;
+pub use self::tx_acl::TransactAcl;
@@ -107,7 +107,7 @@ pub struct BlockRefMut<'a> {
 	/// State.
 	pub state: &'a mut State<StateDB>,
 	/// Traces.
-	pub traces: &'a Option<Vec<Vec<FlatTrace>>>,
+	pub traces: &'a mut Option<Vec<Vec<FlatTrace>>>,
 }
 
 /// A set of immutable references to `ExecutedBlock` fields that are publicly accessible.
@@ -148,7 +148,7 @@ impl ExecutedBlock {
 			uncles: &self.uncles,
 			state: &mut self.state,
 			receipts: &self.receipts,
-			traces: &self.traces,
+			traces: &mut self.traces,
 		}
 	}
 
@@ -376,7 +376,7 @@ impl<'x> OpenBlock<'x> {
 
 		let unclosed_state = s.block.state.clone();
 
-		if let Err(e) = s.engine.on_close_block(&mut s.block) {
+		if let Err(e) = s.engine.on_close_block(&mut s.block, s.last_hashes.clone()) {
 			warn!("Encountered error on closing the block: {}", e);
 		}
 		if let Err(e) = s.block.state.commit() {
@@ -402,7 +402,7 @@ impl<'x> OpenBlock<'x> {
 	pub fn close_and_lock(self) -> LockedBlock {
 		let mut s = self;
 
-		if let Err(e) = s.engine.on_close_block(&mut s.block) {
+		if let Err(e) = s.engine.on_close_block(&mut s.block, s.last_hashes.clone()) {
 			warn!("Encountered error on closing the block: {}", e);
 		}
 
@@ -481,13 +481,21 @@ impl LockedBlock {
 	/// NOTE: This does not check the validity of `seal` with the engine.
 	pub fn seal(self, engine: &Engine, seal: Vec<Bytes>) -> Result<SealedBlock, BlockError> {
 		let mut s = self;
-		if seal.len() != engine.seal_fields() {
-			return Err(BlockError::InvalidSealArity(Mismatch{expected: engine.seal_fields(), found: seal.len()}));
+		if seal.len() != engine.seal_fields(s.header()) {
+			return Err(BlockError::InvalidSealArity(Mismatch{expected: engine.seal_fields(s.header()), found: seal.len()}));
 		}
 		s.block.header.set_seal(seal);
 		Ok(SealedBlock { block: s.block, uncle_bytes: s.uncle_bytes })
 	}
 
+	/// Provide a seal generated by the engine itself in order to turn this into a `SealedBlock`.
+	/// Gives the engine a chance to finalize the block afterwards, e.g. by signing the header.
+	pub fn seal_internally(self, engine: &Engine, seal: Vec<Bytes>) -> Result<SealedBlock, Error> {
+		let mut sealed = self.seal(engine, seal)?;
+		engine.on_seal_block(&mut sealed.block)?;
+		Ok(sealed)
+	}
+
 	/// Provide a valid seal in order to turn this into a `SealedBlock`.
 	/// This does check the validity of `seal` with the engine.
 	/// Returns the `ClosedBlock` back again if the seal is no good.
@@ -605,6 +605,74 @@ impl BlockChain {
 		self.db.read_with_cache(db::COL_EXTRA, &self.block_details, parent).map_or(false, |d| d.children.contains(hash))
 	}
 
+	/// Makes `hash`, a canonical ancestor of the best block, the best block again.
+	/// The blocks after it are retracted as in a reorg and stay in the database as a branch.
+	/// Returns `None` if `hash` is not a canonical ancestor of the best block.
+	pub fn retract_to(&self, batch: &mut DBTransaction, hash: H256) -> Option<ImportRoute> {
+		let best_number = self.best_block_number();
+		let route = self.tree_route(self.best_block_hash(), hash)?;
+		if route.ancestor != hash {
+			return None;
+		}
+		if route.blocks.is_empty() {
+			return Some(ImportRoute::none());
+		}
+
+		assert!(self.pending_best_block.read().is_none());
+
+		let details = self.block_details(&hash)?;
+		let block = self.block(&hash)?;
+		let retracted = route.blocks;
+
+		info!(target: "reorg", "Retracting to {} ({})",
+			Colour::Yellow.bold().paint(format!("#{} {}", details.number, hash)),
+			Colour::Red.paint(retracted.iter().join(" "))
+		);
+
+		// the retracted blocks are not part of the canon chain anymore.
+		for number in details.number + 1..best_number + 1 {
+			batch.delete::<H256, BlockNumberKey>(db::COL_EXTRA, &number);
+		}
+
+		let transactions_addresses = retracted.iter()
+			.flat_map(|hash| self.block_body(hash).expect("Retracted block must be in database.").transaction_hashes())
+			.map(|hash| (hash, None))
+			.collect::<HashMap<H256, Option<TransactionAddress>>>();
+
+		let range = (details.number + 1) as bc::Number..best_number as bc::Number;
+		let chain = bc::group::BloomGroupChain::new(self.blooms_config, self);
+		let blocks_blooms = chain.replace(&range, vec![])
+			.into_iter()
+			.map(|p| (From::from(p.0), From::from(p.1)))
+			.collect::<HashMap<LogGroupPosition, BloomGroup>>();
+
+		{
+			let mut write_blocks_blooms = self.blocks_blooms.write();
+			batch.extend_with_cache(db::COL_EXTRA, &mut *write_blocks_blooms, blocks_blooms, CacheUpdatePolicy::Remove);
+		}
+
+		{
+			let mut best_block = self.pending_best_block.write();
+			batch.put(db::COL_EXTRA, b"best", &hash);
+			*best_block = Some(BestBlock {
+				hash: hash,
+				number: details.number,
+				total_difficulty: details.total_difficulty,
+				timestamp: block.header_view().timestamp(),
+				block: block.into_inner(),
+			});
+
+			let mut write_txs = self.pending_transaction_addresses.write();
+			batch.extend_with_option_cache(db::COL_EXTRA, &mut *write_txs, transactions_addresses, CacheUpdatePolicy::Overwrite);
+		}
+
+		Some(ImportRoute {
+			retracted: retracted,
+			enacted: vec![],
+			omitted: vec![],
+		})
+	}
+
 	/// Rewind to a previous block
 	#[cfg(test)]
 	fn rewind(&self) -> Option<H256> {
@@ -1092,6 +1160,10 @@ impl BlockChain {
 		let mut write_txs = self.transaction_addresses.write();
 		// update best block
 		if let Some(block) = pending_best_block.take() {
+			// blocks above a retracted to best block are not part of the canon chain anymore.
+			if block.number < best_block.number {
+				write_hashes.retain(|number, _| *number <= block.number);
+			}
 			*best_block = block;
 		}
 
@@ -2244,6 +2316,62 @@ mod tests {
 		assert_eq!(bc.rewind(), None);
 	}
 
+	#[test]
+	fn test_retract_to() {
+		let mut canon_chain = ChainGenerator::default();
+		let mut finalizer = BlockFinalizer::default();
+		let genesis = canon_chain.generate(&mut finalizer).unwrap();
+		let mut fork_chain = canon_chain.fork(1);
+		let mut fork_finalizer = finalizer.fork();
+		let first = canon_chain.generate(&mut finalizer).unwrap();
+		let second = canon_chain.generate(&mut finalizer).unwrap();
+		let fork_first = fork_chain.generate(&mut fork_finalizer).unwrap();
+		let genesis_hash = BlockView::new(&genesis).header_view().sha3();
+		let first_hash = BlockView::new(&first).header_view().sha3();
+		let second_hash = BlockView::new(&second).header_view().sha3();
+		let fork_first_hash = BlockView::new(&fork_first).header_view().sha3();
+
+		let db = new_db();
+		let bc = new_chain(&genesis, db.clone());
+		insert_block(&db, &bc, &first, vec![]);
+		insert_block(&db, &bc, &second, vec![]);
+
+		let mut batch = db.transaction();
+		assert_eq!(bc.retract_to(&mut batch, H256::default()), None);
+		assert_eq!(bc.retract_to(&mut batch, second_hash.clone()), Some(ImportRoute::none()));
+		let route = bc.retract_to(&mut batch, genesis_hash.clone());
+		db.write(batch).unwrap();
+		bc.commit();
+
+		assert_eq!(route, Some(ImportRoute {
+			retracted: vec![second_hash.clone(), first_hash.clone()],
+			enacted: vec![],
+			omitted: vec![],
+		}));
+		assert_eq!(bc.best_block_number(), 0);
+		assert_eq!(bc.best_block_hash(), genesis_hash.clone());
+		assert_eq!(bc.block_hash(1), None);
+		assert_eq!(bc.block_hash(2), None);
+		// retracted blocks stay in the database as a branch.
+		assert!(bc.is_known(&first_hash));
+		assert!(bc.is_known(&second_hash));
+		assert_eq!(bc.block_details(&genesis_hash).unwrap().children, vec![first_hash.clone()]);
+
+		// new blocks can be imported on top of the new best block.
+		assert_eq!(insert_block(&db, &bc, &fork_first, vec![]), ImportRoute {
+			retracted: vec![],
+			enacted: vec![fork_first_hash.clone()],
+			omitted: vec![],
+		});
+		assert_eq!(bc.best_block_hash(), fork_first_hash.clone());
+		assert_eq!(bc.block_hash(1), Some(fork_first_hash));
+
+		// and the new chain survives a restart.
+		let bc = new_chain(&genesis, db.clone());
+		assert_eq!(bc.best_block_number(), 1);
+		assert_eq!(bc.block_hash(2), None);
+	}
+
 	#[test]
 	fn epoch_transitions_iter() {
 		use ::engines::EpochTransition;
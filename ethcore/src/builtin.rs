@@ -15,9 +15,10 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::cmp::{max, min};
+use std::collections::BTreeMap;
 use std::io::{self, Read};
 
-use byteorder::{ByteOrder, BigEndian};
+use byteorder::{ByteOrder, BigEndian, LittleEndian};
 use crypto::sha2::Sha256 as Sha256Digest;
 use crypto::ripemd160::Ripemd160 as Ripemd160Digest;
 use crypto::digest::Digest;
@@ -59,6 +60,11 @@ struct Modexp {
 	divisor: usize,
 }
 
+/// A pricing model for the Blake2 compression function, charging per round.
+struct Blake2FPricer {
+	gas_per_round: u64,
+}
+
 impl Pricer for Linear {
 	fn cost(&self, input: &[u8]) -> U256 {
 		U256::from(self.base) + U256::from(self.word) * U256::from((input.len() + 31) / 32)
@@ -94,21 +100,39 @@ impl Pricer for Modexp {
 	}
 }
 
-/// Pricing scheme, execution definition, and activation block for a built-in contract.
+impl Pricer for Blake2FPricer {
+	fn cost(&self, input: &[u8]) -> U256 {
+		// malformed input is rejected by `execute`, so it's fine to charge nothing for it here.
+		if input.len() < 4 {
+			return U256::zero();
+		}
+
+		let rounds = BigEndian::read_u32(&input[..4]);
+		U256::from(self.gas_per_round) * U256::from(rounds)
+	}
+}
+
+/// Pricing schemes, execution definition, and activation block for a built-in contract.
 ///
-/// Call `cost` to compute cost for the given input, `execute` to execute the contract
-/// on the given input, and `is_active` to determine whether the contract is active.
+/// Call `cost` to compute cost for the given input at the given block, `execute` to execute
+/// the contract on the given input, and `is_active` to determine whether the contract is active.
 ///
 /// Unless `is_active` is true,
 pub struct Builtin {
-	pricer: Box<Pricer>,
+	pricer: BTreeMap<u64, Box<Pricer>>,
 	native: Box<Impl>,
 	activate_at: u64,
 }
 
 impl Builtin {
-	/// Simple forwarder for cost.
-	pub fn cost(&self, input: &[u8]) -> U256 { self.pricer.cost(input) }
+	/// Cost of running the builtin on the given input, using the pricing scheme active at the given block.
+	pub fn cost(&self, input: &[u8], at: u64) -> U256 {
+		let (_, pricer) = self.pricer.iter().rev()
+			.find(|&(block, _)| *block <= at)
+			.or_else(|| self.pricer.iter().next())
+			.expect("builtins are always constructed with at least one pricing scheme; qed");
+		pricer.cost(input)
+	}
 
 	/// Simple forwarder for execute.
 	pub fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), Error> {
@@ -119,31 +143,55 @@ impl Builtin {
 	pub fn is_active(&self, at: u64) -> bool { at >= self.activate_at }
 }
 
+// Pricing scheme creator.
+fn pricer_from(pricing: ethjson::spec::Pricing) -> Box<Pricer> {
+	match pricing {
+		ethjson::spec::Pricing::Linear(linear) => {
+			Box::new(Linear {
+				base: linear.base,
+				word: linear.word,
+			})
+		}
+		ethjson::spec::Pricing::Modexp(exp) => {
+			Box::new(Modexp {
+				divisor: if exp.divisor == 0 {
+					warn!("Zero modexp divisor specified. Falling back to default.");
+					10
+				} else {
+					exp.divisor
+				}
+			})
+		}
+		ethjson::spec::Pricing::Blake2F(blake2_f) => {
+			Box::new(Blake2FPricer {
+				gas_per_round: blake2_f.gas_per_round,
+			})
+		}
+	}
+}
+
 impl From<ethjson::spec::Builtin> for Builtin {
 	fn from(b: ethjson::spec::Builtin) -> Self {
-		let pricer: Box<Pricer> = match b.pricing {
-			ethjson::spec::Pricing::Linear(linear) => {
-				Box::new(Linear {
-					base: linear.base,
-					word: linear.word,
-				})
-			}
-			ethjson::spec::Pricing::Modexp(exp) => {
-				Box::new(Modexp {
-					divisor: if exp.divisor == 0 {
-						warn!("Zero modexp divisor specified. Falling back to default.");
-						10
-					} else {
-						exp.divisor
-					}
-				})
-			}
+		let pricer: BTreeMap<u64, Box<Pricer>> = match b.pricing {
+			ethjson::spec::PricingSchedule::Single(pricing) => {
+				let mut pricer = BTreeMap::new();
+				pricer.insert(0, pricer_from(pricing));
+				pricer
+			},
+			ethjson::spec::PricingSchedule::Multi(pricing) => pricing.into_iter().map(|(block, pricing)| (block.into(), pricer_from(pricing))).collect(),
 		};
 
+		if pricer.is_empty() {
+			panic!("no pricing specified for builtin: {}", b.name);
+		}
+
+		let activate_at = b.activate_at.map(Into::into)
+			.unwrap_or_else(|| *pricer.keys().next().expect("checked not empty above; qed"));
+
 		Builtin {
 			pricer: pricer,
 			native: ethereum_builtin(&b.name),
-			activate_at: b.activate_at.map(Into::into).unwrap_or(0),
+			activate_at: activate_at,
 		}
 	}
 }
@@ -159,6 +207,7 @@ fn ethereum_builtin(name: &str) -> Box<Impl> {
 		"bn128_add" => Box::new(Bn128AddImpl) as Box<Impl>,
 		"bn128_mul" => Box::new(Bn128MulImpl) as Box<Impl>,
 		"bn128_pairing" => Box::new(Bn128PairingImpl) as Box<Impl>,
+		"blake2_f" => Box::new(Blake2F) as Box<Impl>,
 		_ => panic!("invalid builtin name: {}", name),
 	}
 }
@@ -170,6 +219,8 @@ fn ethereum_builtin(name: &str) -> Box<Impl> {
 // - sha256
 // - ripemd160
 // - modexp (EIP198)
+// - bn128 add, mul and pairing (EIP196, EIP197)
+// - blake2 compression function (EIP152)
 
 #[derive(Debug)]
 struct Identity;
@@ -195,6 +246,9 @@ struct Bn128MulImpl;
 #[derive(Debug)]
 struct Bn128PairingImpl;
 
+#[derive(Debug)]
+struct Blake2F;
+
 impl Impl for Identity {
 	fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), Error> {
 		output.write(0, input);
@@ -495,9 +549,117 @@ impl Impl for Bn128PairingImpl {
 	}
 }
 
+mod blake2_f {
+	const IV: [u64; 8] = [
+		0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+		0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+	];
+
+	const SIGMA: [[usize; 16]; 10] = [
+		[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+		[14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+		[11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+		[7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+		[9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+		[2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+		[12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+		[13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+		[6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+		[10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+	];
+
+	// the mixing function, as in RFC 7693.
+	fn g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+		v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+		v[d] = (v[d] ^ v[a]).rotate_right(32);
+		v[c] = v[c].wrapping_add(v[d]);
+		v[b] = (v[b] ^ v[c]).rotate_right(24);
+		v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+		v[d] = (v[d] ^ v[a]).rotate_right(16);
+		v[c] = v[c].wrapping_add(v[d]);
+		v[b] = (v[b] ^ v[c]).rotate_right(63);
+	}
+
+	/// The Blake2b compression function F with a configurable number of rounds.
+	pub fn compress(h: &mut [u64; 8], m: &[u64; 16], t: &[u64; 2], f: bool, rounds: u32) {
+		let mut v = [0u64; 16];
+		v[..8].copy_from_slice(&h[..]);
+		v[8..].copy_from_slice(&IV);
+
+		v[12] ^= t[0];
+		v[13] ^= t[1];
+		if f {
+			v[14] = !v[14];
+		}
+
+		for i in 0..rounds as usize {
+			let s = &SIGMA[i % 10];
+			g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+			g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+			g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+			g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+			g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+			g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+			g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+			g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+		}
+
+		for i in 0..8 {
+			h[i] ^= v[i] ^ v[i + 8];
+		}
+	}
+}
+
+impl Impl for Blake2F {
+	/// Can fail if:
+	///     - input length is not exactly 213 bytes
+	///     - the final block indicator flag is neither 0 nor 1
+	fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), Error> {
+		const BLAKE2_F_ARG_LEN: usize = 213;
+
+		if input.len() != BLAKE2_F_ARG_LEN {
+			return Err("Invalid input length, must be exactly 213 bytes".into())
+		}
+
+		let rounds = BigEndian::read_u32(&input[..4]);
+
+		let mut h = [0u64; 8];
+		for (i, state) in h.iter_mut().enumerate() {
+			*state = LittleEndian::read_u64(&input[4 + i * 8..]);
+		}
+
+		let mut m = [0u64; 16];
+		for (i, word) in m.iter_mut().enumerate() {
+			*word = LittleEndian::read_u64(&input[68 + i * 8..]);
+		}
+
+		let t = [
+			LittleEndian::read_u64(&input[196..]),
+			LittleEndian::read_u64(&input[204..]),
+		];
+
+		let f = match input[212] {
+			1 => true,
+			0 => false,
+			_ => return Err("Invalid final block indicator flag".into()),
+		};
+
+		blake2_f::compress(&mut h, &m, &t, f, rounds);
+
+		let mut write_buf = [0u8; 64];
+		for (i, state) in h.iter().enumerate() {
+			LittleEndian::write_u64(&mut write_buf[i * 8..(i + 1) * 8], *state);
+		}
+		output.write(0, &write_buf);
+
+		Ok(())
+	}
+}
+
 #[cfg(test)]
 mod tests {
-	use super::{Builtin, Linear, ethereum_builtin, Pricer, Modexp};
+	use std::collections::BTreeMap;
+	use super::{Builtin, Linear, ethereum_builtin, Pricer, Modexp, Blake2FPricer};
 	use ethjson;
 	use util::{U256, BytesRef};
 	use rustc_hex::FromHex;
@@ -626,7 +788,7 @@ mod tests {
 	fn modexp() {
 
 		let f = Builtin {
-			pricer: Box::new(Modexp { divisor: 20 }),
+			pricer: map![0 => Box::new(Modexp { divisor: 20 }) as Box<Pricer>],
 			native: ethereum_builtin("modexp"),
 			activate_at: 0,
 		};
@@ -647,7 +809,7 @@ mod tests {
 
 			f.execute(&input[..], &mut BytesRef::Fixed(&mut output[..])).expect("Builtin should not fail");
 			assert_eq!(output, expected);
-			assert_eq!(f.cost(&input[..]), expected_cost.into());
+			assert_eq!(f.cost(&input[..], 0), expected_cost.into());
 		}
 
 		// second example from EIP: zero base.
@@ -666,7 +828,7 @@ mod tests {
 
 			f.execute(&input[..], &mut BytesRef::Fixed(&mut output[..])).expect("Builtin should not fail");
 			assert_eq!(output, expected);
-			assert_eq!(f.cost(&input[..]), expected_cost.into());
+			assert_eq!(f.cost(&input[..], 0), expected_cost.into());
 		}
 
 		// another example from EIP: zero-padding
@@ -686,7 +848,7 @@ mod tests {
 
 			f.execute(&input[..], &mut BytesRef::Fixed(&mut output[..])).expect("Builtin should not fail");
 			assert_eq!(output, expected);
-			assert_eq!(f.cost(&input[..]), expected_cost.into());
+			assert_eq!(f.cost(&input[..], 0), expected_cost.into());
 		}
 
 		// zero-length modulus.
@@ -704,7 +866,7 @@ mod tests {
 
 			f.execute(&input[..], &mut BytesRef::Flexible(&mut output)).expect("Builtin should not fail");
 			assert_eq!(output.len(), 0); // shouldn't have written any output.
-			assert_eq!(f.cost(&input[..]), expected_cost.into());
+			assert_eq!(f.cost(&input[..], 0), expected_cost.into());
 		}
 	}
 
@@ -712,7 +874,7 @@ mod tests {
 	fn bn128_add() {
 
 		let f = Builtin {
-			pricer: Box::new(Linear { base: 0, word: 0 }),
+			pricer: map![0 => Box::new(Linear { base: 0, word: 0 }) as Box<Pricer>],
 			native: ethereum_builtin("bn128_add"),
 			activate_at: 0,
 		};
@@ -773,7 +935,7 @@ mod tests {
 	fn bn128_mul() {
 
 		let f = Builtin {
-			pricer: Box::new(Linear { base: 0, word: 0 }),
+			pricer: map![0 => Box::new(Linear { base: 0, word: 0 }) as Box<Pricer>],
 			native: ethereum_builtin("bn128_mul"),
 			activate_at: 0,
 		};
@@ -813,7 +975,7 @@ mod tests {
 
 	fn builtin_pairing() -> Builtin {
 		Builtin {
-			pricer: Box::new(Linear { base: 0, word: 0 }),
+			pricer: map![0 => Box::new(Linear { base: 0, word: 0 }) as Box<Pricer>],
 			native: ethereum_builtin("bn128_pairing"),
 			activate_at: 0,
 		}
@@ -897,7 +1059,7 @@ mod tests {
 	fn is_active() {
 		let pricer = Box::new(Linear { base: 10, word: 20} );
 		let b = Builtin {
-			pricer: pricer as Box<Pricer>,
+			pricer: map![0 => pricer as Box<Pricer>],
 			native: ethereum_builtin("identity"),
 			activate_at: 100_000,
 		};
@@ -911,15 +1073,15 @@ mod tests {
 	fn from_named_linear() {
 		let pricer = Box::new(Linear { base: 10, word: 20 });
 		let b = Builtin {
-			pricer: pricer as Box<Pricer>,
+			pricer: map![0 => pricer as Box<Pricer>],
 			native: ethereum_builtin("identity"),
 			activate_at: 1,
 		};
 
-		assert_eq!(b.cost(&[0; 0]), U256::from(10));
-		assert_eq!(b.cost(&[0; 1]), U256::from(30));
-		assert_eq!(b.cost(&[0; 32]), U256::from(30));
-		assert_eq!(b.cost(&[0; 33]), U256::from(50));
+		assert_eq!(b.cost(&[0; 0], 0), U256::from(10));
+		assert_eq!(b.cost(&[0; 1], 0), U256::from(30));
+		assert_eq!(b.cost(&[0; 32], 0), U256::from(30));
+		assert_eq!(b.cost(&[0; 33], 0), U256::from(50));
 
 		let i = [0u8, 1, 2, 3];
 		let mut o = [255u8; 4];
@@ -931,21 +1093,107 @@ mod tests {
 	fn from_json() {
 		let b = Builtin::from(ethjson::spec::Builtin {
 			name: "identity".to_owned(),
-			pricing: ethjson::spec::Pricing::Linear(ethjson::spec::Linear {
+			pricing: ethjson::spec::PricingSchedule::Single(ethjson::spec::Pricing::Linear(ethjson::spec::Linear {
 				base: 10,
 				word: 20,
-			}),
+			})),
 			activate_at: None,
 		});
 
-		assert_eq!(b.cost(&[0; 0]), U256::from(10));
-		assert_eq!(b.cost(&[0; 1]), U256::from(30));
-		assert_eq!(b.cost(&[0; 32]), U256::from(30));
-		assert_eq!(b.cost(&[0; 33]), U256::from(50));
+		assert_eq!(b.cost(&[0; 0], 0), U256::from(10));
+		assert_eq!(b.cost(&[0; 1], 0), U256::from(30));
+		assert_eq!(b.cost(&[0; 32], 0), U256::from(30));
+		assert_eq!(b.cost(&[0; 33], 0), U256::from(50));
 
 		let i = [0u8, 1, 2, 3];
 		let mut o = [255u8; 4];
 		b.execute(&i[..], &mut BytesRef::Fixed(&mut o[..])).expect("Builtin should not fail");
 		assert_eq!(i, o);
 	}
+
+	#[test]
+	fn from_json_pricing_per_block() {
+		let b = Builtin::from(ethjson::spec::Builtin {
+			name: "identity".to_owned(),
+			pricing: ethjson::spec::PricingSchedule::Multi(vec![
+				(ethjson::uint::Uint(10.into()), ethjson::spec::Pricing::Linear(ethjson::spec::Linear { base: 10, word: 20 })),
+				(ethjson::uint::Uint(20.into()), ethjson::spec::Pricing::Linear(ethjson::spec::Linear { base: 5, word: 1 })),
+			].into_iter().collect()),
+			activate_at: None,
+		});
+
+		assert!(!b.is_active(9));
+		assert!(b.is_active(10));
+
+		assert_eq!(b.cost(&[0; 1], 10), U256::from(30));
+		assert_eq!(b.cost(&[0; 1], 19), U256::from(30));
+		assert_eq!(b.cost(&[0; 1], 20), U256::from(6));
+		assert_eq!(b.cost(&[0; 33], 100), U256::from(7));
+	}
+
+	fn builtin_blake2_f() -> Builtin {
+		Builtin {
+			pricer: map![0 => Box::new(Blake2FPricer { gas_per_round: 1 }) as Box<Pricer>],
+			native: ethereum_builtin("blake2_f"),
+			activate_at: 0,
+		}
+	}
+
+	fn blake2_f_input(rounds: &str, final_block: &str) -> Vec<u8> {
+		bytes_owned(format!("{}\
+			48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+			d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b\
+			6162630000000000000000000000000000000000000000000000000000000000\
+			0000000000000000000000000000000000000000000000000000000000000000\
+			0000000000000000000000000000000000000000000000000000000000000000\
+			0000000000000000000000000000000000000000000000000000000000000000\
+			0300000000000000\
+			0000000000000000\
+			{}", rounds, final_block))
+	}
+
+	fn bytes_owned(s: String) -> Vec<u8> {
+		FromHex::from_hex(&s[..]).expect("test input should contain valid hex bytes")
+	}
+
+	#[test]
+	fn blake2_f() {
+		// test vectors from EIP-152.
+		let f = builtin_blake2_f();
+
+		let cases = [
+			("0000000c", "01", "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"),
+			("0000000c", "00", "75ab69d3190a562c51aef8d88f1c2775876944407270c42c9844252c26d2875298743e7f6d5ea2f2d3e8d226039cd31b4e426ac4f2d3d666a610c2116fde4735"),
+			("00000000", "01", "08c9bcf367e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d282e6ad7f520e511f6c3e2b8c68059b9442be0454267ce079217e1319cde05b"),
+			("00000001", "01", "b63a380cb2897d521994a85234ee2c181b5f844d2c624c002677e9703449d2fba551b3a8333bcdf5f2f7e08993d53923de3d64fcc68c034e717b9293fed7a421"),
+		];
+
+		for &(rounds, final_block, expected) in cases.iter() {
+			let input = blake2_f_input(rounds, final_block);
+			let mut output = vec![0u8; 64];
+
+			f.execute(&input[..], &mut BytesRef::Fixed(&mut output[..])).expect("Builtin should not fail");
+			assert_eq!(output, bytes(expected));
+		}
+
+		assert_eq!(f.cost(&blake2_f_input("0000000c", "01")[..], 0), U256::from(12));
+		assert_eq!(f.cost(&blake2_f_input("ffffffff", "01")[..], 0), U256::from(0xffffffffu64));
+		assert_eq!(f.cost(&[], 0), U256::zero());
+	}
+
+	#[test]
+	fn blake2_f_invalid_input() {
+		// empty input.
+		error_test(builtin_blake2_f(), &[], Some("Invalid input length"));
+
+		// one byte too short and one byte too long.
+		let input = blake2_f_input("0000000c", "01");
+		error_test(builtin_blake2_f(), &input[1..], Some("Invalid input length"));
+		let mut long_input = input.clone();
+		long_input.push(0);
+		error_test(builtin_blake2_f(), &long_input[..], Some("Invalid input length"));
+
+		// final block indicator flag is neither 0 nor 1.
+		error_test(builtin_blake2_f(), &blake2_f_input("0000000c", "02")[..], Some("Invalid final block indicator flag"));
+	}
 }
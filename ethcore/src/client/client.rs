@@ -18,6 +18,7 @@ use std::collections::{HashSet, HashMap, BTreeMap, VecDeque};
 use std::str::FromStr;
 use std::sync::{Arc, Weak};
 use std::fmt;
+use std::ops::Range;
 use std::sync::atomic::{AtomicUsize, AtomicBool, Ordering as AtomicOrdering};
 use std::time::{Instant};
 use time::precise_time_ns;
@@ -38,7 +39,7 @@ use client::ancient_import::AncientVerifier;
 use client::Error as ClientError;
 use client::{
 	BlockId, TransactionId, UncleId, TraceId, ClientConfig, BlockChainClient,
-	MiningBlockChainClient, EngineClient, TraceFilter, CallAnalytics, BlockImportError, Mode,
+	MiningBlockChainClient, EngineClient, DevBlockChainClient, TraceFilter, CallAnalytics, StateOverride, BlockImportError, Mode,
 	ChainNotify, PruningInfo, ProvingBlockChainClient,
 };
 use encoded;
@@ -53,7 +54,7 @@ use futures::{future, Future};
 use header::{BlockNumber, Header};
 use io::*;
 use log_entry::LocalizedLogEntry;
-use miner::{Miner, MinerService, TransactionImportResult};
+use miner::{Miner, MinerService, TransactionImportResult, LocalTransactionStatus, LocalTransactionPropagation};
 use native_contracts::Registry;
 use rand::OsRng;
 use receipt::{Receipt, LocalizedReceipt};
@@ -69,6 +70,7 @@ use trace::FlatTransactionTraces;
 use transaction::{LocalizedTransaction, UnverifiedTransaction, SignedTransaction, Transaction, PendingTransaction, Action};
 use types::filter::Filter;
 use types::mode::Mode as IpcMode;
+use tx_filter::TransactionFilter;
 use verification;
 use verification::{PreverifiedBlock, Verifier};
 use verification::queue::BlockQueue;
@@ -84,6 +86,9 @@ const MAX_TX_QUEUE_SIZE: usize = 4096;
 const MAX_QUEUE_SIZE_TO_SLEEP_ON: usize = 2;
 const MIN_HISTORY_SIZE: u64 = 8;
 
+// key under which the progress of an interrupted fast sync is stored.
+const FAST_SYNC_PROGRESS_KEY: &'static [u8] = b"fast_sync_progress";
+
 impl fmt::Display for BlockChainInfo {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		write!(f, "#{}.{}", self.best_block_number, self.best_block_hash)
@@ -172,6 +177,9 @@ pub struct Client {
 	on_user_defaults_change: Mutex<Option<Box<FnMut(Option<Mode>) + 'static + Send>>>,
 	registrar: Mutex<Option<Registry>>,
 	exit_handler: Mutex<Option<Box<Fn(bool, Option<String>) + 'static + Send>>>,
+	/// Best block hash and engine time offset recorded by `DevBlockChainClient::snapshot`.
+	dev_snapshots: Mutex<Vec<(H256, u64)>>,
+	tx_filter: Option<TransactionFilter>,
 }
 
 impl Client {
@@ -259,6 +267,8 @@ impl Client {
 			on_user_defaults_change: Mutex::new(None),
 			registrar: Mutex::new(None),
 			exit_handler: Mutex::new(None),
+			dev_snapshots: Mutex::new(Vec::new()),
+			tx_filter: TransactionFilter::from_params(spec.params()),
 		});
 
 		// prune old states.
@@ -430,6 +440,13 @@ impl Client {
 			return Err(());
 		};
 
+		if let Some(ref filter) = self.tx_filter {
+			if let Err(e) = verification::verify_transactions_allowed(header, &block.transactions, filter, self) {
+				warn!(target: "client", "Stage 3 block verification failed for #{} ({})\nError: {:?}", header.number(), header.hash(), e);
+				return Err(());
+			}
+		}
+
 		let verify_external_result = self.verifier.verify_block_external(header, &block.bytes, engine);
 		if let Err(e) = verify_external_result {
 			warn!(target: "client", "Stage 4 block verification failed for #{} ({})\nError: {:?}", header.number(), header.hash(), e);
@@ -891,6 +908,55 @@ impl Client {
 		}
 	}
 
+	/// Execute a transaction on top of the given state without committing it, running the requested analytics.
+	fn do_virtual_call(&self, env_info: &EnvInfo, state: &mut State<StateDB>, t: &SignedTransaction, analytics: CallAnalytics) -> Result<Executed, CallError> {
+		let original_state = if analytics.state_diffing { Some(state.clone()) } else { None };
+
+		let options = TransactOptions { tracing: analytics.transaction_tracing, vm_tracing: analytics.vm_tracing, check_nonce: false };
+		let mut ret = Executive::new(state, env_info, &*self.engine).transact_virtual(t, options)?;
+
+		// TODO gav move this into Executive.
+		if let Some(original) = original_state {
+			ret.state_diff = Some(state.diff_from(original).map_err(ExecutionError::from)?);
+		}
+
+		Ok(ret)
+	}
+
+	/// Replay the transactions of the given block up to the end of `analyzed`, each on top of the
+	/// state left by the previous one. The requested analytics are run only for the transactions
+	/// within `analyzed`, whose results are returned.
+	fn replay_transactions(&self, block: BlockId, analyzed: Range<usize>, analytics: CallAnalytics) -> Result<Vec<Executed>, CallError> {
+		let body = self.block_body(block).ok_or(CallError::BlockNotFound)?;
+		let mut env_info = self.env_info(block).ok_or(CallError::StatePruned)?;
+		let mut state = self.state_at_beginning(block).ok_or(CallError::StatePruned)?;
+
+		let options = TransactOptions { tracing: analytics.transaction_tracing, vm_tracing: analytics.vm_tracing, check_nonce: false };
+		const PROOF: &'static str = "Transactions fetched from blockchain; blockchain transactions are valid; qed";
+
+		let mut results = Vec::new();
+		for (i, t) in body.transactions().into_iter().enumerate().take_while(|&(i, _)| i < analyzed.end) {
+			let t = SignedTransaction::new(t).expect(PROOF);
+			let ret = if i < analyzed.start {
+				Executive::new(&mut state, &env_info, &*self.engine).transact(&t, Default::default())?
+			} else {
+				let original_state = if analytics.state_diffing { Some(state.clone()) } else { None };
+				let mut ret = Executive::new(&mut state, &env_info, &*self.engine).transact(&t, options)?;
+				if let Some(original) = original_state {
+					ret.state_diff = Some(state.diff_from(original).map_err(ExecutionError::from)?)
+				}
+				ret
+			};
+
+			env_info.gas_used = env_info.gas_used + ret.gas_used;
+			if i >= analyzed.start {
+				results.push(ret);
+			}
+		}
+
+		Ok(results)
+	}
+
 	/// Get a copy of the best block's state.
 	pub fn state(&self) -> State<StateDB> {
 		let header = self.best_block_header();
@@ -1057,6 +1123,12 @@ impl Client {
 			data: data,
 		}.fake_sign(from)
 	}
+
+	// time offset of engines meant for development chains, which are the only ones controlled through `DevBlockChainClient`.
+	fn dev_time_offset(&self) -> Result<u64, String> {
+		self.engine.time_offset()
+			.ok_or_else(|| format!("{} engine does not support development chain controls.", self.engine.name()))
+	}
 }
 
 impl snapshot::DatabaseRestore for Client {
@@ -1081,26 +1153,35 @@ impl snapshot::DatabaseRestore for Client {
 }
 
 impl BlockChainClient for Client {
-	fn call(&self, t: &SignedTransaction, block: BlockId, analytics: CallAnalytics) -> Result<Executed, CallError> {
+	fn call(&self, t: &SignedTransaction, block: BlockId, analytics: CallAnalytics, state_override: &StateOverride) -> Result<Executed, CallError> {
 		let mut env_info = self.env_info(block).ok_or(CallError::StatePruned)?;
 		env_info.gas_limit = U256::max_value();
 
 		// that's just a copy of the state.
 		let mut state = self.state_at(block).ok_or(CallError::StatePruned)?;
-		let original_state = if analytics.state_diffing { Some(state.clone()) } else { None };
+		state.apply_overrides(state_override).map_err(ExecutionError::from)?;
 
-		let options = TransactOptions { tracing: analytics.transaction_tracing, vm_tracing: analytics.vm_tracing, check_nonce: false };
-		let mut ret = Executive::new(&mut state, &env_info, &*self.engine).transact_virtual(t, options)?;
+		self.do_virtual_call(&env_info, &mut state, t, analytics)
+	}
 
-		// TODO gav move this into Executive.
-		if let Some(original) = original_state {
-			ret.state_diff = Some(state.diff_from(original).map_err(ExecutionError::from)?);
+	fn call_many(&self, transactions: Vec<(SignedTransaction, CallAnalytics)>, block: BlockId) -> Result<Vec<Executed>, CallError> {
+		let mut env_info = self.env_info(block).ok_or(CallError::StatePruned)?;
+		env_info.gas_limit = U256::max_value();
+
+		// that's just a copy of the state.
+		let mut state = self.state_at(block).ok_or(CallError::StatePruned)?;
+		let mut results = Vec::with_capacity(transactions.len());
+
+		for (t, analytics) in transactions {
+			let ret = self.do_virtual_call(&env_info, &mut state, &t, analytics)?;
+			env_info.gas_used = env_info.gas_used + ret.gas_used;
+			results.push(ret);
 		}
 
-		Ok(ret)
+		Ok(results)
 	}
 
-	fn estimate_gas(&self, t: &SignedTransaction, block: BlockId) -> Result<U256, CallError> {
+	fn estimate_gas(&self, t: &SignedTransaction, block: BlockId, state_override: &StateOverride) -> Result<U256, CallError> {
 		const UPPER_CEILING: u64 = 1_000_000_000_000u64;
 		let (mut upper, env_info)  = {
 			let mut env_info = self.env_info(block).ok_or(CallError::StatePruned)?;
@@ -1110,7 +1191,8 @@ impl BlockChainClient for Client {
 		};
 
 		// that's just a copy of the state.
-		let original_state = self.state_at(block).ok_or(CallError::StatePruned)?;
+		let mut original_state = self.state_at(block).ok_or(CallError::StatePruned)?;
+		original_state.apply_overrides(state_override).map_err(ExecutionError::from)?;
 		let sender = t.sender();
 		let options = TransactOptions { tracing: true, vm_tracing: false, check_nonce: false };
 
@@ -1168,31 +1250,14 @@ impl BlockChainClient for Client {
 
 	fn replay(&self, id: TransactionId, analytics: CallAnalytics) -> Result<Executed, CallError> {
 		let address = self.transaction_address(id).ok_or(CallError::TransactionNotFound)?;
-		let mut env_info = self.env_info(BlockId::Hash(address.block_hash)).ok_or(CallError::StatePruned)?;
-		let body = self.block_body(BlockId::Hash(address.block_hash)).ok_or(CallError::StatePruned)?;
-		let mut state = self.state_at_beginning(BlockId::Hash(address.block_hash)).ok_or(CallError::StatePruned)?;
-		let mut txs = body.transactions();
-
-		if address.index >= txs.len() {
-			return Err(CallError::TransactionNotFound);
-		}
+		let index = address.index;
+		self.replay_transactions(BlockId::Hash(address.block_hash), index..index + 1, analytics)?
+			.pop()
+			.ok_or(CallError::TransactionNotFound)
+	}
 
-		let options = TransactOptions { tracing: analytics.transaction_tracing, vm_tracing: analytics.vm_tracing, check_nonce: false };
-		const PROOF: &'static str = "Transactions fetched from blockchain; blockchain transactions are valid; qed";
-		let rest = txs.split_off(address.index);
-		for t in txs {
-			let t = SignedTransaction::new(t).expect(PROOF);
-			let x = Executive::new(&mut state, &env_info, &*self.engine).transact(&t, Default::default())?;
-			env_info.gas_used = env_info.gas_used + x.gas_used;
-		}
-		let first = rest.into_iter().next().expect("We split off < `address.index`; Length is checked earlier; qed");
-		let t = SignedTransaction::new(first).expect(PROOF);
-		let original_state = if analytics.state_diffing { Some(state.clone()) } else { None };
-		let mut ret = Executive::new(&mut state, &env_info, &*self.engine).transact(&t, options)?;
-		if let Some(original) = original_state {
-			ret.state_diff = Some(state.diff_from(original).map_err(ExecutionError::from)?)
-		}
-		Ok(ret)
+	fn replay_block_transactions(&self, block: BlockId, analytics: CallAnalytics) -> Result<Vec<Executed>, CallError> {
+		self.replay_transactions(block, 0..::std::usize::MAX, analytics)
 	}
 
 	fn mode(&self) -> IpcMode {
@@ -1455,6 +1520,8 @@ impl BlockChainClient for Client {
 	}
 
 	fn state_data(&self, hash: &H256) -> Option<Bytes> {
+		// NOTE: storage nodes and code can't be found without the address hash of their account,
+		// so other Parity nodes can't fast sync from this one. See `serves_state_nodes` in sync.
 		self.state_db.lock().journal_db().state(hash)
 	}
 
@@ -1494,6 +1561,86 @@ impl BlockChainClient for Client {
 		self.import_old_block(block_bytes, receipts_bytes).map_err(Into::into)
 	}
 
+	fn import_state_nodes(&self, nodes: Vec<(Option<H256>, Bytes)>, accounts: Vec<H256>) {
+		let mut state_db = self.state_db.lock();
+		for (address_hash, node) in nodes {
+			let hash = node.sha3();
+			let value = DBValue::from_vec(node);
+			match address_hash {
+				Some(address_hash) => self.factories.accountdb.create(state_db.as_hashdb_mut(), address_hash).emplace(hash, value),
+				None => state_db.as_hashdb_mut().emplace(hash, value),
+			}
+		}
+
+		let mut batch = DBTransaction::new();
+		state_db.inject(&mut batch).expect("Low level database error. Some issue with disk?");
+		state_db.note_account_hashes(&mut batch, &accounts).expect("Low level database error. Some issue with disk?");
+		self.db.read().write_buffered(batch);
+	}
+
+	fn state_node(&self, address_hash: Option<H256>, hash: &H256) -> Option<Bytes> {
+		let state_db = self.state_db.lock();
+		let node = match address_hash {
+			Some(address_hash) => self.factories.accountdb.readonly(state_db.as_hashdb(), address_hash).get(hash),
+			None => state_db.as_hashdb().get(hash),
+		};
+		node.map(|node| node.into_vec())
+	}
+
+	fn verify_header_with_parent(&self, header: &Header, parent: &Header) -> Result<(), EthcoreError> {
+		verification::verify_header_with_parent(header, parent, &*self.engine)
+	}
+
+	fn import_fast_sync_pivot(&self, block_bytes: Bytes, receipts_bytes: Bytes, parent_total_difficulty: U256) -> Result<H256, BlockImportError> {
+		let header = BlockView::new(&block_bytes).header();
+		let hash = header.hash();
+		let receipts = ::rlp::decode_list(&receipts_bytes);
+
+		let _import_lock = self.import_lock.lock();
+		let mut state_db = self.state_db.lock();
+		let mut chain = self.chain.write();
+
+		if chain.is_known(&hash) {
+			return Err(BlockImportError::Import(ImportError::AlreadyInChain));
+		}
+		if !state_db.as_hashdb().contains(&header.state_root()) {
+			return Err(BlockImportError::Other(format!("State of the pivot block {} is missing", hash)));
+		}
+
+		let db = self.db.read();
+		let mut batch = DBTransaction::new();
+		state_db.journal_under(&mut batch, header.number(), &hash)
+			.map_err(|e| BlockImportError::Other(format!("{:?}", e)))?;
+		chain.insert_unordered_block(&mut batch, &block_bytes, receipts, Some(parent_total_difficulty), true, false);
+		db.write_buffered(batch);
+		chain.commit();
+		db.flush().map_err(BlockImportError::Other)?;
+
+		// reopen the chain so that the blocks before the pivot are picked up as missing ancient blocks.
+		*chain = Arc::new(BlockChain::new(self.config.blockchain.clone(), &[], db.clone()));
+		*self.tracedb.write() = TraceDB::new(self.config.tracing.clone(), db.clone(), chain.clone());
+		Ok(hash)
+	}
+
+	fn fast_sync_progress(&self) -> Option<Bytes> {
+		self.db.read().get(::db::COL_NODE_INFO, FAST_SYNC_PROGRESS_KEY)
+			.expect("Low level database error. Some issue with disk?")
+			.map(|v| v.into_vec())
+	}
+
+	fn set_fast_sync_progress(&self, progress: Option<Bytes>) {
+		let db = self.db.read();
+		// progress refers to state nodes which must hit the disk first.
+		db.flush().expect("Low level database error. Some issue with disk?");
+
+		let mut batch = DBTransaction::new();
+		match progress {
+			Some(progress) => batch.put(::db::COL_NODE_INFO, FAST_SYNC_PROGRESS_KEY, &progress),
+			None => batch.delete(::db::COL_NODE_INFO, FAST_SYNC_PROGRESS_KEY),
+		}
+		db.write(batch).expect("Low level database error. Some issue with disk?");
+	}
+
 	fn queue_info(&self) -> BlockQueueInfo {
 		self.block_queue.queue_info()
 	}
@@ -1602,6 +1749,18 @@ impl BlockChainClient for Client {
 		self.miner.ready_transactions(number, timestamp)
 	}
 
+	fn local_transactions(&self) -> BTreeMap<H256, LocalTransactionStatus> {
+		self.miner.local_transactions()
+	}
+
+	fn local_transactions_propagation(&self) -> BTreeMap<H256, LocalTransactionPropagation> {
+		self.miner.local_transactions_propagation()
+	}
+
+	fn mark_local_transactions_rebroadcast(&self, hashes: &[H256]) {
+		self.miner.mark_local_transactions_rebroadcast(hashes, self.chain_info().best_block_number)
+	}
+
 	fn queue_consensus_message(&self, message: Bytes) {
 		let channel = self.io_channel.lock().clone();
 		if let Err(e) = channel.send(ClientIoMessage::NewMessage(message)) {
@@ -1633,7 +1792,7 @@ impl BlockChainClient for Client {
 	fn call_contract(&self, block_id: BlockId, address: Address, data: Bytes) -> Result<Bytes, String> {
 		let transaction = self.contract_call_tx(block_id, address, data);
 
-		self.call(&transaction, block_id, Default::default())
+		self.call(&transaction, block_id, Default::default(), &Default::default())
 			.map_err(|e| format!("{:?}", e))
 			.map(|executed| {
 				executed.output
@@ -1673,6 +1832,19 @@ impl BlockChainClient for Client {
 	fn eip86_transition(&self) -> u64 {
 		self.engine().params().eip86_transition
 	}
+
+	fn transaction_allowed(&self, transaction: &SignedTransaction) -> bool {
+		match self.tx_filter {
+			Some(ref filter) => {
+				let best = self.chain.read().best_block_header();
+				filter.transaction_allowed(&best.hash(), best.number() + 1, transaction, self).unwrap_or_else(|e| {
+					warn!(target: "client", "Error checking transaction permissions: {}", e);
+					false
+				})
+			},
+			None => true,
+		}
+	}
 }
 
 impl MiningBlockChainClient for Client {
@@ -1816,6 +1988,88 @@ impl EngineClient for Client {
 	}
 }
 
+impl DevBlockChainClient for Client {
+	fn mine_block(&self) -> Result<(), String> {
+		let best_block_number = self.chain.read().best_block_number();
+		// the engine updates sealing through the registered client right away.
+		if !self.engine.request_seal() {
+			return Err(format!("{} engine does not seal blocks on demand.", self.engine.name()));
+		}
+		match self.chain.read().best_block_number() > best_block_number {
+			true => Ok(()),
+			false => Err("Block was not sealed.".into()),
+		}
+	}
+
+	fn increase_time(&self, seconds: u64) -> Result<u64, String> {
+		let offset = self.dev_time_offset()?;
+		let offset = offset.checked_add(seconds).ok_or_else(|| "Time offset overflow.".to_owned())?;
+		self.engine.set_time_offset(offset);
+		Ok(offset)
+	}
+
+	fn snapshot(&self) -> Result<usize, String> {
+		let offset = self.dev_time_offset()?;
+		let best_block_hash = self.chain.read().best_block_hash();
+		let mut snapshots = self.dev_snapshots.lock();
+		snapshots.push((best_block_hash, offset));
+		Ok(snapshots.len())
+	}
+
+	fn revert(&self, id: usize) -> Result<bool, String> {
+		// only development chains may be rolled back.
+		self.dev_time_offset()?;
+		let snapshot = match id {
+			0 => None,
+			id => self.dev_snapshots.lock().get(id - 1).cloned(),
+		};
+		let (target, offset) = match snapshot {
+			Some(snapshot) => snapshot,
+			None => return Ok(false),
+		};
+
+		let start = precise_time_ns();
+		let retracted = {
+			// scope for self.import_lock
+			let _import_lock = self.import_lock.lock();
+			if self.state_at(BlockId::Hash(target)).is_none() {
+				return Err(format!("State of snapshot block {} is not available.", target));
+			}
+
+			let chain = self.chain.read();
+			let mut batch = DBTransaction::new();
+			let route = match chain.retract_to(&mut batch, target) {
+				Some(route) => route,
+				None => return Err(format!("Snapshot block {} is not on the canonical chain.", target)),
+			};
+			self.state_db.lock().sync_cache(&route.enacted, &route.retracted, false);
+			self.db.read().write_buffered(batch);
+			chain.commit();
+			route.retracted
+		};
+
+		self.dev_snapshots.lock().truncate(id - 1);
+		self.engine.set_time_offset(offset);
+		trace!(target: "client", "Reverted to snapshot #{} ({}), retracted {} blocks", id, target, retracted.len());
+
+		// transactions of the reverted blocks are dropped rather than queued again.
+		self.miner.chain_new_blocks(self, &[], &[], &[], &[]);
+		self.notify(|notify| {
+			notify.new_blocks(
+				vec![],
+				vec![],
+				vec![],
+				retracted.clone(),
+				vec![],
+				vec![],
+				precise_time_ns() - start,
+			);
+		});
+		self.db.read().flush().expect("DB flush failed.");
+		Ok(true)
+	}
+}
+
 impl ProvingBlockChainClient for Client {
 	fn prove_storage(&self, key1: H256, key2: H256, id: BlockId) -> Option<(Vec<Bytes>, H256)> {
 		self.state_at(id)
@@ -1860,7 +2114,7 @@ impl Drop for Client {
 
 /// Returns `LocalizedReceipt` given `LocalizedTransaction`
 /// and a vector of receipts from given block up to transaction index.
-fn transaction_receipt(engine: &Engine, mut tx: LocalizedTransaction, mut receipts: Vec<Receipt>) -> LocalizedReceipt {
+pub fn transaction_receipt(engine: &Engine, mut tx: LocalizedTransaction, mut receipts: Vec<Receipt>) -> LocalizedReceipt {
 	assert_eq!(receipts.len(), tx.transaction_index + 1, "All previous receipts are provided.");
 
 	let sender = tx.sender();
@@ -30,7 +30,7 @@ pub use self::error::Error;
 pub use self::evm_test_client::{EvmTestClient, EvmTestError};
 pub use self::test_client::{TestBlockChainClient, EachBlockWith};
 pub use self::chain_notify::ChainNotify;
-pub use self::traits::{BlockChainClient, MiningBlockChainClient, EngineClient};
+pub use self::traits::{BlockChainClient, MiningBlockChainClient, EngineClient, DevBlockChainClient};
 
 pub use self::traits::ProvingBlockChainClient;
 
@@ -38,6 +38,7 @@ pub use types::ids::*;
 pub use types::trace_filter::Filter as TraceFilter;
 pub use types::pruning_info::PruningInfo;
 pub use types::call_analytics::CallAnalytics;
+pub use types::state_override::{AccountOverride, StateOverride};
 
 pub use executive::{Executed, Executive, TransactOptions};
 pub use env_info::{LastHashes, EnvInfo};
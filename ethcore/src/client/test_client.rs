@@ -26,7 +26,7 @@ use transaction::{Transaction, LocalizedTransaction, PendingTransaction, SignedT
 use blockchain::TreeRoute;
 use client::{
 	BlockChainClient, MiningBlockChainClient, EngineClient, BlockChainInfo, BlockStatus, BlockId,
-	TransactionId, UncleId, TraceId, TraceFilter, LastHashes, CallAnalytics, BlockImportError,
+	TransactionId, UncleId, TraceId, TraceFilter, LastHashes, CallAnalytics, StateOverride, BlockImportError,
 	ProvingBlockChainClient,
 };
 use db::{NUM_COLUMNS, COL_STATE};
@@ -35,9 +35,9 @@ use filter::Filter;
 use log_entry::LocalizedLogEntry;
 use receipt::{Receipt, LocalizedReceipt};
 use blockchain::extras::BlockReceipts;
-use error::{ImportResult, Error as EthcoreError};
+use error::{ImportResult, BlockError, Error as EthcoreError};
 use evm::{Factory as EvmFactory, VMType, Schedule};
-use miner::{Miner, MinerService, TransactionImportResult};
+use miner::{Miner, MinerService, TransactionImportResult, LocalTransactionStatus, LocalTransactionPropagation};
 use spec::Spec;
 use types::basic_account::BasicAccount;
 use types::mode::Mode;
@@ -97,6 +97,12 @@ pub struct TestBlockChainClient {
 	pub traces: RwLock<Option<Vec<LocalizedTrace>>>,
 	/// Pruning history size to report.
 	pub history: RwLock<Option<u64>>,
+	/// State nodes imported by fast sync, with the address hash they were imported under.
+	pub state_nodes: RwLock<HashMap<H256, (Option<H256>, Bytes)>>,
+	/// Persisted fast sync progress.
+	pub fast_sync_progress: RwLock<Option<Bytes>>,
+	/// Fast sync pivot block imported once the state is downloaded.
+	pub fast_sync_pivot: RwLock<Option<Bytes>>,
 }
 
 /// Used for generating test client blocks.
@@ -163,6 +169,9 @@ impl TestBlockChainClient {
 			first_block: RwLock::new(None),
 			traces: RwLock::new(None),
 			history: RwLock::new(None),
+			state_nodes: RwLock::new(HashMap::new()),
+			fast_sync_progress: RwLock::new(None),
+			fast_sync_pivot: RwLock::new(None),
 		};
 
 		// insert genesis hash.
@@ -397,11 +406,19 @@ impl MiningBlockChainClient for TestBlockChainClient {
 }
 
 impl BlockChainClient for TestBlockChainClient {
-	fn call(&self, _t: &SignedTransaction, _block: BlockId, _analytics: CallAnalytics) -> Result<Executed, CallError> {
+	fn call(&self, _t: &SignedTransaction, _block: BlockId, _analytics: CallAnalytics, _state_override: &StateOverride) -> Result<Executed, CallError> {
 		self.execution_result.read().clone().unwrap()
 	}
 
-	fn estimate_gas(&self, _t: &SignedTransaction, _block: BlockId) -> Result<U256, CallError> {
+	fn call_many(&self, transactions: Vec<(SignedTransaction, CallAnalytics)>, _block: BlockId) -> Result<Vec<Executed>, CallError> {
+		let mut res = Vec::with_capacity(transactions.len());
+		for _ in transactions {
+			res.push(self.execution_result.read().clone().unwrap()?);
+		}
+		Ok(res)
+	}
+
+	fn estimate_gas(&self, _t: &SignedTransaction, _block: BlockId, _state_override: &StateOverride) -> Result<U256, CallError> {
 		Ok(21000.into())
 	}
 
@@ -409,6 +426,10 @@ impl BlockChainClient for TestBlockChainClient {
 		self.execution_result.read().clone().unwrap()
 	}
 
+	fn replay_block_transactions(&self, _block: BlockId, _analytics: CallAnalytics) -> Result<Vec<Executed>, CallError> {
+		self.execution_result.read().clone().unwrap().map(|res| vec![res])
+	}
+
 	fn block_total_difficulty(&self, _id: BlockId) -> Option<U256> {
 		Some(U256::zero())
 	}
@@ -657,6 +678,42 @@ impl BlockChainClient for TestBlockChainClient {
 		self.import_block(b)
 	}
 
+	fn import_state_nodes(&self, nodes: Vec<(Option<H256>, Bytes)>, _accounts: Vec<H256>) {
+		let mut state_nodes = self.state_nodes.write();
+		for (address_hash, node) in nodes {
+			state_nodes.insert(node.sha3(), (address_hash, node));
+		}
+	}
+
+	fn state_node(&self, address_hash: Option<H256>, hash: &H256) -> Option<Bytes> {
+		match self.state_nodes.read().get(hash) {
+			Some(&(ref a, ref node)) if *a == address_hash => Some(node.clone()),
+			_ => None,
+		}
+	}
+
+	fn verify_header_with_parent(&self, header: &BlockHeader, parent: &BlockHeader) -> Result<(), EthcoreError> {
+		// test blocks are not sealed, only check that the header extends its parent.
+		if *header.parent_hash() != parent.hash() || header.number() != parent.number() + 1 {
+			return Err(BlockError::InvalidParentHash(Mismatch { expected: parent.hash(), found: *header.parent_hash() }).into());
+		}
+		Ok(())
+	}
+
+	fn import_fast_sync_pivot(&self, block_bytes: Bytes, _receipts_bytes: Bytes, _parent_total_difficulty: U256) -> Result<H256, BlockImportError> {
+		let hash = Rlp::new(&block_bytes).at(0).as_raw().sha3();
+		*self.fast_sync_pivot.write() = Some(block_bytes);
+		Ok(hash)
+	}
+
+	fn fast_sync_progress(&self) -> Option<Bytes> {
+		self.fast_sync_progress.read().clone()
+	}
+
+	fn set_fast_sync_progress(&self, progress: Option<Bytes>) {
+		*self.fast_sync_progress.write() = progress;
+	}
+
 	fn queue_info(&self) -> QueueInfo {
 		QueueInfo {
 			verified_queue_size: self.queue_size.load(AtomicOrder::Relaxed),
@@ -722,6 +779,18 @@ impl BlockChainClient for TestBlockChainClient {
 		self.miner.ready_transactions(info.best_block_number, info.best_block_timestamp)
 	}
 
+	fn local_transactions(&self) -> BTreeMap<H256, LocalTransactionStatus> {
+		self.miner.local_transactions()
+	}
+
+	fn local_transactions_propagation(&self) -> BTreeMap<H256, LocalTransactionPropagation> {
+		self.miner.local_transactions_propagation()
+	}
+
+	fn mark_local_transactions_rebroadcast(&self, hashes: &[H256]) {
+		self.miner.mark_local_transactions_rebroadcast(hashes, self.chain_info().best_block_number)
+	}
+
 	fn signing_network_id(&self) -> Option<u64> { None }
 
 	fn mode(&self) -> Mode { Mode::Active }
@@ -764,6 +833,8 @@ impl BlockChainClient for TestBlockChainClient {
 	fn registry_address(&self, _name: String) -> Option<Address> { None }
 
 	fn eip86_transition(&self) -> u64 { u64::max_value() }
+
+	fn transaction_allowed(&self, _transaction: &SignedTransaction) -> bool { true }
 }
 
 impl ProvingBlockChainClient for TestBlockChainClient {
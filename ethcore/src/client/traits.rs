@@ -20,10 +20,11 @@ use util::hashdb::DBValue;
 use blockchain::TreeRoute;
 use verification::queue::QueueInfo as BlockQueueInfo;
 use block::{OpenBlock, SealedBlock, ClosedBlock};
-use header::{BlockNumber};
+use header::{BlockNumber, Header};
 use transaction::{LocalizedTransaction, PendingTransaction, SignedTransaction};
 use transaction_import::TransactionImportResult;
 use log_entry::LocalizedLogEntry;
+use miner::{LocalTransactionStatus, LocalTransactionPropagation};
 use filter::Filter;
 use error::{ImportResult, CallError, Error as EthcoreError};
 use receipt::LocalizedReceipt;
@@ -37,6 +38,7 @@ use types::ids::*;
 use types::basic_account::BasicAccount;
 use types::trace_filter::Filter as TraceFilter;
 use types::call_analytics::CallAnalytics;
+use types::state_override::StateOverride;
 use types::blockchain_info::BlockChainInfo;
 use types::block_status::BlockStatus;
 use types::mode::Mode;
@@ -146,7 +148,8 @@ pub trait BlockChainClient : Sync + Send {
 	/// Get all possible uncle hashes for a block.
 	fn find_uncles(&self, hash: &H256) -> Option<Vec<H256>>;
 
-	/// Get latest state node
+	/// Get latest state node. Storage trie nodes and code are stored under keys combined with
+	/// the account address hash, so only the nodes of the account trie can be found by hash.
 	fn state_data(&self, hash: &H256) -> Option<Bytes>;
 
 	/// Get raw block receipts data by block header hash.
@@ -158,6 +161,28 @@ pub trait BlockChainClient : Sync + Send {
 	/// Import a block with transaction receipts. Does no sealing and transaction validation.
 	fn import_block_with_receipts(&self, block_bytes: Bytes, receipts_bytes: Bytes) -> Result<H256, BlockImportError>;
 
+	/// Import state trie nodes downloaded during fast sync. Nodes of an account's storage trie
+	/// and its code come with the account's address hash. `accounts` lists the address hashes
+	/// of all non-empty accounts found in the imported nodes.
+	fn import_state_nodes(&self, nodes: Vec<(Option<H256>, Bytes)>, accounts: Vec<H256>);
+
+	/// Get a state trie node or code stored under an account with the given address hash,
+	/// or a node of the account trie if `None`.
+	fn state_node(&self, address_hash: Option<H256>, hash: &H256) -> Option<Bytes>;
+
+	/// Verify the seal of a header downloaded without its ancestry and check it against its parent.
+	fn verify_header_with_parent(&self, header: &Header, parent: &Header) -> Result<(), EthcoreError>;
+
+	/// Make the fast sync pivot block the best block once its state has been fully imported.
+	/// The blocks before it are downloaded later as ancient blocks.
+	fn import_fast_sync_pivot(&self, block_bytes: Bytes, receipts_bytes: Bytes, parent_total_difficulty: U256) -> Result<H256, BlockImportError>;
+
+	/// Get the persisted progress of an interrupted fast sync.
+	fn fast_sync_progress(&self) -> Option<Bytes>;
+
+	/// Persist the progress of a fast sync, or clear it.
+	fn set_fast_sync_progress(&self, progress: Option<Bytes>);
+
 	/// Get block queue information.
 	fn queue_info(&self) -> BlockQueueInfo;
 
@@ -179,15 +204,21 @@ pub trait BlockChainClient : Sync + Send {
 	/// Returns logs matching given filter.
 	fn logs(&self, filter: Filter) -> Vec<LocalizedLogEntry>;
 
-	/// Makes a non-persistent transaction call.
-	fn call(&self, t: &SignedTransaction, block: BlockId, analytics: CallAnalytics) -> Result<Executed, CallError>;
+	/// Makes a non-persistent transaction call, applying the given overrides to the state first.
+	fn call(&self, t: &SignedTransaction, block: BlockId, analytics: CallAnalytics, state_override: &StateOverride) -> Result<Executed, CallError>;
+
+	/// Makes multiple non-persistent transaction calls, each one executed on top of the state left by the previous one.
+	fn call_many(&self, transactions: Vec<(SignedTransaction, CallAnalytics)>, block: BlockId) -> Result<Vec<Executed>, CallError>;
 
-	/// Estimates how much gas will be necessary for a call.
-	fn estimate_gas(&self, t: &SignedTransaction, block: BlockId) -> Result<U256, CallError>;
+	/// Estimates how much gas will be necessary for a call, applying the given overrides to the state first.
+	fn estimate_gas(&self, t: &SignedTransaction, block: BlockId, state_override: &StateOverride) -> Result<U256, CallError>;
 
 	/// Replays a given transaction for inspection.
 	fn replay(&self, t: TransactionId, analytics: CallAnalytics) -> Result<Executed, CallError>;
 
+	/// Replays all the transactions in a given block for inspection.
+	fn replay_block_transactions(&self, block: BlockId, analytics: CallAnalytics) -> Result<Vec<Executed>, CallError>;
+
 	/// Returns traces matching given filter.
 	fn filter_traces(&self, filter: TraceFilter) -> Option<Vec<LocalizedTrace>>;
 
@@ -212,6 +243,15 @@ pub trait BlockChainClient : Sync + Send {
 	/// List all transactions that are allowed into the next block.
 	fn ready_transactions(&self) -> Vec<PendingTransaction>;
 
+	/// List local transactions with their statuses.
+	fn local_transactions(&self) -> BTreeMap<H256, LocalTransactionStatus>;
+
+	/// Propagation details (rebroadcasts, stuck flag) of pending local transactions.
+	fn local_transactions_propagation(&self) -> BTreeMap<H256, LocalTransactionPropagation>;
+
+	/// Record that local transactions due for rebroadcast were sent to all peers again.
+	fn mark_local_transactions_rebroadcast(&self, hashes: &[H256]);
+
 	/// Sorted list of transaction gas prices from at least last sample_size blocks.
 	fn gas_price_corpus(&self, sample_size: usize) -> ::stats::Corpus<U256> {
 		let mut h = self.chain_info().best_block_hash;
@@ -275,6 +315,10 @@ pub trait BlockChainClient : Sync + Send {
 
 	/// Get the EIP-86 transition block number.
 	fn eip86_transition(&self) -> u64;
+
+	/// Check whether the transaction permission contract allows the transaction in the next block.
+	/// The transaction is not allowed if the contract cannot be called.
+	fn transaction_allowed(&self, transaction: &SignedTransaction) -> bool;
 }
 
 impl IpcConfig for BlockChainClient { }
@@ -341,3 +385,23 @@ pub trait ProvingBlockChainClient: BlockChainClient {
 	/// to reproduce it.
 	fn prove_transaction(&self, transaction: SignedTransaction, id: BlockId) -> Option<(Bytes, Vec<DBValue>)>;
 }
+
+/// Client facilities for development chains: sealing blocks on demand,
+/// shifting time and rolling the chain back.
+pub trait DevBlockChainClient: Sync + Send {
+	/// Seal a new block now, even if there are no pending transactions.
+	fn mine_block(&self) -> Result<(), String>;
+
+	/// Move the timestamps of new blocks `seconds` further forward.
+	/// Returns the total offset in seconds.
+	fn increase_time(&self, seconds: u64) -> Result<u64, String>;
+
+	/// Record the current head of the chain. Returns the snapshot id.
+	/// Fails unless the engine is meant for development chains.
+	fn snapshot(&self) -> Result<usize, String>;
+
+	/// Roll the chain back to the given snapshot, dropping it and all later snapshots.
+	/// Returns `false` if there is no such snapshot.
+	/// Fails unless the engine is meant for development chains.
+	fn revert(&self, id: usize) -> Result<bool, String>;
+}
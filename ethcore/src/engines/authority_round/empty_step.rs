@@ -0,0 +1,173 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Empty step messages, broadcast by validators instead of sealing empty blocks.
+
+use util::*;
+use error::Error;
+use ethkey::{recover, public_to_address, verify_address};
+use rlp::{UntrustedRlp, RlpStream, Encodable, Decodable, DecoderError};
+
+use engines::validator_set::ValidatorSet;
+use super::step_proposer;
+
+/// Message broadcast by the proposer of a step who had nothing to seal.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
+pub struct EmptyStep {
+	pub step: usize,
+	pub parent_hash: H256,
+	pub signature: H520,
+}
+
+impl EmptyStep {
+	/// Recreate an empty step which was sealed in a child of `parent_hash`.
+	pub fn from_sealed(sealed: SealedEmptyStep, parent_hash: &H256) -> Self {
+		EmptyStep {
+			step: sealed.step,
+			parent_hash: parent_hash.clone(),
+			signature: sealed.signature,
+		}
+	}
+
+	/// Hash signed by the author of the empty step.
+	pub fn bare_hash(&self) -> H256 {
+		empty_step_rlp(self.step, &self.parent_hash).sha3()
+	}
+
+	/// Check whether the empty step is signed by the proposer of its step.
+	pub fn verify(&self, validators: &ValidatorSet) -> Result<bool, Error> {
+		let correct_proposer = step_proposer(validators, &self.parent_hash, self.step);
+		Ok(verify_address(&correct_proposer, &self.signature.into(), &self.bare_hash())?)
+	}
+
+	/// Recover the author of the empty step.
+	pub fn author(&self) -> Result<Address, Error> {
+		let public = recover(&self.signature.into(), &self.bare_hash())?;
+		Ok(public_to_address(&public))
+	}
+
+	/// The part of the empty step which is included in a block seal.
+	pub fn sealed(&self) -> SealedEmptyStep {
+		SealedEmptyStep {
+			step: self.step,
+			signature: self.signature,
+		}
+	}
+}
+
+/// (signature, (step, parent_hash))
+impl Decodable for EmptyStep {
+	fn decode(rlp: &UntrustedRlp) -> Result<Self, DecoderError> {
+		let message = rlp.at(1)?;
+		Ok(EmptyStep {
+			step: message.val_at(0)?,
+			parent_hash: message.val_at(1)?,
+			signature: rlp.val_at(0)?,
+		})
+	}
+}
+
+impl Encodable for EmptyStep {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		let message = empty_step_rlp(self.step, &self.parent_hash);
+		s.begin_list(2)
+			.append(&self.signature)
+			.append_raw(&message, 1);
+	}
+}
+
+/// Empty step as included in a block seal. The parent hash is the one of the block.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SealedEmptyStep {
+	pub step: usize,
+	pub signature: H520,
+}
+
+/// (signature, step)
+impl Decodable for SealedEmptyStep {
+	fn decode(rlp: &UntrustedRlp) -> Result<Self, DecoderError> {
+		Ok(SealedEmptyStep {
+			signature: rlp.val_at(0)?,
+			step: rlp.val_at(1)?,
+		})
+	}
+}
+
+impl Encodable for SealedEmptyStep {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(2)
+			.append(&self.signature)
+			.append(&self.step);
+	}
+}
+
+/// The message signed by the author of an empty step.
+pub fn empty_step_rlp(step: usize, parent_hash: &H256) -> Bytes {
+	let mut s = RlpStream::new_list(2);
+	s.append(&step).append(parent_hash);
+	s.out()
+}
+
+#[cfg(test)]
+mod tests {
+	use util::*;
+	use rlp::{encode, decode};
+	use account_provider::AccountProvider;
+	use engines::validator_set::SimpleList;
+	use super::{EmptyStep, SealedEmptyStep, empty_step_rlp};
+
+	fn empty_step(tap: &AccountProvider, signer: Address, step: usize, parent_hash: H256) -> EmptyStep {
+		let hash = empty_step_rlp(step, &parent_hash).sha3();
+		let signature = tap.sign(signer, Some("0".into()), hash).unwrap();
+		EmptyStep {
+			step: step,
+			parent_hash: parent_hash,
+			signature: signature.into(),
+		}
+	}
+
+	#[test]
+	fn encode_and_decode() {
+		let empty_step = EmptyStep {
+			step: 7,
+			parent_hash: H256::random(),
+			signature: H520::random(),
+		};
+
+		assert_eq!(decode::<EmptyStep>(&encode(&empty_step)), empty_step);
+
+		let sealed = empty_step.sealed();
+		assert_eq!(decode::<SealedEmptyStep>(&encode(&sealed)), sealed);
+		assert_eq!(EmptyStep::from_sealed(sealed, &empty_step.parent_hash), empty_step);
+	}
+
+	#[test]
+	fn recovers_author_and_verifies_proposer() {
+		let tap = AccountProvider::transient_provider();
+		let v0 = tap.insert_account("0".sha3().into(), "0").unwrap();
+		let v1 = tap.insert_account("1".sha3().into(), "0").unwrap();
+		let validators = SimpleList::new(vec![v0, v1]);
+
+		// v1 proposes the odd steps.
+		let valid = empty_step(&tap, v1, 3, H256::random());
+		assert_eq!(valid.author().unwrap(), v1);
+		assert!(valid.verify(&validators).unwrap());
+
+		let wrong_step = empty_step(&tap, v0, 3, H256::random());
+		assert_eq!(wrong_step.author().unwrap(), v0);
+		assert!(!wrong_step.verify(&validators).unwrap());
+	}
+}
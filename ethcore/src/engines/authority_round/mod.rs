@@ -23,18 +23,19 @@ use std::time::{UNIX_EPOCH, Duration};
 use account_provider::AccountProvider;
 use block::*;
 use builtin::Builtin;
-use client::{Client, EngineClient};
+use client::{Client, EngineClient, BlockChainClient, BlockId};
 use engines::{Call, Engine, Seal, EngineError, ConstructedVerifier};
 use error::{Error, TransactionError, BlockError};
 use ethjson;
 use header::{Header, BlockNumber};
 use spec::CommonParams;
-use state::CleanupMode;
 use transaction::UnverifiedTransaction;
 
+use super::block_reward::{BlockRewardContract, RewardKind, apply_block_rewards};
 use super::signer::EngineSigner;
 use super::validator_set::{ValidatorSet, SimpleList, new_validator_set};
 
+use self::empty_step::{EmptyStep, SealedEmptyStep, empty_step_rlp};
 use self::finality::RollingFinality;
 
 use ethkey::{verify_address, Signature};
@@ -43,8 +44,13 @@ use itertools::{self, Itertools};
 use rlp::{UntrustedRlp, encode};
 use util::*;
 
+mod empty_step;
 mod finality;
 
+/// Maximum number of empty steps included in a block seal. Once that many are pending,
+/// the proposer seals an empty block instead.
+const MAX_EMPTY_STEPS: usize = 128;
+
 /// `AuthorityRound` params.
 pub struct AuthorityRoundParams {
 	/// Gas limit divisor.
@@ -67,6 +73,12 @@ pub struct AuthorityRoundParams {
 	pub validate_step_transition: u64,
 	/// Immediate transitions.
 	pub immediate_transitions: bool,
+	/// Number of first block where validators broadcast empty steps instead of sealing empty blocks.
+	pub empty_steps_transition: u64,
+	/// Number of first block where the block reward contract replaces the fixed block reward.
+	pub block_reward_contract_transition: u64,
+	/// Block reward contract.
+	pub block_reward_contract: Option<BlockRewardContract>,
 }
 
 impl From<ethjson::spec::AuthorityRoundParams> for AuthorityRoundParams {
@@ -82,6 +94,10 @@ impl From<ethjson::spec::AuthorityRoundParams> for AuthorityRoundParams {
 			eip155_transition: p.eip155_transition.map_or(0, Into::into),
 			validate_step_transition: p.validate_step_transition.map_or(0, Into::into),
 			immediate_transitions: p.immediate_transitions.unwrap_or(false),
+			// the genesis seal has no empty steps.
+			empty_steps_transition: p.empty_steps_transition.map_or(u64::max_value(), |n| max(n.into(), 1)),
+			block_reward_contract_transition: p.block_reward_contract_transition.map_or(0, Into::into),
+			block_reward_contract: p.block_reward_contract_address.map(|address| BlockRewardContract::new(address.into())),
 		}
 	}
 }
@@ -229,19 +245,24 @@ pub struct AuthorityRound {
 	validate_step_transition: u64,
 	epoch_manager: Mutex<EpochManager>,
 	immediate_transitions: bool,
+	empty_steps_transition: u64,
+	empty_steps: Mutex<BTreeSet<EmptyStep>>,
+	block_reward_contract_transition: u64,
+	block_reward_contract: Option<BlockRewardContract>,
 }
 
 // header-chain validator.
 struct EpochVerifier {
 	step: Arc<Step>,
 	subchain_validators: SimpleList,
+	empty_steps_transition: u64,
 }
 
 impl super::EpochVerifier for EpochVerifier {
 	fn verify_light(&self, header: &Header) -> Result<(), Error> {
 		// always check the seal since it's fast.
 		// nothing heavier to do.
-		verify_external(header, &self.subchain_validators, &*self.step, |_| {})
+		verify_external(header, &self.subchain_validators, &*self.step, self.empty_steps_transition, |_| {})
 	}
 
 	fn check_finality_proof(&self, proof: &[u8]) -> Option<Vec<H256>> {
@@ -265,10 +286,12 @@ impl super::EpochVerifier for EpochVerifier {
 			// without panic.
 			//
 			// `verify_external` checks that signature is correct and author == signer.
-			if header.seal().len() != 2 { return None }
-			otry!(verify_external(header, &self.subchain_validators, &*self.step, |_| {}).ok());
+			let seal_fields = if header.number() >= self.empty_steps_transition { 3 } else { 2 };
+			if header.seal().len() != seal_fields { return None }
+			otry!(verify_external(header, &self.subchain_validators, &*self.step, self.empty_steps_transition, |_| {}).ok());
 
-			let newly_finalized = otry!(finality_checker.push_hash(header.hash(), header.author().clone()).ok());
+			let signers = otry!(header_signers(header, self.empty_steps_transition).ok());
+			let newly_finalized = otry!(finality_checker.push_hash(header.hash(), signers).ok());
 			finalized.extend(newly_finalized);
 		}
 
@@ -294,6 +317,22 @@ fn header_signature(header: &Header) -> Result<Signature, ::rlp::DecoderError> {
 	UntrustedRlp::new(&header.seal().get(1).expect("was checked with verify_block_basic; has 2 fields; qed")).as_val::<H520>().map(Into::into)
 }
 
+fn header_empty_steps(header: &Header) -> Result<Vec<EmptyStep>, ::rlp::DecoderError> {
+	let empty_steps = UntrustedRlp::new(&header.seal().get(2).expect("was checked with verify_block_basic; has 3 fields; qed")).as_list::<SealedEmptyStep>()?;
+	Ok(empty_steps.into_iter().map(|sealed| EmptyStep::from_sealed(sealed, header.parent_hash())).collect())
+}
+
+// The author of the header and the authors of the empty steps it includes.
+fn header_signers(header: &Header, empty_steps_transition: u64) -> Result<Vec<Address>, Error> {
+	let mut signers = vec![*header.author()];
+	if header.number() >= empty_steps_transition {
+		for empty_step in header_empty_steps(header)? {
+			signers.push(empty_step.author()?);
+		}
+	}
+	Ok(signers)
+}
+
 fn step_proposer(validators: &ValidatorSet, bh: &H256, step: usize) -> Address {
 	let proposer = validators.get(bh, step);
 	trace!(target: "engine", "Fetched proposer for step {}: {}", step, proposer);
@@ -304,7 +343,7 @@ fn is_step_proposer(validators: &ValidatorSet, bh: &H256, step: usize, address:
 	step_proposer(validators, bh, step) == *address
 }
 
-fn verify_external<F: Fn(Report)>(header: &Header, validators: &ValidatorSet, step: &Step, report: F)
+fn verify_external<F: Fn(Report)>(header: &Header, validators: &ValidatorSet, step: &Step, empty_steps_transition: u64, report: F)
 	-> Result<(), Error>
 {
 	let header_step = header_step(header)?;
@@ -322,10 +361,29 @@ fn verify_external<F: Fn(Report)>(header: &Header, validators: &ValidatorSet, st
 
 		if is_invalid_proposer {
 			trace!(target: "engine", "verify_block_unordered: bad proposer for step: {}", header_step);
-			Err(EngineError::NotProposer(Mismatch { expected: correct_proposer, found: header.author().clone() }))?
-		} else {
-			Ok(())
+			return Err(EngineError::NotProposer(Mismatch { expected: correct_proposer, found: header.author().clone() }).into());
 		}
+
+		if header.number() >= empty_steps_transition {
+			let empty_steps = header_empty_steps(header)?;
+			if empty_steps.len() > MAX_EMPTY_STEPS {
+				trace!(target: "engine", "verify_block_unordered: too many empty steps: {}", empty_steps.len());
+				return Err(EngineError::InsufficientProof(format!("too many empty steps: {}", empty_steps.len())).into());
+			}
+
+			let mut last_step = None;
+			for empty_step in empty_steps {
+				// empty steps are ordered and precede the step of the block.
+				let ordered = last_step.map_or(true, |last| empty_step.step > last);
+				if !ordered || empty_step.step >= header_step || !empty_step.verify(validators)? {
+					trace!(target: "engine", "verify_block_unordered: invalid empty step: {:?}", empty_step);
+					return Err(EngineError::InsufficientProof(format!("invalid empty step: {:?}", empty_step)).into());
+				}
+				last_step = Some(empty_step.step);
+			}
+		}
+
+		Ok(())
 	}
 }
 
@@ -381,6 +439,10 @@ impl AuthorityRound {
 				validate_step_transition: our_params.validate_step_transition,
 				epoch_manager: Mutex::new(EpochManager::blank()),
 				immediate_transitions: our_params.immediate_transitions,
+				empty_steps_transition: our_params.empty_steps_transition,
+				empty_steps: Mutex::new(BTreeSet::new()),
+				block_reward_contract_transition: our_params.block_reward_contract_transition,
+				block_reward_contract: our_params.block_reward_contract,
 			});
 
 		// Do not initialize timeouts for tests.
@@ -390,6 +452,59 @@ impl AuthorityRound {
 		}
 		Ok(engine)
 	}
+
+	fn client(&self) -> Option<Arc<EngineClient>> {
+		self.client.read().as_ref().and_then(|weak| weak.upgrade())
+	}
+
+	fn broadcast_message(&self, message: Bytes) {
+		if let Some(client) = self.client() {
+			client.broadcast_consensus_message(message);
+		}
+	}
+
+	fn parent_step(&self, parent_hash: &H256) -> Option<usize> {
+		self.client()
+			.and_then(|client| client.block_header(BlockId::Hash(*parent_hash)))
+			.and_then(|header| header_step(&header.decode()).ok())
+	}
+
+	/// Known empty steps of the given parent which can be included in a block sealed at `step`.
+	fn pending_empty_steps(&self, parent_step: usize, step: usize, parent_hash: &H256, validators: &ValidatorSet) -> Vec<EmptyStep> {
+		self.empty_steps.lock().iter()
+			.filter(|e| e.step > parent_step && e.step < step && e.parent_hash == *parent_hash)
+			.filter(|e| e.verify(validators).unwrap_or(false))
+			.take(MAX_EMPTY_STEPS)
+			.cloned()
+			.collect()
+	}
+
+	/// Forget the empty steps which can no longer be included in a block.
+	fn clear_empty_steps(&self, parent_step: usize) {
+		let mut empty_steps = self.empty_steps.lock();
+		*empty_steps = empty_steps.split_off(&EmptyStep {
+			step: parent_step + 1,
+			parent_hash: H256::zero(),
+			signature: H520::zero(),
+		});
+	}
+
+	fn generate_empty_step(&self, parent_hash: &H256) {
+		let step = self.step.load();
+		match self.signer.sign(empty_step_rlp(step, parent_hash).sha3()) {
+			Ok(signature) => {
+				let empty_step = EmptyStep {
+					step: step,
+					parent_hash: *parent_hash,
+					signature: signature.into(),
+				};
+				trace!(target: "engine", "generate_empty_step: Broadcasting empty step for step {}.", step);
+				self.broadcast_message(encode(&empty_step).into_vec());
+				self.empty_steps.lock().insert(empty_step);
+			},
+			Err(e) => warn!(target: "engine", "generate_empty_step: FAIL: Accounts secret key unavailable: {}", e),
+		}
+	}
 }
 
 fn unix_now() -> Duration {
@@ -428,8 +543,11 @@ impl Engine for AuthorityRound {
 
 	fn version(&self) -> SemanticVersion { SemanticVersion::new(1, 0, 0) }
 
-	/// Two fields - consensus step and the corresponding proposer signature.
-	fn seal_fields(&self) -> usize { 2 }
+	/// Two fields - consensus step and the corresponding proposer signature,
+	/// followed by the included empty steps after the empty steps transition.
+	fn seal_fields(&self, header: &Header) -> usize {
+		if header.number() >= self.empty_steps_transition { 3 } else { 2 }
+	}
 
 	fn params(&self) -> &CommonParams { &self.params }
 
@@ -512,12 +630,40 @@ impl Engine for AuthorityRound {
 		};
 
 		if is_step_proposer(validators, header.parent_hash(), step, header.author()) {
+			let empty_steps = if header.number() >= self.empty_steps_transition {
+				let parent_step = match self.parent_step(header.parent_hash()) {
+					Some(parent_step) => parent_step,
+					None => {
+						warn!(target: "engine", "Unable to generate seal: missing parent header.");
+						return Seal::None;
+					}
+				};
+				self.clear_empty_steps(parent_step);
+				let empty_steps = self.pending_empty_steps(parent_step, step, header.parent_hash(), validators);
+
+				// broadcast an empty step rather than sealing an empty block.
+				if block.transactions().is_empty() && empty_steps.len() < MAX_EMPTY_STEPS {
+					if !self.proposed.compare_and_swap(false, true, AtomicOrdering::SeqCst) {
+						self.generate_empty_step(header.parent_hash());
+					}
+					return Seal::None;
+				}
+				Some(empty_steps)
+			} else {
+				None
+			};
+
 			if let Ok(signature) = self.signer.sign(header.bare_hash()) {
 				trace!(target: "engine", "generate_seal: Issuing a block for step {}.", step);
 
 				// only issue the seal if we were the first to reach the compare_and_swap.
 				if !self.proposed.compare_and_swap(false, true, AtomicOrdering::SeqCst) {
-					return Seal::Regular(vec![encode(&step).into_vec(), encode(&(&H520::from(signature) as &[u8])).into_vec()]);
+					let mut seal = vec![encode(&step).into_vec(), encode(&(&H520::from(signature) as &[u8])).into_vec()];
+					if let Some(empty_steps) = empty_steps {
+						let sealed: Vec<_> = empty_steps.iter().map(EmptyStep::sealed).collect();
+						seal.push(::rlp::encode_list(&sealed).into_vec());
+					}
+					return Seal::Regular(seal);
 				}
 			} else {
 				warn!(target: "engine", "generate_seal: FAIL: Accounts secret key unavailable.");
@@ -529,6 +675,34 @@ impl Engine for AuthorityRound {
 		Seal::None
 	}
 
+	fn handle_message(&self, rlp: &[u8]) -> Result<(), Error> {
+		if self.empty_steps_transition == u64::max_value() {
+			return Err(EngineError::UnexpectedMessage.into());
+		}
+
+		let empty_step: EmptyStep = UntrustedRlp::new(rlp).as_val()?;
+		if self.step.is_future(empty_step.step) {
+			trace!(target: "engine", "handle_message: empty step from the future: {:?}", empty_step);
+			return Err(BlockError::InvalidSeal.into());
+		}
+		// only empty steps building on our best block can be included in the next one.
+		let best_block_hash = self.client().map(|client| client.chain_info().best_block_hash);
+		if best_block_hash != Some(empty_step.parent_hash) {
+			trace!(target: "engine", "handle_message: empty step for another parent: {:?}", empty_step);
+			return Err(BlockError::InvalidSeal.into());
+		}
+		// the validator set of the parent's epoch is checked again when sealing.
+		if !empty_step.verify(&*self.validators)? {
+			return Err(EngineError::NotAuthorized(empty_step.author()?).into());
+		}
+
+		if self.empty_steps.lock().insert(empty_step.clone()) {
+			trace!(target: "engine", "handle_message: Received a valid empty step for step {}.", empty_step.step);
+			self.broadcast_message(rlp.to_vec());
+		}
+		Ok(())
+	}
+
 	fn on_new_block(
 		&self,
 		block: &mut ExecutedBlock,
@@ -561,25 +735,22 @@ impl Engine for AuthorityRound {
 	}
 
 	/// Apply the block reward on finalisation of the block.
-	fn on_close_block(&self, block: &mut ExecutedBlock) -> Result<(), Error> {
-		let fields = block.fields_mut();
-		// Bestow block reward
-		let res = fields.state.add_balance(fields.header.author(), &self.block_reward, CleanupMode::NoEmpty)
-			.map_err(::error::Error::from)
-			.and_then(|_| fields.state.commit());
-		// Commit state so that we can actually figure out the state root.
-		if let Err(ref e) = res {
-			warn!("Encountered error on closing block: {}", e);
-		}
-		res
+	fn on_close_block(&self, block: &mut ExecutedBlock, last_hashes: Arc<::env_info::LastHashes>) -> Result<(), Error> {
+		let author = *block.fields().header.author();
+		let contract = match self.block_reward_contract {
+			Some(ref contract) if block.fields().header.number() >= self.block_reward_contract_transition => Some(contract),
+			_ => None,
+		};
+
+		apply_block_rewards(block, last_hashes, self, contract, self.block_reward, &[(author, RewardKind::Author)])
 	}
 
 	/// Check the number of seal fields.
 	fn verify_block_basic(&self, header: &Header, _block: Option<&[u8]>) -> Result<(), Error> {
-		if header.seal().len() != self.seal_fields() {
+		if header.seal().len() != self.seal_fields(header) {
 			trace!(target: "engine", "verify_block_basic: wrong number of seal fields");
 			Err(From::from(BlockError::InvalidSealArity(
-				Mismatch { expected: self.seal_fields(), found: header.seal().len() }
+				Mismatch { expected: self.seal_fields(header), found: header.seal().len() }
 			)))
 		} else if header.number() >= self.validate_score_transition && *header.difficulty() >= U256::from(U128::max_value()) {
 			Err(From::from(BlockError::DifficultyOutOfBounds(
@@ -613,7 +784,19 @@ impl Engine for AuthorityRound {
 			self.validators.report_malicious(header.author(), header.number(), header.number(), Default::default());
 			Err(EngineError::DoubleVote(header.author().clone()))?;
 		}
-		// Report skipped primaries.
+		let empty_steps: HashSet<usize> = if header.number() >= self.empty_steps_transition {
+			let empty_steps = header_empty_steps(header)?;
+			if empty_steps.iter().any(|e| e.step <= parent_step) {
+				trace!(target: "engine", "verify_block_family: empty step preceding the parent step {}.", parent_step);
+				return Err(EngineError::InsufficientProof(format!("empty step preceding the parent step {}", parent_step)).into());
+			}
+			self.clear_empty_steps(parent_step);
+			empty_steps.into_iter().map(|e| e.step).collect()
+		} else {
+			HashSet::new()
+		};
+
+		// Report skipped primaries, unless they broadcast an empty step.
 		if step > parent_step + 1 {
 			// TODO: use epochmanager to get correct validator set for reporting?
 			// or just rely on the fact that in general these will be the same
@@ -621,7 +804,7 @@ impl Engine for AuthorityRound {
 			trace!(target: "engine", "Author {} built block with step gap. current step: {}, parent step: {}",
 				header.author(), step, parent_step);
 
-			for s in parent_step + 1..step {
+			for s in (parent_step + 1..step).filter(|s| !empty_steps.contains(s)) {
 				let skipped_primary = step_proposer(&*self.validators, &parent.hash(), s);
 				self.validators.report_benign(&skipped_primary, header.number(), header.number());
 			}
@@ -673,7 +856,7 @@ impl Engine for AuthorityRound {
 
 		// verify signature against fixed list, but reports should go to the
 		// contract itself.
-		verify_external(header, validators, &*self.step, report)
+		verify_external(header, validators, &*self.step, self.empty_steps_transition, report)
 	}
 
 	fn genesis_epoch_data(&self, header: &Header, call: &Call) -> Result<Vec<u8>, String> {
@@ -725,6 +908,7 @@ impl Engine for AuthorityRound {
 			// not including chain head itself yet.
 			let mut hash = chain_head.parent_hash().clone();
 			let epoch_transition_hash = epoch_manager.epoch_transition_hash;
+			let empty_steps_transition = self.empty_steps_transition;
 
 			// walk the chain within current epoch backwards.
 			// author == ec_recover(sig) known since
@@ -733,7 +917,11 @@ impl Engine for AuthorityRound {
 				chain(hash).and_then(|header| {
 					if header.number() == 0 { return None }
 
-					let res = (hash, header.author().clone());
+					let signers = match header_signers(&header, empty_steps_transition) {
+						Ok(signers) => signers,
+						Err(_) => return None,
+					};
+					let res = (hash, signers);
 					hash = header.parent_hash().clone();
 					Some(res)
 				})
@@ -748,7 +936,11 @@ impl Engine for AuthorityRound {
 		}
 
 		{
-			if let Ok(finalized) = epoch_manager.finality_checker.push_hash(chain_head.hash(), *chain_head.author()) {
+			let signers = match header_signers(chain_head, self.empty_steps_transition) {
+				Ok(signers) => signers,
+				Err(_) => return None,
+			};
+			if let Ok(finalized) = epoch_manager.finality_checker.push_hash(chain_head.hash(), signers) {
 				let mut finalized = finalized.into_iter();
 				while let Some(hash) = finalized.next() {
 					if let Some(pending) = transition_store(hash) {
@@ -787,6 +979,7 @@ impl Engine for AuthorityRound {
 				let verifier = Box::new(EpochVerifier {
 					step: self.step.clone(),
 					subchain_validators: list,
+					empty_steps_transition: self.empty_steps_transition,
 				});
 
 				match finalize {
@@ -845,7 +1038,10 @@ mod tests {
 	use spec::Spec;
 	use engines::{Seal, Engine};
 	use engines::validator_set::TestSet;
-	use super::{AuthorityRoundParams, AuthorityRound};
+	use client::BlockChainClient;
+	use transaction::{Transaction, Action};
+	use super::{AuthorityRoundParams, AuthorityRound, header_signers};
+	use super::empty_step::{EmptyStep, empty_step_rlp};
 
 	#[test]
 	fn has_valid_metadata() {
@@ -1007,6 +1203,9 @@ mod tests {
 			validate_step_transition: 0,
 			eip155_transition: 0,
 			immediate_transitions: true,
+			empty_steps_transition: u64::max_value(),
+			block_reward_contract_transition: 0,
+			block_reward_contract: None,
 		};
 		let aura = AuthorityRound::new(Default::default(), params, Default::default()).unwrap();
 
@@ -1021,4 +1220,119 @@ mod tests {
 		assert!(aura.verify_block_family(&header, &parent_header, None).is_ok());
 		assert_eq!(last_benign.load(AtomicOrdering::SeqCst), 1);
 	}
+
+	#[test]
+	fn broadcasts_empty_step_instead_of_empty_block() {
+		let tap = Arc::new(AccountProvider::transient_provider());
+		let addr0 = tap.insert_account("0".sha3().into(), "0").unwrap();
+		let addr1 = tap.insert_account("1".sha3().into(), "1").unwrap();
+
+		let client = generate_dummy_client_with_spec_and_accounts(Spec::new_test_round_empty_steps, None);
+		let engine = client.engine();
+		engine.register_client(Arc::downgrade(&client));
+
+		let spec = Spec::new_test_round_empty_steps();
+		let genesis_header = spec.genesis_header();
+		let db1 = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let db2 = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+
+		// step 2: nothing to seal, an empty step is broadcast instead.
+		engine.set_signer(tap.clone(), addr1, "1".into());
+		let b1 = OpenBlock::new(engine, Default::default(), false, db1, &genesis_header, last_hashes.clone(), addr1, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b1 = b1.close_and_lock();
+		assert!(engine.generate_seal(b1.block()) == Seal::None);
+
+		// step 3: the next block includes the empty step.
+		engine.step();
+		engine.set_signer(tap.clone(), addr0, "0".into());
+		let mut b2 = OpenBlock::new(engine, Default::default(), false, db2, &genesis_header, last_hashes, addr0, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let tx = Transaction {
+			nonce: 0.into(),
+			gas_price: 0.into(),
+			gas: 21000.into(),
+			action: Action::Call(Address::default()),
+			value: 0.into(),
+			data: Vec::new(),
+		}.sign(&"2".sha3().into(), None);
+		b2.push_transaction(tx, None).unwrap();
+		let b2 = b2.close_and_lock();
+
+		let seal = match engine.generate_seal(b2.block()) {
+			Seal::Regular(seal) => seal,
+			_ => panic!("should generate a seal"),
+		};
+		assert_eq!(seal.len(), 3);
+		let b2 = b2.try_seal(engine, seal).unwrap();
+		let header = b2.header();
+
+		assert!(engine.verify_block_basic(header, None).is_ok());
+		assert!(engine.verify_block_family(header, &genesis_header, None).is_ok());
+		assert!(engine.verify_block_external(header, None).is_ok());
+		assert_eq!(header_signers(header, 1).unwrap(), vec![addr0, addr1]);
+	}
+
+	#[test]
+	fn accepts_empty_steps_of_step_proposers_only() {
+		let tap = AccountProvider::transient_provider();
+		let addr1 = tap.insert_account("1".sha3().into(), "1").unwrap();
+		let client = generate_dummy_client_with_spec_and_accounts(Spec::new_test_round_empty_steps, None);
+		let engine = client.engine();
+		engine.register_client(Arc::downgrade(&client));
+		let parent_hash = client.chain_info().best_block_hash;
+
+		let empty_step = |step: usize| {
+			let signature = tap.sign(addr1, Some("1".into()), empty_step_rlp(step, &parent_hash).sha3()).unwrap();
+			encode(&EmptyStep { step: step, parent_hash: parent_hash, signature: signature.into() }).into_vec()
+		};
+
+		// addr1 is the proposer of step 2 but not of step 3.
+		assert!(engine.handle_message(&empty_step(2)).is_ok());
+		assert!(engine.handle_message(&empty_step(3)).is_err());
+		// a step too far in the future is rejected as well.
+		assert!(engine.handle_message(&empty_step(6)).is_err());
+	}
+
+	#[test]
+	fn accepts_empty_steps_on_best_block_only() {
+		let tap = AccountProvider::transient_provider();
+		let addr1 = tap.insert_account("1".sha3().into(), "1").unwrap();
+		let client = generate_dummy_client_with_spec_and_accounts(Spec::new_test_round_empty_steps, None);
+		let engine = client.engine();
+
+		let empty_step = |parent_hash: H256| {
+			let signature = tap.sign(addr1, Some("1".into()), empty_step_rlp(2, &parent_hash).sha3()).unwrap();
+			encode(&EmptyStep { step: 2, parent_hash: parent_hash, signature: signature.into() }).into_vec()
+		};
+
+		// without a client there is no best block to build on.
+		assert!(engine.handle_message(&empty_step(client.chain_info().best_block_hash)).is_err());
+
+		engine.register_client(Arc::downgrade(&client));
+		assert!(engine.handle_message(&empty_step(H256::from(1))).is_err());
+		assert!(engine.handle_message(&empty_step(client.chain_info().best_block_hash)).is_ok());
+	}
+
+	#[test]
+	fn rewards_from_block_reward_contract() {
+		let spec = Spec::new_test_round_block_reward_contract();
+		let engine = &*spec.engine;
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let author = Address::from(0x1234);
+
+		let b = OpenBlock::new(engine, Default::default(), false, db, &genesis_header, last_hashes, author, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b = b.close_and_lock();
+
+		// the contract reward replaces the fixed block reward.
+		assert_eq!(b.state().balance(&author).unwrap(), U256::from(1000));
+	}
+
+	#[test]
+	fn rejects_empty_steps_before_transition() {
+		let engine = Spec::new_test_round().engine;
+		let empty_step = EmptyStep { step: 2, parent_hash: Default::default(), signature: Default::default() };
+		assert!(engine.handle_message(&encode(&empty_step)).is_err());
+	}
 }
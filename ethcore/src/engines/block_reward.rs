@@ -0,0 +1,155 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Block reward contract, determining the rewards of PoA engines on-chain.
+
+use futures::Future;
+use native_contracts::BlockReward as Provider;
+
+use util::*;
+use block::ExecutedBlock;
+use env_info::LastHashes;
+use error::Error;
+use super::{Engine, EngineError};
+use super::validator_set::SystemCall;
+
+/// The kind of a reward benefactor, as passed to the contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewardKind {
+	/// The author of the block.
+	Author = 0,
+	/// The author of an uncle of the block.
+	Uncle = 1,
+	/// A benefactor rewarded by some external protocol.
+	External = 2,
+}
+
+/// Block reward contract, called with `reward(address[] benefactors, uint16[] kind)`
+/// which returns the addresses to credit and the corresponding amounts.
+pub struct BlockRewardContract {
+	provider: Provider,
+}
+
+impl BlockRewardContract {
+	/// Create a new block reward contract client at the given address.
+	pub fn new(address: Address) -> Self {
+		BlockRewardContract {
+			provider: Provider::new(address),
+		}
+	}
+
+	/// Calls the contract with the given benefactors and returns the rewards to apply.
+	/// `caller` must execute the call as the system address.
+	pub fn reward(&self, benefactors: &[(Address, RewardKind)], caller: &mut SystemCall) -> Result<Vec<(Address, U256)>, Error> {
+		let addresses = benefactors.iter().map(|&(address, _)| address).collect();
+		let kinds = benefactors.iter().map(|&(_, kind)| kind as u16).collect();
+
+		let (addresses, rewards) = self.provider.reward(caller, addresses, kinds)
+			.wait()
+			.map_err(EngineError::FailedSystemCall)?;
+
+		if addresses.len() != rewards.len() {
+			return Err(EngineError::FailedSystemCall(
+				format!("invalid data returned by the block reward contract: {} addresses, {} rewards", addresses.len(), rewards.len())
+			).into());
+		}
+
+		Ok(addresses.into_iter().zip(rewards.into_iter()).collect())
+	}
+}
+
+/// Applies the rewards of the given benefactors to the block being closed: the ones returned
+/// by the contract if any, otherwise `block_reward` to each benefactor.
+pub fn apply_block_rewards<E: Engine + ?Sized>(
+	block: &mut ExecutedBlock,
+	last_hashes: Arc<LastHashes>,
+	engine: &E,
+	contract: Option<&BlockRewardContract>,
+	block_reward: U256,
+	benefactors: &[(Address, RewardKind)],
+) -> Result<(), Error> {
+	let rewards = match contract {
+		Some(contract) => {
+			let mut call = |to, data| {
+				super::common::execute_as_system(
+					block,
+					last_hashes.clone(),
+					engine,
+					to,
+					U256::max_value(),
+					Some(data),
+				).map_err(|e| format!("{}", e))
+			};
+
+			contract.reward(benefactors, &mut call)?
+		},
+		None => benefactors.iter().map(|&(address, _)| (address, block_reward)).collect(),
+	};
+
+	// Bestow block rewards
+	let res = super::common::bestow_rewards(block, &rewards);
+	if let Err(ref e) = res {
+		warn!("Encountered error on closing block: {}", e);
+	}
+	res
+}
+
+#[cfg(test)]
+mod tests {
+	use rustc_serialize::hex::FromHex;
+	use util::*;
+	use super::{BlockRewardContract, RewardKind};
+
+	#[test]
+	fn decodes_rewards() {
+		let contract = BlockRewardContract::new(Address::from(0x42));
+		let mut caller = |to: Address, data: Bytes| -> Result<Bytes, String> {
+			assert_eq!(to, Address::from(0x42));
+			// reward([0x01], [0])
+			assert_eq!(data.len(), 4 + 6 * 32);
+			Ok(concat!(
+				"0000000000000000000000000000000000000000000000000000000000000040",
+				"0000000000000000000000000000000000000000000000000000000000000080",
+				"0000000000000000000000000000000000000000000000000000000000000001",
+				"0000000000000000000000000000000000000000000000000000000000000001",
+				"0000000000000000000000000000000000000000000000000000000000000001",
+				"00000000000000000000000000000000000000000000000000000000000003e8",
+			).from_hex().unwrap())
+		};
+
+		let rewards = contract.reward(&[(Address::from(1), RewardKind::Author)], &mut caller).unwrap();
+		assert_eq!(rewards, vec![(Address::from(1), U256::from(1000))]);
+	}
+
+	#[test]
+	fn rejects_mismatched_rewards() {
+		let contract = BlockRewardContract::new(Address::from(0x42));
+		let mut caller = |_: Address, _: Bytes| -> Result<Bytes, String> {
+			// two addresses, one reward.
+			Ok(concat!(
+				"0000000000000000000000000000000000000000000000000000000000000040",
+				"00000000000000000000000000000000000000000000000000000000000000a0",
+				"0000000000000000000000000000000000000000000000000000000000000002",
+				"0000000000000000000000000000000000000000000000000000000000000001",
+				"0000000000000000000000000000000000000000000000000000000000000002",
+				"0000000000000000000000000000000000000000000000000000000000000001",
+				"00000000000000000000000000000000000000000000000000000000000003e8",
+			).from_hex().unwrap())
+		};
+
+		assert!(contract.reward(&[(Address::from(1), RewardKind::Author)], &mut caller).is_err());
+	}
+}
@@ -0,0 +1,896 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique (EIP-225) proof-of-authority engine.
+//!
+//! Signers seal blocks in turns by signing the header into the last 65 bytes of its extra-data.
+//! Any signer may vote to add or remove a signer through the beneficiary and nonce of the blocks
+//! it seals, and every `epoch` blocks the votes are reset and the signer list is written
+//! to the extra-data as a checkpoint.
+
+mod params;
+mod state;
+
+use std::sync::Weak;
+use std::time::{UNIX_EPOCH, Duration};
+
+use account_provider::AccountProvider;
+use block::*;
+use builtin::Builtin;
+use client::{Client, EngineClient};
+use engines::{Engine, Seal, Headers, PendingTransitionStore, ConstructedVerifier, EngineError};
+use error::{BlockError, Error};
+use ethkey::{recover, public_to_address, Signature};
+use header::{Header, BlockNumber};
+use ids::BlockId;
+use io::{IoContext, IoHandler, TimerToken, IoService};
+use lru_cache::LruCache;
+use rand::{self, Rng};
+use rlp::{UntrustedRlp, RlpStream, encode};
+use spec::CommonParams;
+use util::*;
+
+use super::signer::EngineSigner;
+
+pub use self::params::CliqueParams;
+use self::state::CliqueState;
+
+/// Number of extra-data prefix bytes reserved for signer vanity.
+const VANITY_LENGTH: usize = 32;
+/// Number of extra-data suffix bytes reserved for the signer seal.
+const SIGNATURE_LENGTH: usize = 65;
+/// Nonce of a block voting to authorize its beneficiary.
+const NONCE_AUTH_VOTE: [u8; 8] = [0xff; 8];
+/// Nonce of a block voting to deauthorize its beneficiary.
+const NONCE_DROP_VOTE: [u8; 8] = [0; 8];
+/// Difficulty of a block sealed by the in-turn signer.
+const DIFF_INTURN: u64 = 2;
+/// Difficulty of a block sealed by an out-of-turn signer.
+const DIFF_NOTURN: u64 = 1;
+/// Gas limit divisor, as used by other Clique implementations.
+const GAS_LIMIT_BOUND_DIVISOR: u64 = 1024;
+/// Number of signer states kept in memory.
+const STATE_CACHE_SIZE: usize = 128;
+/// Delay per out-of-turn slot before an out-of-turn signer seals, in milliseconds.
+const SIGNING_WIGGLE_MS: u64 = 500;
+/// Interval of checking whether a new block can be sealed, in milliseconds.
+const STEP_INTERVAL_MS: u64 = 500;
+
+/// Engine using `Clique` proof-of-authority consensus.
+pub struct Clique {
+	params: CommonParams,
+	period: u64,
+	epoch_length: u64,
+	builtins: BTreeMap<Address, Builtin>,
+	step_service: Option<IoService<()>>,
+	client: RwLock<Option<Weak<EngineClient>>>,
+	signer: EngineSigner,
+	states: Mutex<LruCache<H256, CliqueState>>,
+	proposals: RwLock<HashMap<Address, bool>>,
+}
+
+// header-chain verifier, following the signer state from the epoch transition.
+struct EpochVerifier {
+	epoch_length: u64,
+	state: Mutex<(H256, CliqueState)>,
+}
+
+impl super::EpochVerifier for EpochVerifier {
+	fn verify_light(&self, header: &Header) -> Result<(), Error> {
+		let signer = recover_signer(header)?;
+		let mut state = self.state.lock();
+
+		if *header.parent_hash() == state.0 {
+			let mut next = state.1.clone();
+			next.apply(header, &signer, header_vote(header)?, self.epoch_length)?;
+			*state = (header.hash(), next);
+			Ok(())
+		} else if state.1.is_authorized(&signer) {
+			Ok(())
+		} else {
+			Err(EngineError::NotAuthorized(signer).into())
+		}
+	}
+}
+
+/// Hash signed by the signer of the header: the sealed header without the signature.
+fn seal_hash(header: &Header) -> H256 {
+	let mut header = header.clone();
+	let len = header.extra_data().len();
+	header.extra_data_mut().truncate(len.saturating_sub(SIGNATURE_LENGTH));
+	header.rlp_sha3(::basic_types::Seal::With)
+}
+
+fn check_extra_data_length(header: &Header) -> Result<(), Error> {
+	let found = header.extra_data().len();
+	if found < VANITY_LENGTH + SIGNATURE_LENGTH {
+		return Err(BlockError::ExtraDataOutOfBounds(OutOfBounds { min: Some(VANITY_LENGTH + SIGNATURE_LENGTH), max: None, found: found }).into());
+	}
+	Ok(())
+}
+
+/// Recover the signer of the header from the signature in its extra-data.
+fn recover_signer(header: &Header) -> Result<Address, Error> {
+	check_extra_data_length(header)?;
+	let extra_data = header.extra_data();
+	let sig = H520::from_slice(&extra_data[extra_data.len() - SIGNATURE_LENGTH..]);
+	Ok(public_to_address(&recover(&sig.into(), &seal_hash(header))?))
+}
+
+/// Signers listed in the extra-data of the header, empty for non-checkpoint blocks.
+fn header_signers(header: &Header) -> Result<Vec<Address>, Error> {
+	check_extra_data_length(header)?;
+	let extra_data = header.extra_data();
+	let signers = &extra_data[VANITY_LENGTH..extra_data.len() - SIGNATURE_LENGTH];
+	if signers.len() % 20 != 0 {
+		return Err(BlockError::InvalidSeal.into());
+	}
+	Ok(signers.chunks(20).map(Address::from_slice).collect())
+}
+
+/// Vote carried by the header nonce, `true` to authorize the beneficiary.
+fn header_vote(header: &Header) -> Result<bool, Error> {
+	let nonce: H64 = UntrustedRlp::new(&header.seal()[1]).as_val()?;
+	if nonce == H64::from(NONCE_AUTH_VOTE) {
+		Ok(true)
+	} else if nonce == H64::from(NONCE_DROP_VOTE) {
+		Ok(false)
+	} else {
+		Err(BlockError::InvalidSeal.into())
+	}
+}
+
+fn unix_now() -> Duration {
+	UNIX_EPOCH.elapsed().expect("Valid time has to be set in your system.")
+}
+
+impl Clique {
+	/// Create a new instance of Clique engine.
+	pub fn new(params: CommonParams, our_params: CliqueParams, builtins: BTreeMap<Address, Builtin>) -> Result<Arc<Self>, Error> {
+		// Blocks are only sealed on demand when there is no period.
+		let step_service = match our_params.period {
+			0 => None,
+			_ => Some(IoService::<()>::start()?),
+		};
+		let engine = Arc::new(
+			Clique {
+				params: params,
+				period: our_params.period,
+				epoch_length: our_params.epoch,
+				builtins: builtins,
+				step_service: step_service,
+				client: RwLock::new(None),
+				signer: Default::default(),
+				states: Mutex::new(LruCache::new(STATE_CACHE_SIZE)),
+				proposals: RwLock::new(HashMap::new()),
+			});
+
+		if let Some(ref step_service) = engine.step_service {
+			let handler = StepHandler { engine: Arc::downgrade(&engine) };
+			step_service.register_handler(Arc::new(handler))?;
+		}
+		Ok(engine)
+	}
+
+	fn is_checkpoint(&self, number: BlockNumber) -> bool {
+		number % self.epoch_length == 0
+	}
+
+	/// Signer state after the block with the given hash, using the registered client
+	/// and optionally an already known header to find its ancestry.
+	fn state(&self, hash: H256, known: Option<&Header>) -> Result<CliqueState, Error> {
+		let client = self.client.read().as_ref().and_then(Weak::upgrade);
+		let headers = |h: H256| match known {
+			Some(header) if header.hash() == h => Some(header.clone()),
+			_ => client.as_ref()
+				.and_then(|c| c.block_header(BlockId::Hash(h)))
+				.map(|header| header.decode()),
+		};
+		self.state_with(hash, &headers)
+	}
+
+	/// Signer state after the block with the given hash.
+	///
+	/// Starts from the nearest cached state, the genesis or the last epoch transition and
+	/// applies the blocks in between.
+	fn state_with(&self, hash: H256, headers: &Headers) -> Result<CliqueState, Error> {
+		let transition = self.client.read().as_ref()
+			.and_then(Weak::upgrade)
+			.and_then(|c| c.epoch_transition_for(hash));
+
+		let mut pending = Vec::new();
+		let mut current = hash;
+		let mut state = loop {
+			if let Some(state) = self.states.lock().get_mut(&current) {
+				break state.clone();
+			}
+			if let Some(ref transition) = transition {
+				if transition.block_hash == current {
+					break CliqueState::from_proof(&transition.proof)?;
+				}
+			}
+
+			let header = match headers(current) {
+				Some(header) => header,
+				None => match transition {
+					// ancestry might be missing after a warp sync.
+					Some(ref transition) => {
+						warn!(target: "engine", "Missing ancestry of block {}, assuming signers of epoch transition at block {}.", current, transition.block_number);
+						break CliqueState::from_proof(&transition.proof)?;
+					},
+					_ => return Err(BlockError::UnknownParent(current).into()),
+				},
+			};
+
+			if header.number() == 0 {
+				let state = CliqueState::new(header_signers(&header)?);
+				self.states.lock().insert(current, state.clone());
+				break state;
+			}
+
+			current = *header.parent_hash();
+			pending.push(header);
+		};
+
+		for header in pending.into_iter().rev() {
+			let signer = recover_signer(&header)?;
+			state.apply(&header, &signer, header_vote(&header)?, self.epoch_length)?;
+			self.states.lock().insert(header.hash(), state.clone());
+		}
+
+		Ok(state)
+	}
+
+	/// Difficulty of a block with the given number when sealed by `signer`.
+	fn difficulty(state: &CliqueState, number: BlockNumber, signer: &Address) -> U256 {
+		match state.is_inturn(number, signer) {
+			true => DIFF_INTURN.into(),
+			false => DIFF_NOTURN.into(),
+		}
+	}
+
+	/// Random but deterministic delay before an out-of-turn `signer` seals block `number`,
+	/// so that not all of them seal at the same time.
+	fn wiggle(state: &CliqueState, number: BlockNumber, signer: &Address) -> Duration {
+		let slots = state.signers().len() as u64 / 2 + 1;
+		let mut s = RlpStream::new_list(2);
+		s.append(signer).append(&number);
+		Duration::from_millis(s.out().sha3().low_u64() % (slots * SIGNING_WIGGLE_MS))
+	}
+}
+
+struct StepHandler {
+	engine: Weak<Clique>,
+}
+
+const ENGINE_TIMEOUT_TOKEN: TimerToken = 23;
+
+impl IoHandler<()> for StepHandler {
+	fn initialize(&self, io: &IoContext<()>) {
+		io.register_timer(ENGINE_TIMEOUT_TOKEN, STEP_INTERVAL_MS)
+			.unwrap_or_else(|e| warn!(target: "engine", "Failed to start Clique step timer: {}.", e))
+	}
+
+	fn timeout(&self, _io: &IoContext<()>, timer: TimerToken) {
+		if timer == ENGINE_TIMEOUT_TOKEN {
+			if let Some(engine) = self.engine.upgrade() {
+				engine.step();
+			}
+		}
+	}
+}
+
+impl Engine for Clique {
+	fn name(&self) -> &str { "Clique" }
+
+	fn version(&self) -> SemanticVersion { SemanticVersion::new(1, 0, 0) }
+
+	/// Two fields - the mix hash, always zero, and the nonce carrying the vote.
+	fn seal_fields(&self, _header: &Header) -> usize { 2 }
+
+	fn params(&self) -> &CommonParams { &self.params }
+
+	fn builtins(&self) -> &BTreeMap<Address, Builtin> { &self.builtins }
+
+	fn maximum_uncle_count(&self) -> usize { 0 }
+
+	fn step(&self) {
+		if self.signer.address() == Address::default() {
+			return;
+		}
+		if let Some(ref weak) = *self.client.read() {
+			if let Some(c) = weak.upgrade() {
+				c.update_sealing();
+			}
+		}
+	}
+
+	/// Additional engine-specific information for the user/developer concerning `header`.
+	fn extra_info(&self, header: &Header) -> BTreeMap<String, String> {
+		map![
+			"signer".into() => recover_signer(header).map(|signer| signer.hex()).unwrap_or("".into())
+		]
+	}
+
+	fn populate_from_parent(&self, header: &mut Header, parent: &Header, gas_floor_target: U256, _gas_ceil_target: U256) {
+		header.set_gas_limit({
+			let gas_limit = parent.gas_limit().clone();
+			let bound_divisor = U256::from(GAS_LIMIT_BOUND_DIVISOR);
+			if gas_limit < gas_floor_target {
+				min(gas_floor_target, gas_limit + gas_limit / bound_divisor - 1.into())
+			} else {
+				max(gas_floor_target, gas_limit - gas_limit / bound_divisor + 1.into())
+			}
+		});
+		let timestamp = max(header.timestamp(), parent.timestamp() + self.period);
+		header.set_timestamp(timestamp);
+
+		let state = match self.state(parent.hash(), Some(parent)) {
+			Ok(state) => state,
+			Err(e) => {
+				warn!(target: "engine", "Unable to determine signers at block {}: {}", parent.number(), e);
+				return;
+			}
+		};
+		let number = header.number();
+		let checkpoint = self.is_checkpoint(number);
+		header.set_difficulty(Clique::difficulty(&state, number, &self.signer.address()));
+
+		// cast one of our proposals which would still change the signer list.
+		let vote = match checkpoint {
+			true => None,
+			false => {
+				let proposals = self.proposals.read();
+				let valid: Vec<_> = proposals.iter()
+					.filter(|&(address, authorize)| state.is_valid_vote(address, *authorize))
+					.map(|(address, authorize)| (*address, *authorize))
+					.collect();
+				rand::thread_rng().choose(&valid).cloned()
+			},
+		};
+		let (beneficiary, nonce) = match vote {
+			Some((address, true)) => (address, NONCE_AUTH_VOTE),
+			Some((address, false)) => (address, NONCE_DROP_VOTE),
+			None => (Address::default(), NONCE_DROP_VOTE),
+		};
+		header.set_author(beneficiary);
+		header.set_seal(vec![encode(&H256::default()).to_vec(), encode(&H64::from(nonce)).to_vec()]);
+
+		// vanity, signers at checkpoints and room for the signature.
+		let mut extra_data = header.extra_data().clone();
+		extra_data.resize(VANITY_LENGTH, 0);
+		if checkpoint {
+			for signer in state.signers() {
+				extra_data.extend_from_slice(signer);
+			}
+		}
+		extra_data.extend_from_slice(&[0; SIGNATURE_LENGTH]);
+		header.set_extra_data(extra_data);
+	}
+
+	fn seals_internally(&self) -> Option<bool> {
+		Some(self.signer.address() != Address::default())
+	}
+
+	/// Attempt to seal the block internally.
+	///
+	/// The seal is taken as prepared in `populate_from_parent`, the header is signed in `on_seal_block`.
+	fn generate_seal(&self, block: &ExecutedBlock) -> Seal {
+		let header = block.header();
+		let number = header.number();
+		let signer = self.signer.address();
+
+		let state = match self.state(*header.parent_hash(), None) {
+			Ok(state) => state,
+			Err(e) => {
+				warn!(target: "engine", "generate_seal: Unable to determine signers at block {}: {}", number - 1, e);
+				return Seal::None;
+			}
+		};
+		if !state.is_authorized(&signer) {
+			trace!(target: "engine", "generate_seal: Not an authorized signer at block {}.", number);
+			return Seal::None;
+		}
+		if state.is_recent(number, &signer) {
+			trace!(target: "engine", "generate_seal: Signed recently, waiting for other signers at block {}.", number);
+			return Seal::None;
+		}
+
+		// make sure the block was prepared for this signer and signer list.
+		let checkpoint_signers: Vec<_> = match self.is_checkpoint(number) {
+			true => state.signers().iter().cloned().collect(),
+			false => Vec::new(),
+		};
+		if *header.difficulty() != Clique::difficulty(&state, number, &signer)
+			|| header_signers(header).ok().map_or(true, |signers| signers != checkpoint_signers)
+		{
+			trace!(target: "engine", "generate_seal: Block {} was not prepared for signer {}.", number, signer);
+			return Seal::None;
+		}
+
+		let mut ready_at = Duration::from_secs(header.timestamp());
+		if !state.is_inturn(number, &signer) {
+			ready_at = ready_at + Clique::wiggle(&state, number, &signer);
+		}
+		if unix_now() < ready_at {
+			return Seal::None;
+		}
+
+		Seal::Regular(header.seal().to_vec())
+	}
+
+	fn on_seal_block(&self, block: &mut ExecutedBlock) -> Result<(), Error> {
+		let header = block.fields_mut().header;
+		check_extra_data_length(header)?;
+		let signature = self.signer.sign(seal_hash(header))?;
+		{
+			let extra_data = header.extra_data_mut();
+			let len = extra_data.len();
+			extra_data[len - SIGNATURE_LENGTH..].copy_from_slice(&*H520::from(signature));
+		}
+
+		// sealed blocks are imported without family verification, so remember the state here.
+		let mut state = self.state(*header.parent_hash(), None)?;
+		state.apply(header, &self.signer.address(), header_vote(header)?, self.epoch_length)?;
+		self.states.lock().insert(header.hash(), state);
+		Ok(())
+	}
+
+	fn verify_block_basic(&self, header: &Header, _block: Option<&[u8]>) -> Result<(), Error> {
+		// Check if the seal has the expected arity and values.
+		let seal_fields = self.seal_fields(header);
+		if header.seal().len() != seal_fields {
+			return Err(From::from(BlockError::InvalidSealArity(
+				Mismatch { expected: seal_fields, found: header.seal().len() }
+			)));
+		}
+		let mix_hash: H256 = UntrustedRlp::new(&header.seal()[0]).as_val()?;
+		if !mix_hash.is_zero() {
+			return Err(BlockError::MismatchedH256SealElement(Mismatch { expected: H256::default(), found: mix_hash }).into());
+		}
+
+		// Checkpoints carry the signer list and no vote.
+		let authorize = header_vote(header)?;
+		let signers = header_signers(header)?;
+		if self.is_checkpoint(header.number()) {
+			if authorize || !header.author().is_zero() {
+				return Err(BlockError::InvalidSeal.into());
+			}
+		} else if !signers.is_empty() {
+			let expected = VANITY_LENGTH + SIGNATURE_LENGTH;
+			return Err(BlockError::ExtraDataOutOfBounds(OutOfBounds { min: Some(expected), max: Some(expected), found: header.extra_data().len() }).into());
+		}
+
+		let (min_difficulty, max_difficulty) = (U256::from(DIFF_NOTURN), U256::from(DIFF_INTURN));
+		if *header.difficulty() != min_difficulty && *header.difficulty() != max_difficulty {
+			return Err(BlockError::DifficultyOutOfBounds(OutOfBounds { min: Some(min_difficulty), max: Some(max_difficulty), found: *header.difficulty() }).into());
+		}
+		if *header.uncles_hash() != SHA3_EMPTY_LIST_RLP {
+			return Err(BlockError::InvalidUnclesHash(Mismatch { expected: SHA3_EMPTY_LIST_RLP, found: *header.uncles_hash() }).into());
+		}
+
+		Ok(())
+	}
+
+	fn verify_block_unordered(&self, header: &Header, _block: Option<&[u8]>) -> Result<(), Error> {
+		recover_signer(header).map(|_| ())
+	}
+
+	fn verify_block_family(&self, header: &Header, parent: &Header, _block: Option<&[u8]>) -> Result<(), Error> {
+		let min_timestamp = parent.timestamp() + self.period;
+		if header.timestamp() < min_timestamp {
+			return Err(BlockError::InvalidTimestamp(OutOfBounds { min: Some(min_timestamp), max: None, found: header.timestamp() }).into());
+		}
+
+		// a light client does not register itself, so after a restart or a sync from a CHT
+		// the ancestry of the parent is unknown. The signature itself was checked already.
+		let mut state = match self.state(parent.hash(), Some(parent)) {
+			Ok(state) => state,
+			Err(Error::Block(BlockError::UnknownParent(hash))) => {
+				debug!(target: "engine", "Unable to determine signers at block {}, missing block {}. Skipping signer checks.", parent.number(), hash);
+				return Ok(());
+			},
+			Err(e) => return Err(e),
+		};
+
+		let number = header.number();
+		if self.is_checkpoint(number) {
+			let expected: Vec<_> = state.signers().iter().cloned().collect();
+			if header_signers(header)? != expected {
+				return Err(BlockError::InvalidSeal.into());
+			}
+		}
+
+		let signer = recover_signer(header)?;
+		let expected_difficulty = Clique::difficulty(&state, number, &signer);
+		if *header.difficulty() != expected_difficulty {
+			return Err(BlockError::InvalidDifficulty(Mismatch { expected: expected_difficulty, found: *header.difficulty() }).into());
+		}
+
+		state.apply(header, &signer, header_vote(header)?, self.epoch_length)?;
+		self.states.lock().insert(header.hash(), state);
+		Ok(())
+	}
+
+	fn genesis_epoch_data(&self, header: &Header, _call: &super::Call) -> Result<Vec<u8>, String> {
+		header_signers(header)
+			.map(|signers| CliqueState::new(signers).proof())
+			.map_err(|e| format!("Invalid genesis signers: {}", e))
+	}
+
+	fn is_epoch_end(
+		&self,
+		chain_head: &Header,
+		chain: &Headers,
+		_transition_store: &PendingTransitionStore,
+	) -> Option<Vec<u8>> {
+		if !self.is_checkpoint(chain_head.number()) {
+			return None;
+		}
+
+		let hash = chain_head.hash();
+		let headers = |h: H256| if h == hash { Some(chain_head.clone()) } else { chain(h) };
+		match self.state_with(hash, &headers) {
+			Ok(state) => Some(state.proof()),
+			Err(e) => {
+				warn!(target: "engine", "Unable to determine signers at checkpoint {}: {}", chain_head.number(), e);
+				None
+			}
+		}
+	}
+
+	fn epoch_verifier<'a>(&self, header: &Header, proof: &'a [u8]) -> ConstructedVerifier<'a> {
+		let state = match CliqueState::from_proof(proof) {
+			Ok(state) => state,
+			Err(e) => return ConstructedVerifier::Err(e.into()),
+		};
+
+		// the proof has to match the signers checkpointed in the header.
+		let expected: Vec<_> = state.signers().iter().cloned().collect();
+		match header_signers(header) {
+			Ok(ref signers) if *signers == expected => {},
+			Ok(_) => return ConstructedVerifier::Err(EngineError::InsufficientProof("Checkpoint signers do not match the epoch proof.".into()).into()),
+			Err(e) => return ConstructedVerifier::Err(e),
+		}
+
+		ConstructedVerifier::Trusted(Box::new(EpochVerifier {
+			epoch_length: self.epoch_length,
+			state: Mutex::new((header.hash(), state)),
+		}))
+	}
+
+	fn register_client(&self, client: Weak<Client>) {
+		*self.client.write() = Some(client.clone());
+	}
+
+	fn set_signer(&self, ap: Arc<AccountProvider>, address: Address, password: String) {
+		self.signer.set(ap, address, password);
+	}
+
+	fn sign(&self, hash: H256) -> Result<Signature, Error> {
+		self.signer.sign(hash).map_err(Into::into)
+	}
+
+	fn propose_signer(&self, address: Address, authorize: bool) -> Result<(), EngineError> {
+		self.proposals.write().insert(address, authorize);
+		Ok(())
+	}
+
+	fn discard_signer_proposal(&self, address: &Address) -> Result<(), EngineError> {
+		self.proposals.write().remove(address);
+		Ok(())
+	}
+
+	fn snapshot_components(&self) -> Option<Box<::snapshot::SnapshotComponents>> {
+		Some(Box::new(::snapshot::PoaSnapshot))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use util::*;
+	use block::*;
+	use error::{BlockError, Error};
+	use tests::helpers::*;
+	use account_provider::AccountProvider;
+	use ethkey::{Secret, KeyPair, sign};
+	use header::Header;
+	use rlp::encode;
+	use spec::Spec;
+	use engines::{Engine, Seal, EngineError, ConstructedVerifier};
+	use engines::epoch::PendingTransition;
+	use super::{CliqueState, seal_hash, recover_signer, NONCE_AUTH_VOTE, NONCE_DROP_VOTE, SIGNATURE_LENGTH};
+
+	fn secret(s: &str) -> Secret {
+		s.sha3().into()
+	}
+
+	fn address(s: &str) -> Address {
+		KeyPair::from_secret(secret(s)).unwrap().address()
+	}
+
+	fn header(parent: &Header, beneficiary: Address, authorize: bool, difficulty: u64, signers: &[Address]) -> Header {
+		let mut header = Header::default();
+		header.set_parent_hash(parent.hash());
+		header.set_number(parent.number() + 1);
+		header.set_timestamp(parent.timestamp() + 1);
+		header.set_difficulty(difficulty.into());
+		header.set_author(beneficiary);
+		let nonce = if authorize { NONCE_AUTH_VOTE } else { NONCE_DROP_VOTE };
+		header.set_seal(vec![encode(&H256::default()).into_vec(), encode(&H64::from(nonce)).into_vec()]);
+		let mut extra_data = vec![0; 32];
+		for signer in signers {
+			extra_data.extend_from_slice(signer);
+		}
+		extra_data.extend_from_slice(&[0; SIGNATURE_LENGTH]);
+		header.set_extra_data(extra_data);
+		header
+	}
+
+	fn signed(mut header: Header, signer: &str) -> Header {
+		let signature = sign(&secret(signer), &seal_hash(&header)).unwrap();
+		let len = header.extra_data().len();
+		header.extra_data_mut()[len - SIGNATURE_LENGTH..].copy_from_slice(&*H520::from(signature));
+		header
+	}
+
+	#[test]
+	fn has_valid_metadata() {
+		let engine = Spec::new_test_clique().engine;
+		assert!(!engine.name().is_empty());
+		assert!(engine.version().major >= 1);
+	}
+
+	#[test]
+	fn can_do_seal_verification_fail() {
+		let engine = Spec::new_test_clique().engine;
+		let header: Header = Header::default();
+
+		match engine.verify_block_basic(&header, None) {
+			Err(Error::Block(BlockError::InvalidSealArity(_))) => {},
+			other => panic!("should be block seal-arity mismatch error (got {:?})", other),
+		}
+	}
+
+	#[test]
+	fn verifies_signer_and_difficulty() {
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+		let genesis = spec.genesis_header();
+
+		// "1" is in turn for block 1.
+		let block = signed(header(&genesis, Address::default(), false, 2, &[]), "1");
+		assert_eq!(recover_signer(&block).unwrap(), address("1"));
+		assert!(engine.verify_block_basic(&block, None).is_ok());
+		assert!(engine.verify_block_unordered(&block, None).is_ok());
+		assert!(engine.verify_block_family(&block, &genesis, None).is_ok());
+
+		let out_of_turn = signed(header(&genesis, Address::default(), false, 1, &[]), "0");
+		assert!(engine.verify_block_family(&out_of_turn, &genesis, None).is_ok());
+
+		match engine.verify_block_family(&signed(header(&genesis, Address::default(), false, 2, &[]), "0"), &genesis, None) {
+			Err(Error::Block(BlockError::InvalidDifficulty(_))) => {},
+			other => panic!("should be invalid difficulty error (got {:?})", other),
+		}
+		match engine.verify_block_family(&signed(header(&genesis, Address::default(), false, 1, &[]), "2"), &genesis, None) {
+			Err(Error::Engine(EngineError::NotAuthorized(_))) => {},
+			other => panic!("should be not authorized error (got {:?})", other),
+		}
+
+		// "1" may not seal again before "0" did.
+		let next = signed(header(&block, Address::default(), false, 1, &[]), "1");
+		match engine.verify_block_family(&next, &block, None) {
+			Err(Error::Engine(EngineError::DoubleVote(_))) => {},
+			other => panic!("should be recently signed error (got {:?})", other),
+		}
+		let next = signed(header(&block, Address::default(), false, 2, &[]), "0");
+		assert!(engine.verify_block_family(&next, &block, None).is_ok());
+	}
+
+	#[test]
+	fn verifies_checkpoints() {
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+		let mut parent = spec.genesis_header();
+		parent.set_number(3);
+		let signers = [address("0"), address("1")];
+
+		let checkpoint = signed(header(&parent, Address::default(), false, 1, &signers), "0");
+		assert!(engine.verify_block_basic(&checkpoint, None).is_ok());
+
+		let voting = signed(header(&parent, address("2"), true, 1, &signers), "0");
+		assert!(engine.verify_block_basic(&voting, None).is_err());
+
+		let mut parent = spec.genesis_header();
+		parent.set_number(4);
+		let listing = signed(header(&parent, Address::default(), false, 1, &signers), "0");
+		match engine.verify_block_basic(&listing, None) {
+			Err(Error::Block(BlockError::ExtraDataOutOfBounds(_))) => {},
+			other => panic!("should be extra data error (got {:?})", other),
+		}
+	}
+
+	#[test]
+	fn rejects_blocks_with_unknown_signers() {
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+
+		// neither the parent nor its ancestry are known without a client.
+		let mut parent = spec.genesis_header();
+		parent.set_number(5);
+		let block = signed(header(&parent, Address::default(), false, 1, &[]), "0");
+		match engine.verify_block_family(&block, &parent, None) {
+			Err(Error::Block(BlockError::UnknownParent(_))) => {},
+			other => panic!("should be unknown parent error (got {:?})", other),
+		}
+	}
+
+	#[test]
+	fn votes_change_signers() {
+		let genesis = Header::default();
+		let mut state = CliqueState::new(vec![address("0"), address("1")]);
+
+		// a second vote of the same signer does not count.
+		let block1 = header(&genesis, address("2"), true, 1, &[]);
+		state.apply(&block1, &address("1"), true, 30000).unwrap();
+		let mut block3 = header(&block1, address("2"), true, 1, &[]);
+		block3.set_number(3);
+		let mut repeated = state.clone();
+		repeated.apply(&block3, &address("1"), true, 30000).unwrap();
+		assert!(!repeated.is_authorized(&address("2")));
+		let block2 = header(&block1, address("2"), true, 1, &[]);
+		state.apply(&block2, &address("0"), true, 30000).unwrap();
+		assert!(state.is_authorized(&address("2")));
+		assert_eq!(state.signers().len(), 3);
+
+		let block3 = header(&block2, address("2"), false, 1, &[]);
+		state.apply(&block3, &address("1"), false, 30000).unwrap();
+		assert!(state.is_authorized(&address("2")));
+		let block4 = header(&block3, address("2"), false, 1, &[]);
+		state.apply(&block4, &address("0"), false, 30000).unwrap();
+		assert!(!state.is_authorized(&address("2")));
+		assert_eq!(state.signers().len(), 2);
+
+		// invalid votes are ignored.
+		let block5 = header(&block4, address("0"), true, 1, &[]);
+		state.apply(&block5, &address("1"), true, 30000).unwrap();
+		assert_eq!(state.signers().len(), 2);
+	}
+
+	#[test]
+	fn tallies_proposed_votes() {
+		let tap = Arc::new(AccountProvider::transient_provider());
+		tap.insert_account(secret("0"), "0").unwrap();
+		tap.insert_account(secret("1"), "1").unwrap();
+
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+		let genesis = spec.genesis_header();
+		let gas_range = (3141562.into(), 31415620.into());
+		let prepare = |parent: &Header, signer: &str| {
+			engine.set_signer(tap.clone(), address(signer), signer.into());
+			let mut header = Header::default();
+			header.set_parent_hash(parent.hash());
+			header.set_number(parent.number() + 1);
+			engine.populate_from_parent(&mut header, parent, gas_range.0, gas_range.1);
+			header
+		};
+
+		engine.propose_signer(address("2"), true).unwrap();
+		let block1 = prepare(&genesis, "1");
+		assert_eq!(*block1.author(), address("2"));
+		assert_eq!(block1.seal()[1], encode(&H64::from(NONCE_AUTH_VOTE)).into_vec());
+		let block1 = signed(block1, "1");
+		engine.verify_block_family(&block1, &genesis, None).unwrap();
+
+		// the vote of a single signer out of two does not authorize, so it is cast again.
+		let block2 = signed(prepare(&block1, "0"), "0");
+		assert_eq!(*block2.author(), address("2"));
+		engine.verify_block_family(&block2, &block1, None).unwrap();
+
+		// the proposal does not change the signers anymore.
+		let block3 = prepare(&block2, "1");
+		assert_eq!(*block3.author(), Address::default());
+
+		engine.propose_signer(address("1"), false).unwrap();
+		engine.discard_signer_proposal(&address("1")).unwrap();
+		let block3 = prepare(&block2, "1");
+		assert_eq!(*block3.author(), Address::default());
+	}
+
+	#[test]
+	fn stores_signers_at_checkpoints() {
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+		let genesis = spec.genesis_header();
+
+		// "2" is voted in by both signers before the checkpoint at block 4.
+		let block1 = signed(header(&genesis, address("2"), true, 2, &[]), "1");
+		let block2 = signed(header(&block1, address("2"), true, 2, &[]), "0");
+		let block3 = signed(header(&block2, Address::default(), false, 1, &[]), "1");
+		let signers = [address("0"), address("1"), address("2")];
+		let block4 = signed(header(&block3, Address::default(), false, 1, &signers), "2");
+
+		let headers: HashMap<H256, Header> = vec![genesis.clone(), block1, block2, block3.clone(), block4.clone()].into_iter()
+			.map(|h| (h.hash(), h))
+			.collect();
+		let chain = |hash: H256| headers.get(&hash).cloned();
+		let no_transitions = |_: H256| -> Option<PendingTransition> { None };
+
+		assert!(engine.is_epoch_end(&block3, &chain, &no_transitions).is_none());
+		let proof = engine.is_epoch_end(&block4, &chain, &no_transitions).expect("block 4 is a checkpoint");
+		assert_eq!(CliqueState::from_proof(&proof).unwrap().signers().len(), 3);
+		assert!(CliqueState::from_proof(&proof).unwrap().is_authorized(&address("2")));
+	}
+
+	#[test]
+	fn epoch_verifier_checks_proof() {
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+		let genesis = spec.genesis_header();
+
+		let proof = CliqueState::new(vec![address("0"), address("1")]).proof();
+		assert_eq!(CliqueState::from_proof(&proof).unwrap(), CliqueState::new(vec![address("1"), address("0")]));
+		let verifier = match engine.epoch_verifier(&genesis, &proof) {
+			ConstructedVerifier::Trusted(verifier) => verifier,
+			_ => panic!("should be trusted verifier"),
+		};
+		assert!(verifier.verify_light(&signed(header(&genesis, Address::default(), false, 2, &[]), "1")).is_ok());
+		assert!(verifier.verify_light(&signed(header(&genesis, Address::default(), false, 2, &[]), "2")).is_err());
+
+		let proof = CliqueState::new(vec![address("0")]).proof();
+		match engine.epoch_verifier(&genesis, &proof) {
+			ConstructedVerifier::Err(_) => {},
+			_ => panic!("should reject proof not matching the checkpoint"),
+		}
+	}
+
+	#[test]
+	fn can_generate_seal() {
+		let tap = AccountProvider::transient_provider();
+		let addr = tap.insert_account(secret("1"), "1").unwrap();
+
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+		engine.set_signer(Arc::new(tap), addr, "1".into());
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let b = OpenBlock::new(engine, Default::default(), false, db, &genesis_header, last_hashes, addr, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b = b.close_and_lock();
+		if let Seal::Regular(seal) = engine.generate_seal(b.block()) {
+			let sealed = b.seal_internally(engine, seal).unwrap();
+			let header = sealed.header();
+			assert_eq!(recover_signer(header).unwrap(), addr);
+			assert_eq!(*header.difficulty(), 2.into());
+			assert!(engine.verify_block_basic(header, None).is_ok());
+			assert!(engine.verify_block_family(header, &genesis_header, None).is_ok());
+		} else {
+			panic!("in-turn signer should seal");
+		}
+	}
+
+	#[test]
+	fn seals_internally() {
+		let tap = AccountProvider::transient_provider();
+		let signer = tap.insert_account(secret("0"), "0").unwrap();
+
+		let engine = Spec::new_test_clique().engine;
+		assert!(!engine.seals_internally().unwrap());
+		engine.set_signer(Arc::new(tap), signer, "0".into());
+		assert!(engine.seals_internally().unwrap());
+	}
+}
@@ -0,0 +1,43 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique specific parameters.
+
+use ethjson;
+
+/// Default number of blocks after which votes are reset and the signers are checkpointed.
+pub const DEFAULT_EPOCH_LENGTH: u64 = 30000;
+/// Default minimum difference in seconds between consecutive block timestamps.
+pub const DEFAULT_PERIOD: u64 = 15;
+
+/// `Clique` params.
+#[derive(Debug, PartialEq)]
+pub struct CliqueParams {
+	/// Minimum number of seconds between consecutive blocks.
+	pub period: u64,
+	/// Number of blocks after which votes are reset and the signers are checkpointed.
+	pub epoch: u64,
+}
+
+impl From<ethjson::spec::CliqueParams> for CliqueParams {
+	fn from(p: ethjson::spec::CliqueParams) -> Self {
+		let epoch = p.epoch.map_or(DEFAULT_EPOCH_LENGTH, Into::into);
+		CliqueParams {
+			period: p.period.map_or(DEFAULT_PERIOD, Into::into),
+			epoch: if epoch == 0 { DEFAULT_EPOCH_LENGTH } else { epoch },
+		}
+	}
+}
@@ -0,0 +1,172 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique signer state: the authorized signers, recent signers and pending votes.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use engines::EngineError;
+use error::Error;
+use header::{Header, BlockNumber};
+use rlp::{UntrustedRlp, RlpStream, Encodable, Decodable, DecoderError};
+use util::Address;
+
+/// Signer state after a given block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliqueState {
+	/// Currently authorized signers, in ascending order.
+	signers: BTreeSet<Address>,
+	/// Recent signers by the number of the block they sealed, used for spam protection.
+	recents: BTreeMap<BlockNumber, Address>,
+	/// Pending votes by (signer, beneficiary), `true` to authorize the beneficiary.
+	votes: BTreeMap<(Address, Address), bool>,
+}
+
+impl CliqueState {
+	/// Create a state with the given signers and no recent signers or votes.
+	pub fn new<I: IntoIterator<Item=Address>>(signers: I) -> Self {
+		CliqueState {
+			signers: signers.into_iter().collect(),
+			recents: BTreeMap::new(),
+			votes: BTreeMap::new(),
+		}
+	}
+
+	/// Decode the state stored as an epoch transition proof.
+	pub fn from_proof(proof: &[u8]) -> Result<Self, DecoderError> {
+		UntrustedRlp::new(proof).as_val()
+	}
+
+	/// Encode the state as an epoch transition proof.
+	///
+	/// Pending votes are not included, they are only stored at checkpoints where votes are reset.
+	pub fn proof(&self) -> Vec<u8> {
+		::rlp::encode(self).into_vec()
+	}
+
+	/// Authorized signers, in ascending order.
+	pub fn signers(&self) -> &BTreeSet<Address> {
+		&self.signers
+	}
+
+	/// Whether the given address is an authorized signer.
+	pub fn is_authorized(&self, address: &Address) -> bool {
+		self.signers.contains(address)
+	}
+
+	/// Whether the given signer is the in-turn signer for block `number`.
+	pub fn is_inturn(&self, number: BlockNumber, signer: &Address) -> bool {
+		let len = self.signers.len() as u64;
+		len != 0 && self.signers.iter().nth((number % len) as usize) == Some(signer)
+	}
+
+	/// Whether the given signer sealed one of the last `signers / 2 + 1` blocks before `number`
+	/// and so is not allowed to seal it.
+	pub fn is_recent(&self, number: BlockNumber, signer: &Address) -> bool {
+		let limit = self.recents_limit();
+		self.recents.iter().any(|(seen, recent)| recent == signer && (number < limit || *seen > number - limit))
+	}
+
+	/// Whether a vote for `beneficiary` would change the signer list.
+	pub fn is_valid_vote(&self, beneficiary: &Address, authorize: bool) -> bool {
+		authorize != self.signers.contains(beneficiary)
+	}
+
+	/// Apply the block sealed by `signer`, tallying the vote it carries.
+	pub fn apply(&mut self, header: &Header, signer: &Address, authorize: bool, epoch_length: u64) -> Result<(), Error> {
+		let number = header.number();
+		if !self.is_authorized(signer) {
+			return Err(EngineError::NotAuthorized(*signer).into());
+		}
+		if self.is_recent(number, signer) {
+			return Err(EngineError::DoubleVote(*signer).into());
+		}
+
+		if number % epoch_length == 0 {
+			self.votes.clear();
+		}
+		self.recents.insert(number, *signer);
+		self.expire_recents(number);
+
+		// A signer can only have a single vote for each beneficiary, and only for changes.
+		let beneficiary = *header.author();
+		self.votes.remove(&(*signer, beneficiary));
+		if self.is_valid_vote(&beneficiary, authorize) {
+			self.votes.insert((*signer, beneficiary), authorize);
+		}
+
+		let tally = self.votes.iter().filter(|&(&(_, ref b), a)| *b == beneficiary && *a == authorize).count();
+		if tally > self.signers.len() / 2 {
+			if authorize {
+				self.signers.insert(beneficiary);
+			} else {
+				self.signers.remove(&beneficiary);
+				self.expire_recents(number);
+				self.votes = self.votes.iter()
+					.filter(|&(&(ref s, _), _)| *s != beneficiary)
+					.map(|(k, v)| (*k, *v))
+					.collect();
+			}
+			self.votes = self.votes.iter()
+				.filter(|&(&(_, ref b), _)| *b != beneficiary)
+				.map(|(k, v)| (*k, *v))
+				.collect();
+		}
+
+		Ok(())
+	}
+
+	fn recents_limit(&self) -> u64 {
+		self.signers.len() as u64 / 2 + 1
+	}
+
+	// forget recent signers which are allowed to seal again after block `number`.
+	fn expire_recents(&mut self, number: BlockNumber) {
+		let limit = self.recents_limit();
+		if number + 1 >= limit {
+			self.recents = self.recents.split_off(&(number + 2 - limit));
+		}
+	}
+}
+
+impl Encodable for CliqueState {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(2);
+		s.begin_list(self.signers.len());
+		for signer in &self.signers {
+			s.append(signer);
+		}
+		s.begin_list(self.recents.len());
+		for (number, signer) in &self.recents {
+			s.begin_list(2).append(number).append(signer);
+		}
+	}
+}
+
+impl Decodable for CliqueState {
+	fn decode(rlp: &UntrustedRlp) -> Result<Self, DecoderError> {
+		let signers: Vec<Address> = rlp.list_at(0)?;
+		let mut recents = BTreeMap::new();
+		for recent in rlp.at(1)?.iter() {
+			recents.insert(recent.val_at(0)?, recent.val_at(1)?);
+		}
+		Ok(CliqueState {
+			signers: signers.into_iter().collect(),
+			recents: recents,
+			votes: BTreeMap::new(),
+		})
+	}
+}
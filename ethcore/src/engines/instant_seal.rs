@@ -14,27 +14,110 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::cmp;
 use std::collections::BTreeMap;
-use util::{Address, HashMap};
+use std::sync::Weak;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
+use util::*;
 use builtin::Builtin;
+use client::{Client, EngineClient};
 use engines::{Engine, Seal};
+use error::Error;
+use ethjson;
+use header::Header;
+use io::{IoContext, IoHandler, TimerToken, IoService};
 use spec::CommonParams;
 use block::ExecutedBlock;
+use time::get_time;
+
+/// `InstantSeal` params.
+#[derive(Debug, PartialEq)]
+pub struct InstantSealParams {
+	/// Address of the registrar contract.
+	pub registrar: Address,
+	/// Seconds between sealed blocks, `0` to seal only on demand.
+	/// When not set a block is sealed for every transaction.
+	pub block_period: Option<u64>,
+}
+
+impl From<ethjson::spec::InstantSealParams> for InstantSealParams {
+	fn from(p: ethjson::spec::InstantSealParams) -> Self {
+		InstantSealParams {
+			registrar: p.registrar.map_or_else(Address::new, Into::into),
+			block_period: p.block_period.map(Into::into),
+		}
+	}
+}
 
 /// An engine which does not provide any consensus mechanism, just seals blocks internally.
 pub struct InstantSeal {
 	params: CommonParams,
 	registrar: Address,
+	block_period: Option<u64>,
 	builtins: BTreeMap<Address, Builtin>,
+	period_service: IoService<()>,
+	client: RwLock<Option<Weak<EngineClient>>>,
+	seal_requested: AtomicBool,
+	time_offset: AtomicUsize,
 }
 
 impl InstantSeal {
 	/// Returns new instance of InstantSeal with default VM Factory
-	pub fn new(params: CommonParams, registrar: Address, builtins: BTreeMap<Address, Builtin>) -> Self {
-		InstantSeal {
-			params: params,
-			registrar: registrar,
-			builtins: builtins,
+	pub fn new(params: CommonParams, our_params: InstantSealParams, builtins: BTreeMap<Address, Builtin>) -> Result<Arc<Self>, Error> {
+		let engine = Arc::new(
+			InstantSeal {
+				params: params,
+				registrar: our_params.registrar,
+				block_period: our_params.block_period,
+				builtins: builtins,
+				period_service: IoService::<()>::start()?,
+				client: RwLock::new(None),
+				seal_requested: AtomicBool::new(false),
+				time_offset: AtomicUsize::new(0),
+			});
+
+		match engine.block_period {
+			Some(period) if period > 0 => {
+				let handler = PeriodHandler { engine: Arc::downgrade(&engine), period: period };
+				engine.period_service.register_handler(Arc::new(handler))?;
+			},
+			_ => {},
+		}
+		Ok(engine)
+	}
+
+	/// Whether blocks are only sealed at a fixed period or on demand.
+	fn seals_on_period(&self) -> bool {
+		self.block_period.is_some()
+	}
+
+	fn update_sealing(&self) {
+		if let Some(ref weak) = *self.client.read() {
+			if let Some(c) = weak.upgrade() {
+				c.update_sealing();
+			}
+		}
+	}
+}
+
+struct PeriodHandler {
+	engine: Weak<InstantSeal>,
+	period: u64,
+}
+
+const ENGINE_TIMEOUT_TOKEN: TimerToken = 23;
+
+impl IoHandler<()> for PeriodHandler {
+	fn initialize(&self, io: &IoContext<()>) {
+		io.register_timer(ENGINE_TIMEOUT_TOKEN, self.period * 1000)
+			.unwrap_or_else(|e| warn!(target: "engine", "Failed to start block period timer: {}.", e))
+	}
+
+	fn timeout(&self, _io: &IoContext<()>, timer: TimerToken) {
+		if timer == ENGINE_TIMEOUT_TOKEN {
+			if let Some(engine) = self.engine.upgrade() {
+				engine.request_seal();
+			}
 		}
 	}
 }
@@ -59,7 +142,46 @@ impl Engine for InstantSeal {
 	fn seals_internally(&self) -> Option<bool> { Some(true) }
 
 	fn generate_seal(&self, _block: &ExecutedBlock) -> Seal {
-		Seal::Regular(Vec::new())
+		let requested = self.seal_requested.swap(false, AtomicOrdering::SeqCst);
+		if requested || !self.seals_on_period() {
+			Seal::Regular(Vec::new())
+		} else {
+			Seal::None
+		}
+	}
+
+	fn should_seal_empty_block(&self) -> bool {
+		self.seal_requested.load(AtomicOrdering::SeqCst)
+	}
+
+	fn request_seal(&self) -> bool {
+		self.seal_requested.store(true, AtomicOrdering::SeqCst);
+		self.update_sealing();
+		true
+	}
+
+	fn time_offset(&self) -> Option<u64> {
+		Some(self.time_offset.load(AtomicOrdering::SeqCst) as u64)
+	}
+
+	fn set_time_offset(&self, offset: u64) -> bool {
+		self.time_offset.store(offset as usize, AtomicOrdering::SeqCst);
+		true
+	}
+
+	fn populate_from_parent(&self, header: &mut Header, parent: &Header, _gas_floor_target: U256, _gas_ceil_target: U256) {
+		header.set_difficulty(parent.difficulty().clone());
+		header.set_gas_limit(parent.gas_limit().clone());
+
+		let offset = self.time_offset.load(AtomicOrdering::SeqCst) as u64;
+		if offset > 0 {
+			let now = get_time().sec as u64 + offset;
+			header.set_timestamp(cmp::max(now, parent.timestamp() + 1));
+		}
+	}
+
+	fn register_client(&self, client: Weak<Client>) {
+		*self.client.write() = Some(client.clone());
 	}
 }
 
@@ -70,7 +192,13 @@ mod tests {
 	use spec::Spec;
 	use header::Header;
 	use block::*;
-	use engines::Seal;
+	use engines::{Engine, Seal};
+	use super::{InstantSeal, InstantSealParams};
+
+	fn on_demand_engine(spec: &Spec) -> Arc<InstantSeal> {
+		let params = InstantSealParams { registrar: Address::default(), block_period: Some(0) };
+		InstantSeal::new(spec.params().clone(), params, Default::default()).unwrap()
+	}
 
 	#[test]
 	fn instant_can_seal() {
@@ -97,4 +225,38 @@ mod tests {
 
 		assert!(engine.verify_block_unordered(&header, None).is_ok());
 	}
+
+	#[test]
+	fn on_demand_seals_only_when_requested() {
+		let spec = Spec::new_instant();
+		let engine = on_demand_engine(&spec);
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let genesis_header = spec.genesis_header();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let b = OpenBlock::new(&*engine, Default::default(), false, db, &genesis_header, last_hashes, Address::default(), (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b = b.close_and_lock();
+
+		assert!(!engine.should_seal_empty_block());
+		assert_eq!(engine.generate_seal(b.block()), Seal::None);
+
+		assert!(engine.request_seal());
+		assert!(engine.should_seal_empty_block());
+		assert_eq!(engine.generate_seal(b.block()), Seal::Regular(vec![]));
+		assert!(!engine.should_seal_empty_block());
+	}
+
+	#[test]
+	fn time_offset_moves_timestamp_forward() {
+		let spec = Spec::new_instant();
+		let engine = on_demand_engine(&spec);
+		let parent = spec.genesis_header();
+		let mut header = Header::default();
+
+		assert_eq!(engine.time_offset(), Some(0));
+		assert!(engine.set_time_offset(3600));
+		assert_eq!(engine.time_offset(), Some(3600));
+
+		engine.populate_from_parent(&mut header, &parent, 0.into(), 0.into());
+		assert!(header.timestamp() >= ::time::get_time().sec as u64 + 3600);
+	}
 }
@@ -18,6 +18,8 @@
 
 mod authority_round;
 mod basic_authority;
+mod block_reward;
+mod clique;
 mod instant_seal;
 mod null_engine;
 mod signer;
@@ -30,6 +32,8 @@ pub mod epoch;
 
 pub use self::authority_round::AuthorityRound;
 pub use self::basic_authority::BasicAuthority;
+pub use self::block_reward::{BlockRewardContract, RewardKind};
+pub use self::clique::Clique;
 pub use self::epoch::{EpochVerifier, Transition as EpochTransition};
 pub use self::instant_seal::InstantSeal;
 pub use self::null_engine::NullEngine;
@@ -79,6 +83,8 @@ pub enum EngineError {
 	FailedSystemCall(String),
 	/// Requires client ref, but none registered.
 	RequiresClient,
+	/// The engine does not support voting on its signers.
+	SignerVotingUnsupported,
 }
 
 impl fmt::Display for EngineError {
@@ -93,6 +99,7 @@ impl fmt::Display for EngineError {
 			InsufficientProof(ref msg) => format!("Insufficient validation proof: {}", msg),
 			FailedSystemCall(ref msg) => format!("Failed to make system call: {}", msg),
 			RequiresClient => format!("Call requires client but none registered"),
+			SignerVotingUnsupported => "This Engine does not support voting on signers.".into(),
 		};
 
 		f.write_fmt(format_args!("Engine error ({})", msg))
@@ -179,8 +186,8 @@ pub trait Engine : Sync + Send {
 	/// The version of this engine. Should be of the form
 	fn version(&self) -> SemanticVersion { SemanticVersion::new(0, 0, 0) }
 
-	/// The number of additional header fields required for this engine.
-	fn seal_fields(&self) -> usize { 0 }
+	/// The number of additional header fields required for this engine for the given header.
+	fn seal_fields(&self, _header: &Header) -> usize { 0 }
 
 	/// Additional engine-specific information for the user/developer concerning `header`.
 	fn extra_info(&self, _header: &Header) -> BTreeMap<String, String> { BTreeMap::new() }
@@ -228,7 +235,7 @@ pub trait Engine : Sync + Send {
 	}
 
 	/// Block transformation functions, after the transactions.
-	fn on_close_block(&self, _block: &mut ExecutedBlock) -> Result<(), Error> {
+	fn on_close_block(&self, _block: &mut ExecutedBlock, _last_hashes: Arc<LastHashes>) -> Result<(), Error> {
 		Ok(())
 	}
 
@@ -244,6 +251,26 @@ pub trait Engine : Sync + Send {
 	/// be returned.
 	fn generate_seal(&self, _block: &ExecutedBlock) -> Seal { Seal::None }
 
+	/// Finalize a block sealed internally with the seal from `generate_seal`, before it is imported.
+	/// Not called for blocks received from the network.
+	fn on_seal_block(&self, _block: &mut ExecutedBlock) -> Result<(), Error> { Ok(()) }
+
+	/// Whether a block should be sealed internally even though it has no transactions,
+	/// e.g. because one was requested or the block period elapsed.
+	fn should_seal_empty_block(&self) -> bool { false }
+
+	/// Request a block to be sealed as soon as possible, even without transactions.
+	/// Returns `false` if the engine doesn't seal blocks on demand.
+	fn request_seal(&self) -> bool { false }
+
+	/// Seconds by which the timestamps of new blocks are moved forward.
+	/// `None` if the engine doesn't support shifting time.
+	fn time_offset(&self) -> Option<u64> { None }
+
+	/// Move the timestamps of new blocks `offset` seconds forward.
+	/// Returns `false` if the engine doesn't support shifting time.
+	fn set_time_offset(&self, _offset: u64) -> bool { false }
+
 	/// Phase 1 quick block verification. Only does checks that are cheap. `block` (the header's full block)
 	/// may be provided for additional checks. Returns either a null `Ok` or a general error detailing the problem with import.
 	fn verify_block_basic(&self, _header: &Header,  _block: Option<&[u8]>) -> Result<(), Error> { Ok(()) }
@@ -356,6 +383,17 @@ pub trait Engine : Sync + Send {
 	/// Sign using the EngineSigner, to be used for consensus tx signing.
 	fn sign(&self, _hash: H256) -> Result<Signature, Error> { unimplemented!() }
 
+	/// Vote to authorize (`true`) or deauthorize (`false`) a signer in the blocks we seal.
+	/// The vote is cast until discarded, whenever it would still change the signer list.
+	fn propose_signer(&self, _address: Address, _authorize: bool) -> Result<(), EngineError> {
+		Err(EngineError::SignerVotingUnsupported)
+	}
+
+	/// Stop voting on the given signer.
+	fn discard_signer_proposal(&self, _address: &Address) -> Result<(), EngineError> {
+		Err(EngineError::SignerVotingUnsupported)
+	}
+
 	/// Add Client which can be used for sealing, querying the state and sending messages.
 	fn register_client(&self, _client: Weak<Client>) {}
 
@@ -400,8 +438,9 @@ pub mod common {
 	use executive::Executive;
 	use types::executed::CallType;
 	use action_params::{ActionParams, ActionValue};
-	use trace::{NoopTracer, NoopVMTracer};
-	use state::Substate;
+	use trace::{NoopTracer, NoopVMTracer, Tracer, ExecutiveTracer};
+	use trace::trace::RewardType;
+	use state::{Substate, CleanupMode};
 
 	use util::*;
 	use super::Engine;
@@ -470,4 +509,25 @@ pub mod common {
 		}
 		Ok(())
 	}
+
+	/// Credit the given block rewards, tracing them if tracing is enabled,
+	/// and commit the state.
+	pub fn bestow_rewards(block: &mut ExecutedBlock, rewards: &[(Address, U256)]) -> Result<(), Error> {
+		let fields = block.fields_mut();
+		for &(ref address, ref reward) in rewards {
+			fields.state.add_balance(address, reward, CleanupMode::NoEmpty)?;
+		}
+
+		if let Some(ref mut traces) = *fields.traces {
+			let mut tracer = ExecutiveTracer::default();
+			for &(address, reward) in rewards {
+				tracer.trace_reward(address, reward, RewardType::Block);
+			}
+			traces.push(tracer.traces());
+		}
+
+		// Commit state so that we can actually figure out the state root.
+		fields.state.commit()?;
+		Ok(())
+	}
 }
@@ -38,8 +38,8 @@ use account_provider::AccountProvider;
 use block::*;
 use spec::CommonParams;
 use engines::{Engine, Seal, EngineError};
-use state::CleanupMode;
 use io::IoService;
+use super::block_reward::{BlockRewardContract, RewardKind, apply_block_rewards};
 use super::signer::EngineSigner;
 use super::validator_set::ValidatorSet;
 use super::transition::TransitionHandler;
@@ -99,6 +99,10 @@ pub struct Tendermint {
 	last_proposed: RwLock<H256>,
 	/// Set used to determine the current validators.
 	validators: Box<ValidatorSet>,
+	/// Number of first block where the block reward contract replaces the fixed block reward.
+	block_reward_contract_transition: u64,
+	/// Contract determining the block rewards.
+	block_reward_contract: Option<BlockRewardContract>,
 }
 
 impl Tendermint {
@@ -124,6 +128,8 @@ impl Tendermint {
 				proposal_parent: Default::default(),
 				last_proposed: Default::default(),
 				validators: our_params.validators,
+				block_reward_contract_transition: our_params.block_reward_contract_transition,
+				block_reward_contract: our_params.block_reward_contract,
 			});
 		let handler = TransitionHandler::new(Arc::downgrade(&engine) as Weak<Engine>, Box::new(our_params.timeouts));
 		engine.step_service.register_handler(Arc::new(handler))?;
@@ -380,7 +386,7 @@ impl Engine for Tendermint {
 	fn version(&self) -> SemanticVersion { SemanticVersion::new(1, 0, 0) }
 
 	/// (consensus view, proposal signature, authority signatures)
-	fn seal_fields(&self) -> usize { 3 }
+	fn seal_fields(&self, _header: &Header) -> usize { 3 }
 
 	fn params(&self) -> &CommonParams { &self.params }
 
@@ -477,22 +483,19 @@ impl Engine for Tendermint {
 	}
 
 	/// Apply the block reward on finalisation of the block.
-	fn on_close_block(&self, block: &mut ExecutedBlock) -> Result<(), Error>{
-		let fields = block.fields_mut();
-		// Bestow block reward
-		let res = fields.state.add_balance(fields.header.author(), &self.block_reward, CleanupMode::NoEmpty)
-			.map_err(::error::Error::from)
-			.and_then(|_| fields.state.commit());
-		// Commit state so that we can actually figure out the state root.
-		if let Err(ref e) = res {
-			warn!("Encountered error on closing block: {}", e);
-		}
-		res
+	fn on_close_block(&self, block: &mut ExecutedBlock, last_hashes: Arc<::env_info::LastHashes>) -> Result<(), Error>{
+		let author = *block.fields().header.author();
+		let contract = match self.block_reward_contract {
+			Some(ref contract) if block.fields().header.number() >= self.block_reward_contract_transition => Some(contract),
+			_ => None,
+		};
+
+		apply_block_rewards(block, last_hashes, self, contract, self.block_reward, &[(author, RewardKind::Author)])
 	}
 
 	fn verify_block_basic(&self, header: &Header, _block: Option<&[u8]>) -> Result<(), Error> {
 		let seal_length = header.seal().len();
-		if seal_length == self.seal_fields() {
+		if seal_length == self.seal_fields(header) {
 			// Either proposal or commit.
 			if (header.seal()[1] == ::rlp::NULL_RLP)
 				!= (header.seal()[2] == ::rlp::EMPTY_LIST_RLP) {
@@ -503,7 +506,7 @@ impl Engine for Tendermint {
 			}
 		} else {
 			Err(BlockError::InvalidSealArity(
-				Mismatch { expected: self.seal_fields(), found: seal_length }
+				Mismatch { expected: self.seal_fields(header), found: seal_length }
 			).into())
 		}
 	}
@@ -946,4 +949,20 @@ mod tests {
 		vote(engine, |mh| tap.sign(v0, None, mh).map(H520::from), h, r, Step::Precommit, proposal);
 		assert_eq!(client.chain_info().best_block_number, 1);
 	}
+
+	#[test]
+	fn rewards_from_block_reward_contract() {
+		let spec = Spec::new_test_tendermint_block_reward_contract();
+		let engine = &*spec.engine;
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let author = Address::from(0x1234);
+
+		let b = OpenBlock::new(engine, Default::default(), false, db, &genesis_header, last_hashes, author, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b = b.close_and_lock();
+
+		// the contract reward replaces the fixed block reward.
+		assert_eq!(b.state().balance(&author).unwrap(), U256::from(1000));
+	}
 }
@@ -19,6 +19,7 @@
 use ethjson;
 use util::{U256, Address};
 use time::Duration;
+use super::super::block_reward::BlockRewardContract;
 use super::super::validator_set::{ValidatorSet, new_validator_set};
 use super::super::transition::Timeouts;
 use super::Step;
@@ -35,6 +36,10 @@ pub struct TendermintParams {
 	pub block_reward: U256,
 	/// Namereg contract address.
 	pub registrar: Address,
+	/// Number of first block where the block reward contract replaces the fixed block reward.
+	pub block_reward_contract_transition: u64,
+	/// Block reward contract.
+	pub block_reward_contract: Option<BlockRewardContract>,
 }
 
 /// Base timeout of each step in ms.
@@ -91,6 +96,8 @@ impl From<ethjson::spec::TendermintParams> for TendermintParams {
 			},
 			block_reward: p.block_reward.map_or_else(U256::zero, Into::into),
 			registrar: p.registrar.map_or_else(Address::new, Into::into),
+			block_reward_contract_transition: p.block_reward_contract_transition.map_or(0, Into::into),
+			block_reward_contract: p.block_reward_contract_address.map(|address| BlockRewardContract::new(address.into())),
 		}
 	}
 }
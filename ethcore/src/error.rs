@@ -42,6 +42,11 @@ pub enum TransactionError {
 	TooCheapToReplace,
 	/// Transaction was not imported to the queue because limit has been reached.
 	LimitReached,
+	/// Transaction was not imported to the queue because the sender already has too many transactions queued.
+	SenderLimitReached {
+		/// Maximal number of transactions per sender
+		limit: usize,
+	},
 	/// Transaction's gas price is below threshold.
 	InsufficientGasPrice {
 		/// Minimal expected gas price
@@ -80,6 +85,8 @@ pub enum TransactionError {
 	CodeBanned,
 	/// Invalid network ID given.
 	InvalidNetworkId,
+	/// Transaction is not permitted by the transaction permission contract.
+	NotAllowed,
 }
 
 impl fmt::Display for TransactionError {
@@ -90,6 +97,8 @@ impl fmt::Display for TransactionError {
 			Old => "No longer valid".into(),
 			TooCheapToReplace => "Gas price too low to replace".into(),
 			LimitReached => "Transaction limit reached".into(),
+			SenderLimitReached { limit } =>
+				format!("Transaction limit per sender reached. Limit={}", limit),
 			InsufficientGasPrice { minimal, got } =>
 				format!("Insufficient gas price. Min={}, Given={}", minimal, got),
 			InsufficientGas { minimal, got } =>
@@ -104,6 +113,7 @@ impl fmt::Display for TransactionError {
 			RecipientBanned => "Recipient is temporarily banned.".into(),
 			CodeBanned => "Contract code is temporarily banned.".into(),
 			InvalidNetworkId => "Transaction of this network ID is not allowed on this chain.".into(),
+			NotAllowed => "Sender does not have permissions to execute this type of transaction".into(),
 		};
 
 		f.write_fmt(format_args!("Transaction error ({})", msg))
@@ -23,6 +23,8 @@ use env_info::EnvInfo;
 use error::{BlockError, Error, TransactionError};
 use header::{Header, BlockNumber};
 use state::CleanupMode;
+use trace::{Tracer, ExecutiveTracer};
+use trace::trace::RewardType;
 use spec::CommonParams;
 use transaction::UnverifiedTransaction;
 use engines::{self, Engine};
@@ -53,8 +55,8 @@ pub struct EthashParams {
 	pub metropolis_difficulty_increment_divisor: u64,
 	/// Block duration.
 	pub duration_limit: u64,
-	/// Block reward.
-	pub block_reward: U256,
+	/// Block reward by the block number it starts at.
+	pub block_reward: BTreeMap<BlockNumber, U256>,
 	/// Namereg contract address.
 	pub registrar: Address,
 	/// Homestead transition block number.
@@ -71,6 +73,8 @@ pub struct EthashParams {
 	pub difficulty_hardfork_bound_divisor: U256,
 	/// Block on which there is no additional difficulty from the exponential bomb.
 	pub bomb_defuse_transition: u64,
+	/// Number of blocks the difficulty bomb is delayed by, by the block number the delay starts at.
+	pub difficulty_bomb_delays: BTreeMap<BlockNumber, BlockNumber>,
 	/// Number of first block where EIP-100 rules begin.
 	pub eip100b_transition: u64,
 	/// Number of first block where EIP-150 rules begin.
@@ -101,6 +105,22 @@ pub struct EthashParams {
 	pub min_gas_price: U256,
 }
 
+impl EthashParams {
+	/// Reward for mining the block with given number.
+	pub fn block_reward(&self, number: BlockNumber) -> U256 {
+		self.block_reward.iter().rev()
+			.find(|&(block, _)| *block <= number)
+			.map_or_else(U256::zero, |(_, reward)| *reward)
+	}
+
+	/// Block number the difficulty bomb is computed from, taking all the delays active at given block into account.
+	pub fn bomb_block_number(&self, number: BlockNumber) -> BlockNumber {
+		self.difficulty_bomb_delays.iter()
+			.filter(|&(block, _)| *block <= number)
+			.fold(number, |number, (_, delay)| number.saturating_sub(*delay))
+	}
+}
+
 impl From<ethjson::spec::EthashParams> for EthashParams {
 	fn from(p: ethjson::spec::EthashParams) -> Self {
 		EthashParams {
@@ -110,7 +130,14 @@ impl From<ethjson::spec::EthashParams> for EthashParams {
 			difficulty_increment_divisor: p.difficulty_increment_divisor.map_or(10, Into::into),
 			metropolis_difficulty_increment_divisor: p.metropolis_difficulty_increment_divisor.map_or(9, Into::into),
 			duration_limit: p.duration_limit.map_or(0, Into::into),
-			block_reward: p.block_reward.into(),
+			block_reward: match p.block_reward {
+				ethjson::spec::BlockReward::Single(reward) => {
+					let mut rewards = BTreeMap::new();
+					rewards.insert(0, reward.into());
+					rewards
+				},
+				ethjson::spec::BlockReward::Multi(rewards) => rewards.into_iter().map(|(block, reward)| (block.into(), reward.into())).collect(),
+			},
 			registrar: p.registrar.map_or_else(Address::new, Into::into),
 			homestead_transition: p.homestead_transition.map_or(0, Into::into),
 			dao_hardfork_transition: p.dao_hardfork_transition.map_or(u64::max_value(), Into::into),
@@ -119,6 +146,7 @@ impl From<ethjson::spec::EthashParams> for EthashParams {
 			difficulty_hardfork_transition: p.difficulty_hardfork_transition.map_or(u64::max_value(), Into::into),
 			difficulty_hardfork_bound_divisor: p.difficulty_hardfork_bound_divisor.map_or(p.difficulty_bound_divisor.into(), Into::into),
 			bomb_defuse_transition: p.bomb_defuse_transition.map_or(u64::max_value(), Into::into),
+			difficulty_bomb_delays: p.difficulty_bomb_delays.unwrap_or_else(BTreeMap::new).into_iter().map(|(block, delay)| (block.into(), delay.into())).collect(),
 			eip100b_transition: p.eip100b_transition.map_or(u64::max_value(), Into::into),
 			eip150_transition: p.eip150_transition.map_or(0, Into::into),
 			eip155_transition: p.eip155_transition.map_or(0, Into::into),
@@ -182,7 +210,7 @@ impl Engine for Arc<Ethash> {
 	fn name(&self) -> &str { "Ethash" }
 	fn version(&self) -> SemanticVersion { SemanticVersion::new(1, 0, 0) }
 	// Two fields - mix
-	fn seal_fields(&self) -> usize { 2 }
+	fn seal_fields(&self, _header: &Header) -> usize { 2 }
 
 	fn params(&self) -> &CommonParams { &self.params }
 	fn additional_params(&self) -> HashMap<String, String> { hash_map!["registrar".to_owned() => self.ethash_params.registrar.hex()] }
@@ -282,47 +310,61 @@ impl Engine for Arc<Ethash> {
 
 	/// Apply the block reward on finalisation of the block.
 	/// This assumes that all uncles are valid uncles (i.e. of at least one generation before the current).
-	fn on_close_block(&self, block: &mut ExecutedBlock) -> Result<(), Error> {
-		let reward = self.ethash_params.block_reward;
+	fn on_close_block(&self, block: &mut ExecutedBlock, _last_hashes: Arc<LastHashes>) -> Result<(), Error> {
 		let fields = block.fields_mut();
+		let tracing_enabled = fields.traces.is_some();
 		let eras_rounds = self.ethash_params.ecip1017_era_rounds;
+		let reward = self.ethash_params.block_reward(fields.header.number());
 		let (eras, reward) = ecip1017_eras_block_reward(eras_rounds, reward, fields.header.number());
+		let mut tracer = ExecutiveTracer::default();
 
 		// Bestow block reward
+		let result_block_reward = reward + reward / U256::from(32) * U256::from(fields.uncles.len());
 		fields.state.add_balance(
 			fields.header.author(),
-			&(reward + reward / U256::from(32) * U256::from(fields.uncles.len())),
+			&result_block_reward,
 			CleanupMode::NoEmpty
 		)?;
 
+		if tracing_enabled {
+			let block_author = fields.header.author().clone();
+			tracer.trace_reward(block_author, result_block_reward, RewardType::Block);
+		}
+
 		// Bestow uncle rewards
 		let current_number = fields.header.number();
 		for u in fields.uncles.iter() {
-			if eras == 0 {
-				fields.state.add_balance(
-					u.author(),
-					&(reward * U256::from(8 + u.number() - current_number) / U256::from(8)),
-					CleanupMode::NoEmpty
-				)
+			let uncle_author = u.author().clone();
+			let result_uncle_reward = if eras == 0 {
+				reward * U256::from(8 + u.number() - current_number) / U256::from(8)
 			} else {
-				fields.state.add_balance(
-					u.author(),
-					&(reward / U256::from(32)),
-					CleanupMode::NoEmpty
-				)
-			}?;
+				reward / U256::from(32)
+			};
+
+			fields.state.add_balance(
+				&uncle_author,
+				&result_uncle_reward,
+				CleanupMode::NoEmpty
+			)?;
+
+			if tracing_enabled {
+				tracer.trace_reward(uncle_author, result_uncle_reward, RewardType::Uncle);
+			}
 		}
 
 		// Commit state so that we can actually figure out the state root.
 		fields.state.commit()?;
+		if let Some(ref mut traces) = *fields.traces {
+			traces.push(tracer.traces());
+		}
 		Ok(())
 	}
 
 	fn verify_block_basic(&self, header: &Header, _block: Option<&[u8]>) -> result::Result<(), Error> {
 		// check the seal fields.
-		if header.seal().len() != self.seal_fields() {
+		if header.seal().len() != self.seal_fields(header) {
 			return Err(From::from(BlockError::InvalidSealArity(
-				Mismatch { expected: self.seal_fields(), found: header.seal().len() }
+				Mismatch { expected: self.seal_fields(header), found: header.seal().len() }
 			)));
 		}
 		UntrustedRlp::new(&header.seal()[0]).as_val::<H256>()?;
@@ -357,9 +399,9 @@ impl Engine for Arc<Ethash> {
 	}
 
 	fn verify_block_unordered(&self, header: &Header, _block: Option<&[u8]>) -> result::Result<(), Error> {
-		if header.seal().len() != self.seal_fields() {
+		if header.seal().len() != self.seal_fields(header) {
 			return Err(From::from(BlockError::InvalidSealArity(
-				Mismatch { expected: self.seal_fields(), found: header.seal().len() }
+				Mismatch { expected: self.seal_fields(header), found: header.seal().len() }
 			)));
 		}
 		let result = self.pow.compute_light(header.number() as u64, &header.bare_hash().0, header.nonce().low_u64());
@@ -501,7 +543,7 @@ impl Ethash {
 		target = max(min_difficulty, target);
 		if header.number() < self.ethash_params.bomb_defuse_transition {
 			if header.number() < self.ethash_params.ecip1010_pause_transition {
-				let period = ((parent.number() + 1) / EXP_DIFF_PERIOD) as usize;
+				let period = (self.ethash_params.bomb_block_number(header.number()) / EXP_DIFF_PERIOD) as usize;
 				if period > 1 {
 					target = max(min_difficulty, target + (U256::from(1) << (period - 2)));
 				}
@@ -511,7 +553,7 @@ impl Ethash {
 				target = max(min_difficulty, target + (U256::from(1) << fixed_difficulty));
 			}
 			else {
-				let period = ((parent.number() + 1) / EXP_DIFF_PERIOD) as usize;
+				let period = (self.ethash_params.bomb_block_number(header.number()) / EXP_DIFF_PERIOD) as usize;
 				let delay = ((self.ethash_params.ecip1010_continue_transition - self.ethash_params.ecip1010_pause_transition) / EXP_DIFF_PERIOD) as usize;
 				target = max(min_difficulty, target + (U256::from(1) << (period - delay - 2)));
 			}
@@ -565,9 +607,11 @@ mod tests {
 	use error::{BlockError, Error};
 	use header::Header;
 	use spec::Spec;
-	use super::super::{new_morden, new_homestead_test};
+	use super::super::{new_morden, new_homestead_test, new_reward_transition_test};
 	use super::{Ethash, EthashParams, PARITY_GAS_LIMIT_DETERMINANT, ecip1017_eras_block_reward};
 	use rlp;
+	use trace::FlatTrace;
+	use trace::trace::{Action, Res, Reward, RewardType};
 
 	fn test_spec() -> Spec {
 		new_morden(&::std::env::temp_dir())
@@ -585,6 +629,18 @@ mod tests {
 		assert_eq!(b.state().balance(&Address::zero()).unwrap(), U256::from_str("4563918244f40000").unwrap());
 	}
 
+	#[test]
+	fn on_close_block_with_reward_transition() {
+		let spec = new_reward_transition_test();
+		let engine = &*spec.engine;
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let b = OpenBlock::new(engine, Default::default(), false, db, &genesis_header, last_hashes, Address::zero(), (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b = b.close();
+		assert_eq!(b.state().balance(&Address::zero()).unwrap(), U256::from_str("29a2241af62c0000").unwrap());
+	}
+
 	#[test]
 	fn on_close_block_with_uncle() {
 		let spec = test_spec();
@@ -603,6 +659,46 @@ mod tests {
 		assert_eq!(b.state().balance(&uncle_author).unwrap(), "3cb71f51fc558000".into());
 	}
 
+	#[test]
+	fn on_close_block_traces_rewards() {
+		let spec = test_spec();
+		let engine = &*spec.engine;
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let mut b = OpenBlock::new(engine, Default::default(), true, db, &genesis_header, last_hashes, Address::zero(), (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let mut uncle = Header::new();
+		let uncle_author: Address = "ef2d6d194084c2de36e0dabfce45d046b37d1106".into();
+		uncle.set_author(uncle_author);
+		b.push_uncle(uncle).unwrap();
+
+		let b = b.close();
+		let traces = b.traces().as_ref().unwrap();
+		assert_eq!(traces.len(), 1);
+		assert_eq!(traces[0], vec![
+			FlatTrace {
+				action: Action::Reward(Reward {
+					author: Address::zero(),
+					value: "478eae0e571ba000".into(),
+					reward_type: RewardType::Block,
+				}),
+				result: Res::None,
+				trace_address: Default::default(),
+				subtraces: 0,
+			},
+			FlatTrace {
+				action: Action::Reward(Reward {
+					author: uncle_author,
+					value: "3cb71f51fc558000".into(),
+					reward_type: RewardType::Uncle,
+				}),
+				result: Res::None,
+				trace_address: Default::default(),
+				subtraces: 0,
+			},
+		]);
+	}
+
 	#[test]
 	fn has_valid_metadata() {
 		let engine = test_spec().engine;
@@ -811,37 +907,88 @@ mod tests {
 		let ethparams = EthashParams {
 			// see ethcore/res/ethereum/classic.json
 			ecip1017_era_rounds: 5000000,
-			block_reward: U256::from_str("4563918244F40000").unwrap(),
+			block_reward: vec![(0, U256::from_str("4563918244F40000").unwrap())].into_iter().collect(),
 			..get_default_ethash_params()
 		};
 		let eras_rounds = ethparams.ecip1017_era_rounds;
-		let reward = ethparams.block_reward;
+		let reward = ethparams.block_reward(0);
 		let block_number = 0;
 		let (eras, reward) = ecip1017_eras_block_reward(eras_rounds, reward, block_number);
 		assert_eq!(0, eras);
 		assert_eq!(U256::from_str("4563918244F40000").unwrap(), reward);
-		let reward = ethparams.block_reward;
+		let reward = ethparams.block_reward(0);
 		let block_number = 5000000;
 		let (eras, reward) = ecip1017_eras_block_reward(eras_rounds, reward, block_number);
 		assert_eq!(0, eras);
 		assert_eq!(U256::from_str("4563918244F40000").unwrap(), reward);
-		let reward = ethparams.block_reward;
+		let reward = ethparams.block_reward(0);
 		let block_number = 10000000;
 		let (eras, reward) = ecip1017_eras_block_reward(eras_rounds, reward, block_number);
 		assert_eq!(1, eras);
 		assert_eq!(U256::from_str("3782DACE9D900000").unwrap(), reward);
-		let reward = ethparams.block_reward;
+		let reward = ethparams.block_reward(0);
 		let block_number = 20000000;
 		let (eras, reward) = ecip1017_eras_block_reward(eras_rounds, reward, block_number);
 		assert_eq!(3, eras);
 		assert_eq!(U256::from_str("2386F26FC1000000").unwrap(), reward);
-		let reward = ethparams.block_reward;
+		let reward = ethparams.block_reward(0);
 		let block_number = 80000000;
 		let (eras, reward) = ecip1017_eras_block_reward(eras_rounds, reward, block_number);
 		assert_eq!(15, eras);
 		assert_eq!(U256::from_str("271000000000000").unwrap(), reward);
 	}
 
+	#[test]
+	fn block_reward_and_bomb_delay_transitions() {
+		let ethparams = EthashParams {
+			block_reward: vec![(0, U256::from(5)), (10, U256::from(3)), (20, U256::from(2))].into_iter().collect(),
+			difficulty_bomb_delays: vec![(10, 5), (20, 7)].into_iter().collect(),
+			..get_default_ethash_params()
+		};
+		assert_eq!(ethparams.block_reward(0), U256::from(5));
+		assert_eq!(ethparams.block_reward(9), U256::from(5));
+		assert_eq!(ethparams.block_reward(10), U256::from(3));
+		assert_eq!(ethparams.block_reward(25), U256::from(2));
+		assert_eq!(ethparams.bomb_block_number(9), 9);
+		assert_eq!(ethparams.bomb_block_number(10), 5);
+		assert_eq!(ethparams.bomb_block_number(25), 13);
+
+		let no_rewards = get_default_ethash_params();
+		assert_eq!(no_rewards.block_reward(100), U256::zero());
+		assert_eq!(no_rewards.bomb_block_number(100), 100);
+	}
+
+	#[test]
+	fn difficulty_bomb_delays() {
+		let delayed = new_reward_transition_test();
+		let plain = new_homestead_test();
+
+		let mut parent_header = Header::default();
+		parent_header.set_difficulty(U256::from_str("6F62EAF8D3C").unwrap());
+		parent_header.set_timestamp(1452838500);
+		parent_header.set_gas_limit(3141562.into());
+		let mut header = Header::default();
+		header.set_timestamp(parent_header.timestamp() + 20);
+
+		// delay is active: bomb is computed as for a block 3000000 lower.
+		parent_header.set_number(4099999);
+		header.set_number(4100000);
+		delayed.engine.populate_from_parent(&mut header, &parent_header, 3141562.into(), 31415620.into());
+		let delayed_difficulty = *header.difficulty();
+		parent_header.set_number(1099999);
+		header.set_number(1100000);
+		plain.engine.populate_from_parent(&mut header, &parent_header, 3141562.into(), 31415620.into());
+		assert_eq!(delayed_difficulty, *header.difficulty());
+
+		// delay is not active yet.
+		parent_header.set_number(3999998);
+		header.set_number(3999999);
+		delayed.engine.populate_from_parent(&mut header, &parent_header, 3141562.into(), 31415620.into());
+		let delayed_difficulty = *header.difficulty();
+		plain.engine.populate_from_parent(&mut header, &parent_header, 3141562.into(), 31415620.into());
+		assert_eq!(delayed_difficulty, *header.difficulty());
+	}
+
 	#[test]
 	fn difficulty_classic_bomb_delay() {
 		let spec = new_homestead_test();
@@ -925,6 +1072,38 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn difficulty_bomb_continue_with_delays() {
+		let spec = new_homestead_test();
+		let ethparams = EthashParams {
+			ecip1010_pause_transition: 3000000,
+			ecip1010_continue_transition: 5000000,
+			..get_default_ethash_params()
+		};
+		let delayed_params = EthashParams {
+			ecip1010_pause_transition: 3000000,
+			ecip1010_continue_transition: 5000000,
+			difficulty_bomb_delays: vec![(5000000, 100000)].into_iter().collect(),
+			..get_default_ethash_params()
+		};
+		let ethash = Ethash::new(&::std::env::temp_dir(), spec.params().clone(), ethparams, BTreeMap::new());
+		let delayed = Ethash::new(&::std::env::temp_dir(), spec.params().clone(), delayed_params, BTreeMap::new());
+
+		let mut parent_header = Header::default();
+		parent_header.set_difficulty(U256::from_str("14D24B39C7CF").unwrap());
+		parent_header.set_timestamp(1514609324);
+		let mut header = Header::default();
+		header.set_timestamp(parent_header.timestamp() + 41);
+
+		// the continued bomb is computed as for a block 100000 lower.
+		parent_header.set_number(5100123);
+		header.set_number(parent_header.number() + 1);
+		let delayed_difficulty = delayed.calculate_difficulty(&header, &parent_header);
+		parent_header.set_number(5000123);
+		header.set_number(parent_header.number() + 1);
+		assert_eq!(delayed_difficulty, ethash.calculate_difficulty(&header, &parent_header));
+	}
+
 	#[test]
 	fn gas_limit_is_multiple_of_determinant() {
 		let spec = new_homestead_test();
@@ -90,6 +90,15 @@ pub fn new_mainnet_like() -> Spec { load(None, include_bytes!("../../res/ethereu
 /// Create a new Foundation Metropolis era spec.
 pub fn new_metropolis_test() -> Spec { load(None, include_bytes!("../../res/ethereum/metropolis_test.json")) }
 
+/// Create a new Foundation Constantinople era spec.
+pub fn new_constantinople_test() -> Spec { load(None, include_bytes!("../../res/ethereum/constantinople_test.json")) }
+
+/// Create a new Homestead-era chain spec with a block reward change at #1 and a difficulty bomb delay from #4000000.
+pub fn new_reward_transition_test() -> Spec { load(None, include_bytes!("../../res/ethereum/reward_transition_test.json")) }
+
+/// Create a new Frontier-era chain spec with a difficulty low enough to mine on a CPU without the full dataset.
+pub fn new_low_difficulty_test() -> Spec { load(None, include_bytes!("../../res/ethereum/low_difficulty_test.json")) }
+
 #[cfg(test)]
 mod tests {
 	use util::*;
@@ -152,5 +161,6 @@ mod tests {
 		new_transition_test();
 		new_mainnet_like();
 		new_metropolis_test();
+		new_low_difficulty_test();
 	}
 }
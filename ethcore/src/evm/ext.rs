@@ -48,12 +48,17 @@ pub enum CreateContractAddress {
 	FromSenderAndNonce,
 	/// Address is calculated from code hash. Default since EIP-86
 	FromCodeHash,
-	/// Address is calculated from code hash and sender. Used by CREATE_P2SH instruction.
+	/// Address is calculated from code hash and sender. Used by the WASM runtime.
 	FromSenderAndCodeHash,
+	/// Address is calculated from sender, salt and code hash. Used by CREATE2 instruction (EIP-1014).
+	FromSenderSaltAndCodeHash(H256),
 }
 
 /// Externalities interface for EVMs
 pub trait Ext {
+	/// Returns the storage value for a given key if reversion happens on the current transaction.
+	fn initial_storage_at(&self, key: &H256) -> evm::Result<H256>;
+
 	/// Returns a value for given key.
 	fn storage_at(&self, key: &H256) -> evm::Result<H256>;
 
@@ -103,6 +108,9 @@ pub trait Ext {
 	/// Returns code size at given address
 	fn extcodesize(&self, address: &Address) -> evm::Result<usize>;
 
+	/// Returns code hash at given address, or zero if the account does not exist.
+	fn extcodehash(&self, address: &Address) -> evm::Result<H256>;
+
 	/// Creates log entry with given topics and data
 	fn log(&mut self, topics: Vec<H256>, data: &[u8]) -> evm::Result<()>;
 
@@ -126,8 +134,11 @@ pub trait Ext {
 	/// then A depth is 0, B is 1, C is 2 and so on.
 	fn depth(&self) -> usize;
 
-	/// Increments sstore refunds count by 1.
-	fn inc_sstore_clears(&mut self);
+	/// Increments sstore refunds counter.
+	fn add_sstore_refund(&mut self, value: usize);
+
+	/// Decrements sstore refunds counter.
+	fn sub_sstore_refund(&mut self, value: usize);
 
 	/// Decide if any more operations should be traced. Passthrough for the VM trace.
 	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8) -> bool { false }
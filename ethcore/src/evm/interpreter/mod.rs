@@ -210,7 +210,9 @@ impl<Cost: CostType> Interpreter<Cost> {
 		if (instruction == instructions::DELEGATECALL && !schedule.have_delegate_call) ||
 			(instruction == instructions::CREATE2 && !schedule.have_create2) ||
 			(instruction == instructions::STATICCALL && !schedule.have_static_call) ||
-			(instruction == instructions::REVERT && !schedule.have_revert) {
+			(instruction == instructions::REVERT && !schedule.have_revert) ||
+			((instruction == instructions::SHL || instruction == instructions::SHR || instruction == instructions::SAR) && !schedule.have_bitwise_shifting) ||
+			(instruction == instructions::EXTCODEHASH && !schedule.have_extcodehash) {
 
 			return Err(evm::Error::BadInstruction {
 				instruction: instruction
@@ -300,7 +302,11 @@ impl<Cost: CostType> Interpreter<Cost> {
 				let init_off = stack.pop_back();
 				let init_size = stack.pop_back();
 
-				let address_scheme = if instruction == instructions::CREATE { CreateContractAddress::FromSenderAndNonce } else { CreateContractAddress::FromSenderAndCodeHash };
+				let address_scheme = match instruction {
+					instructions::CREATE => CreateContractAddress::FromSenderAndNonce,
+					instructions::CREATE2 => CreateContractAddress::FromSenderSaltAndCodeHash(H256::from(&stack.pop_back())),
+					_ => unreachable!("instruction can only be CREATE/CREATE2 checked above; qed"),
+				};
 				let create_gas = provided.expect("`provided` comes through Self::exec from `Gasometer::get_gas_cost_mem`; `gas_gas_mem_cost` guarantees `Some` when instruction is `CALL`/`CALLCODE`/`DELEGATECALL`/`CREATE`; this is `CREATE`; qed");
 
 				let contract_code = self.mem.read_slice(init_off, init_size);
@@ -458,9 +464,13 @@ impl<Cost: CostType> Interpreter<Cost> {
 				let val = stack.pop_back();
 
 				let current_val = U256::from(&*ext.storage_at(&address)?);
-				// Increase refund for clear
-				if !self.is_zero(&current_val) && self.is_zero(&val) {
-					ext.inc_sstore_clears();
+				if ext.schedule().eip1283 {
+					let original_val = U256::from(&*ext.initial_storage_at(&address)?);
+					gasometer::handle_eip1283_sstore_clears_refund(ext, &original_val, &current_val, &val);
+				} else if !self.is_zero(&current_val) && self.is_zero(&val) {
+					// Increase refund for clear
+					let sstore_clears_schedule = ext.schedule().sstore_refund_gas;
+					ext.add_sstore_refund(sstore_clears_schedule);
 				}
 				ext.set_storage(address, H256::from(&val))?;
 			},
@@ -520,6 +530,11 @@ impl<Cost: CostType> Interpreter<Cost> {
 				let len = ext.extcodesize(&address)?;
 				stack.push(U256::from(len));
 			},
+			instructions::EXTCODEHASH => {
+				let address = u256_to_address(&stack.pop_back());
+				let hash = ext.extcodehash(&address)?;
+				stack.push(U256::from(&*hash));
+			},
 			instructions::CALLDATACOPY => {
 				Self::copy_data_to_memory(&mut self.mem, stack, params.data.as_ref().map_or_else(|| &[] as &[u8], |d| &*d as &[u8]));
 			},
@@ -775,6 +790,57 @@ impl<Cost: CostType> Interpreter<Cost> {
 				};
 				stack.push(byte);
 			},
+			instructions::SHL => {
+				const CONST_256: U256 = U256([256, 0, 0, 0]);
+
+				let shift = stack.pop_back();
+				let value = stack.pop_back();
+
+				let result = if shift >= CONST_256 {
+					U256::zero()
+				} else {
+					value << (shift.as_u32() as usize)
+				};
+				stack.push(result);
+			},
+			instructions::SHR => {
+				const CONST_256: U256 = U256([256, 0, 0, 0]);
+
+				let shift = stack.pop_back();
+				let value = stack.pop_back();
+
+				let result = if shift >= CONST_256 {
+					U256::zero()
+				} else {
+					value >> (shift.as_u32() as usize)
+				};
+				stack.push(result);
+			},
+			instructions::SAR => {
+				const CONST_256: U256 = U256([256, 0, 0, 0]);
+				const CONST_HIBIT: U256 = U256([0, 0, 0, 0x8000000000000000]);
+
+				let shift = stack.pop_back();
+				let value = stack.pop_back();
+				let sign = value & CONST_HIBIT != U256::zero();
+
+				let result = if shift >= CONST_256 {
+					if sign {
+						U256::max_value()
+					} else {
+						U256::zero()
+					}
+				} else {
+					let shift = shift.as_u32() as usize;
+					if sign {
+						// Shift the complement so the vacated high bits are filled with ones.
+						!(!value >> shift)
+					} else {
+						value >> shift
+					}
+				};
+				stack.push(result);
+			},
 			instructions::ADDMOD => {
 				let a = stack.pop_back();
 				let b = stack.pop_back();
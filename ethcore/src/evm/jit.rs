@@ -140,7 +140,8 @@ impl<'a> evmjit::Ext for ExtAdapter<'a> {
 		let old_value = self.ext.storage_at(&key);
 		// if SSTORE nonzero -> zero, increment refund count
 		if !old_value.is_zero() && value.is_zero() {
-			self.ext.inc_sstore_clears();
+			let sstore_clears_schedule = self.ext.schedule().sstore_refund_gas;
+			self.ext.add_sstore_refund(sstore_clears_schedule);
 		}
 		self.ext.set_storage(key, value);
 	}
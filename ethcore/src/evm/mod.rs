@@ -23,9 +23,9 @@ pub mod interpreter;
 pub mod factory;
 pub mod schedule;
 pub mod wasm;
+pub mod instructions;
 
 mod vmtype;
-mod instructions;
 #[cfg(feature = "jit" )]
 mod jit;
 
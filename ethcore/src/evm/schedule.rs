@@ -27,6 +27,10 @@ pub struct Schedule {
 	pub have_create2: bool,
 	/// Does it have a REVERT instruction
 	pub have_revert: bool,
+	/// Does it have SHL, SHR and SAR instructions (EIP-145)
+	pub have_bitwise_shifting: bool,
+	/// Does it have an EXTCODEHASH instruction (EIP-1052)
+	pub have_extcodehash: bool,
 	/// VM stack limit
 	pub stack_limit: usize,
 	/// Max number of nested calls/creates
@@ -49,6 +53,10 @@ pub struct Schedule {
 	pub sstore_reset_gas: usize,
 	/// Gas refund for `SSTORE` clearing (when `storage!=0`, `new==0`)
 	pub sstore_refund_gas: usize,
+	/// Gas price for `SSTORE` on a slot already written in this transaction (EIP-1283)
+	pub sstore_dirty_gas: usize,
+	/// Use net gas metering for `SSTORE` (EIP-1283)
+	pub eip1283: bool,
 	/// Gas price for `JUMPDEST` opcode
 	pub jumpdest_gas: usize,
 	/// Gas price for `LOG*`
@@ -89,6 +97,8 @@ pub struct Schedule {
 	pub copy_gas: usize,
 	/// Price of EXTCODESIZE
 	pub extcodesize_gas: usize,
+	/// Price of EXTCODEHASH
+	pub extcodehash_gas: usize,
 	/// Base price of EXTCODECOPY
 	pub extcodecopy_base_gas: usize,
 	/// Price of BALANCE
@@ -141,6 +151,8 @@ impl Schedule {
 			have_delegate_call: true,
 			have_create2: false,
 			have_revert: false,
+			have_bitwise_shifting: false,
+			have_extcodehash: false,
 			stack_limit: 1024,
 			max_depth: 1024,
 			tier_step_gas: [0, 2, 3, 5, 8, 10, 20, 0],
@@ -152,6 +164,8 @@ impl Schedule {
 			sstore_set_gas: 20000,
 			sstore_reset_gas: 5000,
 			sstore_refund_gas: 15000,
+			sstore_dirty_gas: 200,
+			eip1283: false,
 			jumpdest_gas: 1,
 			log_gas: 375,
 			log_data_gas: 8,
@@ -172,6 +186,7 @@ impl Schedule {
 			tx_data_non_zero_gas: 68,
 			copy_gas: 3,
 			extcodesize_gas: 700,
+			extcodehash_gas: 400,
 			extcodecopy_base_gas: 700,
 			balance_gas: 400,
 			suicide_gas: 5000,
@@ -194,9 +209,12 @@ impl Schedule {
 
 	/// Apply common spec config parameters to the schedule.
  	pub fn apply_params(&mut self, block_number: u64, params: &CommonParams) {
-		self.have_create2 = block_number >= params.eip86_transition;
+		self.have_create2 = block_number >= params.eip1014_transition;
 		self.have_revert = block_number >= params.eip140_transition;
 		self.have_static_call = block_number >= params.eip214_transition;
+		self.have_bitwise_shifting = block_number >= params.eip145_transition;
+		self.have_extcodehash = block_number >= params.eip1052_transition;
+		self.eip1283 = block_number >= params.eip1283_transition;
 		if block_number >= params.eip210_transition {
 			self.blockhash_gas = 350;
 		}
@@ -208,19 +226,30 @@ impl Schedule {
 	/// Schedule for the Metropolis of the Ethereum main net.
 	pub fn new_metropolis() -> Schedule {
 		let mut schedule = Self::new_post_eip150(24576, true, true, true);
-		schedule.have_create2 = true;
 		schedule.have_revert = true;
 		schedule.have_static_call = true;
 		schedule.blockhash_gas = 350;
 		schedule
 	}
 
+	/// Schedule for the Constantinople fork of the Ethereum main net.
+	pub fn new_constantinople() -> Schedule {
+		let mut schedule = Self::new_metropolis();
+		schedule.have_create2 = true;
+		schedule.have_bitwise_shifting = true;
+		schedule.have_extcodehash = true;
+		schedule.eip1283 = true;
+		schedule
+	}
+
 	fn new(efcd: bool, hdc: bool, tcg: usize) -> Schedule {
 		Schedule {
 			exceptional_failed_code_deposit: efcd,
 			have_delegate_call: hdc,
 			have_create2: false,
 			have_revert: false,
+			have_bitwise_shifting: false,
+			have_extcodehash: false,
 			stack_limit: 1024,
 			max_depth: 1024,
 			tier_step_gas: [0, 2, 3, 5, 8, 10, 20, 0],
@@ -232,6 +261,8 @@ impl Schedule {
 			sstore_set_gas: 20000,
 			sstore_reset_gas: 5000,
 			sstore_refund_gas: 15000,
+			sstore_dirty_gas: 200,
+			eip1283: false,
 			jumpdest_gas: 1,
 			log_gas: 375,
 			log_data_gas: 8,
@@ -252,6 +283,7 @@ impl Schedule {
 			tx_data_non_zero_gas: 68,
 			copy_gas: 3,
 			extcodesize_gas: 20,
+			extcodehash_gas: 400,
 			extcodecopy_base_gas: 20,
 			balance_gas: 20,
 			suicide_gas: 0,
@@ -54,9 +54,10 @@ pub struct FakeCall {
 #[derive(Default)]
 pub struct FakeExt {
 	pub store: HashMap<H256, H256>,
+	pub initial_store: HashMap<H256, H256>,
 	pub suicides: HashSet<Address>,
 	pub calls: HashSet<FakeCall>,
-	sstore_clears: usize,
+	sstore_clears: i64,
 	depth: usize,
 	blockhashes: HashMap<U256, H256>,
 	codes: HashMap<Address, Arc<Bytes>>,
@@ -79,6 +80,12 @@ impl FakeExt {
 	pub fn new() -> Self {
 		FakeExt::default()
 	}
+
+	pub fn new_constantinople() -> Self {
+		let mut ext = FakeExt::default();
+		ext.schedule = Schedule::new_constantinople();
+		ext
+	}
 }
 
 impl Default for Schedule {
@@ -88,6 +95,10 @@ impl Default for Schedule {
 }
 
 impl Ext for FakeExt {
+	fn initial_storage_at(&self, key: &H256) -> evm::Result<H256> {
+		Ok(self.initial_store.get(key).unwrap_or(&H256::new()).clone())
+	}
+
 	fn storage_at(&self, key: &H256) -> evm::Result<H256> {
 		Ok(self.store.get(key).unwrap_or(&H256::new()).clone())
 	}
@@ -161,6 +172,10 @@ impl Ext for FakeExt {
 		Ok(self.codes.get(address).map_or(0, |c| c.len()))
 	}
 
+	fn extcodehash(&self, address: &Address) -> evm::Result<H256> {
+		Ok(self.codes.get(address).map_or(H256::zero(), |c| c.sha3()))
+	}
+
 	fn log(&mut self, topics: Vec<H256>, data: &[u8]) -> evm::Result<()> {
 		self.logs.push(FakeLogEntry {
 			topics: topics,
@@ -190,8 +205,12 @@ impl Ext for FakeExt {
 		self.depth
 	}
 
-	fn inc_sstore_clears(&mut self) {
-		self.sstore_clears += 1;
+	fn add_sstore_refund(&mut self, value: usize) {
+		self.sstore_clears += value as i64;
+	}
+
+	fn sub_sstore_refund(&mut self, value: usize) {
+		self.sstore_clears -= value as i64;
 	}
 }
 
@@ -973,7 +992,7 @@ fn test_jumps(factory: super::Factory) {
 		test_finalize(vm.exec(params, &mut ext)).unwrap()
 	};
 
-	assert_eq!(ext.sstore_clears, 1);
+	assert_eq!(ext.sstore_clears, ext.schedule().sstore_refund_gas as i64);
 	assert_store(&ext, 0, "0000000000000000000000000000000000000000000000000000000000000000"); // 5!
 	assert_store(&ext, 1, "0000000000000000000000000000000000000000000000000000000000000078"); // 5!
 	assert_eq!(gas_left, U256::from(54_117));
@@ -1024,6 +1043,247 @@ fn test_calls(factory: super::Factory) {
 	assert_eq!(ext.calls.len(), 2);
 }
 
+#[test] // JIT does not support Constantinople instructions
+fn test_shift_instructions_int() {
+	// SHL
+	push_two_pop_one_constantinople_test(
+		0x1b,
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		"00",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		0x1b,
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		"01",
+		"0000000000000000000000000000000000000000000000000000000000000002");
+	push_two_pop_one_constantinople_test(
+		0x1b,
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		"ff",
+		"8000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		0x1b,
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		"0100",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		0x1b,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"01",
+		"fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe");
+	// SHR
+	push_two_pop_one_constantinople_test(
+		0x1c,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"01",
+		"4000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		0x1c,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"ff",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		0x1c,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"0100",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	// SAR
+	push_two_pop_one_constantinople_test(
+		0x1d,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"01",
+		"c000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		0x1d,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"ff",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		0x1d,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"0100",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		0x1d,
+		"4000000000000000000000000000000000000000000000000000000000000000",
+		"fe",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		0x1d,
+		"7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"0100",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+}
+
+fn push_two_pop_one_constantinople_test(opcode: u8, push1: &str, push2: &str, result: &str) {
+	let push1 = push1.from_hex().unwrap();
+	let push2 = push2.from_hex().unwrap();
+	assert!(push1.len() <= 32 && push1.len() != 0);
+	assert!(push2.len() <= 32 && push2.len() != 0);
+
+	let mut code = Vec::new();
+	code.push(0x60 + ((push1.len() - 1) as u8));
+	code.extend(push1);
+	code.push(0x60 + ((push2.len() - 1) as u8));
+	code.extend(push2);
+	code.push(opcode);
+	code.extend(vec![0x60, 0x00, 0x55]);
+
+	let factory = super::Factory::new(VMType::Interpreter, 1024 * 32);
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_constantinople();
+
+	let _ = {
+		let mut vm = factory.create(params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap()
+	};
+
+	assert_store(&ext, 0, result);
+}
+
+#[test]
+fn test_shift_instructions_before_constantinople_int() {
+	let factory = super::Factory::new(VMType::Interpreter, 1024 * 32);
+	let code = "600160011b600055".from_hex().unwrap();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new();
+
+	let err = {
+		let mut vm = factory.create(params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap_err()
+	};
+
+	match err {
+		evm::Error::BadInstruction { instruction: 0x1b } => (),
+		_ => assert!(false, "Expected bad instruction")
+	}
+}
+
+#[test]
+fn test_extcodehash_int() {
+	let factory = super::Factory::new(VMType::Interpreter, 1024 * 32);
+	// EXTCODEHASH of 0x155 into slot 0, EXTCODEHASH of 0x156 (no code) into slot 1.
+	let code = "6101553f6000556101563f600155".from_hex().unwrap();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_constantinople();
+	ext.codes.insert(Address::from(0x155), Arc::new("6001600055".from_hex().unwrap()));
+
+	let gas_left = {
+		let mut vm = factory.create(params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap()
+	};
+
+	assert_eq!(ext.store.get(&H256::from(0u64)).unwrap(), &"6001600055".from_hex().unwrap().sha3());
+	assert_store(&ext, 1, "0000000000000000000000000000000000000000000000000000000000000000");
+	// Storing zero over zero only costs `sstore_dirty_gas` under EIP-1283.
+	assert_eq!(gas_left, U256::from(100_000 - 3 - 400 - 3 - 20_000 - 3 - 400 - 3 - 200));
+}
+
+#[test]
+fn test_create2_int() {
+	let factory = super::Factory::new(VMType::Interpreter, 1024 * 32);
+	// CREATE2 with salt 0x2a and 32 bytes of (zeroed) init code.
+	let code = "602a602060006000fb".from_hex().unwrap();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_constantinople();
+	ext.balances.insert(params.address.clone(), U256::zero());
+
+	let gas_left = {
+		let mut vm = factory.create(params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap()
+	};
+
+	// 4 pushes, then create gas, one word of init code hashing and one word of memory.
+	let needed = 3 * 4 + 32_000 + 6 + 3;
+	let remaining = 100_000 - needed;
+	let provided = remaining - remaining / 64;
+	assert_set_contains(&ext.calls, &FakeCall {
+		call_type: FakeCallType::Create,
+		gas: U256::from(provided),
+		sender_address: None,
+		receive_address: None,
+		value: Some(U256::zero()),
+		data: vec![0; 32],
+		code_address: None
+	});
+	assert_eq!(gas_left, U256::from(remaining - provided));
+}
+
+#[test]
+fn test_create2_before_constantinople_int() {
+	let factory = super::Factory::new(VMType::Interpreter, 1024 * 32);
+	let code = "602a602060006000fb".from_hex().unwrap();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new();
+	ext.schedule = Schedule::new_metropolis();
+
+	let err = {
+		let mut vm = factory.create(params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap_err()
+	};
+
+	match err {
+		evm::Error::BadInstruction { instruction: 0xfb } => (),
+		_ => assert!(false, "Expected bad instruction")
+	}
+}
+
+#[test]
+fn test_eip1283_sstore_gas_int() {
+	// (code, gas used, refund, original value) from EIP-1283.
+	let cases: &[(&str, usize, i64, u64)] = &[
+		("60006000556000600055", 412, 0, 0),
+		("60006000556001600055", 20212, 0, 0),
+		("60016000556000600055", 20212, 19800, 0),
+		("60016000556002600055", 20212, 0, 0),
+		("60016000556001600055", 20212, 0, 0),
+		("60006000556000600055", 5212, 15000, 1),
+		("60006000556001600055", 5212, 4800, 1),
+		("60006000556002600055", 5212, 0, 1),
+		("60026000556000600055", 5212, 15000, 1),
+		("60026000556003600055", 5212, 0, 1),
+		("60026000556001600055", 5212, 4800, 1),
+		("60026000556002600055", 5212, 0, 1),
+		("60016000556000600055", 5212, 15000, 1),
+		("60016000556002600055", 5212, 0, 1),
+		("60016000556001600055", 412, 0, 1),
+		("600160005560006000556001600055", 40218, 19800, 0),
+		("600060005560016000556000600055", 10218, 19800, 1),
+	];
+
+	for &(code, gas_used, refund, original) in cases {
+		let factory = super::Factory::new(VMType::Interpreter, 1024 * 32);
+		let mut params = ActionParams::default();
+		params.gas = U256::from(100_000);
+		params.code = Some(Arc::new(code.from_hex().unwrap()));
+		let mut ext = FakeExt::new_constantinople();
+		ext.store.insert(H256::from(0u64), H256::from(original));
+		ext.initial_store.insert(H256::from(0u64), H256::from(original));
+
+		let gas_left = {
+			let mut vm = factory.create(params.gas);
+			test_finalize(vm.exec(params, &mut ext)).unwrap()
+		};
+
+		assert_eq!(gas_left, U256::from(100_000 - gas_used), "gas used by {}", code);
+		assert_eq!(ext.sstore_clears, refund, "refund for {}", code);
+	}
+}
+
 fn assert_set_contains<T : Debug + Eq + PartialEq + Hash>(set: &HashSet<T>, val: &T) {
 	let contains = set.contains(val);
 	if !contains {
@@ -60,6 +60,15 @@ pub fn contract_address(address_scheme: CreateContractAddress, sender: &Address,
 			&mut buffer[20..].copy_from_slice(&code_hash[..]);
 			(From::from((&buffer[..]).sha3()), Some(code_hash))
 		},
+		CreateContractAddress::FromSenderSaltAndCodeHash(salt) => {
+			let code_hash = code.sha3();
+			let mut buffer = [0u8; 1 + 20 + 32 + 32];
+			buffer[0] = 0xff;
+			&mut buffer[1..(1+20)].copy_from_slice(&sender[..]);
+			&mut buffer[(1+20)..(1+20+32)].copy_from_slice(&salt[..]);
+			&mut buffer[(1+20+32)..].copy_from_slice(&code_hash[..]);
+			(From::from((&buffer[..]).sha3()), Some(code_hash))
+		},
 	}
 }
 
@@ -172,6 +181,9 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 		mut tracer: T,
 		mut vm_tracer: V
 	) -> Result<Executed, ExecutionError> where T: Tracer, V: VMTracer {
+		// original storage values (EIP-1283) are tracked per transaction.
+		self.state.clear_original_storage();
+
 		let sender = t.sender();
 		let nonce = self.state.nonce(&sender)?;
 
@@ -340,7 +352,7 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 
 			let trace_info = tracer.prepare_trace_call(&params);
 
-			let cost = builtin.cost(data);
+			let cost = builtin.cost(data, self.info.number);
 			if cost <= params.gas {
 				if let Err(e) = builtin.execute(data, &mut output) {
 					self.state.revert_to_checkpoint();
@@ -503,8 +515,9 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 	) -> ExecutionResult {
 		let schedule = self.engine.schedule(self.info.number);
 
-		// refunds from SSTORE nonzero -> zero
-		let sstore_refunds = U256::from(schedule.sstore_refund_gas) * substate.sstore_clears_count;
+		// refunds from SSTORE
+		assert!(substate.sstore_clears_refund >= 0, "On transaction level, sstore clears refund cannot go below zero.");
+		let sstore_refunds = U256::from(substate.sstore_clears_refund as u64);
 		// refunds from contract suicides
 		let suicide_refunds = U256::from(schedule.suicide_refund_gas) * U256::from(substate.suicides.len());
 		let refunds_bound = sstore_refunds + suicide_refunds;
@@ -622,6 +635,30 @@ mod tests {
 		assert_eq!(expected_address, contract_address(CreateContractAddress::FromSenderAndNonce, &address, &U256::from(88), &[]).0);
 	}
 
+	#[test]
+	fn test_create2_contract_address() {
+		// (sender, salt, init code, address) from EIP-1014.
+		let cases = [
+			("0000000000000000000000000000000000000000", "0000000000000000000000000000000000000000000000000000000000000000", "00", "4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38"),
+			("deadbeef00000000000000000000000000000000", "0000000000000000000000000000000000000000000000000000000000000000", "00", "b928f69bb1d91cd65274e3c79d8986362984fda3"),
+			("deadbeef00000000000000000000000000000000", "000000000000000000000000feed000000000000000000000000000000000000", "00", "d04116cdd17bebe565eb2422f2497e06cc1c9833"),
+			("0000000000000000000000000000000000000000", "0000000000000000000000000000000000000000000000000000000000000000", "deadbeef", "70f2b2914a2a4b783faefb75f459a580616fcb5e"),
+			("00000000000000000000000000000000deadbeef", "00000000000000000000000000000000000000000000000000000000cafebabe", "deadbeef", "60f3f640a8508fc6a86d45df051962668e1e8ac7"),
+			("00000000000000000000000000000000deadbeef", "00000000000000000000000000000000000000000000000000000000cafebabe", "deadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef", "1d8bfdc5d46dc4f61d6b6115972536ebe6a8854c"),
+			("0000000000000000000000000000000000000000", "0000000000000000000000000000000000000000000000000000000000000000", "", "e33c0c7f7df4809055c3eba6c09cfe4baf1bd9e0"),
+		];
+
+		for &(sender, salt, code, expected) in cases.iter() {
+			let sender = Address::from_str(sender).unwrap();
+			let salt = H256::from_str(salt).unwrap();
+			let code = code.from_hex().unwrap();
+			let expected_address = Address::from_str(expected).unwrap();
+			let (address, code_hash) = contract_address(CreateContractAddress::FromSenderSaltAndCodeHash(salt), &sender, &U256::zero(), &code);
+			assert_eq!(expected_address, address);
+			assert_eq!(Some(code.sha3()), code_hash);
+		}
+	}
+
 	// TODO: replace params with transactions!
 	evm_test!{test_sender_balance: test_sender_balance_jit, test_sender_balance_int}
 	fn test_sender_balance(factory: Factory) {
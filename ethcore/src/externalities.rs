@@ -109,6 +109,10 @@ impl<'a, T: 'a, V: 'a, B: 'a, E: 'a> Externalities<'a, T, V, B, E>
 impl<'a, T: 'a, V: 'a, B: 'a, E: 'a> Ext for Externalities<'a, T, V, B, E>
 	where T: Tracer, V: VMTracer, B: StateBackend, E: Engine + ?Sized
 {
+	fn initial_storage_at(&self, key: &H256) -> evm::Result<H256> {
+		self.state.original_storage_at(&self.origin_info.address, key).map_err(Into::into)
+	}
+
 	fn storage_at(&self, key: &H256) -> evm::Result<H256> {
 		self.state.storage_at(&self.origin_info.address, key).map_err(Into::into)
 	}
@@ -282,6 +286,14 @@ impl<'a, T: 'a, V: 'a, B: 'a, E: 'a> Ext for Externalities<'a, T, V, B, E>
 		Ok(self.state.code_size(address)?.unwrap_or(0))
 	}
 
+	fn extcodehash(&self, address: &Address) -> evm::Result<H256> {
+		if self.state.exists_and_not_null(address)? {
+			Ok(self.state.code_hash(address)?)
+		} else {
+			Ok(H256::zero())
+		}
+	}
+
 	#[cfg_attr(feature="dev", allow(match_ref_pats))]
 	fn ret(mut self, gas: &U256, data: &ReturnData) -> evm::Result<U256>
 		where Self: Sized {
@@ -375,8 +387,12 @@ impl<'a, T: 'a, V: 'a, B: 'a, E: 'a> Ext for Externalities<'a, T, V, B, E>
 		self.depth
 	}
 
-	fn inc_sstore_clears(&mut self) {
-		self.substate.sstore_clears_count = self.substate.sstore_clears_count + U256::one();
+	fn add_sstore_refund(&mut self, value: usize) {
+		self.substate.sstore_clears_refund += value as i64;
+	}
+
+	fn sub_sstore_refund(&mut self, value: usize) {
+		self.substate.sstore_clears_refund -= value as i64;
 	}
 
 	fn trace_next_instruction(&mut self, pc: usize, instruction: u8) -> bool {
@@ -88,6 +88,10 @@ impl<'a, T: 'a, V: 'a, B: 'a, E: 'a> TestExt<'a, T, V, B, E>
 impl<'a, T: 'a, V: 'a, B: 'a, E: 'a> Ext for TestExt<'a, T, V, B, E>
 	where T: Tracer, V: VMTracer, B: StateBackend, E: Engine + ?Sized
 {
+	fn initial_storage_at(&self, key: &H256) -> evm::Result<H256> {
+		self.ext.initial_storage_at(key)
+	}
+
 	fn storage_at(&self, key: &H256) -> evm::Result<H256> {
 		self.ext.storage_at(key)
 	}
@@ -154,6 +158,10 @@ impl<'a, T: 'a, V: 'a, B: 'a, E: 'a> Ext for TestExt<'a, T, V, B, E>
 		self.ext.extcodesize(address)
 	}
 
+	fn extcodehash(&self, address: &Address) -> evm::Result<H256> {
+		self.ext.extcodehash(address)
+	}
+
 	fn log(&mut self, topics: Vec<H256>, data: &[u8]) -> evm::Result<()> {
 		self.ext.log(topics, data)
 	}
@@ -178,8 +186,12 @@ impl<'a, T: 'a, V: 'a, B: 'a, E: 'a> Ext for TestExt<'a, T, V, B, E>
 		0
 	}
 
-	fn inc_sstore_clears(&mut self) {
-		self.ext.inc_sstore_clears()
+	fn add_sstore_refund(&mut self, value: usize) {
+		self.ext.add_sstore_refund(value)
+	}
+
+	fn sub_sstore_refund(&mut self, value: usize) {
+		self.ext.sub_sstore_refund(value)
 	}
 }
 
@@ -187,11 +199,16 @@ fn do_json_test(json_data: &[u8]) -> Vec<String> {
 	let vms = VMType::all();
 	vms
 		.iter()
-		.flat_map(|vm| do_json_test_for(vm, json_data))
+		.flat_map(|vm| do_json_test_for(vm, json_data, || TestEngine::new(1)))
 		.collect()
 }
 
-fn do_json_test_for(vm_type: &VMType, json_data: &[u8]) -> Vec<String> {
+fn do_constantinople_json_test(json_data: &[u8]) -> Vec<String> {
+	// JIT does not support Constantinople instructions.
+	do_json_test_for(&VMType::Interpreter, json_data, TestEngine::new_constantinople)
+}
+
+fn do_json_test_for<F>(vm_type: &VMType, json_data: &[u8], new_engine: F) -> Vec<String> where F: Fn() -> TestEngine {
 	let tests = ethjson::vm::Test::load(json_data).unwrap();
 	let mut failed = Vec::new();
 
@@ -221,7 +238,7 @@ fn do_json_test_for(vm_type: &VMType, json_data: &[u8]) -> Vec<String> {
 		let mut state = get_temp_state();
 		state.populate_from(From::from(vm.pre_state.clone()));
 		let info = From::from(vm.env);
-		let engine = TestEngine::new(1);
+		let engine = new_engine();
 		let params = ActionParams::from(vm.transaction);
 
 		let mut substate = Substate::new();
@@ -303,3 +320,9 @@ declare_test!{ExecutiveTests_vmPushDupSwapTest, "VMTests/vmPushDupSwapTest"}
 declare_test!{ExecutiveTests_vmSha3Test, "VMTests/vmSha3Test"}
 declare_test!{ExecutiveTests_vmSystemOperationsTest, "VMTests/vmSystemOperationsTest"}
 declare_test!{ExecutiveTests_vmtests, "VMTests/vmtests"}
+
+#[test]
+#[allow(non_snake_case)]
+fn ExecutiveTests_vmConstantinopleTest() {
+	run_test_path(::std::path::Path::new("res/ethereum/constantinople_tests/VMTests/vmConstantinopleTest"), &[], do_constantinople_json_test);
+}
@@ -30,6 +30,7 @@ lazy_static! {
 	pub static ref EIP150: Spec = ethereum::new_eip150_test();
 	pub static ref EIP161: Spec = ethereum::new_eip161_test();
 	pub static ref _METROPOLIS: Spec = ethereum::new_metropolis_test();
+	pub static ref CONSTANTINOPLE: Spec = ethereum::new_constantinople_test();
 }
 
 pub fn json_chain_test(json_data: &[u8]) -> Vec<String> {
@@ -51,6 +52,7 @@ pub fn json_chain_test(json_data: &[u8]) -> Vec<String> {
 					ForkSpec::EIP150 => &EIP150.engine,
 					ForkSpec::EIP158 => &EIP161.engine,
 					ForkSpec::Metropolis => continue,
+					ForkSpec::Constantinople => &CONSTANTINOPLE.engine,
 				};
 
 				for (i, state) in states.into_iter().enumerate() {
@@ -63,6 +65,8 @@ pub fn json_chain_test(json_data: &[u8]) -> Vec<String> {
 					if transaction.verify_basic(true, None, env.number >= engine.params().eip86_transition).is_ok() {
 						state.commit().expect(&format!("State test {} failed due to internal error.", name));
 						let _res = state.apply(&env, &**engine, &transaction, false);
+						// no intermediate state root is committed after EIP-98.
+						state.commit().expect(&format!("State test {} failed due to internal error.", name));
 					} else {
 						let _rest = state.commit();
 					}
@@ -127,5 +131,15 @@ mod state_tests {
 	declare_test!{GeneralStateTest_stWalletTest, "GeneralStateTests/stWalletTest/"}
 	declare_test!{GeneralStateTest_stZeroCallsRevert, "GeneralStateTests/stZeroCallsRevert/"}
 	declare_test!{GeneralStateTest_stZeroCallsTest, "GeneralStateTests/stZeroCallsTest/"}
+
+	#[test]
+	#[allow(non_snake_case)]
+	fn GeneralStateTest_stConstantinopleTest() {
+		::json_tests::test_common::run_test_path(
+			::std::path::Path::new("res/ethereum/constantinople_tests/GeneralStateTests/stConstantinopleTest"),
+			&[],
+			do_json_test
+		);
+	}
 }
 
@@ -156,6 +156,7 @@ mod externalities;
 mod blockchain;
 mod types;
 mod factory;
+mod tx_filter;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,344 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Built-in multi-threaded Ethash CPU miner, meant for development and private chains.
+
+use std::sync::{Arc, Weak};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
+use std::thread::{self, JoinHandle};
+use std::path::PathBuf;
+use std::io;
+
+use byteorder::{BigEndian, ByteOrder};
+use ethash::{EthashManager, ProofOfWork, Light, Full};
+use ethereum::ethash::Ethash;
+use util::{H64, H256, U256, Mutex, Condvar};
+use miner::{self, Miner, MinerService};
+use client::Client;
+use block::IsBlock;
+use rand;
+use rlp::encode;
+
+/// Below this difficulty solutions are searched with the light cache,
+/// as generating the full dataset would take longer than finding a solution.
+const LIGHT_MINING_DIFFICULTY: u64 = 1 << 16;
+
+/// Configures the CPU miner.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Options {
+	/// Number of mining threads
+	pub threads: usize,
+	/// Directory for the Ethash caches and datasets
+	pub cache_dir: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Work {
+	pow_hash: H256,
+	difficulty: U256,
+	number: u64,
+}
+
+enum Dataset {
+	Light(Arc<Light>),
+	Full(Arc<Full>),
+}
+
+impl Dataset {
+	fn compute(&self, header_hash: &H256, nonce: u64) -> ProofOfWork {
+		match *self {
+			Dataset::Light(ref light) => light.compute(&header_hash.0, nonce),
+			Dataset::Full(ref full) => full.compute(&header_hash.0, nonce),
+		}
+	}
+}
+
+/// Search nonces starting at `nonce` until a solution for `work` is found or `abort` returns true.
+/// Returns the nonce and mix hash of the solution.
+fn search<F>(dataset: &Dataset, work: &Work, mut nonce: u64, abort: F) -> Option<(u64, H256)> where F: Fn() -> bool {
+	let boundary = Ethash::difficulty_to_boundary(&work.difficulty);
+	while !abort() {
+		let pow = dataset.compute(&work.pow_hash, nonce);
+		if H256(pow.value) <= boundary {
+			return Some((nonce, H256(pow.mix_hash)));
+		}
+		nonce = nonce.wrapping_add(1);
+	}
+	None
+}
+
+struct Shared {
+	work: Mutex<Option<Work>>,
+	// bumped whenever the work changes, so that workers can cheaply check if they should restart.
+	generation: AtomicUsize,
+	new_work: Condvar,
+	exit: AtomicBool,
+	pow: EthashManager,
+	threads: usize,
+	miner: Weak<Miner>,
+	client: Weak<Client>,
+}
+
+impl Shared {
+	fn set_work(&self, work: Work) {
+		let mut current = self.work.lock();
+		if current.as_ref() == Some(&work) { return; }
+		trace!(target: "miner", "CPU miner: new work {} at #{}", work.pow_hash, work.number);
+		*current = Some(work);
+		self.generation.fetch_add(1, AtomicOrdering::SeqCst);
+		self.new_work.notify_all();
+	}
+
+	/// Wait for work of a generation other than `last`. `None` if the miner is shutting down.
+	fn next_work(&self, last: Option<usize>) -> Option<(usize, Work)> {
+		let mut work = self.work.lock();
+		loop {
+			if self.exit.load(AtomicOrdering::SeqCst) {
+				return None;
+			}
+			let generation = self.generation.load(AtomicOrdering::SeqCst);
+			if let Some(ref w) = *work {
+				if Some(generation) != last {
+					return Some((generation, w.clone()));
+				}
+			}
+			self.new_work.wait(&mut work);
+		}
+	}
+
+	fn is_stale(&self, generation: usize) -> bool {
+		self.exit.load(AtomicOrdering::SeqCst) || self.generation.load(AtomicOrdering::SeqCst) != generation
+	}
+
+	fn dataset(&self, work: &Work) -> io::Result<Dataset> {
+		if work.difficulty < LIGHT_MINING_DIFFICULTY.into() {
+			Ok(Dataset::Light(self.pow.light(work.number)))
+		} else {
+			self.pow.full(work.number, self.threads).map(Dataset::Full)
+		}
+	}
+
+	fn submit(&self, generation: usize, work: &Work, nonce: u64, mix_hash: H256) {
+		{
+			// stop the other workers, they would only find competing solutions.
+			let mut current = self.work.lock();
+			if self.generation.load(AtomicOrdering::SeqCst) != generation {
+				return;
+			}
+			*current = None;
+			self.generation.fetch_add(1, AtomicOrdering::SeqCst);
+		}
+
+		let mut nonce_bytes = [0u8; 8];
+		BigEndian::write_u64(&mut nonce_bytes, nonce);
+		let nonce = H64::from(nonce_bytes);
+		debug!(target: "miner", "CPU miner: found solution for #{}: nonce={}, mix_hash={}", work.number, nonce, mix_hash);
+
+		if let (Some(client), Some(miner)) = (self.client.upgrade(), self.miner.upgrade()) {
+			let seal = vec![encode(&mix_hash).into_vec(), encode(&nonce).into_vec()];
+			if let Err(e) = miner.submit_seal(&*client, work.pow_hash, seal) {
+				warn!(target: "miner", "CPU miner: submit_seal error: {:?}", e);
+			}
+		}
+	}
+}
+
+fn mine(shared: Arc<Shared>) {
+	let mut nonce: u64 = rand::random();
+	let mut last = None;
+	while let Some((generation, work)) = shared.next_work(last) {
+		last = Some(generation);
+		let dataset = match shared.dataset(&work) {
+			Ok(dataset) => dataset,
+			Err(e) => {
+				warn!(target: "miner", "CPU miner: unable to prepare Ethash dataset for #{}: {}", work.number, e);
+				continue;
+			},
+		};
+
+		if let Some((solution, mix_hash)) = search(&dataset, &work, nonce, || shared.is_stale(generation)) {
+			shared.submit(generation, &work, solution, mix_hash);
+			nonce = solution.wrapping_add(1);
+		} else {
+			nonce = rand::random();
+		}
+	}
+}
+
+/// Ethash CPU miner. Searches for solutions of the miner's sealing work
+/// and submits them back to it.
+pub struct CpuMiner {
+	shared: Arc<Shared>,
+	workers: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl CpuMiner {
+	/// Start the mining threads, given the miner and client.
+	pub fn start(options: &Options, miner: Weak<Miner>, client: Weak<Client>) -> Result<CpuMiner, io::Error> {
+		let shared = Arc::new(Shared {
+			work: Mutex::new(None),
+			generation: AtomicUsize::new(0),
+			new_work: Condvar::new(),
+			exit: AtomicBool::new(false),
+			pow: EthashManager::new(&options.cache_dir),
+			threads: options.threads,
+			miner: miner,
+			client: client,
+		});
+
+		let mut workers = Vec::with_capacity(options.threads);
+		for i in 0..options.threads {
+			let worker_shared = shared.clone();
+			let worker = thread::Builder::new()
+				.name(format!("CPU Miner #{}", i))
+				.spawn(move || mine(worker_shared));
+
+			match worker {
+				Ok(worker) => workers.push(worker),
+				Err(e) => {
+					// make the already spawned workers quit.
+					drop(CpuMiner { shared: shared, workers: Mutex::new(workers) });
+					return Err(e);
+				},
+			}
+		}
+
+		Ok(CpuMiner {
+			shared: shared,
+			workers: Mutex::new(workers),
+		})
+	}
+
+	/// Start mining and subscribe to the new work of the miner.
+	pub fn register(options: &Options, miner: Arc<Miner>, client: Weak<Client>) -> Result<(), io::Error> {
+		let cpu_miner = CpuMiner::start(options, Arc::downgrade(&miner), client.clone())?;
+
+		// later work is pushed through notifications.
+		if let Some(client) = client.upgrade() {
+			let work = miner.map_sealing_work(&*client, |b| Work {
+				pow_hash: b.hash(),
+				difficulty: *b.block().header().difficulty(),
+				number: b.block().header().number(),
+			});
+			if let Some(work) = work {
+				cpu_miner.shared.set_work(work);
+			}
+		}
+
+		miner.push_notifier(Box::new(cpu_miner) as Box<miner::NotifyWork>);
+		Ok(())
+	}
+}
+
+impl super::work_notify::NotifyWork for CpuMiner {
+	fn notify(&self, pow_hash: H256, difficulty: U256, number: u64) {
+		self.shared.set_work(Work {
+			pow_hash: pow_hash,
+			difficulty: difficulty,
+			number: number,
+		});
+	}
+}
+
+impl Drop for CpuMiner {
+	fn drop(&mut self) {
+		{
+			let _work = self.shared.work.lock();
+			self.shared.exit.store(true, AtomicOrdering::SeqCst);
+			self.shared.new_work.notify_all();
+		}
+
+		let current = thread::current().id();
+		for worker in self.workers.lock().drain(..) {
+			// the last reference to the miner might be released by a worker itself.
+			if worker.thread().id() != current {
+				let _ = worker.join();
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{env, thread};
+	use std::sync::{Arc, Weak};
+	use std::time::{Duration, Instant};
+	use util::{H256, U256};
+	use ethash::{Light, quick_get_difficulty};
+	use ethereum;
+	use ethereum::ethash::Ethash;
+	use client::{BlockChainClient, Client, ClientConfig};
+	use io::IoChannel;
+	use miner::{Miner, NotifyWork};
+	use super::{CpuMiner, Options, Work, Dataset, search};
+
+	#[test]
+	fn finds_solution_at_low_difficulty() {
+		let light = Arc::new(Light::new(&env::temp_dir(), 0));
+		let work = Work {
+			pow_hash: H256::from(0x1234),
+			difficulty: U256::from(16),
+			number: 0,
+		};
+
+		let (nonce, mix_hash) = search(&Dataset::Light(light.clone()), &work, 0, || false).unwrap();
+
+		let boundary = Ethash::difficulty_to_boundary(&work.difficulty);
+		assert!(H256(quick_get_difficulty(&work.pow_hash.0, nonce, &mix_hash.0)) <= boundary);
+		assert_eq!(H256(light.compute(&work.pow_hash.0, nonce).mix_hash), mix_hash);
+	}
+
+	#[test]
+	fn search_can_be_aborted() {
+		let light = Arc::new(Light::new(&env::temp_dir(), 0));
+		let work = Work {
+			pow_hash: H256::from(0x1234),
+			difficulty: U256::max_value(),
+			number: 0,
+		};
+
+		assert_eq!(search(&Dataset::Light(light), &work, 0, || true), None);
+	}
+
+	#[test]
+	fn workers_stop_on_drop() {
+		let options = Options { threads: 2, cache_dir: env::temp_dir() };
+		let cpu_miner = CpuMiner::start(&options, Weak::new(), Weak::new()).unwrap();
+		cpu_miner.notify(H256::from(1), U256::from(1) << 15, 0);
+		drop(cpu_miner);
+	}
+
+	#[test]
+	fn mines_block_at_low_difficulty() {
+		let spec = ethereum::new_low_difficulty_test();
+		let miner = Arc::new(Miner::with_spec(&spec));
+		let db = Arc::new(::util::kvdb::in_memory(::db::NUM_COLUMNS.unwrap_or(0)));
+		let client = Client::new(ClientConfig::default(), &spec, db, miner.clone(), IoChannel::disconnected()).unwrap();
+
+		let options = Options { threads: 2, cache_dir: env::temp_dir() };
+		CpuMiner::register(&options, miner.clone(), Arc::downgrade(&client)).unwrap();
+
+		let start = Instant::now();
+		while client.chain_info().best_block_number == 0 {
+			assert!(start.elapsed() < Duration::from_secs(120), "no block mined");
+			thread::sleep(Duration::from_millis(100));
+		}
+
+		let header = client.best_block_header();
+		assert_eq!(header.number(), 1);
+		assert_eq!(header.difficulty(), U256::from(16));
+	}
+}
@@ -0,0 +1,71 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Gas price oracle based on the gas prices of transactions in recent blocks.
+//!
+//! Full and light clients gather the corpus differently (local blocks vs. block bodies
+//! fetched from the network), but pick the gas price from it in the same way.
+
+use stats::Corpus;
+use util::U256;
+use client::BlockChainClient;
+
+/// Picks a gas price at a percentile of the gas prices of transactions in recent blocks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GasPriceOracle {
+	/// Percentile (0-100) of the recent gas prices to pick.
+	pub percentile: usize,
+	/// Number of recent blocks to sample.
+	pub blocks: usize,
+}
+
+impl Default for GasPriceOracle {
+	fn default() -> Self {
+		GasPriceOracle {
+			percentile: 50,
+			blocks: 100,
+		}
+	}
+}
+
+impl GasPriceOracle {
+	/// Picks the gas price from a corpus of recent transaction gas prices.
+	/// `None` if the corpus is empty.
+	pub fn gas_price(&self, corpus: &Corpus<U256>) -> Option<U256> {
+		corpus.percentile(self.percentile).cloned()
+	}
+
+	/// Picks the gas price from the recent blocks of the given client.
+	pub fn gas_price_from_chain<C: BlockChainClient + ?Sized>(&self, chain: &C) -> Option<U256> {
+		self.gas_price(&chain.gas_price_corpus(self.blocks))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use stats::Corpus;
+	use util::U256;
+	use super::GasPriceOracle;
+
+	#[test]
+	fn picks_configured_percentile() {
+		let corpus: Corpus<U256> = (1..101u64).map(U256::from).collect();
+
+		assert_eq!(GasPriceOracle::default().gas_price(&corpus), Some(51.into()));
+		assert_eq!(GasPriceOracle { percentile: 10, blocks: 10 }.gas_price(&corpus), Some(11.into()));
+		assert_eq!(GasPriceOracle { percentile: 100, blocks: 10 }.gas_price(&corpus), Some(100.into()));
+	}
+}
@@ -16,9 +16,11 @@
 
 //! Local Transactions List.
 
+use std::collections::HashMap;
 use linked_hash_map::LinkedHashMap;
 use transaction::{SignedTransaction, PendingTransaction};
 use error::TransactionError;
+use header::BlockNumber;
 use util::{U256, H256};
 
 /// Status of local transaction.
@@ -50,11 +52,25 @@ impl Status {
 	}
 }
 
+/// Propagation details of a pending local transaction.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Propagation {
+	/// Block at which the transaction was broadcast last time.
+	pub broadcast_at: BlockNumber,
+	/// Number of times the transaction was rebroadcast because it was not mined.
+	pub rebroadcasts: usize,
+	/// Gas price of the transaction is below the recent inclusion gas price.
+	pub stuck: bool,
+	/// The transaction is waiting to be sent to all peers again.
+	pub due: bool,
+}
+
 /// Keeps track of local transactions that are in the queue or were mined/dropped recently.
 #[derive(Debug)]
 pub struct LocalTransactionsList {
 	max_old: usize,
 	transactions: LinkedHashMap<H256, Status>,
+	propagation: HashMap<H256, Propagation>,
 }
 
 impl Default for LocalTransactionsList {
@@ -68,6 +84,7 @@ impl LocalTransactionsList {
 		LocalTransactionsList {
 			max_old: max_old,
 			transactions: Default::default(),
+			propagation: Default::default(),
 		}
 	}
 
@@ -127,6 +144,57 @@ impl LocalTransactionsList {
 		&self.transactions
 	}
 
+	/// Propagation details of pending transactions.
+	pub fn propagation(&self) -> &HashMap<H256, Propagation> {
+		&self.propagation
+	}
+
+	/// Returns pending transactions which were not mined within `period` blocks since they were
+	/// last broadcast and marks them as due until they are actually rebroadcast.
+	/// Rebroadcasting is disabled if `period` is 0.
+	pub fn due_for_rebroadcast(&mut self, best_block: BlockNumber, period: BlockNumber) -> Vec<H256> {
+		let transactions = &self.transactions;
+		self.propagation.retain(|hash, _| transactions.get(hash) == Some(&Status::Pending));
+
+		let mut due = Vec::new();
+		for (hash, status) in &self.transactions {
+			if *status != Status::Pending {
+				continue;
+			}
+
+			let propagation = self.propagation.entry(*hash).or_insert_with(|| Propagation {
+				broadcast_at: best_block,
+				..Default::default()
+			});
+			if period > 0 && best_block >= propagation.broadcast_at.saturating_add(period) {
+				propagation.due = true;
+				due.push(*hash);
+			}
+		}
+		due
+	}
+
+	/// Counts a transaction which was due as rebroadcast at `best_block`.
+	pub fn mark_rebroadcast(&mut self, hash: &H256, best_block: BlockNumber) {
+		if let Some(propagation) = self.propagation.get_mut(hash) {
+			if propagation.due {
+				propagation.due = false;
+				propagation.broadcast_at = best_block;
+				propagation.rebroadcasts += 1;
+			}
+		}
+	}
+
+	/// Marks a pending transaction as stuck (or not).
+	pub fn mark_stuck(&mut self, hash: &H256, stuck: bool) {
+		if let Some(propagation) = self.propagation.get_mut(hash) {
+			if stuck && !propagation.stuck {
+				warn!(target: "own_tx", "Transaction seems stuck (hash {:?}), its gas price is below recent blocks.", hash);
+			}
+			propagation.stuck = stuck;
+		}
+	}
+
 	fn clear_old(&mut self) {
 		let number_of_old = self.transactions
 			.values()
@@ -155,7 +223,7 @@ mod tests {
 	use util::U256;
 	use ethkey::{Random, Generator};
 	use transaction::{Action, Transaction, SignedTransaction};
-	use super::{LocalTransactionsList, Status};
+	use super::{LocalTransactionsList, Status, Propagation};
 
 	#[test]
 	fn should_add_transaction_as_pending() {
@@ -197,6 +265,39 @@ mod tests {
 		assert!(list.contains(&15.into()));
 	}
 
+	#[test]
+	fn should_rebroadcast_pending_transactions_after_period() {
+		// given
+		let mut list = LocalTransactionsList::default();
+		let tx = new_tx(10.into());
+		list.mark_pending(tx.hash());
+		list.mark_future(20.into());
+		assert_eq!(list.due_for_rebroadcast(5, 3), vec![]);
+
+		// when
+		let not_due = list.due_for_rebroadcast(7, 3);
+		let due = list.due_for_rebroadcast(8, 3);
+		list.mark_stuck(&tx.hash(), true);
+
+		// then
+		assert_eq!(not_due, vec![]);
+		assert_eq!(due, vec![tx.hash()]);
+		assert_eq!(list.propagation().get(&tx.hash()), Some(&Propagation { broadcast_at: 5, rebroadcasts: 0, stuck: true, due: true }));
+		assert_eq!(list.propagation().len(), 1);
+
+		// stays due until it's actually rebroadcast
+		assert_eq!(list.due_for_rebroadcast(9, 3), vec![tx.hash()]);
+		list.mark_rebroadcast(&tx.hash(), 9);
+		list.mark_rebroadcast(&tx.hash(), 10);
+		assert_eq!(list.propagation().get(&tx.hash()), Some(&Propagation { broadcast_at: 9, rebroadcasts: 1, stuck: true, due: false }));
+		assert_eq!(list.due_for_rebroadcast(11, 3), vec![]);
+
+		// when mined
+		list.mark_mined(tx);
+		assert_eq!(list.due_for_rebroadcast(20, 3), vec![]);
+		assert!(list.propagation().is_empty());
+	}
+
 	fn new_tx(nonce: U256) -> SignedTransaction {
 		let keypair = Random.generate().unwrap();
 		Transaction {
@@ -20,7 +20,7 @@ use util::*;
 use util::using_queue::{UsingQueue, GetAction};
 use account_provider::{AccountProvider, SignError as AccountError};
 use state::{State, CleanupMode};
-use client::{MiningBlockChainClient, Executive, Executed, EnvInfo, TransactOptions, BlockId, CallAnalytics, TransactionId};
+use client::{MiningBlockChainClient, Executive, Executed, EnvInfo, TransactOptions, BlockId, CallAnalytics, StateOverride, TransactionId};
 use client::TransactionImportResult;
 use executive::contract_address;
 use block::{ClosedBlock, IsBlock, Block};
@@ -28,15 +28,15 @@ use error::*;
 use transaction::{Action, UnverifiedTransaction, PendingTransaction, SignedTransaction, Condition as TransactionCondition};
 use receipt::{Receipt, RichReceipt};
 use spec::Spec;
-use engines::{Engine, Seal};
+use engines::{Engine, EngineError, Seal};
 use miner::{MinerService, MinerStatus, TransactionQueue, RemovalReason, TransactionQueueDetailsProvider, PrioritizationStrategy,
-	AccountDetails, TransactionOrigin};
+	AccountDetails, TransactionOrigin, GasPriceOracle, QueuedTransaction, QueuingInstant};
 use miner::banning_queue::{BanningTransactionQueue, Threshold};
 use miner::work_notify::{WorkPoster, NotifyWork};
 use miner::price_info::PriceInfo;
-use miner::local_transactions::{Status as LocalTransactionStatus};
+use miner::local_transactions::{Status as LocalTransactionStatus, Propagation as LocalTransactionPropagation};
 use miner::service_transaction_checker::ServiceTransactionChecker;
-use header::BlockNumber;
+use header::{BlockNumber, Header};
 
 /// Different possible definitions for pending transaction set.
 #[derive(Debug, PartialEq)]
@@ -112,6 +112,16 @@ pub struct MinerOptions {
 	pub tx_queue_banning: Banning,
 	/// Do we refuse to accept service transactions even if sender is certified.
 	pub refuse_service_transactions: bool,
+	/// Minimal gas price bump (in percents) required to replace a transaction in the queue.
+	/// `None` requires the gas price to be bumped by 1/8 (12.5%).
+	pub tx_queue_min_gas_price_bump: Option<usize>,
+	/// Maximum number of transactions from a single sender in the queue.
+	pub tx_queue_per_sender: usize,
+	/// Do not apply the per-sender limit to local transactions.
+	pub tx_queue_no_local_limit: bool,
+	/// Number of blocks after which pending local transactions that were not mined are broadcast again.
+	/// `0` disables rebroadcasting.
+	pub tx_rebroadcast_period: BlockNumber,
 }
 
 impl Default for MinerOptions {
@@ -133,6 +143,10 @@ impl Default for MinerOptions {
 			enable_resubmission: true,
 			tx_queue_banning: Banning::Disabled,
 			refuse_service_transactions: false,
+			tx_queue_min_gas_price_bump: None,
+			tx_queue_per_sender: usize::max_value(),
+			tx_queue_no_local_limit: false,
+			tx_rebroadcast_period: 10,
 		}
 	}
 }
@@ -182,6 +196,8 @@ pub enum GasPricer {
 	Fixed(U256),
 	/// Gas price is calibrated according to a fixed amount of USD.
 	Calibrated(GasPriceCalibrator),
+	/// Gas price is a percentile of the gas prices of transactions in recent blocks.
+	Oracle(GasPriceOracle),
 }
 
 impl GasPricer {
@@ -198,10 +214,17 @@ impl GasPricer {
 		GasPricer::Fixed(gas_price)
 	}
 
+	/// Create a new `GasPricer` following the gas prices in recent blocks.
+	pub fn new_oracle(oracle: GasPriceOracle) -> GasPricer {
+		GasPricer::Oracle(oracle)
+	}
+
 	fn recalibrate<F: Fn(U256) + Sync + Send + 'static>(&mut self, set_price: F) {
 		match *self {
 			GasPricer::Fixed(ref max) => set_price(max.clone()),
 			GasPricer::Calibrated(ref mut cal) => cal.recalibrate(set_price),
+			// recalibrated from the chain, see `recalibrate_minimal_gas_price_from_chain`.
+			GasPricer::Oracle(_) => {},
 		}
 	}
 }
@@ -253,7 +276,9 @@ impl Miner {
 			_ => !U256::zero(),
 		};
 
-		let txq = TransactionQueue::with_limits(options.tx_queue_strategy, options.tx_queue_size, gas_limit, options.tx_gas_limit);
+		let mut txq = TransactionQueue::with_limits(options.tx_queue_strategy, options.tx_queue_size, gas_limit, options.tx_gas_limit);
+		txq.set_min_gas_price_bump(options.tx_queue_min_gas_price_bump);
+		txq.set_per_sender_limit(options.tx_queue_per_sender, options.tx_queue_no_local_limit);
 		let txq = match options.tx_queue_banning {
 			Banning::Disabled => BanningTransactionQueue::new(txq, Threshold::NeverBan, Duration::from_secs(180)),
 			Banning::Enabled { ban_duration, min_offends, .. } => BanningTransactionQueue::new(
@@ -373,6 +398,12 @@ impl Miner {
 		let tx_total = transactions.len();
 		for tx in transactions {
 			let hash = tx.hash();
+			if !chain.transaction_allowed(&tx) {
+				debug!(target: "miner", "Skipping transaction not permitted by the transaction permission contract: {:?}", hash);
+				invalid_transactions.insert(hash);
+				continue;
+			}
+
 			let start = Instant::now();
 			let result = open_block.push_transaction(tx, None);
 			let took = start.elapsed();
@@ -454,6 +485,89 @@ impl Miner {
 		});
 	}
 
+	/// Get all transactions in the queue together with their origin and insertion time,
+	/// e.g. to persist them across restarts.
+	pub fn queued_transactions(&self) -> Vec<QueuedTransaction> {
+		self.transaction_queue.read().queued_transactions()
+	}
+
+	/// Re-import previously queued transactions, keeping their origin and insertion time.
+	/// The transactions are verified again against the latest state, so stale or invalid ones are rejected.
+	pub fn import_queued_transactions(
+		&self,
+		chain: &MiningBlockChainClient,
+		transactions: Vec<QueuedTransaction>,
+	) -> Vec<Result<TransactionImportResult, Error>> {
+		trace!(target: "miner", "Importing {} queued transactions", transactions.len());
+		let accounts = self.accounts.as_ref()
+			.and_then(|provider| provider.accounts().ok())
+			.map(|accounts| accounts.into_iter().collect::<HashSet<_>>());
+		let best_block_header = chain.best_block_header().decode();
+
+		let results = {
+			let mut transaction_queue = self.transaction_queue.write();
+			transactions.into_iter()
+				.map(|queued| self.add_transaction_to_queue(
+					chain,
+					accounts.as_ref(),
+					&best_block_header,
+					queued.pending.transaction.into(),
+					queued.origin,
+					queued.pending.condition,
+					queued.insertion_time,
+					&mut transaction_queue,
+				))
+				.collect::<Vec<_>>()
+		};
+
+		if results.iter().any(|r| r.is_ok()) {
+			// --------------------------------------------------------------------------
+			// | NOTE Code below requires transaction_queue and sealing_work locks.     |
+			// | Make sure to release the locks before calling that method.             |
+			// --------------------------------------------------------------------------
+			self.update_sealing(chain);
+		}
+		results
+	}
+
+	/// Finds pending local transactions due for rebroadcast and flags those with a gas price
+	/// below the recent inclusion gas price as stuck.
+	/// They are counted as rebroadcast once sync actually sends them again.
+	fn check_local_transactions_propagation(&self, chain: &MiningBlockChainClient) {
+		let best_block = chain.chain_info().best_block_number;
+		let due = self.transaction_queue.write()
+			.local_transactions_due_for_rebroadcast(best_block, self.options.tx_rebroadcast_period);
+		if due.is_empty() {
+			return;
+		}
+
+		debug!(target: "own_tx", "{} local transactions are due for rebroadcast", due.len());
+		// NOTE: the gas pricer is locked before the transaction queue elsewhere, so don't hold the queue here.
+		let inclusion_gas_price = self.gas_price_oracle().gas_price_from_chain(chain);
+		let mut transaction_queue = self.transaction_queue.write();
+		for hash in &due {
+			let stuck = match (transaction_queue.find(hash), inclusion_gas_price) {
+				(Some(tx), Some(price)) => tx.gas_price < price,
+				_ => false,
+			};
+			transaction_queue.mark_local_transaction_stuck(hash, stuck);
+		}
+	}
+
+	/// Updates minimal gas price for transaction queue from the gas prices of transactions in recent blocks.
+	/// Does nothing unless the gas price oracle is used.
+	pub fn recalibrate_minimal_gas_price_from_chain(&self, chain: &MiningBlockChainClient) {
+		let oracle = match *self.gas_pricer.lock() {
+			GasPricer::Oracle(ref oracle) => oracle.clone(),
+			_ => return,
+		};
+
+		if let Some(price) = oracle.gas_price_from_chain(chain) {
+			debug!(target: "miner", "minimal_gas_price: Got gas price from recent blocks! {}", price);
+			self.transaction_queue.write().set_minimal_gas_price(price);
+		}
+	}
+
 	/// Check is reseal is allowed and necessary.
 	fn requires_reseal(&self, best_block: BlockNumber) -> bool {
 		let has_local_transactions = self.transaction_queue.read().has_local_pending_transactions();
@@ -487,7 +601,7 @@ impl Miner {
 
 	/// Attempts to perform internal sealing (one that does not require work) and handles the result depending on the type of Seal.
 	fn seal_and_import_block_internally(&self, chain: &MiningBlockChainClient, block: ClosedBlock) -> bool {
-		if !block.transactions().is_empty() || self.forced_sealing() || Instant::now() > *self.next_mandatory_reseal.read() {
+		if !block.transactions().is_empty() || self.forced_sealing() || self.engine.should_seal_empty_block() || Instant::now() > *self.next_mandatory_reseal.read() {
 			trace!(target: "miner", "seal_block_internally: attempting internal seal.");
 			match self.engine.generate_seal(block.block()) {
 				// Save proposal for later seal submission and broadcast it.
@@ -513,7 +627,7 @@ impl Miner {
 					*self.next_mandatory_reseal.write() = Instant::now() + self.options.reseal_max_period;
 					block
 						.lock()
-						.seal(&*self.engine, seal)
+						.seal_internally(&*self.engine, seal)
 						.map(|sealed| chain.import_sealed_block(sealed).is_ok())
 						.unwrap_or_else(|e| {
 							warn!("ERROR: seal failed when given internally generated seal: {}", e);
@@ -619,43 +733,58 @@ impl Miner {
 		let insertion_time = client.chain_info().best_block_number;
 
 		transactions.into_iter()
-			.map(|tx| {
-				let hash = tx.hash();
-				if client.transaction_block(TransactionId::Hash(hash)).is_some() {
-					debug!(target: "miner", "Rejected tx {:?}: already in the blockchain", hash);
-					return Err(Error::Transaction(TransactionError::AlreadyImported));
-				}
-				match self.engine.verify_transaction_basic(&tx, &best_block_header)
-					.and_then(|_| self.engine.verify_transaction(tx, &best_block_header))
-				{
-					Err(e) => {
-						debug!(target: "miner", "Rejected tx {:?} with invalid signature: {:?}", hash, e);
-						Err(e)
+			.map(|tx| self.add_transaction_to_queue(
+				client, accounts.as_ref(), &best_block_header, tx, default_origin, condition.clone(), insertion_time, transaction_queue
+			))
+			.collect()
+	}
+
+	#[cfg_attr(feature="dev", allow(too_many_arguments))]
+	fn add_transaction_to_queue(
+		&self,
+		client: &MiningBlockChainClient,
+		accounts: Option<&HashSet<Address>>,
+		best_block_header: &Header,
+		tx: UnverifiedTransaction,
+		default_origin: TransactionOrigin,
+		condition: Option<TransactionCondition>,
+		insertion_time: QueuingInstant,
+		transaction_queue: &mut BanningTransactionQueue,
+	) -> Result<TransactionImportResult, Error> {
+		let hash = tx.hash();
+		if client.transaction_block(TransactionId::Hash(hash)).is_some() {
+			debug!(target: "miner", "Rejected tx {:?}: already in the blockchain", hash);
+			return Err(Error::Transaction(TransactionError::AlreadyImported));
+		}
+		match self.engine.verify_transaction_basic(&tx, best_block_header)
+			.and_then(|_| self.engine.verify_transaction(tx, best_block_header))
+		{
+			Err(e) => {
+				debug!(target: "miner", "Rejected tx {:?} with invalid signature: {:?}", hash, e);
+				Err(e)
+			},
+			Ok(transaction) => {
+				let origin = accounts.and_then(|accounts| {
+					match accounts.contains(&transaction.sender()) {
+						true => Some(TransactionOrigin::Local),
+						false => None,
+					}
+				}).unwrap_or(default_origin);
+
+				// try to install service transaction checker before appending transactions
+				self.service_transaction_action.update_from_chain_client(client);
+
+				let details_provider = TransactionDetailsProvider::new(client, &self.service_transaction_action);
+				match origin {
+					TransactionOrigin::Local | TransactionOrigin::RetractedBlock => {
+						transaction_queue.add(transaction, origin, insertion_time, condition, &details_provider)
 					},
-					Ok(transaction) => {
-						let origin = accounts.as_ref().and_then(|accounts| {
-							match accounts.contains(&transaction.sender()) {
-								true => Some(TransactionOrigin::Local),
-								false => None,
-							}
-						}).unwrap_or(default_origin);
-
-						// try to install service transaction checker before appending transactions
-						self.service_transaction_action.update_from_chain_client(client);
-
-						let details_provider = TransactionDetailsProvider::new(client, &self.service_transaction_action);
-						match origin {
-							TransactionOrigin::Local | TransactionOrigin::RetractedBlock => {
-								transaction_queue.add(transaction, origin, insertion_time, condition.clone(), &details_provider)
-							},
-							TransactionOrigin::External => {
-								transaction_queue.add_with_banlist(transaction, insertion_time, &details_provider)
-							},
-						}
+					TransactionOrigin::External => {
+						transaction_queue.add_with_banlist(transaction, insertion_time, &details_provider)
 					},
 				}
-			})
-			.collect()
+			},
+		}
 	}
 
 	/// Are we allowed to do a non-mandatory reseal?
@@ -702,7 +831,7 @@ impl MinerService for Miner {
 		}
 	}
 
-	fn call(&self, client: &MiningBlockChainClient, t: &SignedTransaction, analytics: CallAnalytics) -> Result<Executed, CallError> {
+	fn call(&self, client: &MiningBlockChainClient, t: &SignedTransaction, analytics: CallAnalytics, state_override: &StateOverride) -> Result<Executed, CallError> {
 		let sealing_work = self.sealing_work.lock();
 		match sealing_work.queue.peek_last_ref() {
 			Some(work) => {
@@ -722,6 +851,7 @@ impl MinerService for Miner {
 				};
 				// that's just a copy of the state.
 				let mut state = block.state().clone();
+				state.apply_overrides(state_override).map_err(ExecutionError::from)?;
 				let original_state = if analytics.state_diffing { Some(state.clone()) } else { None };
 
 				let sender = t.sender();
@@ -742,7 +872,7 @@ impl MinerService for Miner {
 
 				Ok(ret)
 			},
-			None => client.call(t, BlockId::Latest, analytics)
+			None => client.call(t, BlockId::Latest, analytics, state_override)
 		}
 	}
 
@@ -815,6 +945,14 @@ impl MinerService for Miner {
 		}
 	}
 
+	fn propose_signer(&self, address: Address, authorize: bool) -> Result<(), EngineError> {
+		self.engine.propose_signer(address, authorize)
+	}
+
+	fn discard_signer_proposal(&self, address: &Address) -> Result<(), EngineError> {
+		self.engine.discard_signer_proposal(address)
+	}
+
 	fn set_extra_data(&self, extra_data: Bytes) {
 		*self.extra_data.write() = extra_data;
 	}
@@ -841,6 +979,13 @@ impl MinerService for Miner {
 		*self.transaction_queue.read().minimal_gas_price() * 110.into() / 100.into()
 	}
 
+	fn gas_price_oracle(&self) -> GasPriceOracle {
+		match *self.gas_pricer.lock() {
+			GasPricer::Oracle(ref oracle) => oracle.clone(),
+			_ => GasPriceOracle::default(),
+		}
+	}
+
 	fn sensible_gas_limit(&self) -> U256 {
 		self.gas_range_target.read().0 / 5.into()
 	}
@@ -960,6 +1105,21 @@ impl MinerService for Miner {
 			.collect()
 	}
 
+	fn local_transactions_propagation(&self) -> BTreeMap<H256, LocalTransactionPropagation> {
+		let queue = self.transaction_queue.read();
+		queue.local_transactions_propagation()
+			.iter()
+			.map(|(hash, propagation)| (*hash, propagation.clone()))
+			.collect()
+	}
+
+	fn mark_local_transactions_rebroadcast(&self, hashes: &[H256], best_block: BlockNumber) {
+		let mut queue = self.transaction_queue.write();
+		for hash in hashes {
+			queue.mark_local_transaction_rebroadcast(hash, best_block);
+		}
+	}
+
 	fn future_transactions(&self) -> Vec<PendingTransaction> {
 		self.transaction_queue.read().future_transactions()
 	}
@@ -1177,6 +1337,7 @@ impl MinerService for Miner {
 
 		// Update minimal gas price
 		self.recalibrate_minimal_gas_price();
+		self.recalibrate_minimal_gas_price_from_chain(chain);
 
 		// Then import all transactions...
 		{
@@ -1203,6 +1364,9 @@ impl MinerService for Miner {
 			transaction_queue.remove_old(&fetch_account, time);
 		}
 
+		// Local transactions that are still not mined are going to be rebroadcast.
+		self.check_local_transactions_propagation(chain);
+
 		if enacted.len() > 0 || (imported.len() > 0 && self.options.reseal_on_uncle) {
 			// --------------------------------------------------------------------------
 			// | NOTE Code below requires transaction_queue and sealing_work locks.     |
@@ -1265,6 +1429,10 @@ impl<'a> TransactionQueueDetailsProvider for TransactionDetailsProvider<'a> {
 	fn is_service_transaction_acceptable(&self, tx: &SignedTransaction) -> Result<bool, String> {
 		self.service_transaction_action.check(self.client, tx)
 	}
+
+	fn is_transaction_allowed(&self, tx: &SignedTransaction) -> bool {
+		self.client.transaction_allowed(tx)
+	}
 }
 
 #[cfg(test)]
@@ -1335,6 +1503,10 @@ mod tests {
 				enable_resubmission: true,
 				tx_queue_banning: Banning::Disabled,
 				refuse_service_transactions: false,
+				tx_queue_min_gas_price_bump: None,
+				tx_queue_per_sender: usize::max_value(),
+				tx_queue_no_local_limit: false,
+				tx_rebroadcast_period: 10,
 			},
 			GasPricer::new_fixed(0u64.into()),
 			&Spec::new_test(),
@@ -1416,6 +1588,30 @@ mod tests {
 		assert!(miner.prepare_work_sealing(&client));
 	}
 
+	#[test]
+	fn should_restore_queued_transactions() {
+		// given
+		let client = TestBlockChainClient::default();
+		let miner = miner();
+		let fresh = transaction();
+		let stale = transaction();
+		miner.import_external_transactions(&client, vec![fresh.clone().into(), stale.clone().into()]);
+		let queued = miner.queued_transactions();
+		assert_eq!(queued.len(), 2);
+		assert_eq!(queued[0].origin, TransactionOrigin::External);
+
+		// when
+		client.set_nonce(stale.sender(), 1.into());
+		let restored = miner();
+		let res = restored.import_queued_transactions(&client, queued);
+
+		// then
+		assert_eq!(res.len(), 2);
+		assert_eq!(res[0].as_ref().unwrap(), &TransactionImportResult::Current);
+		assert!(res[1].is_err());
+		assert_eq!(restored.pending_transactions(), vec![fresh.into()]);
+	}
+
 	#[test]
 	fn should_not_seal_unless_enabled() {
 		let miner = miner();
@@ -1468,4 +1664,27 @@ mod tests {
 		let client = generate_dummy_client_with_spec_and_accounts(spec, None);
 		assert!(match client.miner().set_engine_signer(addr, "".into()) { Err(AccountError::NotFound) => true, _ => false });
 	}
+
+	#[test]
+	fn should_set_minimal_gas_price_from_recent_blocks() {
+		// given
+		let client = TestBlockChainClient::default();
+		client.add_blocks(3, EachBlockWith::Transaction);
+		let miner = Miner::new_raw(
+			Default::default(),
+			GasPricer::new_oracle(GasPriceOracle::default()),
+			&Spec::new_test(),
+			None,
+		);
+		miner.set_minimal_gas_price(1.into());
+
+		// when
+		miner.recalibrate_minimal_gas_price();
+		assert_eq!(miner.minimal_gas_price(), 1.into());
+		miner.recalibrate_minimal_gas_price_from_chain(&client);
+
+		// then
+		assert_eq!(miner.minimal_gas_price(), 200_000_000_000u64.into());
+		assert_eq!(miner.gas_price_oracle(), GasPriceOracle::default());
+	}
 }
@@ -42,7 +42,9 @@
 //! ```
 
 mod banning_queue;
+mod cpu_miner;
 mod external;
+mod gas_price_oracle;
 mod local_transactions;
 mod miner;
 mod price_info;
@@ -52,18 +54,20 @@ mod work_notify;
 mod stratum;
 
 pub use self::external::{ExternalMiner, ExternalMinerService};
+pub use self::gas_price_oracle::GasPriceOracle;
 
 pub use self::miner::{Miner, MinerOptions, Banning, PendingSet, GasPricer, GasPriceCalibratorOptions, GasLimit};
 pub use self::transaction_queue::{TransactionQueue, RemovalReason, TransactionDetailsProvider as TransactionQueueDetailsProvider,
-	PrioritizationStrategy, AccountDetails, TransactionOrigin};
-pub use self::local_transactions::{Status as LocalTransactionStatus};
+	PrioritizationStrategy, AccountDetails, TransactionOrigin, QueuedTransaction, QueuingInstant};
+pub use self::local_transactions::{Status as LocalTransactionStatus, Propagation as LocalTransactionPropagation};
 pub use client::TransactionImportResult;
 pub use self::work_notify::NotifyWork;
-pub use self::stratum::{Stratum, Error as StratumError, Options as StratumOptions};
+pub use self::stratum::{Stratum, Error as StratumError, Options as StratumOptions, WorkerStats as StratumWorkerStats};
+pub use self::cpu_miner::{CpuMiner, Options as CpuMinerOptions};
 
 use std::collections::BTreeMap;
 use util::{H256, U256, Address, Bytes};
-use client::{MiningBlockChainClient, Executed, CallAnalytics};
+use client::{MiningBlockChainClient, Executed, CallAnalytics, StateOverride};
 use block::ClosedBlock;
 use header::BlockNumber;
 use receipt::{RichReceipt, Receipt};
@@ -85,6 +89,12 @@ pub trait MinerService : Send + Sync {
 	/// Set info necessary to sign consensus messages.
 	fn set_engine_signer(&self, address: Address, password: String) -> Result<(), ::account_provider::SignError>;
 
+	/// Vote to authorize (`true`) or deauthorize (`false`) a signer in the blocks we seal.
+	fn propose_signer(&self, address: Address, authorize: bool) -> Result<(), ::engines::EngineError>;
+
+	/// Stop voting on the given signer.
+	fn discard_signer_proposal(&self, address: &Address) -> Result<(), ::engines::EngineError>;
+
 	/// Get the extra_data that we will seal blocks with.
 	fn extra_data(&self) -> Bytes;
 
@@ -169,6 +179,12 @@ pub trait MinerService : Send + Sync {
 	/// Get a list of local transactions with statuses.
 	fn local_transactions(&self) -> BTreeMap<H256, LocalTransactionStatus>;
 
+	/// Get propagation details (rebroadcasts, stuck flag) of pending local transactions.
+	fn local_transactions_propagation(&self) -> BTreeMap<H256, LocalTransactionPropagation>;
+
+	/// Record that local transactions due for rebroadcast were sent to all peers again at `best_block`.
+	fn mark_local_transactions_rebroadcast(&self, hashes: &[H256], best_block: BlockNumber);
+
 	/// Get a list of all pending receipts.
 	fn pending_receipts(&self, best_block: BlockNumber) -> BTreeMap<H256, Receipt>;
 
@@ -184,14 +200,17 @@ pub trait MinerService : Send + Sync {
 	/// Suggested gas price.
 	fn sensible_gas_price(&self) -> U256;
 
+	/// Gas price oracle used to suggest gas prices based on recent blocks.
+	fn gas_price_oracle(&self) -> GasPriceOracle { GasPriceOracle::default() }
+
 	/// Suggested gas limit.
 	fn sensible_gas_limit(&self) -> U256 { 21000.into() }
 
 	/// Latest account balance in pending state.
 	fn balance(&self, chain: &MiningBlockChainClient, address: &Address) -> Option<U256>;
 
-	/// Call into contract code using pending state.
-	fn call(&self, chain: &MiningBlockChainClient, t: &SignedTransaction, analytics: CallAnalytics) -> Result<Executed, CallError>;
+	/// Call into contract code using pending state, applying the given overrides to it first.
+	fn call(&self, chain: &MiningBlockChainClient, t: &SignedTransaction, analytics: CallAnalytics, state_override: &StateOverride) -> Result<Executed, CallError>;
 
 	/// Get storage value in pending state.
 	fn storage_at(&self, chain: &MiningBlockChainClient, address: &Address, position: &H256) -> Option<H256>;
@@ -17,17 +17,20 @@
 //! Client-side stratum job dispatcher and mining notifier handler
 
 use ethcore_stratum::{
-	JobDispatcher, PushWorkHandler,
+	JobDispatcher, PushWorkHandler, Work, ShareResult,
 	Stratum as StratumService, Error as StratumServiceError,
 };
+pub use ethcore_stratum::WorkerStats;
 
+use std::collections::BTreeMap;
 use std::sync::{Arc, Weak};
 use std::net::{SocketAddr, AddrParseError};
 use std::fmt;
 
+use byteorder::{BigEndian, ByteOrder};
 use util::{H256, U256, H64, clean_0x};
 use ethereum::ethash::Ethash;
-use ethash::SeedHashCompute;
+use ethash::{SeedHashCompute, EthashManager};
 use util::Mutex;
 use miner::{self, Miner, MinerService};
 use client::Client;
@@ -46,6 +49,8 @@ pub struct Options {
 	pub port: u16,
 	/// Secret for peers
 	pub secret: Option<H256>,
+	/// Difficulty of the EthereumStratum/1.0.0 shares, block difficulty if not set
+	pub share_difficulty: Option<U256>,
 }
 
 struct SubmitPayload {
@@ -109,6 +114,7 @@ impl fmt::Display for PayloadError {
 /// Job dispatcher for stratum service
 pub struct StratumJobDispatcher {
 	seed_compute: Mutex<SeedHashCompute>,
+	pow: EthashManager,
 	client: Weak<Client>,
 	miner: Weak<Miner>,
 }
@@ -153,13 +159,53 @@ impl JobDispatcher for StratumJobDispatcher {
 
 		Ok(())
 	}
+
+	fn work(&self) -> Option<Work> {
+		self.with_core(|client, miner| miner.map_sealing_work(&*client, |b| {
+				let number = b.block().header().number();
+				let seed_hash = self.seed_compute.lock().get_seedhash(number);
+
+				Work {
+					pow_hash: b.hash(),
+					seed_hash: H256::from_slice(&seed_hash[..]),
+					difficulty: *b.block().header().difficulty(),
+					number: number,
+				}
+			})
+		)
+	}
+
+	fn submit_share(&self, work: Work, nonce: u64, share_target: H256) -> ShareResult {
+		let pow = self.pow.compute_light(work.number, &work.pow_hash.0, nonce);
+		let value = H256(pow.value);
+		if value > share_target {
+			return ShareResult::Invalid;
+		}
+		if value > Ethash::difficulty_to_boundary(&work.difficulty) {
+			return ShareResult::Accepted;
+		}
+
+		let mut nonce_bytes = [0u8; 8];
+		BigEndian::write_u64(&mut nonce_bytes, nonce);
+		let seal = vec![encode(&H256(pow.mix_hash)).into_vec(), encode(&H64::from(nonce_bytes)).into_vec()];
+		let sealed = self.with_core(|client, miner| Some(miner.submit_seal(&*client, work.pow_hash, seal.clone())));
+		match sealed {
+			Some(Ok(())) => ShareResult::Block,
+			Some(Err(e)) => {
+				trace!(target: "stratum", "submit_seal error: {:?}", e);
+				ShareResult::Invalid
+			},
+			None => ShareResult::Stale,
+		}
+	}
 }
 
 impl StratumJobDispatcher {
 	/// New stratum job dispatcher given the miner and client
-	fn new(miner: Weak<Miner>, client: Weak<Client>) -> StratumJobDispatcher {
+	fn new(miner: Weak<Miner>, client: Weak<Client>, cache_dir: &str) -> StratumJobDispatcher {
 		StratumJobDispatcher {
 			seed_compute: Mutex::new(SeedHashCompute::new()),
+			pow: EthashManager::new(cache_dir),
 			client: client,
 			miner: miner,
 		}
@@ -187,6 +233,7 @@ impl StratumJobDispatcher {
 }
 
 /// Wrapper for dedicated stratum service
+#[derive(Clone)]
 pub struct Stratum {
 	dispatcher: Arc<StratumJobDispatcher>,
 	service: Arc<StratumService>,
@@ -227,12 +274,13 @@ impl Stratum {
 	pub fn start(options: &Options, miner: Weak<Miner>, client: Weak<Client>) -> Result<Stratum, Error> {
 		use std::net::IpAddr;
 
-		let dispatcher = Arc::new(StratumJobDispatcher::new(miner, client));
+		let dispatcher = Arc::new(StratumJobDispatcher::new(miner, client, &options.io_path));
 
 		let stratum_svc = StratumService::start(
 			&SocketAddr::new(IpAddr::from_str(&options.listen_addr)?, options.port),
 			dispatcher.clone(),
 			options.secret.clone(),
+			options.share_difficulty,
 		)?;
 
 		Ok(Stratum {
@@ -242,9 +290,14 @@ impl Stratum {
 	}
 
 	/// Start STRATUM job dispatcher and register it in the miner
-	pub fn register(cfg: &Options, miner: Arc<Miner>, client: Weak<Client>) -> Result<(), Error> {
+	pub fn register(cfg: &Options, miner: Arc<Miner>, client: Weak<Client>) -> Result<Stratum, Error> {
 		let stratum = miner::Stratum::start(cfg, Arc::downgrade(&miner.clone()), client)?;
-		miner.push_notifier(Box::new(stratum) as Box<miner::NotifyWork>);
-		Ok(())
+		miner.push_notifier(Box::new(stratum.clone()) as Box<miner::NotifyWork>);
+		Ok(stratum)
+	}
+
+	/// Share statistics of the authorized workers
+	pub fn worker_stats(&self) -> BTreeMap<String, WorkerStats> {
+		self.service.worker_stats()
 	}
 }
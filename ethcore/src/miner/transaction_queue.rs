@@ -53,6 +53,10 @@
 //!		fn is_service_transaction_acceptable(&self, _tx: &SignedTransaction) -> Result<bool, String> {
 //!			Ok(true)
 //!		}
+//!
+//!		fn is_transaction_allowed(&self, _tx: &SignedTransaction) -> bool {
+//!			true
+//!		}
 //! }
 //!
 //! fn main() {
@@ -111,7 +115,7 @@ use transaction::*;
 use error::{Error, TransactionError};
 use client::TransactionImportResult;
 use header::BlockNumber;
-use miner::local_transactions::{LocalTransactionsList, Status as LocalTransactionStatus};
+use miner::local_transactions::{LocalTransactionsList, Status as LocalTransactionStatus, Propagation as LocalTransactionPropagation};
 
 /// Transaction origin
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -270,6 +274,17 @@ impl Ord for TransactionOrder {
 	}
 }
 
+/// Transaction in the queue together with its origin and insertion time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedTransaction {
+	/// Transaction with its condition.
+	pub pending: PendingTransaction,
+	/// Transaction origin.
+	pub origin: TransactionOrigin,
+	/// Insertion time.
+	pub insertion_time: QueuingInstant,
+}
+
 /// Verified transaction
 #[derive(Debug)]
 struct VerifiedTransaction {
@@ -500,6 +515,7 @@ pub struct AccountDetails {
 const GAS_LIMIT_HYSTERESIS: usize = 200; // (100/GAS_LIMIT_HYSTERESIS) %
 /// Transaction with the same (sender, nonce) can be replaced only if
 /// `new_gas_price > old_gas_price + old_gas_price >> SHIFT`
+/// unless the minimal bump is configured (in percents).
 const GAS_PRICE_BUMP_SHIFT: usize = 3; // 2 = 25%, 3 = 12.5%, 4 = 6.25%
 
 /// Describes the strategy used to prioritize transactions in the queue.
@@ -546,6 +562,8 @@ pub trait TransactionDetailsProvider {
 	fn estimate_gas_required(&self, tx: &SignedTransaction) -> U256;
 	/// Check if this service transaction can be accepted by `TransactionQueue`.
 	fn is_service_transaction_acceptable(&self, tx: &SignedTransaction) -> Result<bool, String>;
+	/// Check if the transaction permission contract allows this transaction.
+	fn is_transaction_allowed(&self, tx: &SignedTransaction) -> bool;
 }
 
 /// `TransactionQueue` implementation
@@ -574,6 +592,13 @@ pub struct TransactionQueue {
 	local_transactions: LocalTransactionsList,
 	/// Next id that should be assigned to a transaction imported to the queue.
 	next_transaction_id: u64,
+	/// Minimal gas price bump (in percents) required to replace a transaction with the same `(sender, nonce)`.
+	/// When not set the gas price has to be bumped by `1 / 2^GAS_PRICE_BUMP_SHIFT`.
+	min_gas_price_bump: Option<usize>,
+	/// Maximal number of transactions (`current` and `future`) a single sender may have in the queue.
+	per_sender_limit: usize,
+	/// Whether local transactions are allowed to go above `per_sender_limit`.
+	no_local_per_sender_limit: bool,
 }
 
 impl Default for TransactionQueue {
@@ -618,6 +643,9 @@ impl TransactionQueue {
 			last_nonces: HashMap::new(),
 			local_transactions: LocalTransactionsList::default(),
 			next_transaction_id: 0,
+			min_gas_price_bump: None,
+			per_sender_limit: usize::max_value(),
+			no_local_per_sender_limit: false,
 		}
 	}
 
@@ -676,6 +704,20 @@ impl TransactionQueue {
 		self.tx_gas_limit = limit;
 	}
 
+	/// Sets the minimal gas price bump (in percents) required to replace a transaction with the same `(sender, nonce)`.
+	/// `None` restores the default bump of 12.5%.
+	pub fn set_min_gas_price_bump(&mut self, percent: Option<usize>) {
+		self.min_gas_price_bump = percent;
+	}
+
+	/// Sets the maximal number of transactions a single sender may have in the queue.
+	/// Local transactions are exempt from the limit if `no_local_limit` is set.
+	/// Any transaction already imported to the queue is not affected.
+	pub fn set_per_sender_limit(&mut self, limit: usize, no_local_limit: bool) {
+		self.per_sender_limit = limit;
+		self.no_local_per_sender_limit = no_local_limit;
+	}
+
 	/// Returns current status for this queue
 	pub fn status(&self) -> TransactionQueueStatus {
 		TransactionQueueStatus {
@@ -825,6 +867,16 @@ impl TransactionQueue {
 			}));
 		}
 
+		if !details_provider.is_transaction_allowed(&tx) {
+			trace!(target: "txqueue",
+				"Dropping transaction not permitted by the transaction permission contract: {:?} (sender: {:?})",
+				tx.hash(),
+				tx.sender(),
+			);
+
+			return Err(Error::Transaction(TransactionError::NotAllowed));
+		}
+
 		let client_account = details_provider.fetch_account(&tx.sender());
 		let cost = tx.value + tx.gas_price * tx.gas;
 		if client_account.balance < cost {
@@ -1089,7 +1141,7 @@ impl TransactionQueue {
 					self.local_transactions.mark_future(order.hash);
 				}
 				if let Some(old) = self.future.insert(*sender, k, order.clone()) {
-					Self::replace_orders(*sender, k, old, order, &mut self.future, &mut self.by_hash, &mut self.local_transactions);
+					Self::replace_orders(*sender, k, old, order, self.min_gas_price_bump, &mut self.future, &mut self.by_hash, &mut self.local_transactions);
 				}
 			} else {
 				trace!(target: "txqueue", "Removing old transaction: {:?} (nonce: {} < {})", order.hash, k, current_nonce);
@@ -1159,11 +1211,44 @@ impl TransactionQueue {
 			.collect()
 	}
 
+	/// Return all transactions (both current and future) in the order they were inserted.
+	pub fn queued_transactions(&self) -> Vec<QueuedTransaction> {
+		let mut txs: Vec<_> = self.by_hash.values().collect();
+		txs.sort_by_key(|tx| tx.insertion_id);
+		txs.into_iter()
+			.map(|tx| QueuedTransaction {
+				pending: PendingTransaction::new(tx.transaction.clone(), tx.condition.clone()),
+				origin: tx.origin,
+				insertion_time: tx.insertion_time,
+			})
+			.collect()
+	}
+
 	/// Returns local transactions (some of them might not be part of the queue anymore).
 	pub fn local_transactions(&self) -> &LinkedHashMap<H256, LocalTransactionStatus> {
 		self.local_transactions.all_transactions()
 	}
 
+	/// Returns propagation details of pending local transactions.
+	pub fn local_transactions_propagation(&self) -> &HashMap<H256, LocalTransactionPropagation> {
+		self.local_transactions.propagation()
+	}
+
+	/// Returns pending local transactions which were not mined within `period` blocks since they were last broadcast.
+	pub fn local_transactions_due_for_rebroadcast(&mut self, best_block: BlockNumber, period: BlockNumber) -> Vec<H256> {
+		self.local_transactions.due_for_rebroadcast(best_block, period)
+	}
+
+	/// Counts a pending local transaction which was due for rebroadcast as rebroadcast at `best_block`.
+	pub fn mark_local_transaction_rebroadcast(&mut self, hash: &H256, best_block: BlockNumber) {
+		self.local_transactions.mark_rebroadcast(hash, best_block)
+	}
+
+	/// Marks a pending local transaction as stuck (or not).
+	pub fn mark_local_transaction_stuck(&mut self, hash: &H256, stuck: bool) {
+		self.local_transactions.mark_stuck(hash, stuck)
+	}
+
 	/// Returns hashes of all transactions from current, ordered by priority.
 	pub fn pending_hashes(&self) -> Vec<H256> {
 		self.current.by_priority
@@ -1215,7 +1300,7 @@ impl TransactionQueue {
 					self.local_transactions.mark_pending(order.hash);
 				}
 				if let Some(old) = self.current.insert(address, current_nonce, order.clone()) {
-					Self::replace_orders(address, current_nonce, old, order, &mut self.current, &mut self.by_hash, &mut self.local_transactions);
+					Self::replace_orders(address, current_nonce, old, order, self.min_gas_price_bump, &mut self.current, &mut self.by_hash, &mut self.local_transactions);
 				}
 				update_last_nonce_to = Some(current_nonce);
 				current_nonce = current_nonce + U256::one();
@@ -1270,6 +1355,21 @@ impl TransactionQueue {
 			.cloned()
 			.map_or(state_nonce, |n| n + U256::one());
 
+		// Transactions replacing ones already in the queue don't take any more slots.
+		let is_replacement = self.current.by_address.get(&address, &nonce).is_some()
+			|| self.future.by_address.get(&address, &nonce).is_some();
+		let is_exempt = tx.origin == TransactionOrigin::RetractedBlock
+			|| (tx.origin.is_local() && self.no_local_per_sender_limit);
+		if !is_replacement && !is_exempt && self.sender_transactions_count(&address) >= self.per_sender_limit {
+			trace!(target: "txqueue",
+				"Dropping transaction above the per-sender limit: {:?} (sender: {:?}, limit: {})",
+				hash,
+				address,
+				self.per_sender_limit
+			);
+			return Err(TransactionError::SenderLimitReached { limit: self.per_sender_limit });
+		}
+
 		if tx.origin.is_local() {
 			self.mark_transactions_local(&address);
 		}
@@ -1277,9 +1377,13 @@ impl TransactionQueue {
 		// Future transaction
 		if nonce > next_nonce {
 			// We have a gap - put to future.
+			// Make room if future is full
+			if !is_replacement {
+				self.evict_lowest_priority_sender(false, &tx, state_nonce);
+			}
 			// Insert transaction (or replace old one with lower gas price)
 			check_too_cheap(
-				Self::replace_transaction(tx, state_nonce, min_gas_price, &mut self.future, &mut self.by_hash, &mut self.local_transactions)
+				Self::replace_transaction(tx, state_nonce, min_gas_price, self.min_gas_price_bump, &mut self.future, &mut self.by_hash, &mut self.local_transactions)
 			)?;
 			// Enforce limit in Future
 			let removed = self.future.enforce_limit(&mut self.by_hash, &mut self.local_transactions);
@@ -1295,9 +1399,13 @@ impl TransactionQueue {
 		self.move_matching_future_to_current(address, nonce, state_nonce);
 		self.move_matching_future_to_current(address, nonce + U256::one(), state_nonce);
 
+		// Make room if current is full
+		if !is_replacement {
+			self.evict_lowest_priority_sender(true, &tx, state_nonce);
+		}
 		// Replace transaction if any
 		check_too_cheap(
-			Self::replace_transaction(tx, state_nonce, min_gas_price, &mut self.current, &mut self.by_hash, &mut self.local_transactions)
+			Self::replace_transaction(tx, state_nonce, min_gas_price, self.min_gas_price_bump, &mut self.current, &mut self.by_hash, &mut self.local_transactions)
 		)?;
 		// Keep track of highest nonce stored in current
 		let new_max = self.last_nonces.get(&address).map_or(nonce, |n| cmp::max(nonce, *n));
@@ -1315,6 +1423,69 @@ impl TransactionQueue {
 		Ok(TransactionImportResult::Current)
 	}
 
+	/// Returns number of transactions (`current` and `future`) from given sender.
+	fn sender_transactions_count(&self, sender: &Address) -> usize {
+		self.current.by_address.row(sender).map_or(0, |row| row.len())
+			+ self.future.by_address.row(sender).map_or(0, |row| row.len())
+	}
+
+	/// Makes room for a new transaction if `current` (or `future`) set is full.
+	///
+	/// Evicts transactions of the sender owning the lowest priority transaction in the set,
+	/// but only if that transaction has lower priority than the one being imported.
+	/// Local and retracted transactions are never evicted.
+	fn evict_lowest_priority_sender(&mut self, in_current: bool, tx: &VerifiedTransaction, base_nonce: U256) {
+		let order = TransactionOrder::for_transaction(tx, base_nonce, self.minimal_gas_price, self.strategy);
+		let address = tx.sender();
+		let lowest_sender = {
+			let set = if in_current { &self.current } else { &self.future };
+			if set.by_priority.len() < set.limit {
+				return;
+			}
+
+			let by_hash = &self.by_hash;
+			set.by_priority
+				.iter()
+				.rev()
+				.filter(|lowest| lowest.origin == TransactionOrigin::External)
+				.map(|lowest| (lowest, by_hash.get(&lowest.hash)
+					.expect("All transactions in `current` and `future` are always included in `by_hash`")
+					.sender()))
+				.find(|&(_, ref sender)| *sender != address)
+				.and_then(|(lowest, sender)| if *lowest > order { Some(sender) } else { None })
+		};
+
+		if let Some(sender) = lowest_sender {
+			debug!(target: "txqueue", "Queue is full, evicting transactions from the lowest priority sender: {:?}", sender);
+			self.evict_sender(&sender, in_current);
+		}
+	}
+
+	/// Removes all `future` transactions from given sender.
+	/// If `from_current` is set also the sender's `current` transactions are removed.
+	fn evict_sender(&mut self, sender: &Address, from_current: bool) {
+		fn remove_from(sender: &Address, set: &mut TransactionSet, by_hash: &mut HashMap<H256, VerifiedTransaction>, local: &mut LocalTransactionsList) {
+			let nonces_from_sender = match set.by_address.row(sender) {
+				Some(row_map) => row_map.keys().cloned().collect::<Vec<U256>>(),
+				None => vec![],
+			};
+			for k in nonces_from_sender {
+				let order = set.drop(sender, &k).expect("transaction known to be in self.current/self.future; qed");
+				trace!(target: "txqueue", "Evicted transaction: {:?}", order.hash);
+				let tx = by_hash.remove(&order.hash).expect("All transactions in `current` and `future` are also in `by_hash`");
+				if tx.origin.is_local() {
+					local.mark_dropped(tx.transaction);
+				}
+			}
+		}
+
+		remove_from(sender, &mut self.future, &mut self.by_hash, &mut self.local_transactions);
+		if from_current {
+			remove_from(sender, &mut self.current, &mut self.by_hash, &mut self.local_transactions);
+			self.last_nonces.remove(sender);
+		}
+	}
+
 	/// Updates
 	fn update_last_nonces(&mut self, removed_min_nonces: &Option<HashMap<Address, U256>>) {
 		if let Some(ref min_nonces) = *removed_min_nonces {
@@ -1330,7 +1501,8 @@ impl TransactionQueue {
 
 	/// Replaces transaction in given set (could be `future` or `current`).
 	///
-	/// If there is already transaction with same `(sender, nonce)` it will be replaced iff `gas_price` is higher.
+	/// If there is already transaction with same `(sender, nonce)` it will be replaced iff `gas_price` is higher
+	/// by at least `min_gas_price_bump` percents (or 12.5% if not set).
 	/// One of the transactions is dropped from set and also removed from queue entirely (from `by_hash`).
 	///
 	/// Returns `true` if transaction actually got to the queue (`false` if there was already a transaction with higher
@@ -1339,6 +1511,7 @@ impl TransactionQueue {
 		tx: VerifiedTransaction,
 		base_nonce: U256,
 		min_gas_price: (U256, PrioritizationStrategy),
+		min_gas_price_bump: Option<usize>,
 		set: &mut TransactionSet,
 		by_hash: &mut HashMap<H256, VerifiedTransaction>,
 		local: &mut LocalTransactionsList,
@@ -1354,7 +1527,7 @@ impl TransactionQueue {
 		trace!(target: "txqueue", "Inserting: {:?}", order);
 
 		if let Some(old) = set.insert(address, nonce, order.clone()) {
-			Self::replace_orders(address, nonce, old, order, set, by_hash, local)
+			Self::replace_orders(address, nonce, old, order, min_gas_price_bump, set, by_hash, local)
 		} else {
 			true
 		}
@@ -1365,6 +1538,7 @@ impl TransactionQueue {
 		nonce: U256,
 		old: TransactionOrder,
 		order: TransactionOrder,
+		min_gas_price_bump: Option<usize>,
 		set: &mut TransactionSet,
 		by_hash: &mut HashMap<H256, VerifiedTransaction>,
 		local: &mut LocalTransactionsList,
@@ -1375,7 +1549,7 @@ impl TransactionQueue {
 
 		let old_gas_price = old.gas_price;
 		let new_gas_price = order.gas_price;
-		let min_required_gas_price = old_gas_price + (old_gas_price >> GAS_PRICE_BUMP_SHIFT);
+		let min_required_gas_price = min_replacement_gas_price(old_gas_price, min_gas_price_bump);
 
 		if min_required_gas_price > new_gas_price {
 			trace!(target: "txqueue", "Didn't insert transaction because gas price was too low: {:?} ({:?} stays in the queue)", order.hash, old.hash);
@@ -1399,6 +1573,21 @@ impl TransactionQueue {
 	}
 }
 
+/// Returns the lowest gas price a transaction needs to replace one with `old_gas_price`.
+fn min_replacement_gas_price(old_gas_price: U256, bump_percent: Option<usize>) -> U256 {
+	let bump = match bump_percent {
+		None => old_gas_price >> GAS_PRICE_BUMP_SHIFT,
+		Some(percent) => match old_gas_price.overflowing_mul(percent.into()) {
+			(_, true) => return !U256::zero(),
+			(val, false) => val / 100.into(),
+		},
+	};
+	match old_gas_price.overflowing_add(bump) {
+		(_, true) => !U256::zero(),
+		(val, false) => val,
+	}
+}
+
 fn check_too_cheap(is_in: bool) -> Result<(), TransactionError> {
 	if is_in {
 		Ok(())
@@ -1437,6 +1626,7 @@ pub mod test {
 		account_details: AccountDetails,
 		gas_required: U256,
 		service_transactions_check_result: Result<bool, String>,
+		transaction_allowed: bool,
 	}
 
 	impl Default for DummyTransactionDetailsProvider {
@@ -1445,6 +1635,7 @@ pub mod test {
 				account_details: default_account_details(),
 				gas_required: U256::zero(),
 				service_transactions_check_result: Ok(false),
+				transaction_allowed: true,
 			}
 		}
 	}
@@ -1474,6 +1665,11 @@ pub mod test {
 			self.service_transactions_check_result = Ok(accepts);
 			self
 		}
+
+		pub fn transaction_permission_contract_allows(mut self, allowed: bool) -> Self {
+			self.transaction_allowed = allowed;
+			self
+		}
 	}
 
 	impl TransactionDetailsProvider for DummyTransactionDetailsProvider {
@@ -1491,6 +1687,10 @@ pub mod test {
 		fn is_service_transaction_acceptable(&self, _tx: &SignedTransaction) -> Result<bool, String> {
 			self.service_transactions_check_result.clone()
 		}
+
+		fn is_transaction_allowed(&self, _tx: &SignedTransaction) -> bool {
+			self.transaction_allowed
+		}
 	}
 
 	fn unwrap_tx_err(err: Result<TransactionImportResult, Error>) -> TransactionError {
@@ -1963,6 +2163,22 @@ pub mod test {
 		assert_eq!(stats.future, 0);
 	}
 
+	#[test]
+	fn should_not_import_transaction_not_allowed_by_permission_contract() {
+		// given
+		let mut txq = TransactionQueue::default();
+		let tx = new_tx_default();
+
+		// when
+		let res = txq.add(tx, TransactionOrigin::Local, 0, None, &default_tx_provider().transaction_permission_contract_allows(false));
+
+		// then
+		assert_eq!(unwrap_tx_err(res), TransactionError::NotAllowed);
+		let stats = txq.status();
+		assert_eq!(stats.pending, 0);
+		assert_eq!(stats.future, 0);
+	}
+
 	#[test]
 	fn should_import_transaction_below_min_gas_price_threshold_if_local() {
 		// given
@@ -2418,6 +2634,111 @@ pub mod test {
 		assert_eq!(txq.status().pending, 4);
 	}
 
+	#[test]
+	fn should_limit_transactions_per_sender() {
+		// given
+		let mut txq = TransactionQueue::default();
+		txq.set_per_sender_limit(2, false);
+		let keypair = Random.generate().unwrap();
+		let tx1 = new_unsigned_tx(default_nonce(), default_gas_val(), 1.into()).sign(keypair.secret(), None);
+		let tx2 = new_unsigned_tx(default_nonce() + 1.into(), default_gas_val(), 1.into()).sign(keypair.secret(), None);
+		let tx3 = new_unsigned_tx(default_nonce() + 2.into(), default_gas_val(), 1.into()).sign(keypair.secret(), None);
+		let tx2_2 = new_unsigned_tx(default_nonce() + 1.into(), default_gas_val(), 2.into()).sign(keypair.secret(), None);
+		txq.add(tx1, TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx2, TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+
+		// when
+		let res = txq.add(tx3.clone(), TransactionOrigin::External, 0, None, &default_tx_provider());
+		let res_local = txq.add(tx3, TransactionOrigin::Local, 0, None, &default_tx_provider());
+
+		// then
+		assert_eq!(unwrap_tx_err(res), TransactionError::SenderLimitReached { limit: 2 });
+		assert_eq!(unwrap_tx_err(res_local), TransactionError::SenderLimitReached { limit: 2 });
+		// replacing a transaction doesn't take another slot
+		assert_eq!(txq.add(tx2_2, TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap(), TransactionImportResult::Current);
+		assert_eq!(txq.status().pending, 2);
+	}
+
+	#[test]
+	fn should_not_limit_local_transactions_per_sender_if_exempt() {
+		// given
+		let mut txq = TransactionQueue::default();
+		txq.set_per_sender_limit(1, true);
+		let (tx1, tx2) = new_tx_pair_default(1.into(), 0.into());
+		let (tx3, tx4) = new_tx_pair_default(1.into(), 0.into());
+		txq.add(tx1, TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx3, TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+
+		// when
+		let res = txq.add(tx2, TransactionOrigin::Local, 0, None, &default_tx_provider());
+		let res_external = txq.add(tx4, TransactionOrigin::External, 0, None, &default_tx_provider());
+
+		// then
+		assert_eq!(res.unwrap(), TransactionImportResult::Current);
+		assert_eq!(unwrap_tx_err(res_external), TransactionError::SenderLimitReached { limit: 1 });
+		assert_eq!(txq.status().pending, 3);
+	}
+
+	#[test]
+	fn should_evict_all_transactions_of_the_lowest_priority_sender_when_full() {
+		// given
+		let mut txq = TransactionQueue::with_limits(PrioritizationStrategy::GasPriceOnly, 3, !U256::zero(), !U256::zero());
+		let (tx1, tx2) = new_tx_pair(default_nonce(), 2.into(), 1.into(), 0.into());
+		let tx3 = new_tx(default_nonce(), 5.into());
+		let tx4 = new_tx(default_nonce(), 3.into());
+		let spammer = tx1.sender();
+		txq.add(tx1, TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx2, TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx3.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		assert_eq!(txq.status().pending, 3);
+
+		// when
+		let res = txq.add(tx4.clone(), TransactionOrigin::External, 0, None, &default_tx_provider());
+
+		// then
+		assert_eq!(res.unwrap(), TransactionImportResult::Current);
+		assert_eq!(txq.top_transactions(), vec![tx3, tx4]);
+		assert_eq!(txq.last_nonce(&spammer), None);
+	}
+
+	#[test]
+	fn should_evict_senders_in_order_of_priority() {
+		// given
+		let mut txq = TransactionQueue::with_limits(PrioritizationStrategy::GasPriceOnly, 2, !U256::zero(), !U256::zero());
+		let tx1 = new_tx_default();
+		let tx2 = new_tx(default_nonce(), 4.into());
+		txq.add(tx1.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx2.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+
+		// when
+		let tx3 = new_tx(default_nonce(), 3.into());
+		txq.add(tx3.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		let after_first = txq.top_transactions();
+		let tx4 = new_tx(default_nonce(), 5.into());
+		txq.add(tx4.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		let after_second = txq.top_transactions();
+
+		// then
+		assert_eq!(after_first, vec![tx2.clone(), tx3]);
+		assert_eq!(after_second, vec![tx4, tx2]);
+	}
+
+	#[test]
+	fn should_not_evict_local_transactions_when_full() {
+		// given
+		let mut txq = TransactionQueue::with_limits(PrioritizationStrategy::GasPriceOnly, 2, !U256::zero(), !U256::zero());
+		let (tx1, tx2) = new_tx_pair_default(1.into(), 0.into());
+		txq.add(tx1.clone(), TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx2.clone(), TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
+
+		// when
+		let res = txq.add(new_tx(default_nonce(), 3.into()), TransactionOrigin::External, 0, None, &default_tx_provider());
+
+		// then
+		assert_eq!(unwrap_tx_err(res), TransactionError::LimitReached);
+		assert_eq!(txq.top_transactions(), vec![tx1, tx2]);
+	}
+
 	#[test]
 	fn should_drop_transactions_with_old_nonces() {
 		let mut txq = TransactionQueue::default();
@@ -2547,6 +2868,47 @@ pub mod test {
 		assert_eq!(txq.top_transactions()[0].gas_price, U256::from(20));
 	}
 
+	#[test]
+	fn should_require_gas_price_bump_by_one_eighth_by_default() {
+		// given
+		let mut txq = TransactionQueue::default();
+		let keypair = Random.generate().unwrap();
+		let tx = new_unsigned_tx(123.into(), default_gas_val(), 200.into()).sign(keypair.secret(), None);
+		let tx2 = new_unsigned_tx(123.into(), default_gas_val(), 224.into()).sign(keypair.secret(), None);
+		let tx3 = new_unsigned_tx(123.into(), default_gas_val(), 225.into()).sign(keypair.secret(), None);
+
+		// when
+		txq.add(tx, TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		let res = txq.add(tx2, TransactionOrigin::External, 0, None, &default_tx_provider());
+		txq.add(tx3, TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+
+		// then
+		assert_eq!(unwrap_tx_err(res), TransactionError::TooCheapToReplace);
+		assert_eq!(txq.status().pending, 1);
+		assert_eq!(txq.top_transactions()[0].gas_price, U256::from(225));
+	}
+
+	#[test]
+	fn should_respect_configured_minimal_gas_price_bump() {
+		// given
+		let mut txq = TransactionQueue::default();
+		txq.set_min_gas_price_bump(Some(50));
+		let keypair = Random.generate().unwrap();
+		let tx = new_unsigned_tx(123.into(), default_gas_val(), 20.into()).sign(keypair.secret(), None);
+		let tx2 = new_unsigned_tx(123.into(), default_gas_val(), 29.into()).sign(keypair.secret(), None);
+		let tx3 = new_unsigned_tx(123.into(), default_gas_val(), 30.into()).sign(keypair.secret(), None);
+
+		// when
+		txq.add(tx, TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		let res = txq.add(tx2, TransactionOrigin::External, 0, None, &default_tx_provider());
+		txq.add(tx3, TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+
+		// then
+		assert_eq!(unwrap_tx_err(res), TransactionError::TooCheapToReplace);
+		assert_eq!(txq.status().pending, 1);
+		assert_eq!(txq.top_transactions()[0].gas_price, U256::from(30));
+	}
+
 	#[test]
 	fn should_replace_same_transaction_when_importing_to_futures() {
 		// given
@@ -2896,4 +3258,30 @@ pub mod test {
 		// then
 		assert_eq!(txq.top_transactions_at(BlockNumber::max_value(), u64::max_value(), Some(127.into())).len(), 4);
 	}
+
+	#[test]
+	fn should_return_queued_transactions_in_insertion_order() {
+		// given
+		let mut txq = TransactionQueue::default();
+		let (tx1, tx2) = new_tx_pair_default(1.into(), 0.into());
+		let condition = Some(Condition::Number(5));
+
+		// when
+		txq.add(tx2.clone(), TransactionOrigin::External, 3, None, &default_tx_provider()).unwrap();
+		txq.add(tx1.clone(), TransactionOrigin::Local, 4, condition.clone(), &default_tx_provider()).unwrap();
+
+		// then
+		assert_eq!(txq.queued_transactions(), vec![
+			QueuedTransaction {
+				pending: PendingTransaction::new(tx2, None),
+				origin: TransactionOrigin::External,
+				insertion_time: 3,
+			},
+			QueuedTransaction {
+				pending: PendingTransaction::new(tx1, condition),
+				origin: TransactionOrigin::Local,
+				insertion_time: 4,
+			},
+		]);
+	}
 }
@@ -22,7 +22,7 @@ use super::seal::Generic as GenericSeal;
 
 use action_params::{ActionValue, ActionParams};
 use builtin::Builtin;
-use engines::{Engine, NullEngine, InstantSeal, BasicAuthority, AuthorityRound, Tendermint, DEFAULT_BLOCKHASH_CONTRACT};
+use engines::{Engine, NullEngine, InstantSeal, BasicAuthority, AuthorityRound, Tendermint, Clique, DEFAULT_BLOCKHASH_CONTRACT};
 use env_info::EnvInfo;
 use error::Error;
 use ethereum;
@@ -77,6 +77,14 @@ pub struct CommonParams {
 	pub eip211_transition: BlockNumber,
 	/// Number of first block where EIP-214 rules begin.
 	pub eip214_transition: BlockNumber,
+	/// Number of first block where EIP-145 (Constantinople: SHL/SHR/SAR) rules begin.
+	pub eip145_transition: BlockNumber,
+	/// Number of first block where EIP-1052 (Constantinople: EXTCODEHASH) rules begin.
+	pub eip1052_transition: BlockNumber,
+	/// Number of first block where EIP-1283 (Constantinople: net gas metering for SSTORE) rules begin.
+	pub eip1283_transition: BlockNumber,
+	/// Number of first block where EIP-1014 (Constantinople: CREATE2 with salted addresses) rules begin.
+	pub eip1014_transition: BlockNumber,
 	/// Number of first block where dust cleanup rules (EIP-168 and EIP169) begin.
 	pub dust_protection_transition: BlockNumber,
 	/// Nonce cap increase per block. Nonce cap is only checked if dust protection is enabled.
@@ -85,6 +93,10 @@ pub struct CommonParams {
 	pub remove_dust_contracts: bool,
 	/// Wasm support
 	pub wasm: bool,
+	/// Transaction permission contract, limiting who can send which kind of transactions.
+	pub transaction_permission_contract: Option<Address>,
+	/// Number of first block where the transaction permission contract is checked.
+	pub transaction_permission_contract_transition: BlockNumber,
 }
 
 impl From<ethjson::spec::Params> for CommonParams {
@@ -109,10 +121,16 @@ impl From<ethjson::spec::Params> for CommonParams {
 			eip210_contract_gas: p.eip210_contract_gas.map_or(1000000.into(), Into::into),
 			eip211_transition: p.eip211_transition.map_or(BlockNumber::max_value(), Into::into),
 			eip214_transition: p.eip214_transition.map_or(BlockNumber::max_value(), Into::into),
+			eip145_transition: p.eip145_transition.map_or(BlockNumber::max_value(), Into::into),
+			eip1052_transition: p.eip1052_transition.map_or(BlockNumber::max_value(), Into::into),
+			eip1283_transition: p.eip1283_transition.map_or(BlockNumber::max_value(), Into::into),
+			eip1014_transition: p.eip1014_transition.map_or(BlockNumber::max_value(), Into::into),
 			dust_protection_transition: p.dust_protection_transition.map_or(BlockNumber::max_value(), Into::into),
 			nonce_cap_increment: p.nonce_cap_increment.map_or(64, Into::into),
 			remove_dust_contracts: p.remove_dust_contracts.unwrap_or(false),
 			wasm: p.wasm.unwrap_or(false),
+			transaction_permission_contract: p.transaction_permission_contract.map(Into::into),
+			transaction_permission_contract_transition: p.transaction_permission_contract_transition.map_or(0, Into::into),
 		}
 	}
 }
@@ -216,11 +234,12 @@ impl Spec {
 	) -> Arc<Engine> {
 		match engine_spec {
 			ethjson::spec::Engine::Null => Arc::new(NullEngine::new(params, builtins)),
-			ethjson::spec::Engine::InstantSeal(instant) => Arc::new(InstantSeal::new(params, instant.params.registrar.map_or_else(Address::new, Into::into), builtins)),
+			ethjson::spec::Engine::InstantSeal(instant) => InstantSeal::new(params, From::from(instant.params), builtins).expect("Failed to start the InstantSeal engine."),
 			ethjson::spec::Engine::Ethash(ethash) => Arc::new(ethereum::Ethash::new(cache_dir, params, From::from(ethash.params), builtins)),
 			ethjson::spec::Engine::BasicAuthority(basic_authority) => Arc::new(BasicAuthority::new(params, From::from(basic_authority.params), builtins)),
 			ethjson::spec::Engine::AuthorityRound(authority_round) => AuthorityRound::new(params, From::from(authority_round.params), builtins).expect("Failed to start AuthorityRound consensus engine."),
 			ethjson::spec::Engine::Tendermint(tendermint) => Tendermint::new(params, From::from(tendermint.params), builtins).expect("Failed to start the Tendermint consensus engine."),
+			ethjson::spec::Engine::Clique(clique) => Clique::new(params, From::from(clique.params), builtins).expect("Failed to start the Clique consensus engine."),
 		}
 	}
 
@@ -433,10 +452,31 @@ impl Spec {
 	/// Accounts with secrets "0".sha3() and "1".sha3() are the validators.
 	pub fn new_test_round() -> Self { load_bundled!("authority_round") }
 
+	/// Create a new Spec with AuthorityRound consensus where empty steps replace empty blocks.
+	/// Accounts with secrets "0".sha3() and "1".sha3() are the validators.
+	pub fn new_test_round_empty_steps() -> Self { load_bundled!("authority_round_empty_steps") }
+
+	/// Create a new Spec with AuthorityRound consensus where the block rewards are determined by a contract.
+	/// The mock contract at 0x42 rewards the block author with 1000 wei.
+	pub fn new_test_round_block_reward_contract() -> Self { load_bundled!("authority_round_block_reward_contract") }
+
+	/// Create a new Spec with InstantSeal consensus and a transaction permission contract at 0x42.
+	/// The account of secret "1".sha3() may make any transaction, "0".sha3() only basic transfers.
+	/// Anyone may make basic transfers to 0x43.
+	pub fn new_test_tx_permission() -> Self { load_bundled!("tx_permission") }
+
 	/// Create a new Spec with Tendermint consensus which does internal sealing (not requiring work).
 	/// Account "0".sha3() and "1".sha3() are a authorities.
 	pub fn new_test_tendermint() -> Self { load_bundled!("tendermint") }
 
+	/// Create a new Spec with Tendermint consensus where the block rewards are determined by a contract.
+	/// The mock contract at 0x42 rewards the block author with 1000 wei.
+	pub fn new_test_tendermint_block_reward_contract() -> Self { load_bundled!("tendermint_block_reward_contract") }
+
+	/// Create a new Spec with Clique consensus which does internal sealing (not requiring work).
+	/// Accounts with secrets "0".sha3() and "1".sha3() are the initial signers.
+	pub fn new_test_clique() -> Self { load_bundled!("clique") }
+
 	/// TestList.sol used in both specs: https://github.com/paritytech/contracts/pull/30/files
 	/// Accounts with secrets "0".sha3() and "1".sha3() are initially the validators.
 	/// Create a new Spec with BasicAuthority which uses a contract at address 5 to determine the current validators using `getValidators`.
@@ -477,7 +517,11 @@ mod tests {
 		Spec::new_test_constructor();
 		Spec::new_instant();
 		Spec::new_test_round();
+		Spec::new_test_round_empty_steps();
+		Spec::new_test_round_block_reward_contract();
+		Spec::new_test_tx_permission();
 		Spec::new_test_tendermint();
+		Spec::new_test_tendermint_block_reward_contract();
 		Spec::new_validator_safe_contract();
 		Spec::new_validator_contract();
 		Spec::new_validator_multi();
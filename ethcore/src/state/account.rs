@@ -42,6 +42,9 @@ pub struct Account {
 	// Modified storage. Accumulates changes to storage made in `set_storage`
 	// Takes precedence over `storage_cache`.
 	storage_changes: HashMap<H256, H256>,
+	// Values of the storage keys modified by the current transaction, as they were
+	// before it started. Cleared between transactions.
+	original_storage: HashMap<H256, H256>,
 	// Code hash of the account.
 	code_hash: H256,
 	// Size of the accoun code.
@@ -62,6 +65,7 @@ impl From<BasicAccount> for Account {
 			storage_root: basic.storage_root,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: HashMap::new(),
+			original_storage: HashMap::new(),
 			code_hash: basic.code_hash,
 			code_size: None,
 			code_cache: Arc::new(vec![]),
@@ -81,6 +85,7 @@ impl Account {
 			storage_root: SHA3_NULL_RLP,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: storage,
+			original_storage: HashMap::new(),
 			code_hash: code.sha3(),
 			code_size: Some(code.len()),
 			code_cache: Arc::new(code),
@@ -101,6 +106,7 @@ impl Account {
 			storage_root: SHA3_NULL_RLP,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: pod.storage.into_iter().collect(),
+			original_storage: HashMap::new(),
 			code_hash: pod.code.as_ref().map_or(SHA3_EMPTY, |c| c.sha3()),
 			code_filth: Filth::Dirty,
 			code_size: Some(pod.code.as_ref().map_or(0, |c| c.len())),
@@ -117,6 +123,7 @@ impl Account {
 			storage_root: SHA3_NULL_RLP,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: HashMap::new(),
+			original_storage: HashMap::new(),
 			code_hash: SHA3_EMPTY,
 			code_cache: Arc::new(vec![]),
 			code_size: Some(0),
@@ -140,6 +147,7 @@ impl Account {
 			storage_root: SHA3_NULL_RLP,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: HashMap::new(),
+			original_storage: HashMap::new(),
 			code_hash: SHA3_EMPTY,
 			code_cache: Arc::new(vec![]),
 			code_size: None,
@@ -167,6 +175,35 @@ impl Account {
 		self.storage_changes.insert(key, value);
 	}
 
+	/// Note `value` as the value of `key` at the start of the current transaction,
+	/// unless the key has already been changed by it.
+	pub fn note_original_storage(&mut self, key: H256, value: H256) {
+		self.original_storage.entry(key).or_insert(value);
+	}
+
+	/// Get the value of `key` at the start of the current transaction. Returns `None`
+	/// if the key has not been changed by the transaction.
+	pub fn original_storage_at(&self, key: &H256) -> Option<H256> {
+		self.original_storage.get(key).cloned()
+	}
+
+	/// Forget the original storage values noted for the current transaction.
+	pub fn clear_original_storage(&mut self) {
+		self.original_storage.clear();
+	}
+
+	/// Drop all the storage of the account, including uncommitted changes.
+	pub fn reset_storage(&mut self) {
+		self.storage_root = SHA3_NULL_RLP;
+		self.storage_cache = Self::empty_storage_cache();
+		self.storage_changes.clear();
+	}
+
+	/// Whether the committed storage of the account is known to be empty.
+	pub fn has_empty_storage_root(&self) -> bool {
+		self.storage_root == SHA3_NULL_RLP
+	}
+
 	/// Get (and cache) the contents of the trie's storage at `key`.
 	/// Takes modifed storage into account.
 	pub fn storage_at(&self, db: &HashDB, key: &H256) -> trie::Result<H256> {
@@ -331,6 +368,16 @@ impl Account {
 		self.nonce = self.nonce + U256::from(1u8);
 	}
 
+	/// Set the nonce of the account.
+	pub fn set_nonce(&mut self, nonce: U256) {
+		self.nonce = nonce;
+	}
+
+	/// Set the balance of the account.
+	pub fn set_balance(&mut self, balance: U256) {
+		self.balance = balance;
+	}
+
 	/// Increase account balance.
 	pub fn add_balance(&mut self, x: &U256) {
 		self.balance = self.balance + *x;
@@ -356,6 +403,7 @@ impl Account {
 
 			self.storage_cache.borrow_mut().insert(k, v);
 		}
+		self.original_storage.clear();
 		Ok(())
 	}
 
@@ -394,6 +442,7 @@ impl Account {
 			storage_root: self.storage_root.clone(),
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: HashMap::new(),
+			original_storage: HashMap::new(),
 			code_hash: self.code_hash.clone(),
 			code_size: self.code_size.clone(),
 			code_cache: self.code_cache.clone(),
@@ -406,6 +455,7 @@ impl Account {
 	pub fn clone_dirty(&self) -> Account {
 		let mut account = self.clone_basic();
 		account.storage_changes = self.storage_changes.clone();
+		account.original_storage = self.original_storage.clone();
 		account.code_cache = self.code_cache.clone();
 		account
 	}
@@ -434,6 +484,7 @@ impl Account {
 			cache.insert(k.clone() , v.clone()); //TODO: cloning should not be required here
 		}
 		self.storage_changes = other.storage_changes;
+		self.original_storage = other.original_storage;
 	}
 }
 
@@ -32,8 +32,10 @@ use trace::FlatTrace;
 use pod_account::*;
 use pod_state::{self, PodState};
 use types::basic_account::BasicAccount;
+use types::call_analytics::CallAnalytics;
 use types::executed::{Executed, ExecutionError};
 use types::state_diff::StateDiff;
+use types::state_override::{AccountOverride, StateOverride};
 use transaction::SignedTransaction;
 use state_db::StateDB;
 use evm::{Factory as EvmFactory};
@@ -186,27 +188,59 @@ pub fn check_proof(
 	engine: &Engine,
 	env_info: &EnvInfo,
 ) -> ProvedExecution {
-	let backend = self::backend::ProofCheck::new(proof);
-	let mut factories = Factories::default();
-	factories.accountdb = ::account_db::Factory::Plain;
-
-	let res = State::from_existing(
-		backend,
-		root,
-		engine.account_start_nonce(env_info.number),
-		factories
-	);
-
-	let mut state = match res {
+	check_proof_with_analytics(proof, root, transaction, engine, env_info, Default::default())
+}
+
+/// Check the given proof of execution, producing the traces and state diff
+/// requested by `analytics` along the way.
+pub fn check_proof_with_analytics(
+	proof: &[::util::DBValue],
+	root: H256,
+	transaction: &SignedTransaction,
+	engine: &Engine,
+	env_info: &EnvInfo,
+	analytics: CallAnalytics,
+) -> ProvedExecution {
+	let make_state = || {
+		let backend = self::backend::ProofCheck::new(proof);
+		let mut factories = Factories::default();
+		factories.accountdb = ::account_db::Factory::Plain;
+
+		State::from_existing(
+			backend,
+			root,
+			engine.account_start_nonce(env_info.number),
+			factories
+		)
+	};
+
+	let mut state = match make_state() {
 		Ok(state) => state,
 		Err(_) => return ProvedExecution::BadProof,
 	};
 
-	match state.execute(env_info, engine, transaction, false, true) {
-		Ok(executed) => ProvedExecution::Complete(executed),
-		Err(ExecutionError::Internal(_)) => ProvedExecution::BadProof,
-		Err(e) => ProvedExecution::Failed(e),
+	let options = TransactOptions {
+		tracing: analytics.transaction_tracing,
+		vm_tracing: analytics.vm_tracing,
+		check_nonce: true,
+	};
+
+	let mut executed = match Executive::new(&mut state, env_info, engine).transact_virtual(transaction, options) {
+		Ok(executed) => executed,
+		Err(ExecutionError::Internal(_)) => return ProvedExecution::BadProof,
+		Err(e) => return ProvedExecution::Failed(e),
+	};
+
+	if analytics.state_diffing {
+		// everything touched by the transaction is covered by the proof,
+		// so the pre-state can be rebuilt from it as well.
+		match make_state().and_then(|original| state.diff_from(original)) {
+			Ok(diff) => executed.state_diff = Some(diff),
+			Err(_) => return ProvedExecution::BadProof,
+		}
 	}
+
+	ProvedExecution::Complete(executed)
 }
 
 /// Representation of the entire state of all accounts in the system.
@@ -490,6 +524,9 @@ impl<B: Backend> State<B> {
 					Some(ref account) => {
 						if let Some(value) = account.cached_storage_at(key) {
 							return Ok(value);
+						} else if account.has_empty_storage_root() {
+							// the storage may have been reset locally, don't look it up in the global cache.
+							return Ok(H256::new());
 						} else {
 							local_account = Some(maybe_acc);
 						}
@@ -536,6 +573,29 @@ impl<B: Backend> State<B> {
 		r
 	}
 
+	/// Get the value of storage of account `address` at `key` as it was at the start of the
+	/// current transaction, ignoring any changes made since.
+	pub fn original_storage_at(&self, address: &Address, key: &H256) -> trie::Result<H256> {
+		let original = self.cache.borrow().get(address)
+			.and_then(|entry| entry.account.as_ref())
+			.and_then(|account| account.original_storage_at(key));
+
+		match original {
+			Some(value) => Ok(value),
+			None => self.storage_at(address, key),
+		}
+	}
+
+	/// Forget the original storage values noted during the previous transaction.
+	/// Should be called before executing each transaction.
+	pub fn clear_original_storage(&mut self) {
+		for entry in self.cache.get_mut().values_mut() {
+			if let Some(ref mut account) = entry.account {
+				account.clear_original_storage();
+			}
+		}
+	}
+
 	/// Get accounts' code.
 	pub fn code(&self, a: &Address) -> trie::Result<Option<Arc<Bytes>>> {
 		self.ensure_cached(a, RequireCache::Code, true,
@@ -597,8 +657,11 @@ impl<B: Backend> State<B> {
 	/// Mutate storage of account `a` so that it is `value` for `key`.
 	pub fn set_storage(&mut self, a: &Address, key: H256, value: H256) -> trie::Result<()> {
 		trace!(target: "state", "set_storage({}:{} to {})", a, key.hex(), value.hex());
-		if self.storage_at(a, &key)? != value {
-			self.require(a, false)?.set_storage(key, value)
+		let current = self.storage_at(a, &key)?;
+		if current != value {
+			let mut account = self.require(a, false)?;
+			account.note_original_storage(key.clone(), current);
+			account.set_storage(key, value)
 		}
 
 		Ok(())
@@ -617,6 +680,41 @@ impl<B: Backend> State<B> {
 		Ok(())
 	}
 
+	/// Override the balance, nonce, code and storage of account `a`.
+	/// Meant to be used on a copy of the state before executing a virtual call.
+	pub fn override_account(&mut self, a: &Address, account_override: &AccountOverride) -> trie::Result<()> {
+		let mut account = self.require(a, account_override.code.is_none())?;
+		if let Some(ref balance) = account_override.balance {
+			account.set_balance(*balance);
+		}
+		if let Some(ref nonce) = account_override.nonce {
+			account.set_nonce(*nonce);
+		}
+		if let Some(ref code) = account_override.code {
+			account.reset_code(code.clone());
+		}
+		if let Some(ref storage) = account_override.state {
+			account.reset_storage();
+			for (key, value) in storage {
+				account.set_storage(*key, *value);
+			}
+		}
+		if let Some(ref storage_diff) = account_override.state_diff {
+			for (key, value) in storage_diff {
+				account.set_storage(*key, *value);
+			}
+		}
+		Ok(())
+	}
+
+	/// Apply all the overrides to the state. See `override_account`.
+	pub fn apply_overrides(&mut self, state_override: &StateOverride) -> trie::Result<()> {
+		for (address, account_override) in state_override {
+			self.override_account(address, account_override)?;
+		}
+		Ok(())
+	}
+
 	/// Execute a given transaction, producing a receipt and an optional trace.
 	/// This will change the state accordingly.
 	pub fn apply(&mut self, env_info: &EnvInfo, engine: &Engine, t: &SignedTransaction, tracing: bool) -> ApplyResult {
@@ -1850,6 +1948,32 @@ mod tests {
 		assert_eq!(s.storage_at(&a, &H256::from(&U256::from(1u64))).unwrap(), H256::from(&U256::from(69u64)));
 	}
 
+	#[test]
+	fn original_storage_is_tracked_per_transaction() {
+		let a = Address::zero();
+		let key = H256::from(&U256::from(1u64));
+		let mut state = get_temp_state();
+		state.set_storage(&a, key.clone(), H256::from(&U256::from(69u64))).unwrap();
+		state.commit().unwrap();
+
+		state.set_storage(&a, key.clone(), H256::from(&U256::from(70u64))).unwrap();
+		state.set_storage(&a, key.clone(), H256::from(&U256::from(71u64))).unwrap();
+		assert_eq!(state.storage_at(&a, &key).unwrap(), H256::from(&U256::from(71u64)));
+		assert_eq!(state.original_storage_at(&a, &key).unwrap(), H256::from(&U256::from(69u64)));
+
+		// a new transaction starts without committing the previous one.
+		state.clear_original_storage();
+		state.set_storage(&a, key.clone(), H256::from(&U256::from(72u64))).unwrap();
+		assert_eq!(state.original_storage_at(&a, &key).unwrap(), H256::from(&U256::from(71u64)));
+
+		state.checkpoint();
+		state.set_storage(&a, key.clone(), H256::from(&U256::from(73u64))).unwrap();
+		assert_eq!(state.original_storage_at(&a, &key).unwrap(), H256::from(&U256::from(71u64)));
+		state.revert_to_checkpoint();
+		assert_eq!(state.storage_at(&a, &key).unwrap(), H256::from(&U256::from(72u64)));
+		assert_eq!(state.original_storage_at(&a, &key).unwrap(), H256::from(&U256::from(71u64)));
+	}
+
 	#[test]
 	fn get_from_database() {
 		let a = Address::zero();
@@ -1978,6 +2102,40 @@ mod tests {
 		assert_eq!(state.nonce(&a).unwrap(), U256::from(3u64));
 	}
 
+	#[test]
+	fn override_account() {
+		let mut state = get_temp_state();
+		let a = Address::zero();
+		let b = 1u64.into();
+		state.set_storage(&a, H256::from(1u64), H256::from(1u64)).unwrap();
+		state.set_storage(&a, H256::from(2u64), H256::from(2u64)).unwrap();
+		state.set_storage(&b, H256::from(1u64), H256::from(1u64)).unwrap();
+		state.set_storage(&b, H256::from(2u64), H256::from(2u64)).unwrap();
+		state.commit().unwrap();
+
+		let mut overrides = StateOverride::new();
+		overrides.insert(a, AccountOverride {
+			balance: Some(69.into()),
+			nonce: Some(5.into()),
+			code: Some(vec![0x60, 0x00]),
+			state: Some(vec![(H256::from(1u64), H256::from(5u64))].into_iter().collect()),
+			..Default::default()
+		});
+		overrides.insert(b, AccountOverride {
+			state_diff: Some(vec![(H256::from(1u64), H256::from(5u64))].into_iter().collect()),
+			..Default::default()
+		});
+		state.apply_overrides(&overrides).unwrap();
+
+		assert_eq!(state.balance(&a).unwrap(), 69.into());
+		assert_eq!(state.nonce(&a).unwrap(), 5.into());
+		assert_eq!(state.code(&a).unwrap(), Some(Arc::new(vec![0x60, 0x00])));
+		assert_eq!(state.storage_at(&a, &H256::from(1u64)).unwrap(), H256::from(5u64));
+		assert_eq!(state.storage_at(&a, &H256::from(2u64)).unwrap(), H256::new());
+		assert_eq!(state.storage_at(&b, &H256::from(1u64)).unwrap(), H256::from(5u64));
+		assert_eq!(state.storage_at(&b, &H256::from(2u64)).unwrap(), H256::from(2u64));
+	}
+
 	#[test]
 	fn balance_nonce() {
 		let mut state = get_temp_state();
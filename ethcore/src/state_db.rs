@@ -352,6 +352,21 @@ impl StateDB {
 		&*self.db
 	}
 
+	/// Write all pending insertions straight to the backing database, bypassing the journal.
+	pub fn inject(&mut self, batch: &mut DBTransaction) -> Result<u32, UtilError> {
+		self.db.inject(batch)
+	}
+
+	/// Note the accounts with given address hashes as existing and commit the account bloom.
+	/// Used when the state is downloaded rather than built by executing blocks.
+	pub fn note_account_hashes(&self, batch: &mut DBTransaction, hashes: &[H256]) -> Result<(), UtilError> {
+		let mut bloom = self.account_bloom.lock();
+		for hash in hashes {
+			bloom.set(&**hash);
+		}
+		Self::commit_bloom(batch, bloom.drain_journal())
+	}
+
 	/// Query how much memory is set aside for the accounts cache (in bytes).
 	pub fn cache_size(&self) -> usize {
 		self.cache_size
@@ -15,7 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use io::IoChannel;
-use client::{BlockChainClient, MiningBlockChainClient, Client, ClientConfig, BlockId};
+use client::{BlockChainClient, MiningBlockChainClient, DevBlockChainClient, Client, ClientConfig, BlockId};
 use state::{self, State, CleanupMode};
 use executive::Executive;
 use ethereum;
@@ -24,7 +24,7 @@ use tests::helpers::*;
 use types::filter::Filter;
 use util::*;
 use devtools::*;
-use miner::Miner;
+use miner::{Miner, GasPriceOracle};
 use spec::Spec;
 use views::BlockView;
 use ethkey::KeyPair;
@@ -204,6 +204,13 @@ fn can_generate_gas_price_median() {
 	assert_eq!(Some(&U256::from(3)), client.gas_price_corpus(3).median());
 }
 
+#[test]
+fn can_pick_gas_price_percentile() {
+	let client = generate_dummy_client_with_data(4, 1, slice_into![1, 4, 3, 2]);
+	let oracle = GasPriceOracle { percentile: 25, blocks: 4 };
+	assert_eq!(Some(U256::from(2)), oracle.gas_price_from_chain(&*client));
+}
+
 #[test]
 fn can_generate_gas_price_histogram() {
 	let client = generate_dummy_client_with_data(20, 1, slice_into![6354,8593,6065,4842,7845,7002,689,4958,4250,6098,5804,4320,643,8895,2296,8589,7145,2000,2512,1408]);
@@ -255,6 +262,42 @@ fn can_mine() {
 	assert_eq!(*b.block().header().parent_hash(), BlockView::new(&dummy_blocks[0]).header_view().sha3());
 }
 
+#[test]
+fn can_mine_on_demand_and_revert() {
+	let client = generate_dummy_client_with_spec_and_data(Spec::new_instant, 0, 0, &[]);
+	client.engine().register_client(Arc::downgrade(&client));
+
+	let snapshot = client.snapshot().unwrap();
+	client.mine_block().unwrap();
+	client.mine_block().unwrap();
+	assert_eq!(client.chain_info().best_block_number, 2);
+	assert_eq!(client.increase_time(100).unwrap(), 100);
+
+	assert!(client.revert(snapshot).unwrap());
+	assert_eq!(client.chain_info().best_block_number, 0);
+	assert_eq!(client.chain_info().best_block_hash, client.chain_info().genesis_hash);
+	assert_eq!(client.block_hash(BlockId::Number(1)), None);
+	assert_eq!(client.engine().time_offset(), Some(0));
+	assert!(!client.revert(snapshot).unwrap());
+
+	assert_eq!(client.increase_time(3600).unwrap(), 3600);
+	client.mine_block().unwrap();
+	let header = client.block_header(BlockId::Latest).unwrap();
+	assert_eq!(header.number(), 1);
+	assert!(header.timestamp() >= ::time::get_time().sec as u64 + 3600);
+}
+
+#[test]
+fn refuses_to_revert_without_dev_engine() {
+	let client = generate_dummy_client(2);
+	let best_block_hash = client.chain_info().best_block_hash;
+
+	assert!(client.snapshot().is_err());
+	assert!(client.revert(1).is_err());
+	assert_eq!(client.chain_info().best_block_number, 2);
+	assert_eq!(client.chain_info().best_block_hash, best_block_hash);
+}
+
 #[test]
 fn change_history_size() {
 	let dir = RandomTempPath::new();
@@ -364,3 +407,120 @@ fn transaction_proof() {
 	assert_eq!(state.balance(&Address::default()).unwrap(), 5.into());
 	assert_eq!(state.balance(&address).unwrap(), 95.into());
 }
+
+#[test]
+fn transaction_proof_with_analytics() {
+	use ::client::{ProvingBlockChainClient, CallAnalytics};
+
+	let client = generate_dummy_client(0);
+	let address = Address::random();
+	let test_spec = Spec::new_test();
+	for _ in 0..20 {
+		let mut b = client.prepare_open_block(Address::default(), (3141562.into(), 31415620.into()), vec![]);
+		b.block_mut().fields_mut().state.add_balance(&address, &5.into(), CleanupMode::NoEmpty).unwrap();
+		b.block_mut().fields_mut().state.commit().unwrap();
+		let b = b.close_and_lock().seal(&*test_spec.engine, vec![]).unwrap();
+		client.import_sealed_block(b).unwrap();
+	}
+
+	let transaction = Transaction {
+		nonce: 0.into(),
+		gas_price: 0.into(),
+		gas: 21000.into(),
+		action: Action::Call(Address::default()),
+		value: 5.into(),
+		data: Vec::new(),
+	}.fake_sign(address);
+
+	let proof = client.prove_transaction(transaction.clone(), BlockId::Latest).unwrap().1;
+	let root = client.best_block_header().state_root();
+	let analytics = CallAnalytics {
+		transaction_tracing: true,
+		vm_tracing: false,
+		state_diffing: true,
+	};
+
+	let executed = match state::check_proof_with_analytics(&proof, root, &transaction, &*test_spec.engine, &client.latest_env_info(), analytics) {
+		state::ProvedExecution::Complete(executed) => executed,
+		_ => panic!("proof should be valid"),
+	};
+
+	assert_eq!(executed.trace.len(), 1);
+	let diff = executed.state_diff.expect("state diffing requested; qed");
+	assert!(diff.get().contains_key(&address));
+	assert!(diff.get().contains_key(&Address::default()));
+}
+
+#[test]
+fn rejects_blocks_with_transactions_not_allowed_by_permission_contract() {
+	let spec = Spec::new_test_tx_permission();
+	let client = generate_dummy_client_with_spec_and_accounts(Spec::new_test_tx_permission, None);
+	let block_with_transaction_of = |secret: &str| {
+		let transaction = Transaction {
+			nonce: 0.into(),
+			gas_price: 0.into(),
+			gas: 21000.into(),
+			action: Action::Call(Address::from(0x1234)),
+			value: 0.into(),
+			data: Vec::new(),
+		}.sign(&secret.sha3().into(), None);
+		let mut b = client.prepare_open_block(Address::default(), (3141562.into(), 31415620.into()), vec![]);
+		b.push_transaction(transaction, None).unwrap();
+		b.close_and_lock().seal(&*spec.engine, vec![]).unwrap().rlp_bytes()
+	};
+
+	client.import_block(block_with_transaction_of("2")).unwrap();
+	client.flush_queue();
+	client.import_verified_blocks();
+	assert_eq!(client.chain_info().best_block_number, 0);
+
+	client.import_block(block_with_transaction_of("0")).unwrap();
+	client.flush_queue();
+	client.import_verified_blocks();
+	assert_eq!(client.chain_info().best_block_number, 1);
+}
+
+#[test]
+fn executes_transactions_on_the_state_left_by_the_previous_ones() {
+	use client::{CallAnalytics, Executed, TransactionId};
+	use types::account_diff::Diff;
+
+	let client = generate_dummy_client_with_data(1, 3, &[0.into()]);
+	let sender = KeyPair::from_secret_slice(&"".sha3()).unwrap().address();
+	let analytics = CallAnalytics {
+		transaction_tracing: false,
+		vm_tracing: false,
+		state_diffing: true,
+	};
+	let nonce_after = |executed: &Executed| match executed.state_diff.as_ref().expect("state diffing requested; qed").get()[&sender].nonce {
+		Diff::Born(nonce) | Diff::Changed(_, nonce) => nonce,
+		_ => panic!("every transaction increments the nonce of the sender; qed"),
+	};
+
+	let replayed = client.replay_block_transactions(BlockId::Number(1), analytics).unwrap();
+	assert_eq!(replayed.iter().map(&nonce_after).collect::<Vec<_>>(), vec![1.into(), 2.into(), 3.into()]);
+
+	let replayed = client.replay(TransactionId::Location(BlockId::Number(1), 2), analytics).unwrap();
+	assert_eq!(nonce_after(&replayed), 3.into());
+
+	let transactions = (3..5).map(|nonce| (Transaction {
+		nonce: nonce.into(),
+		gas_price: 0.into(),
+		gas: 100000.into(),
+		action: Action::Create,
+		data: vec![],
+		value: 0.into(),
+	}.fake_sign(sender), analytics)).collect();
+	let called = client.call_many(transactions, BlockId::Latest).unwrap();
+	assert_eq!(called.iter().map(&nonce_after).collect::<Vec<_>>(), vec![4.into(), 5.into()]);
+}
+
+#[test]
+fn replaying_unknown_block_is_not_found() {
+	use client::CallAnalytics;
+	use error::CallError;
+
+	let client = generate_dummy_client(1);
+	let result = client.replay_block_transactions(BlockId::Number(10), CallAnalytics::default());
+	assert_eq!(result.unwrap_err(), CallError::BlockNotFound);
+}
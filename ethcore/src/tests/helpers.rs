@@ -64,6 +64,13 @@ impl TestEngine {
 			max_depth: 0,
 		}
 	}
+
+	pub fn new_constantinople() -> TestEngine {
+		TestEngine {
+			engine: ethereum::new_constantinople_test().engine,
+			max_depth: 1,
+		}
+	}
 }
 
 impl Engine for TestEngine {
@@ -402,7 +409,7 @@ pub fn get_default_ethash_params() -> EthashParams{
 		difficulty_increment_divisor: 10,
 		metropolis_difficulty_increment_divisor: 9,
 		duration_limit: 13,
-		block_reward: U256::from(0),
+		block_reward: BTreeMap::new(),
 		registrar: "0000000000000000000000000000000000000001".into(),
 		homestead_transition: 1150000,
 		dao_hardfork_transition: u64::max_value(),
@@ -411,6 +418,7 @@ pub fn get_default_ethash_params() -> EthashParams{
 		difficulty_hardfork_transition: u64::max_value(),
 		difficulty_hardfork_bound_divisor: U256::from(0),
 		bomb_defuse_transition: u64::max_value(),
+		difficulty_bomb_delays: BTreeMap::new(),
 		eip100b_transition: u64::max_value(),
 		eip150_transition: u64::max_value(),
 		eip155_transition: u64::max_value(),
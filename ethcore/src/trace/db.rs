@@ -215,8 +215,8 @@ impl<T> TraceDB<T> where T: DatabaseExtras {
 		block_number: BlockNumber,
 		tx_number: usize
 	) -> Vec<LocalizedTrace> {
-		let tx_hash = self.extras.transaction_hash(block_number, tx_number)
-			.expect("Expected to find transaction hash. Database is probably corrupted");
+		// traces appended after the last transaction (e.g. block rewards) have no transaction hash
+		let tx_hash = self.extras.transaction_hash(block_number, tx_number);
 
 		let flat_traces: Vec<FlatTrace> = traces.into();
 		flat_traces.into_iter()
@@ -227,7 +227,7 @@ impl<T> TraceDB<T> where T: DatabaseExtras {
 						result: trace.result,
 						subtraces: trace.subtraces,
 						trace_address: trace.trace_address.into_iter().collect(),
-						transaction_number: tx_number,
+						transaction_number: tx_hash.map(|_| tx_number),
 						transaction_hash: tx_hash.clone(),
 						block_number: block_number,
 						block_hash: block_hash
@@ -313,15 +313,14 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 				// this may and should be optimized
 				.and_then(|traces| traces.into_iter().find(|trace| trace.trace_address == trace_position_deq))
 				.map(|trace| {
-					let tx_hash = self.extras.transaction_hash(block_number, tx_position)
-						.expect("Expected to find transaction hash. Database is probably corrupted");
+					let tx_hash = self.extras.transaction_hash(block_number, tx_position);
 
 					LocalizedTrace {
 						action: trace.action,
 						result: trace.result,
 						subtraces: trace.subtraces,
 						trace_address: trace.trace_address.into_iter().collect(),
-						transaction_number: tx_position,
+						transaction_number: tx_hash.map(|_| tx_position),
 						transaction_hash: tx_hash,
 						block_number: block_number,
 						block_hash: block_hash,
@@ -336,8 +335,7 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 				.and_then(|traces| traces.into_iter().nth(tx_position))
 				.map(Into::<Vec<FlatTrace>>::into)
 				.map(|traces| {
-					let tx_hash = self.extras.transaction_hash(block_number, tx_position)
-						.expect("Expected to find transaction hash. Database is probably corrupted");
+					let tx_hash = self.extras.transaction_hash(block_number, tx_position);
 
 					traces.into_iter()
 					.map(|trace| LocalizedTrace {
@@ -345,7 +343,7 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 						result: trace.result,
 						subtraces: trace.subtraces,
 						trace_address: trace.trace_address.into_iter().collect(),
-						transaction_number: tx_position,
+						transaction_number: tx_hash.map(|_| tx_position),
 						transaction_hash: tx_hash.clone(),
 						block_number: block_number,
 						block_hash: block_hash
@@ -363,8 +361,7 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 						.map(Into::<Vec<FlatTrace>>::into)
 						.enumerate()
 						.flat_map(|(tx_position, traces)| {
-							let tx_hash = self.extras.transaction_hash(block_number, tx_position)
-								.expect("Expected to find transaction hash. Database is probably corrupted");
+							let tx_hash = self.extras.transaction_hash(block_number, tx_position);
 
 							traces.into_iter()
 								.map(|trace| LocalizedTrace {
@@ -372,7 +369,7 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 									result: trace.result,
 									subtraces: trace.subtraces,
 									trace_address: trace.trace_address.into_iter().collect(),
-									transaction_number: tx_position,
+									transaction_number: tx_hash.map(|_| tx_position),
 									transaction_hash: tx_hash.clone(),
 									block_number: block_number,
 									block_hash: block_hash,
@@ -408,7 +405,7 @@ mod tests {
 	use header::BlockNumber;
 	use trace::{Config, TraceDB, Database as TraceDatabase, DatabaseExtras, ImportRequest};
 	use trace::{Filter, LocalizedTrace, AddressesFilter, TraceError};
-	use trace::trace::{Call, Action, Res};
+	use trace::trace::{Call, Action, Res, Reward, RewardType};
 	use trace::flat::{FlatTrace, FlatBlockTraces, FlatTransactionTraces};
 	use types::executed::CallType;
 
@@ -543,8 +540,8 @@ mod tests {
 			result: Res::FailedCall(TraceError::OutOfGas),
 			trace_address: vec![],
 			subtraces: 0,
-			transaction_number: 0,
-			transaction_hash: tx_hash,
+			transaction_number: Some(0),
+			transaction_hash: Some(tx_hash),
 			block_number: block_number,
 			block_hash: block_hash,
 		}
@@ -708,4 +705,67 @@ mod tests {
 
 		assert_eq!(traces.len(), 0);
 	}
+
+	#[test]
+	fn query_reward_traces() {
+		let db = new_db();
+		let mut config = Config::default();
+		let mut extras = Extras::default();
+		let block_1 = H256::from(0xa1);
+		let tx_1 = H256::from(0xff);
+
+		extras.block_hashes.insert(0, H256::default());
+		extras.transaction_hashes.insert(0, vec![]);
+		extras.block_hashes.insert(1, block_1.clone());
+		extras.transaction_hashes.insert(1, vec![tx_1.clone()]);
+
+		// set tracing on
+		config.enabled = true;
+
+		let tracedb = TraceDB::new(config, db.clone(), Arc::new(extras));
+
+		let mut request = create_simple_import_request(1, block_1.clone());
+		let mut traces: Vec<FlatTransactionTraces> = request.traces.into();
+		traces.push(FlatTransactionTraces::from(vec![FlatTrace {
+			trace_address: Default::default(),
+			subtraces: 0,
+			action: Action::Reward(Reward {
+				author: 5.into(),
+				value: 10.into(),
+				reward_type: RewardType::Block,
+			}),
+			result: Res::None,
+		}]));
+		request.traces = traces.into();
+		let mut batch = DBTransaction::new();
+		tracedb.import(&mut batch, request);
+		db.write(batch).unwrap();
+
+		let reward_trace = LocalizedTrace {
+			action: Action::Reward(Reward {
+				author: 5.into(),
+				value: 10.into(),
+				reward_type: RewardType::Block,
+			}),
+			result: Res::None,
+			trace_address: vec![],
+			subtraces: 0,
+			transaction_number: None,
+			transaction_hash: None,
+			block_number: 1,
+			block_hash: block_1.clone(),
+		};
+
+		let traces = tracedb.block_traces(1).unwrap();
+		assert_eq!(traces, vec![create_simple_localized_trace(1, block_1.clone(), tx_1.clone()), reward_trace.clone()]);
+
+		let filter = Filter {
+			range: (1..1),
+			from_address: AddressesFilter::from(vec![]),
+			to_address: AddressesFilter::from(vec![Address::from(5)]),
+		};
+
+		let traces = tracedb.filter(&filter);
+		assert_eq!(traces, vec![reward_trace]);
+	}
 }
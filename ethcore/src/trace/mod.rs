@@ -35,7 +35,7 @@ pub use types::trace_types::filter::{Filter, AddressesFilter};
 pub use self::import::ImportRequest;
 pub use self::localized::LocalizedTrace;
 use util::{Bytes, Address, U256, H256, DBTransaction};
-use self::trace::{Call, Create};
+use self::trace::{Call, Create, RewardType};
 use action_params::ActionParams;
 use header::BlockNumber;
 
@@ -78,6 +78,9 @@ pub trait Tracer: Send {
 	/// Stores suicide info.
 	fn trace_suicide(&mut self, address: Address, balance: U256, refund_address: Address);
 
+	/// Stores reward info.
+	fn trace_reward(&mut self, author: Address, value: U256, reward_type: RewardType);
+
 	/// Spawn subtracer which will be used to trace deeper levels of execution.
 	fn subtracer(&self) -> Self where Self: Sized;
 
@@ -0,0 +1,159 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Smart contract based transaction filter.
+
+use futures::{future, Future};
+use lru_cache::LruCache;
+use native_contracts::TransactAcl as Contract;
+
+use util::*;
+use client::{BlockChainClient, BlockId};
+use header::BlockNumber;
+use spec::CommonParams;
+use transaction::{Action, SignedTransaction};
+
+const MAX_CACHE_SIZE: usize = 4096;
+
+mod tx_permissions {
+	pub const CALL: u32 = 0b00000001;
+	pub const BASIC: u32 = 0b00000010;
+	pub const CREATE: u32 = 0b00000100;
+}
+
+/// Transaction filter that uses a contract to manage permissions.
+/// The contract is queried with `allowedTxTypes(address sender, address to, uint32 txType) returns (uint32)`,
+/// where `to` is zero for contract creation, returning a bitmask of the transaction types the sender
+/// may make to the target: call (1), basic transfer (2) and create (4).
+pub struct TransactionFilter {
+	contract: Contract,
+	transition_block: BlockNumber,
+	permission_cache: Mutex<LruCache<(H256, Address, Address, u32), u32>>,
+}
+
+impl TransactionFilter {
+	/// Create a new instance if the transaction permission contract is set in the params.
+	pub fn from_params(params: &CommonParams) -> Option<TransactionFilter> {
+		params.transaction_permission_contract.map(|address|
+			TransactionFilter {
+				contract: Contract::new(address),
+				transition_block: params.transaction_permission_contract_transition,
+				permission_cache: Mutex::new(LruCache::new(MAX_CACHE_SIZE)),
+			}
+		)
+	}
+
+	/// Check whether the transaction is allowed in block `block_number`, a child of `parent_hash`.
+	/// The contract is queried at the state of the parent block. Errors calling the contract
+	/// are returned and not cached.
+	pub fn transaction_allowed(&self, parent_hash: &H256, block_number: BlockNumber, transaction: &SignedTransaction, client: &BlockChainClient) -> Result<bool, String> {
+		if block_number < self.transition_block { return Ok(true); }
+
+		let sender = transaction.sender();
+		let (to, tx_type) = match transaction.action {
+			Action::Create => (Address::new(), tx_permissions::CREATE),
+			Action::Call(address) => (address, match client.code(&address, BlockId::Hash(*parent_hash)) {
+				Some(Some(ref code)) if !code.is_empty() => tx_permissions::CALL,
+				_ => tx_permissions::BASIC,
+			}),
+		};
+
+		let key = (*parent_hash, sender, to, tx_type);
+		let cached = self.permission_cache.lock().get_mut(&key).map(|permissions| *permissions);
+		let permissions = match cached {
+			Some(permissions) => permissions,
+			None => {
+				let permissions = self.contract.allowed_tx_types(
+					|addr, data| future::done(client.call_contract(BlockId::Hash(*parent_hash), addr, data)),
+					sender,
+					to,
+					tx_type,
+				).wait().map_err(|e| {
+					debug!(target: "tx_filter", "Error calling the transaction permission contract: {}", e);
+					e
+				})?;
+				self.permission_cache.lock().insert(key, permissions);
+				permissions
+			},
+		};
+
+		trace!(target: "tx_filter", "Permissions of {} to {}: required {:b}, got {:b}", sender, to, tx_type, permissions);
+		Ok(permissions & tx_type != 0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use util::*;
+	use client::BlockChainClient;
+	use spec::Spec;
+	use tests::helpers::generate_dummy_client_with_spec_and_accounts;
+	use transaction::{Transaction, SignedTransaction, Action};
+	use super::TransactionFilter;
+
+	#[test]
+	fn filters_by_transaction_type() {
+		let client = generate_dummy_client_with_spec_and_accounts(Spec::new_test_tx_permission, None);
+		let filter = TransactionFilter::from_params(client.engine().params()).unwrap();
+		let genesis = client.best_block_header().hash();
+		let contract = Address::from(0x42);
+
+		let transaction = |secret: &str, action: Action| Transaction {
+			nonce: 0.into(),
+			gas_price: 0.into(),
+			gas: 21000.into(),
+			action: action,
+			value: 0.into(),
+			data: Vec::new(),
+		}.sign(&secret.sha3().into(), None);
+		let allowed = |transaction: &SignedTransaction| filter.transaction_allowed(&genesis, 1, transaction, &*client).unwrap();
+
+		// allowed to do everything.
+		assert!(allowed(&transaction("1", Action::Create)));
+		assert!(allowed(&transaction("1", Action::Call(contract))));
+		assert!(allowed(&transaction("1", Action::Call(Address::from(0x1234)))));
+
+		// allowed to make basic transfers only.
+		assert!(!allowed(&transaction("0", Action::Create)));
+		assert!(!allowed(&transaction("0", Action::Call(contract))));
+		assert!(allowed(&transaction("0", Action::Call(Address::from(0x1234)))));
+
+		// not allowed at all.
+		assert!(!allowed(&transaction("2", Action::Call(Address::from(0x1234)))));
+
+		// anyone may make basic transfers to 0x43.
+		assert!(allowed(&transaction("2", Action::Call(Address::from(0x43)))));
+		assert!(!allowed(&transaction("2", Action::Create)));
+	}
+
+	#[test]
+	fn does_not_cache_contract_errors() {
+		let client = generate_dummy_client_with_spec_and_accounts(Spec::new_test_tx_permission, None);
+		let filter = TransactionFilter::from_params(client.engine().params()).unwrap();
+		let transaction = Transaction {
+			nonce: 0.into(),
+			gas_price: 0.into(),
+			gas: 21000.into(),
+			action: Action::Call(Address::from(0x1234)),
+			value: 0.into(),
+			data: Vec::new(),
+		}.sign(&"1".sha3().into(), None);
+
+		// there is no state to call the contract at.
+		assert!(filter.transaction_allowed(&H256::random(), 1, &transaction, &*client).is_err());
+		assert_eq!(filter.permission_cache.lock().len(), 0);
+	}
+}
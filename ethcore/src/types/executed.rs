@@ -196,6 +196,8 @@ impl fmt::Display for ExecutionError {
 pub enum CallError {
 	/// Couldn't find the transaction in the chain.
 	TransactionNotFound,
+	/// Couldn't find the requested block in the chain.
+	BlockNotFound,
 	/// Couldn't find requested block's state in the chain.
 	StatePruned,
 	/// Couldn't find an amount of gas that didn't result in an exception.
@@ -218,6 +220,7 @@ impl fmt::Display for CallError {
 
 		let msg = match *self {
 			TransactionNotFound => "Transaction couldn't be found in the chain".into(),
+			BlockNotFound => "Block couldn't be found in the chain".into(),
 			StatePruned => "Couldn't find the transaction block's state in the chain".into(),
 			Exceptional => "An exception happened in the execution".into(),
 			StateCorrupt => "Stored state found to be corrupted.".into(),
@@ -0,0 +1,40 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State overrides for virtual calls.
+
+use std::collections::BTreeMap;
+use util::{Address, U256, H256, Bytes};
+
+/// Changes applied to a single account before executing a virtual call.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "ipc", binary)]
+pub struct AccountOverride {
+	/// Balance to set.
+	pub balance: Option<U256>,
+	/// Nonce to set.
+	pub nonce: Option<U256>,
+	/// Code to set.
+	pub code: Option<Bytes>,
+	/// Storage replacing the whole storage of the account.
+	pub state: Option<BTreeMap<H256, H256>>,
+	/// Storage slots to set, leaving other slots untouched.
+	/// Applied after `state`.
+	pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+/// Changes applied to the state before executing a virtual call, keyed by account address.
+pub type StateOverride = BTreeMap<Address, AccountOverride>;
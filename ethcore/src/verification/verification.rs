@@ -22,14 +22,16 @@
 //! 3. Final verification against the blockchain done before enactment.
 
 use util::*;
-use engines::Engine;
-use error::{BlockError, Error};
+use client::BlockChainClient;
+use engines::{Engine, EngineError};
+use error::{BlockError, TransactionError, Error};
 use blockchain::*;
 use header::{BlockNumber, Header};
 use rlp::UntrustedRlp;
 use transaction::SignedTransaction;
 use views::BlockView;
 use time::get_time;
+use tx_filter::TransactionFilter;
 
 /// Preprocessed block data gathered in `verify_block_unordered` call
 pub struct PreverifiedBlock {
@@ -191,6 +193,17 @@ pub fn verify_block_family(header: &Header, bytes: &[u8], engine: &Engine, bc: &
 	Ok(())
 }
 
+/// Phase 3 verification of the transactions. Check that the transaction permission contract
+/// allows the senders to make them.
+pub fn verify_transactions_allowed(header: &Header, transactions: &[SignedTransaction], filter: &TransactionFilter, client: &BlockChainClient) -> Result<(), Error> {
+	for t in transactions {
+		if !filter.transaction_allowed(header.parent_hash(), header.number(), t, client).map_err(EngineError::FailedSystemCall)? {
+			return Err(TransactionError::NotAllowed.into());
+		}
+	}
+	Ok(())
+}
+
 /// Phase 4 verification. Check block information against transaction enactment results,
 pub fn verify_block_final(expected: &Header, got: &Header) -> Result<(), Error> {
 	if expected.gas_used() != got.gas_used() {
@@ -208,6 +221,16 @@ pub fn verify_block_final(expected: &Header, got: &Header) -> Result<(), Error>
 	Ok(())
 }
 
+/// Verify a header imported without the rest of its ancestry, such as the fast sync pivot,
+/// against its parent header. Checks everything that does not need the block body or state.
+pub fn verify_header_with_parent(header: &Header, parent: &Header, engine: &Engine) -> Result<(), Error> {
+	verify_header_params(header, engine, true)?;
+	engine.verify_block_basic(header, None)?;
+	engine.verify_block_unordered(header, None)?;
+	verify_parent(header, parent)?;
+	engine.verify_block_family(header, parent, None)
+}
+
 /// Check basic header parameters.
 pub fn verify_header_params(header: &Header, engine: &Engine, is_full: bool) -> Result<(), Error> {
 	if header.number() >= From::from(BlockNumber::max_value()) {
@@ -611,4 +634,38 @@ mod tests {
 		check_fail(unordered_test(&create_test_block_with_data(&header, &bad_transactions, &[]), &engine), TooManyTransactions(keypair.address()));
 		unordered_test(&create_test_block_with_data(&header, &good_transactions, &[]), &engine).unwrap();
 	}
+
+	#[test]
+	fn transactions_allowed_by_permission_contract() {
+		use client::BlockChainClient;
+		use engines::EngineError;
+		use tx_filter::TransactionFilter;
+
+		let client = generate_dummy_client_with_spec_and_accounts(Spec::new_test_tx_permission, None);
+		let filter = TransactionFilter::from_params(client.engine().params()).unwrap();
+		let transaction = |secret: &str| Transaction {
+			nonce: 0.into(),
+			gas_price: 0.into(),
+			gas: 21000.into(),
+			action: Action::Call(Address::from(0x1234)),
+			value: 0.into(),
+			data: Vec::new(),
+		}.sign(&secret.sha3().into(), None);
+
+		let mut header = Header::default();
+		header.set_number(1);
+		header.set_parent_hash(client.best_block_header().hash());
+		check_ok(verify_transactions_allowed(&header, &[transaction("1"), transaction("0")], &filter, &*client));
+		match verify_transactions_allowed(&header, &[transaction("1"), transaction("2")], &filter, &*client) {
+			Err(Error::Transaction(TransactionError::NotAllowed)) => (),
+			other => panic!("Expected a not allowed transaction, got {:?}", other),
+		}
+
+		// the contract cannot be called without the parent state.
+		header.set_parent_hash(H256::random());
+		match verify_transactions_allowed(&header, &[transaction("1")], &filter, &*client) {
+			Err(Error::Engine(EngineError::FailedSystemCall(_))) => (),
+			other => panic!("Expected a failed contract call, got {:?}", other),
+		}
+	}
 }
@@ -15,6 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 import db from './interfaces/db';
+import debug from './interfaces/debug';
 import eth from './interfaces/eth';
 import net from './interfaces/net';
 import parity from './interfaces/parity';
@@ -26,6 +27,7 @@ import web3 from './interfaces/web3';
 
 export default {
   db,
+  debug,
   eth,
   parity,
   net,
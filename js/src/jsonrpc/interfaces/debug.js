@@ -0,0 +1,90 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+import { BlockNumber, CallRequest, Hash } from '../types';
+import { withPreamble, Dummy, fromDecimal } from '../helpers';
+
+const TRACE_OPTIONS = {
+  type: Object,
+  desc: 'Tracing options: `disableStack`, `disableMemory`, `disableStorage` (`Boolean`) and `limit` (maximal number of struct logs returned).',
+  example: {
+    disableStorage: true
+  },
+  optional: true
+};
+
+const TRACE_RESULT = {
+  type: Object,
+  desc: 'Struct-log trace of the execution, compatible with the one returned by Geth.',
+  example: {
+    gas: 21006,
+    failed: false,
+    returnValue: '',
+    structLogs: [
+      {
+        pc: 0,
+        op: 'PUSH1',
+        gas: 100,
+        gasCost: 3,
+        depth: 1,
+        stack: [],
+        memory: [],
+        storage: new Dummy('{ ... }')
+      }
+    ]
+  }
+};
+
+export default withPreamble(`
+
+The debug module provides Geth-compatible struct-log traces: a full record of every executed opcode
+together with the stack, memory and storage of the executing contract.
+
+Traces are rebuilt from the VM trace (see \`trace_replayTransaction\`), so replaying an old transaction
+requires the state of its parent block to be available.
+
+`, {
+  traceCall: {
+    desc: 'Executes the given call on top of the given block and returns its struct-log trace.',
+    params: [
+      {
+        type: CallRequest,
+        desc: 'Call options, same as `eth_call`.',
+        example: new Dummy('{ ... }')
+      },
+      {
+        type: BlockNumber,
+        desc: 'Integer block number, or the string `\'latest\'`, `\'earliest\'` or `\'pending\'`.',
+        example: fromDecimal(3068185)
+      },
+      TRACE_OPTIONS
+    ],
+    returns: TRACE_RESULT
+  },
+
+  traceTransaction: {
+    desc: 'Replays a transaction and returns its struct-log trace.',
+    params: [
+      {
+        type: Hash,
+        desc: 'Transaction hash.',
+        example: '0x02d4a872e096445e80d05276ee756cefef7f3b376bcec14246469c0cd97dad8f'
+      },
+      TRACE_OPTIONS
+    ],
+    returns: TRACE_RESULT
+  }
+});
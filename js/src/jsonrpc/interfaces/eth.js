@@ -30,6 +30,7 @@ The following methods have an optional extra \`defaultBlock\` parameter:
 - [eth_getCode](#eth_getcode)
 - [eth_getTransactionCount](#eth_gettransactioncount)
 - [eth_getStorageAt](#eth_getstorageat)
+- [eth_getProof](#eth_getproof)
 - [eth_call](#eth_call)
 
 When requests are made that act on the state of Ethereum, the last parameter determines the height of the block.
@@ -459,6 +460,63 @@ The following options are possible for the \`defaultBlock\` parameter:
     }
   },
 
+  getProof: {
+    desc: 'Returns the account and storage values of the specified account including the Merkle proofs.',
+    params: [
+      {
+        type: Address,
+        desc: '20 Bytes - address of the account.',
+        example: '0x407d73d8a49eeb85d32cf465507dd71d507100c1'
+      },
+      {
+        type: Array,
+        desc: 'Array of storage keys which should be proven and included.',
+        example: ['0x0000000000000000000000000000000000000000000000000000000000000000']
+      },
+      {
+        type: BlockNumber,
+        desc: 'integer block number, or the string `\'latest\'` or `\'earliest\'`, see the [default block parameter](#the-default-block-parameter).',
+        format: 'inputDefaultBlockNumberFormatter',
+        example: fromDecimal(2),
+        optional: true
+      }
+    ],
+    returns: {
+      type: Object,
+      desc: 'The account object.',
+      details: {
+        address: {
+          type: Address,
+          desc: 'The address of the account.'
+        },
+        balance: {
+          type: Quantity,
+          desc: 'The balance of the account.'
+        },
+        nonce: {
+          type: Quantity,
+          desc: 'The nonce of the account.'
+        },
+        codeHash: {
+          type: Hash,
+          desc: 'The hash of the code of the account.'
+        },
+        storageHash: {
+          type: Hash,
+          desc: 'The root of the storage trie of the account.'
+        },
+        accountProof: {
+          type: Array,
+          desc: 'Array of RLP-serialized trie nodes, starting with the state root node, following the path of the hashed address as key.'
+        },
+        storageProof: {
+          type: Array,
+          desc: 'Array of `{ key, value, proof }` objects, one per requested storage key. `proof` follows the path of the hashed storage key, starting with the storage root node.'
+        }
+      }
+    }
+  },
+
   getStorageAt: {
     desc: 'Returns the value from a storage position at a given address.',
     params: [
@@ -2008,6 +2008,24 @@ export default {
     }
   },
 
+  stratumWorkers: {
+    section: SECTION_MINING,
+    desc: 'Returns share statistics of the workers authorized by the Stratum server.',
+    params: [],
+    returns: {
+      type: Object,
+      desc: 'Mapping of worker names to their share statistics.',
+      example: {
+        miner1: {
+          accepted: 120,
+          stale: 2,
+          invalid: 0,
+          hashrate: fromDecimal(23500000)
+        }
+      }
+    }
+  },
+
   // Pub-Sub
   subscribe: {
     subdoc: SUBDOC_PUBSUB,
@@ -49,6 +49,10 @@ Unlike the log filtering API, you are able to search and filter based only upon
 Information returned includes the execution of all \`CREATE\`s, \`SUICIDE\`s and all variants of \`CALL\` together
 with input data, output data, gas usage, amount transferred and the success status of each individual action.
 
+Block and uncle rewards are returned as traces of type \`reward\` with the \`author\`, \`value\` and \`rewardType\`
+(\`block\` or \`uncle\`) of the reward. They are not part of any transaction, so their \`transactionPosition\` and
+\`transactionHash\` are \`null\`, and they can be found by filtering on the \`toAddress\` of the author.
+
 ### \`traceAddress\` field
 
 The \`traceAddress\` field of all returned traces, gives the exact location in the call trace [index in root,
@@ -298,6 +302,47 @@ then it should look something like:
     }
   },
 
+  callMany: {
+    section: SECTION_ADHOC,
+    desc: 'Performs multiple call traces on top of the same block. i.e. transaction `n` will be executed on top of a pending block with all `n-1` transactions applied (traced) first. Allows to trace dependent transactions.',
+    params: [
+      {
+        type: Array,
+        desc: 'List of trace calls with the type of trace, one or more of: `"vmTrace"`, `"trace"`, `"stateDiff"`.',
+        example: [[new Dummy('{ ... }'), ['trace']], [new Dummy('{ ... }'), ['trace']]]
+      },
+      {
+        type: BlockNumber,
+        optional: true,
+        desc: 'Integer of a block number, or the string `\'earliest\'`, `\'latest\'` or `\'pending\'`.'
+      }
+    ],
+    returns: {
+      type: Array,
+      desc: 'Array of the given transactions\' traces',
+      example: [
+        {
+          output: '0x',
+          stateDiff: null,
+          trace: [
+            {
+              action: new Dummy('{ ... }'),
+              result: {
+                gasUsed: '0x0',
+                output: '0x'
+              },
+              subtraces: 0,
+              traceAddress: [],
+              type: 'call'
+            }
+          ],
+          vmTrace: null
+        },
+        new Dummy('...')
+      ]
+    }
+  },
+
   rawTransaction: {
     section: SECTION_ADHOC,
     desc: 'Traces a call to `eth_sendRawTransaction` without making the call, returning the traces',
@@ -336,6 +381,47 @@ then it should look something like:
     }
   },
 
+  replayBlockTransactions: {
+    section: SECTION_ADHOC,
+    desc: 'Replays all transactions in a block returning the requested traces for each transaction.',
+    params: [
+      {
+        type: BlockNumber,
+        desc: 'Integer of a block number, or the string `\'earliest\'`, `\'latest\'` or `\'pending\'`.',
+        example: fromDecimal(3068185)
+      },
+      {
+        type: Array,
+        desc: 'Type of trace, one or more of: `"vmTrace"`, `"trace"`, `"stateDiff"`.',
+        example: ['trace']
+      }
+    ],
+    returns: {
+      type: Array,
+      desc: 'Block transactions traces.',
+      example: [
+        {
+          output: '0x',
+          stateDiff: null,
+          trace: [
+            {
+              action: new Dummy('{ ... }'),
+              result: {
+                gasUsed: '0x0',
+                output: '0x'
+              },
+              subtraces: 0,
+              traceAddress: [],
+              type: 'call'
+            }
+          ],
+          vmTrace: null
+        },
+        new Dummy('...')
+      ]
+    }
+  },
+
   replayTransaction: {
     section: SECTION_ADHOC,
     desc: 'Replays a transaction, returning the traces.',
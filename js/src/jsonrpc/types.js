@@ -60,6 +60,11 @@ export class CallRequest {
       type: Data,
       desc: '4 byte hash of the method signature followed by encoded parameters. For details see [Ethereum Contract ABI](https://github.com/ethereum/wiki/wiki/Ethereum-Contract-ABI).',
       optional: true
+    },
+    stateOverride: {
+      type: Object,
+      desc: 'Map of addresses to account overrides applied to the state before the call is executed. Each override may contain `balance`, `nonce`, `code`, `state` (replaces the whole storage) or `stateDiff` (replaces the given storage slots only). Only honoured by `eth_call`, `eth_estimateGas`, `trace_call` and `debug_traceCall` on full nodes.',
+      optional: true
     }
   }
 }
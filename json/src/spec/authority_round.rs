@@ -52,6 +52,15 @@ pub struct AuthorityRoundParams {
 	/// Whether transitions should be immediate.
 	#[serde(rename="immediateTransitions")]
 	pub immediate_transitions: Option<bool>,
+	/// Block from which validators broadcast empty step messages instead of sealing empty blocks.
+	#[serde(rename="emptyStepsTransition")]
+	pub empty_steps_transition: Option<Uint>,
+	/// Address of the contract which determines the block rewards.
+	#[serde(rename="blockRewardContractAddress")]
+	pub block_reward_contract_address: Option<Address>,
+	/// Block from which the block reward contract is used instead of the fixed block reward.
+	#[serde(rename="blockRewardContractTransition")]
+	pub block_reward_contract_transition: Option<Uint>,
 }
 
 /// Authority engine deserialization.
@@ -83,7 +92,10 @@ mod tests {
 				"blockReward": "0x50",
 				"startStep" : 24,
 				"eip155Transition": "0x42",
-				"validateStepTransition": 150
+				"validateStepTransition": 150,
+				"emptyStepsTransition": "0x64",
+				"blockRewardContractAddress": "0x0000000000000000000000000000000000000042",
+				"blockRewardContractTransition": "0x0a"
 			}
 		}"#;
 
@@ -96,5 +108,8 @@ mod tests {
 		assert_eq!(deserialized.params.start_step, Some(Uint(U256::from(24))));
 		assert_eq!(deserialized.params.eip155_transition, Some(Uint(U256::from(0x42))));
 		assert_eq!(deserialized.params.immediate_transitions, None);
+		assert_eq!(deserialized.params.empty_steps_transition, Some(Uint(U256::from(0x64))));
+		assert_eq!(deserialized.params.block_reward_contract_address, Some(Address(H160::from(0x42))));
+		assert_eq!(deserialized.params.block_reward_contract_transition, Some(Uint(U256::from(0x0a))));
 	}
 }
@@ -16,6 +16,7 @@
 
 //! Spec builtin deserialization.
 
+use std::collections::BTreeMap;
 use uint::Uint;
 
 /// Linear pricing.
@@ -34,6 +35,13 @@ pub struct Modexp {
 	pub divisor: usize,
 }
 
+/// Pricing for the Blake2 compression function.
+#[derive(Debug, PartialEq, Deserialize, Clone)]
+pub struct Blake2F {
+	/// Price per round.
+	pub gas_per_round: u64,
+}
+
 /// Pricing variants.
 #[derive(Debug, PartialEq, Deserialize, Clone)]
 pub enum Pricing {
@@ -43,6 +51,19 @@ pub enum Pricing {
 	/// Pricing for modular exponentiation.
 	#[serde(rename="modexp")]
 	Modexp(Modexp),
+	/// Pricing for the Blake2 compression function.
+	#[serde(rename="blake2_f")]
+	Blake2F(Blake2F),
+}
+
+/// Builtin pricing, either a single scheme or a map of block numbers to the schemes starting there.
+#[derive(Debug, PartialEq, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum PricingSchedule {
+	/// Pricing for all blocks.
+	Single(Pricing),
+	/// Pricing starting at given block numbers.
+	Multi(BTreeMap<Uint, Pricing>),
 }
 
 /// Spec builtin.
@@ -51,7 +72,7 @@ pub struct Builtin {
 	/// Builtin name.
 	pub name: String,
 	/// Builtin pricing.
-	pub pricing: Pricing,
+	pub pricing: PricingSchedule,
 	/// Activation block.
 	pub activate_at: Option<Uint>,
 }
@@ -59,7 +80,7 @@ pub struct Builtin {
 #[cfg(test)]
 mod tests {
 	use serde_json;
-	use spec::builtin::{Builtin, Pricing, Linear, Modexp};
+	use spec::builtin::{Builtin, Pricing, PricingSchedule, Linear, Modexp, Blake2F};
 	use uint::Uint;
 
 	#[test]
@@ -70,7 +91,7 @@ mod tests {
 		}"#;
 		let deserialized: Builtin = serde_json::from_str(s).unwrap();
 		assert_eq!(deserialized.name, "ecrecover");
-		assert_eq!(deserialized.pricing, Pricing::Linear(Linear { base: 3000, word: 0 }));
+		assert_eq!(deserialized.pricing, PricingSchedule::Single(Pricing::Linear(Linear { base: 3000, word: 0 })));
 		assert!(deserialized.activate_at.is_none());
 	}
 
@@ -84,7 +105,40 @@ mod tests {
 
 		let deserialized: Builtin = serde_json::from_str(s).unwrap();
 		assert_eq!(deserialized.name, "late_start");
-		assert_eq!(deserialized.pricing, Pricing::Modexp(Modexp { divisor: 5 }));
+		assert_eq!(deserialized.pricing, PricingSchedule::Single(Pricing::Modexp(Modexp { divisor: 5 })));
 		assert_eq!(deserialized.activate_at, Some(Uint(100000.into())));
 	}
+
+	#[test]
+	fn blake2_f_deserialization() {
+		let s = r#"{
+			"name": "blake2_f",
+			"activate_at": "0xffffff",
+			"pricing": { "blake2_f": { "gas_per_round": 123 } }
+		}"#;
+
+		let deserialized: Builtin = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized.name, "blake2_f");
+		assert_eq!(deserialized.pricing, PricingSchedule::Single(Pricing::Blake2F(Blake2F { gas_per_round: 123 })));
+		assert_eq!(deserialized.activate_at, Some(Uint(0xffffff.into())));
+	}
+
+	#[test]
+	fn pricing_per_block() {
+		let s = r#"{
+			"name": "bn128_add",
+			"pricing": {
+				"0": { "linear": { "base": 500, "word": 0 } },
+				"0x2710": { "linear": { "base": 150, "word": 0 } }
+			}
+		}"#;
+
+		let deserialized: Builtin = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized.name, "bn128_add");
+		assert_eq!(deserialized.pricing, PricingSchedule::Multi(vec![
+			(Uint(0.into()), Pricing::Linear(Linear { base: 500, word: 0 })),
+			(Uint(10000.into()), Pricing::Linear(Linear { base: 150, word: 0 })),
+		].into_iter().collect()));
+		assert!(deserialized.activate_at.is_none());
+	}
 }
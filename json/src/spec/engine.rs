@@ -16,7 +16,7 @@
 
 //! Engine deserialization.
 
-use super::{Ethash, InstantSeal, BasicAuthority, AuthorityRound, Tendermint};
+use super::{Ethash, InstantSeal, BasicAuthority, AuthorityRound, Tendermint, Clique};
 
 /// Engine deserialization.
 #[derive(Debug, PartialEq, Deserialize)]
@@ -37,7 +37,10 @@ pub enum Engine {
 	AuthorityRound(AuthorityRound),
 	/// Tendermint engine.
 	#[serde(rename="tendermint")]
-	Tendermint(Tendermint)
+	Tendermint(Tendermint),
+	/// Clique engine.
+	#[serde(rename="clique")]
+	Clique(Clique)
 }
 
 #[cfg(test)]
@@ -141,6 +144,20 @@ mod tests {
 			Engine::Tendermint(_) => {}, // Tendermint is unit tested in its own file.
 			_ => assert!(false),
 		};
+
+		let s = r#"{
+			"clique": {
+				"params": {
+					"period": 15,
+					"epoch": 30000
+				}
+			}
+		}"#;
+		let deserialized: Engine = serde_json::from_str(s).unwrap();
+		match deserialized {
+			Engine::Clique(_) => {}, // Clique is unit tested in its own file.
+			_ => assert!(false),
+		};
 	}
 }
 
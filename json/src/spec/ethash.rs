@@ -16,9 +16,20 @@
 
 //! Ethash params deserialization.
 
+use std::collections::BTreeMap;
 use uint::Uint;
 use hash::Address;
 
+/// Block reward, either a single value or a map of block numbers to the rewards starting there.
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum BlockReward {
+	/// Reward for all blocks.
+	Single(Uint),
+	/// Rewards starting at given block numbers.
+	Multi(BTreeMap<Uint, Uint>),
+}
+
 /// Deserializable doppelganger of EthashParams.
 #[derive(Debug, PartialEq, Deserialize)]
 pub struct EthashParams {
@@ -42,7 +53,7 @@ pub struct EthashParams {
 	pub duration_limit: Option<Uint>,
 	/// See main EthashParams docs.
 	#[serde(rename="blockReward")]
-	pub block_reward: Uint,
+	pub block_reward: BlockReward,
 	/// See main EthashParams docs.
 	pub registrar: Option<Address>,
 
@@ -69,6 +80,9 @@ pub struct EthashParams {
 	/// See main EthashParams docs.
 	#[serde(rename="bombDefuseTransition")]
 	pub bomb_defuse_transition: Option<Uint>,
+	/// See main EthashParams docs.
+	#[serde(rename="difficultyBombDelays")]
+	pub difficulty_bomb_delays: Option<BTreeMap<Uint, Uint>>,
 
 	/// See main EthashParams docs.
 	#[serde(rename="eip100bTransition")]
@@ -134,12 +148,13 @@ pub struct Ethash {
 
 #[cfg(test)]
 mod tests {
+	use std::collections::BTreeMap;
 	use serde_json;
 	use uint::Uint;
 	use util::U256;
 	use hash::Address;
 	use util::hash::H160;
-	use spec::ethash::{Ethash, EthashParams};
+	use spec::ethash::{Ethash, EthashParams, BlockReward};
 
 	#[test]
 	fn ethash_deserialization() {
@@ -198,7 +213,7 @@ mod tests {
 				difficulty_increment_divisor: None,
 				metropolis_difficulty_increment_divisor: None,
 				duration_limit: Some(Uint(U256::from(0x0d))),
-				block_reward: Uint(U256::from(0x4563918244F40000u64)),
+				block_reward: BlockReward::Single(Uint(U256::from(0x4563918244F40000u64))),
 				registrar: Some(Address(H160::from("0xc6d9d2cd449a754c494264e1809c50e34d64562b"))),
 				homestead_transition: Some(Uint(U256::from(0x42))),
 				dao_hardfork_transition: Some(Uint(U256::from(0x08))),
@@ -228,6 +243,7 @@ mod tests {
 				difficulty_hardfork_transition: Some(Uint(U256::from(0x59d9))),
 				difficulty_hardfork_bound_divisor: Some(Uint(U256::from(0x0200))),
 				bomb_defuse_transition: Some(Uint(U256::from(0x41))),
+				difficulty_bomb_delays: None,
 				eip100b_transition: Some(Uint(U256::from(0x42))),
 				eip150_transition: Some(Uint(U256::from(0x43))),
 				eip155_transition: Some(Uint(U256::from(0x44))),
@@ -266,7 +282,7 @@ mod tests {
 				difficulty_increment_divisor: None,
 				metropolis_difficulty_increment_divisor: None,
 				duration_limit: None,
-				block_reward: Uint(U256::from(0x4563918244F40000u64)),
+				block_reward: BlockReward::Single(Uint(U256::from(0x4563918244F40000u64))),
 				registrar: None,
 				homestead_transition: None,
 				dao_hardfork_transition: None,
@@ -275,6 +291,7 @@ mod tests {
 				difficulty_hardfork_transition: None,
 				difficulty_hardfork_bound_divisor: None,
 				bomb_defuse_transition: None,
+				difficulty_bomb_delays: None,
 				eip100b_transition: None,
 				eip150_transition: None,
 				eip155_transition: None,
@@ -292,4 +309,33 @@ mod tests {
 			}
 		});
 	}
+
+	#[test]
+	fn ethash_deserialization_reward_and_bomb_delay_transitions() {
+		let s = r#"{
+			"params": {
+				"gasLimitBoundDivisor": "0x0400",
+				"minimumDifficulty": "0x020000",
+				"difficultyBoundDivisor": "0x0800",
+				"blockReward": {
+					"0": "0x4563918244F40000",
+					"4370000": "0x29A2241AF62C0000"
+				},
+				"difficultyBombDelays": {
+					"4370000": "3000000"
+				}
+			}
+		}"#;
+
+		let deserialized: Ethash = serde_json::from_str(s).unwrap();
+
+		let mut rewards = BTreeMap::new();
+		rewards.insert(Uint(U256::from(0)), Uint(U256::from(0x4563918244F40000u64)));
+		rewards.insert(Uint(U256::from(4370000)), Uint(U256::from(0x29A2241AF62C0000u64)));
+		let mut delays = BTreeMap::new();
+		delays.insert(Uint(U256::from(4370000)), Uint(U256::from(3000000)));
+
+		assert_eq!(deserialized.params.block_reward, BlockReward::Multi(rewards));
+		assert_eq!(deserialized.params.difficulty_bomb_delays, Some(delays));
+	}
 }
@@ -17,12 +17,17 @@
 //! Instant params deserialization.
 
 use hash::Address;
+use uint::Uint;
 
 /// Instant params deserialization.
 #[derive(Debug, PartialEq, Deserialize)]
 pub struct InstantSealParams {
 	/// Address of the registrar contract.
 	pub registrar: Option<Address>,
+	/// Seconds between sealed blocks, `0` to seal only on demand.
+	/// When not set a block is sealed for every transaction.
+	#[serde(rename="blockPeriod")]
+	pub block_period: Option<Uint>,
 }
 
 /// Instant engine deserialization.
@@ -37,6 +42,8 @@ mod tests {
 	use serde_json;
 	use hash::Address;
 	use util::hash::H160;
+	use util::U256;
+	use uint::Uint;
 	use spec::instant_seal::InstantSeal;
 
 	#[test]
@@ -49,5 +56,19 @@ mod tests {
 
 		let deserialized: InstantSeal = serde_json::from_str(s).unwrap();
 		assert_eq!(deserialized.params.registrar, Some(Address(H160::from("0xc6d9d2cd449a754c494264e1809c50e34d64562b"))));
+		assert_eq!(deserialized.params.block_period, None);
+	}
+
+	#[test]
+	fn instant_seal_block_period_deserialization() {
+		let s = r#"{
+			"params": {
+				"blockPeriod": "0x5"
+			}
+		}"#;
+
+		let deserialized: InstantSeal = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized.params.registrar, None);
+		assert_eq!(deserialized.params.block_period, Some(Uint(U256::from(5))));
 	}
 }
@@ -82,6 +82,18 @@ pub struct Params {
 	#[serde(rename="eip214Transition")]
 	pub eip214_transition: Option<Uint>,
 	/// See `CommonParams` docs.
+	#[serde(rename="eip145Transition")]
+	pub eip145_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	#[serde(rename="eip1052Transition")]
+	pub eip1052_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	#[serde(rename="eip1283Transition")]
+	pub eip1283_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	#[serde(rename="eip1014Transition")]
+	pub eip1014_transition: Option<Uint>,
+	/// See `CommonParams` docs.
 	#[serde(rename="dustProtectionTransition")]
 	pub dust_protection_transition: Option<Uint>,
 	/// See `CommonParams` docs.
@@ -91,13 +103,20 @@ pub struct Params {
 	pub remove_dust_contracts : Option<bool>,
 	/// Wasm support flag
 	pub wasm: Option<bool>,
+	/// See `CommonParams` docs.
+	#[serde(rename="transactionPermissionContract")]
+	pub transaction_permission_contract: Option<Address>,
+	/// See `CommonParams` docs.
+	#[serde(rename="transactionPermissionContractTransition")]
+	pub transaction_permission_contract_transition: Option<Uint>,
 }
 
 #[cfg(test)]
 mod tests {
 	use serde_json;
 	use uint::Uint;
-	use util::U256;
+	use util::{U256, H160};
+	use hash::Address;
 	use spec::params::Params;
 
 	#[test]
@@ -108,7 +127,9 @@ mod tests {
 			"chainID" : "0x15",
 			"subprotocolName" : "exp",
 			"minGasLimit": "0x1388",
-			"accountStartNonce": "0x01"
+			"accountStartNonce": "0x01",
+			"transactionPermissionContract": "0x0000000000000000000000000000000000000005",
+			"transactionPermissionContractTransition": "0x0a"
 		}"#;
 
 		let deserialized: Params = serde_json::from_str(s).unwrap();
@@ -118,5 +139,7 @@ mod tests {
 		assert_eq!(deserialized.subprotocol_name, Some("exp".to_owned()));
 		assert_eq!(deserialized.min_gas_limit, Uint(U256::from(0x1388)));
 		assert_eq!(deserialized.account_start_nonce, Some(Uint(U256::from(0x01))));
+		assert_eq!(deserialized.transaction_permission_contract, Some(Address(H160::from(5))));
+		assert_eq!(deserialized.transaction_permission_contract_transition, Some(Uint(U256::from(0x0a))));
 	}
 }
@@ -45,6 +45,12 @@ pub struct TendermintParams {
 	pub block_reward: Option<Uint>,
 	/// Address of the registrar contract.
 	pub registrar: Option<Address>,
+	/// Address of the contract which determines the block rewards.
+	#[serde(rename="blockRewardContractAddress")]
+	pub block_reward_contract_address: Option<Address>,
+	/// Block from which the block reward contract is used instead of the fixed block reward.
+	#[serde(rename="blockRewardContractTransition")]
+	pub block_reward_contract_transition: Option<Uint>,
 }
 
 /// Tendermint engine deserialization.
@@ -72,7 +78,8 @@ mod tests {
 				"validators": {
 					"list": ["0xc6d9d2cd449a754c494264e1809c50e34d64562b"]
 				},
-				"blockReward": "0x50"
+				"blockReward": "0x50",
+				"blockRewardContractAddress": "0x0000000000000000000000000000000000000042"
 			}
 		}"#;
 
@@ -81,5 +88,7 @@ mod tests {
 		let vs = ValidatorSet::List(vec![Address(H160::from("0xc6d9d2cd449a754c494264e1809c50e34d64562b"))]);
 		assert_eq!(deserialized.params.validators, vs);
 		assert_eq!(deserialized.params.block_reward, Some(Uint(U256::from(0x50))));
+		assert_eq!(deserialized.params.block_reward_contract_address, Some(Address(H160::from(0x42))));
+		assert_eq!(deserialized.params.block_reward_contract_transition, None);
 	}
 }
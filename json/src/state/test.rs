@@ -105,6 +105,7 @@ pub enum ForkSpec {
 	Frontier,
 	Homestead,
 	Metropolis,
+	Constantinople,
 }
 
 /// State test indexes deserialization.
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Manages local node data: pending local transactions, sync security level
+//! Manages local node data: pending local transactions, the transaction queue, sync security level
 
 use std::sync::Arc;
 use std::fmt;
@@ -23,6 +23,7 @@ use ethcore::transaction::{
 	SignedTransaction, PendingTransaction, UnverifiedTransaction,
 	Condition as TransactionCondition
 };
+use ethcore::miner::{QueuedTransaction, TransactionOrigin};
 use ethcore::service::ClientIoMessage;
 use io::IoHandler;
 use rlp::UntrustedRlp;
@@ -45,6 +46,7 @@ extern crate log;
 extern crate ethkey;
 
 const LOCAL_TRANSACTIONS_KEY: &'static [u8] = &*b"LOCAL_TXS";
+const QUEUED_TRANSACTIONS_KEY: &'static [u8] = &*b"QUEUED_TXS";
 
 const UPDATE_TIMER: ::io::TimerToken = 0;
 const UPDATE_TIMEOUT_MS: u64 = 15 * 60 * 1000; // once every 15 minutes.
@@ -91,6 +93,33 @@ impl Into<TransactionCondition> for Condition {
 	}
 }
 
+#[derive(Serialize, Deserialize)]
+enum Origin {
+	Local,
+	External,
+	RetractedBlock,
+}
+
+impl From<TransactionOrigin> for Origin {
+	fn from(origin: TransactionOrigin) -> Self {
+		match origin {
+			TransactionOrigin::Local => Origin::Local,
+			TransactionOrigin::External => Origin::External,
+			TransactionOrigin::RetractedBlock => Origin::RetractedBlock,
+		}
+	}
+}
+
+impl Into<TransactionOrigin> for Origin {
+	fn into(self) -> TransactionOrigin {
+		match self {
+			Origin::Local => TransactionOrigin::Local,
+			Origin::External => TransactionOrigin::External,
+			Origin::RetractedBlock => TransactionOrigin::RetractedBlock,
+		}
+	}
+}
+
 #[derive(Serialize, Deserialize)]
 struct TransactionEntry {
 	rlp_bytes: Vec<u8>,
@@ -127,10 +156,44 @@ impl From<PendingTransaction> for TransactionEntry {
 	}
 }
 
+#[derive(Serialize, Deserialize)]
+struct QueuedTransactionEntry {
+	transaction: TransactionEntry,
+	origin: Origin,
+	insertion_time: u64,
+}
+
+impl QueuedTransactionEntry {
+	fn into_queued(self) -> Option<QueuedTransaction> {
+		let origin = self.origin.into();
+		let insertion_time = self.insertion_time;
+		self.transaction.into_pending().map(|pending| QueuedTransaction {
+			pending: pending,
+			origin: origin,
+			insertion_time: insertion_time,
+		})
+	}
+}
+
+impl From<QueuedTransaction> for QueuedTransactionEntry {
+	fn from(queued: QueuedTransaction) -> Self {
+		QueuedTransactionEntry {
+			transaction: queued.pending.into(),
+			origin: queued.origin.into(),
+			insertion_time: queued.insertion_time,
+		}
+	}
+}
+
 /// Something which can provide information about the local node.
 pub trait NodeInfo: Send + Sync {
 	/// Get all pending transactions of local origin.
 	fn pending_transactions(&self) -> Vec<PendingTransaction>;
+
+	/// Get all transactions in the queue which should survive a restart. Those of local origin
+	/// are skipped, as they are persisted as pending transactions already.
+	/// Nothing by default, i.e. only local transactions are persisted.
+	fn queued_transactions(&self) -> Vec<QueuedTransaction> { Vec::new() }
 }
 
 /// Create a new local data store, given a database, a column to write to, and a node.
@@ -169,6 +232,22 @@ impl<T: NodeInfo> LocalDataStore<T> {
 		}
 	}
 
+	/// Attempt to read the snapshot of the whole transaction queue out of the local store.
+	/// The transactions still need to be verified against the latest state.
+	pub fn queued_transactions(&self) -> Result<Vec<QueuedTransaction>, Error> {
+		if let Some(val) = self.db.get(self.col, QUEUED_TRANSACTIONS_KEY).map_err(Error::Database)? {
+			let queued_txs: Vec<_> = ::serde_json::from_slice::<Vec<QueuedTransactionEntry>>(&val)
+				.map_err(Error::Json)?
+				.into_iter()
+				.filter_map(QueuedTransactionEntry::into_queued)
+				.collect();
+
+			Ok(queued_txs)
+		} else {
+			Ok(Vec::new())
+		}
+	}
+
 	/// Update the entries in the database.
 	pub fn update(&self) -> Result<(), Error> {
 		trace!(target: "local_store", "Updating local store entries.");
@@ -178,24 +257,32 @@ impl<T: NodeInfo> LocalDataStore<T> {
 			.map(Into::into)
 			.collect();
 
-		self.write_txs(&local_entries)
+		// local transactions are already stored as pending ones.
+		let queued_entries: Vec<QueuedTransactionEntry> = self.node.queued_transactions()
+			.into_iter()
+			.filter(|queued| queued.origin != TransactionOrigin::Local)
+			.map(Into::into)
+			.collect();
+
+		self.write_txs(&local_entries, &queued_entries)
 	}
 
 	/// Clear data in this column.
 	pub fn clear(&self) -> Result<(), Error> {
 		trace!(target: "local_store", "Clearing local store entries.");
 
-		self.write_txs(&[])
+		self.write_txs(&[], &[])
 	}
 
-	// helper for writing a vector of transaction entries to disk.
-	fn write_txs(&self, txs: &[TransactionEntry]) -> Result<(), Error> {
+	// helper for writing the local and queued transaction entries to disk.
+	fn write_txs(&self, local_txs: &[TransactionEntry], queued_txs: &[QueuedTransactionEntry]) -> Result<(), Error> {
 		let mut batch = self.db.transaction();
 
-		let local_json = ::serde_json::to_value(txs).map_err(Error::Json)?;
-		let json_str = format!("{}", local_json);
+		let local_json = ::serde_json::to_value(local_txs).map_err(Error::Json)?;
+		let queued_json = ::serde_json::to_value(queued_txs).map_err(Error::Json)?;
 
-		batch.put_vec(self.col, LOCAL_TRANSACTIONS_KEY, json_str.into_bytes());
+		batch.put_vec(self.col, LOCAL_TRANSACTIONS_KEY, format!("{}", local_json).into_bytes());
+		batch.put_vec(self.col, QUEUED_TRANSACTIONS_KEY, format!("{}", queued_json).into_bytes());
 		self.db.write(batch).map_err(Error::Database)
 	}
 }
@@ -229,6 +316,7 @@ mod tests {
 	use super::NodeInfo;
 
 	use std::sync::Arc;
+	use ethcore::miner::{QueuedTransaction, TransactionOrigin};
 	use ethcore::transaction::{Transaction, Condition, PendingTransaction};
 	use ethkey::{Brain, Generator};
 
@@ -240,6 +328,12 @@ mod tests {
 		fn pending_transactions(&self) -> Vec<PendingTransaction> { self.0.clone() }
 	}
 
+	struct DummyQueue(Vec<QueuedTransaction>);
+	impl NodeInfo for DummyQueue {
+		fn pending_transactions(&self) -> Vec<PendingTransaction> { Vec::new() }
+		fn queued_transactions(&self) -> Vec<QueuedTransaction> { self.0.clone() }
+	}
+
 	#[test]
 	fn twice_empty() {
 		let db = Arc::new(::util::kvdb::in_memory(0));
@@ -324,4 +418,44 @@ mod tests {
 			assert_eq!(loaded, transactions);
 		}
 	}
+
+	#[test]
+	fn with_queued_transactions() {
+		let keypair = Brain::new("abcd".into()).generate().unwrap();
+		let queued: Vec<_> = (0..4u64).map(|nonce| {
+			let mut tx = Transaction::default();
+			tx.nonce = nonce.into();
+
+			let signed = tx.sign(keypair.secret(), None);
+			let origin = match nonce {
+				0 => TransactionOrigin::Local,
+				_ => TransactionOrigin::External,
+			};
+
+			QueuedTransaction {
+				pending: PendingTransaction::new(signed, None),
+				origin: origin,
+				insertion_time: 10 + nonce,
+			}
+		}).collect();
+
+		let db = Arc::new(::util::kvdb::in_memory(0));
+
+		{
+			// nothing written yet, will write the queue.
+			let store = super::create(db.clone(), None, DummyQueue(queued.clone()));
+			assert_eq!(store.queued_transactions().unwrap(), vec![])
+		}
+		{
+			// queue written without the local transaction, will write nothing.
+			let store = super::create(db.clone(), None, Dummy(vec![]));
+			assert_eq!(store.queued_transactions().unwrap(), queued[1..].to_vec());
+			assert_eq!(store.pending_transactions().unwrap(), vec![])
+		}
+		{
+			// queue removed.
+			let store = super::create(db.clone(), None, Dummy(vec![]));
+			assert_eq!(store.queued_transactions().unwrap(), vec![])
+		}
+	}
 }
@@ -97,6 +97,8 @@ usage! {
 		flag_light: bool = false, or |c: &Config| otry!(c.parity).light,
 		flag_no_persistent_txqueue: bool = false,
 			or |c: &Config| otry!(c.parity).no_persistent_txqueue,
+		flag_persistent_full_txqueue: bool = false,
+			or |c: &Config| otry!(c.parity).persistent_full_txqueue,
 
 		// -- Convenience Options
 		flag_config: String = "$BASE/config.toml", or |_| None,
@@ -136,6 +138,8 @@ usage! {
 		// -- Networking Options
 		flag_no_warp: bool = false,
 			or |c: &Config| otry!(c.network).warp.clone().map(|w| !w),
+		flag_fast_sync: bool = false,
+			or |c: &Config| otry!(c.network).fast_sync.clone(),
 		flag_port: u16 = 30303u16,
 			or |c: &Config| otry!(c.network).port.clone(),
 		flag_min_peers: u16 = 25u16,
@@ -272,6 +276,10 @@ usage! {
 			or |c: &Config| otry!(c.mining).usd_per_eth.clone(),
 		flag_price_update_period: String = "hourly",
 			or |c: &Config| otry!(c.mining).price_update_period.clone(),
+		flag_gas_price_percentile: Option<usize> = None,
+			or |c: &Config| otry!(c.mining).gas_price_percentile.clone().map(Some),
+		flag_gas_price_blocks: Option<usize> = None,
+			or |c: &Config| otry!(c.mining).gas_price_blocks.clone().map(Some),
 		flag_gas_floor_target: String = "4700000",
 			or |c: &Config| otry!(c.mining).gas_floor_target.clone(),
 		flag_gas_cap: String = "6283184",
@@ -288,12 +296,22 @@ usage! {
 			or |c: &Config| otry!(c.mining).tx_queue_ban_count.clone(),
 		flag_tx_queue_ban_time: u16 = 180u16,
 			or |c: &Config| otry!(c.mining).tx_queue_ban_time.clone(),
+		flag_tx_queue_gas_price_bump: Option<usize> = None,
+			or |c: &Config| otry!(c.mining).tx_queue_gas_price_bump.clone().map(Some),
+		flag_tx_queue_per_sender: Option<usize> = None,
+			or |c: &Config| otry!(c.mining).tx_queue_per_sender.clone().map(Some),
+		flag_tx_queue_no_local_limit: bool = false,
+			or |c: &Config| otry!(c.mining).tx_queue_no_local_limit.clone(),
+		flag_tx_rebroadcast_blocks: u64 = 10u64,
+			or |c: &Config| otry!(c.mining).tx_rebroadcast_blocks.clone(),
 		flag_remove_solved: bool = false,
 			or |c: &Config| otry!(c.mining).remove_solved.clone(),
 		flag_notify_work: Option<String> = None,
 			or |c: &Config| otry!(c.mining).notify_work.as_ref().map(|vec| Some(vec.join(","))),
 		flag_refuse_service_transactions: bool = false,
 			or |c: &Config| otry!(c.mining).refuse_service_transactions.clone(),
+		flag_cpu_miner_threads: Option<usize> = None,
+			or |c: &Config| otry!(c.mining).cpu_miner_threads.clone().map(Some),
 
 		flag_stratum: bool = false,
 			or |c: &Config| Some(c.stratum.is_some()),
@@ -303,6 +321,8 @@ usage! {
 			or |c: &Config| otry!(c.stratum).port.clone(),
 		flag_stratum_secret: Option<String> = None,
 			or |c: &Config| otry!(c.stratum).secret.clone().map(Some),
+		flag_stratum_share_difficulty: Option<String> = None,
+			or |c: &Config| otry!(c.stratum).share_difficulty.clone().map(Some),
 
 		// -- Footprint Options
 		flag_tracing: String = "auto",
@@ -424,6 +444,7 @@ struct Operating {
 	identity: Option<String>,
 	light: Option<bool>,
 	no_persistent_txqueue: Option<bool>,
+	persistent_full_txqueue: Option<bool>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -448,6 +469,7 @@ struct Ui {
 #[derive(Default, Debug, PartialEq, Deserialize)]
 struct Network {
 	warp: Option<bool>,
+	fast_sync: Option<bool>,
 	port: Option<u16>,
 	min_peers: Option<u16>,
 	max_peers: Option<u16>,
@@ -542,6 +564,8 @@ struct Mining {
 	usd_per_tx: Option<String>,
 	usd_per_eth: Option<String>,
 	price_update_period: Option<String>,
+	gas_price_percentile: Option<usize>,
+	gas_price_blocks: Option<usize>,
 	gas_floor_target: Option<String>,
 	gas_cap: Option<String>,
 	extra_data: Option<String>,
@@ -550,9 +574,14 @@ struct Mining {
 	tx_queue_strategy: Option<String>,
 	tx_queue_ban_count: Option<u16>,
 	tx_queue_ban_time: Option<u16>,
+	tx_queue_gas_price_bump: Option<usize>,
+	tx_queue_per_sender: Option<usize>,
+	tx_queue_no_local_limit: Option<bool>,
+	tx_rebroadcast_blocks: Option<u64>,
 	remove_solved: Option<bool>,
 	notify_work: Option<Vec<String>>,
 	refuse_service_transactions: Option<bool>,
+	cpu_miner_threads: Option<usize>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -560,6 +589,7 @@ struct Stratum {
 	interface: Option<String>,
 	port: Option<u16>,
 	secret: Option<String>,
+	share_difficulty: Option<String>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -708,6 +738,7 @@ mod tests {
 			flag_identity: "".into(),
 			flag_light: false,
 			flag_no_persistent_txqueue: false,
+			flag_persistent_full_txqueue: false,
 
 			// -- Convenience Options
 			flag_config: "$BASE/config.toml".into(),
@@ -731,6 +762,7 @@ mod tests {
 
 			// -- Networking Options
 			flag_no_warp: false,
+			flag_fast_sync: false,
 			flag_port: 30303u16,
 			flag_min_peers: 25u16,
 			flag_max_peers: 50u16,
@@ -806,6 +838,8 @@ mod tests {
 			flag_usd_per_tx: "0.0025".into(),
 			flag_usd_per_eth: "auto".into(),
 			flag_price_update_period: "hourly".into(),
+			flag_gas_price_percentile: Some(50usize),
+			flag_gas_price_blocks: Some(100usize),
 			flag_gas_floor_target: "4700000".into(),
 			flag_gas_cap: "6283184".into(),
 			flag_extra_data: Some("Parity".into()),
@@ -814,14 +848,20 @@ mod tests {
 			flag_tx_queue_strategy: "gas_factor".into(),
 			flag_tx_queue_ban_count: 1u16,
 			flag_tx_queue_ban_time: 180u16,
+			flag_tx_queue_gas_price_bump: Some(12),
+			flag_tx_queue_per_sender: Some(16),
+			flag_tx_queue_no_local_limit: false,
+			flag_tx_rebroadcast_blocks: 10u64,
 			flag_remove_solved: false,
 			flag_notify_work: Some("http://localhost:3001".into()),
 			flag_refuse_service_transactions: false,
+			flag_cpu_miner_threads: Some(2),
 
 			flag_stratum: false,
 			flag_stratum_interface: "local".to_owned(),
 			flag_stratum_port: 8008u16,
 			flag_stratum_secret: None,
+			flag_stratum_share_difficulty: None,
 
 			// -- Footprint Options
 			flag_tracing: "auto".into(),
@@ -937,6 +977,7 @@ mod tests {
 				identity: None,
 				light: None,
 				no_persistent_txqueue: None,
+				persistent_full_txqueue: None,
 			}),
 			account: Some(Account {
 				unlock: Some(vec!["0x1".into(), "0x2".into(), "0x3".into()]),
@@ -955,6 +996,7 @@ mod tests {
 			}),
 			network: Some(Network {
 				warp: Some(false),
+				fast_sync: None,
 				port: None,
 				min_peers: Some(10),
 				max_peers: Some(20),
@@ -1033,6 +1075,8 @@ mod tests {
 				usd_per_tx: None,
 				usd_per_eth: None,
 				price_update_period: Some("hourly".into()),
+				gas_price_percentile: None,
+				gas_price_blocks: None,
 				gas_floor_target: None,
 				gas_cap: None,
 				tx_queue_size: Some(1024),
@@ -1040,12 +1084,17 @@ mod tests {
 				tx_queue_strategy: None,
 				tx_queue_ban_count: None,
 				tx_queue_ban_time: None,
+				tx_queue_gas_price_bump: None,
+				tx_queue_per_sender: None,
+				tx_queue_no_local_limit: None,
+				tx_rebroadcast_blocks: None,
 				tx_gas_limit: None,
 				tx_time_limit: None,
 				extra_data: None,
 				remove_solved: None,
 				notify_work: None,
 				refuse_service_transactions: None,
+				cpu_miner_threads: None,
 			}),
 			footprint: Some(Footprint {
 				tracing: Some("on".into()),
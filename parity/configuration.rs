@@ -27,7 +27,7 @@ use util::Colour;
 use ethsync::{NetworkConfiguration, is_valid_node_url, AllowIP};
 use ethcore::ethstore::ethkey::{Secret, Public};
 use ethcore::client::{VMType};
-use ethcore::miner::{MinerOptions, Banning, StratumOptions};
+use ethcore::miner::{MinerOptions, Banning, StratumOptions, CpuMinerOptions, GasPriceOracle};
 use ethcore::verification::queue::VerifierSettings;
 
 use rpc::{IpcConfiguration, HttpConfiguration, WsConfiguration, UiConfiguration};
@@ -131,6 +131,7 @@ impl Configuration {
 		};
 		let public_node = self.args.flag_public_node;
 		let warp_sync = !self.args.flag_no_warp && fat_db != Switch::On && tracing != Switch::On && pruning != Pruning::Specific(Algorithm::Archive);
+		let fast_sync = self.args.flag_fast_sync && fat_db != Switch::On && tracing != Switch::On && pruning != Pruning::Specific(Algorithm::Archive);
 		let geth_compatibility = self.args.flag_geth;
 		let mut dapps_conf = self.dapps_config();
 		let ipfs_conf = self.ipfs_config();
@@ -357,6 +358,7 @@ impl Configuration {
 				gas_pricer: gas_pricer,
 				miner_extras: self.miner_extras()?,
 				stratum: self.stratum_options()?,
+				cpu_miner: self.cpu_miner_options()?,
 				update_policy: update_policy,
 				mode: mode,
 				tracing: tracing,
@@ -365,6 +367,7 @@ impl Configuration {
 				wal: wal,
 				vm_type: vm_type,
 				warp_sync: warp_sync,
+				fast_sync: fast_sync,
 				public_node: public_node,
 				geth_compatibility: geth_compatibility,
 				net_settings: self.network_settings()?,
@@ -383,6 +386,7 @@ impl Configuration {
 				serve_light: !self.args.flag_no_serve_light,
 				light: self.args.flag_light,
 				no_persistent_txqueue: self.args.flag_no_persistent_txqueue,
+				persistent_full_txqueue: self.args.flag_persistent_full_txqueue,
 			};
 			Cmd::Run(run_cmd)
 		};
@@ -510,10 +514,25 @@ impl Configuration {
 				listen_addr: self.stratum_interface(),
 				port: self.args.flag_ports_shift + self.args.flag_stratum_port,
 				secret: self.args.flag_stratum_secret.as_ref().map(|s| s.parse::<H256>().unwrap_or_else(|_| s.sha3())),
+				share_difficulty: match self.args.flag_stratum_share_difficulty {
+					Some(ref difficulty) => Some(to_u256(difficulty)?),
+					None => None,
+				},
 			}))
 		} else { Ok(None) }
 	}
 
+	fn cpu_miner_options(&self) -> Result<Option<CpuMinerOptions>, String> {
+		match self.args.flag_cpu_miner_threads {
+			Some(0) => Err("Number of CPU miner threads must be greater than 0.".into()),
+			Some(threads) => Ok(Some(CpuMinerOptions {
+				threads: threads,
+				cache_dir: self.directories().cache.into(),
+			})),
+			None => Ok(None),
+		}
+	}
+
 	fn miner_options(&self, reseal_min_period: u64) -> Result<MinerOptions, String> {
 		if self.args.flag_force_sealing && reseal_min_period == 0 {
 			return Err("Force sealing can't be used with reseal_min_period = 0".into());
@@ -548,6 +567,11 @@ impl Configuration {
 				None => Banning::Disabled,
 			},
 			refuse_service_transactions: self.args.flag_refuse_service_transactions,
+			tx_queue_min_gas_price_bump: self.args.flag_tx_queue_gas_price_bump,
+			tx_queue_per_sender: self.args.flag_tx_queue_per_sender
+				.unwrap_or_else(|| max(16, self.args.flag_tx_queue_size / 100)),
+			tx_queue_no_local_limit: self.args.flag_tx_queue_no_local_limit,
+			tx_rebroadcast_period: self.args.flag_tx_rebroadcast_blocks,
 		};
 
 		Ok(options)
@@ -630,6 +654,22 @@ impl Configuration {
 		}
 
 		let usd_per_tx = to_price(&self.args.flag_usd_per_tx)?;
+		if self.args.flag_gas_price_percentile.is_some() || self.args.flag_gas_price_blocks.is_some() {
+			let default = GasPriceOracle::default();
+			let percentile = self.args.flag_gas_price_percentile.unwrap_or(default.percentile);
+			if percentile > 100 {
+				return Err(format!("Invalid gas price percentile: {}. Must be between 0 and 100.", percentile));
+			}
+			// Used only until the chain is available to sample the recent gas prices.
+			return Ok(GasPricerConfig::Oracle {
+				initial_minimum: wei_per_gas(usd_per_tx, 10.0),
+				oracle: GasPriceOracle {
+					percentile: percentile,
+					blocks: self.args.flag_gas_price_blocks.unwrap_or(default.blocks),
+				},
+			});
+		}
+
 		if "auto" == self.args.flag_usd_per_eth.as_str() {
 			// Just a very rough estimate to avoid accepting
 			// ZGP transactions before the price is fetched
@@ -1288,7 +1328,7 @@ mod tests {
 			pruning_memory: 32,
 			daemon: None,
 			logger_config: Default::default(),
-			miner_options: Default::default(),
+			miner_options: MinerOptions { tx_queue_per_sender: 16, ..Default::default() },
 			ws_conf: Default::default(),
 			http_conf: Default::default(),
 			ipc_conf: Default::default(),
@@ -1296,6 +1336,7 @@ mod tests {
 			network_id: None,
 			public_node: false,
 			warp_sync: true,
+			fast_sync: false,
 			acc_conf: Default::default(),
 			gas_pricer: Default::default(),
 			miner_extras: Default::default(),
@@ -1318,12 +1359,14 @@ mod tests {
 			fat_db: Default::default(),
 			no_periodic_snapshot: false,
 			stratum: None,
+			cpu_miner: None,
 			check_seal: true,
 			download_old_blocks: true,
 			verifier_settings: Default::default(),
 			serve_light: true,
 			light: false,
 			no_persistent_txqueue: false,
+			persistent_full_txqueue: false,
 		};
 		expected.secretstore_conf.enabled = cfg!(feature = "secretstore");
 		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Run(expected));
@@ -1342,6 +1385,7 @@ mod tests {
 
 		// then
 		let min_period = conf0.args.flag_reseal_min_period;
+		mining_options.tx_queue_per_sender = 16;
 		assert_eq!(conf0.miner_options(min_period).unwrap(), mining_options);
 		mining_options.tx_queue_strategy = PrioritizationStrategy::GasFactorAndGasPrice;
 		assert_eq!(conf1.miner_options(min_period).unwrap(), mining_options);
@@ -1351,6 +1395,50 @@ mod tests {
 		assert_eq!(conf3.miner_options(min_period).unwrap(), mining_options);
 	}
 
+	#[test]
+	fn should_parse_tx_queue_limits() {
+		// given
+		let mut mining_options = MinerOptions::default();
+
+		// when
+		let conf0 = parse(&["parity", "--tx-queue-size", "4096"]);
+		let conf1 = parse(&["parity", "--tx-queue-per-sender", "8", "--tx-queue-no-local-limit", "--tx-queue-gas-price-bump", "25"]);
+
+		// then
+		let min_period = conf0.args.flag_reseal_min_period;
+		mining_options.tx_queue_size = 4096;
+		mining_options.tx_queue_per_sender = 40;
+		assert_eq!(conf0.miner_options(min_period).unwrap(), mining_options);
+		mining_options.tx_queue_size = 1024;
+		mining_options.tx_queue_per_sender = 8;
+		mining_options.tx_queue_no_local_limit = true;
+		mining_options.tx_queue_min_gas_price_bump = Some(25);
+		assert_eq!(conf1.miner_options(min_period).unwrap(), mining_options);
+	}
+
+	#[test]
+	fn should_parse_tx_rebroadcast_blocks() {
+		// when
+		let conf = parse(&["parity", "--tx-rebroadcast-blocks", "0"]);
+
+		// then
+		let min_period = conf.args.flag_reseal_min_period;
+		assert_eq!(conf.miner_options(min_period).unwrap().tx_rebroadcast_period, 0);
+	}
+
+	#[test]
+	fn should_parse_gas_price_oracle() {
+		// when
+		let conf0 = parse(&["parity", "--gas-price-percentile", "60"]);
+		let conf1 = parse(&["parity", "--gas-price-blocks", "20"]);
+		let conf2 = parse(&["parity", "--gas-price-percentile", "101"]);
+
+		// then
+		assert_eq!(conf0.gas_pricer_config().unwrap().oracle(), GasPriceOracle { percentile: 60, blocks: 100 });
+		assert_eq!(conf1.gas_pricer_config().unwrap().oracle(), GasPriceOracle { percentile: 50, blocks: 20 });
+		assert!(conf2.gas_pricer_config().is_err());
+	}
+
 	#[test]
 	fn should_fail_on_force_reseal_and_reseal_min_period() {
 		let conf = parse(&["parity", "--chain", "dev", "--force-sealing"]);
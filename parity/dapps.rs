@@ -122,6 +122,7 @@ impl ContractClient for LightRegistrar {
 					header: header.into(),
 					env_info: env_info,
 					engine: self.client.engine().clone(),
+					analytics: Default::default(),
 				})
 				.expect("No back-references; therefore all back-refs valid; qed")
 				.then(|res| match res {
@@ -136,6 +136,7 @@ pub fn stratum(
 				port: config.port,
 				listen_addr: config.listen_addr.to_owned(),
 				secret: config.secret,
+				share_difficulty: config.share_difficulty,
 			}).expect("Any binary-derived struct is serializable by definition")
 		).cli(vec!["stratum".to_owned()]);
 	hypervisor = hypervisor.module(super::stratum::MODULE_ID, args);
@@ -21,7 +21,7 @@ use util::journaldb::Algorithm;
 use ethcore::spec::Spec;
 use ethcore::ethereum;
 use ethcore::client::Mode;
-use ethcore::miner::{GasPricer, GasPriceCalibratorOptions};
+use ethcore::miner::{GasPricer, GasPriceCalibratorOptions, GasPriceOracle};
 use user_defaults::UserDefaults;
 
 #[derive(Debug, PartialEq)]
@@ -204,7 +204,11 @@ pub enum GasPricerConfig {
 		initial_minimum: U256,
 		usd_per_tx: f32,
 		recalibration_period: Duration,
-	}
+	},
+	Oracle {
+		initial_minimum: U256,
+		oracle: GasPriceOracle,
+	},
 }
 
 impl GasPricerConfig {
@@ -212,6 +216,14 @@ impl GasPricerConfig {
 		match *self {
 			GasPricerConfig::Fixed(ref min) => min.clone(),
 			GasPricerConfig::Calibrated { ref initial_minimum, .. } => initial_minimum.clone(),
+			GasPricerConfig::Oracle { ref initial_minimum, .. } => initial_minimum.clone(),
+		}
+	}
+
+	pub fn oracle(&self) -> GasPriceOracle {
+		match *self {
+			GasPricerConfig::Oracle { oracle, .. } => oracle,
+			_ => GasPriceOracle::default(),
 		}
 	}
 }
@@ -235,7 +247,8 @@ impl Into<GasPricer> for GasPricerConfig {
 					usd_per_tx: usd_per_tx,
 					recalibration_period: recalibration_period,
 				})
-			}
+			},
+			GasPricerConfig::Oracle { oracle, .. } => GasPricer::new_oracle(oracle),
 		}
 	}
 }
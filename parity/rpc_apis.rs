@@ -25,7 +25,7 @@ pub use parity_rpc::dapps::{DappsService, LocalDapp};
 
 use ethcore::account_provider::AccountProvider;
 use ethcore::client::Client;
-use ethcore::miner::{Miner, ExternalMiner};
+use ethcore::miner::{Miner, ExternalMiner, Stratum, GasPriceOracle};
 use ethcore::snapshot::SnapshotService;
 use parity_rpc::{Metadata, NetworkSettings};
 use parity_rpc::informant::{ActivityNotifier, ClientNotifier};
@@ -63,10 +63,14 @@ pub enum Api {
 	ParitySet,
 	/// Traces (Safe)
 	Traces,
+	/// Geth-compatible "debug" API (Safe, but expensive)
+	Debug,
 	/// Rpc (Safe)
 	Rpc,
 	/// SecretStore (Safe)
 	SecretStore,
+	/// Ganache-compatible development chain control (UNSAFE: Side Effects rolling back the chain)
+	Evm,
 }
 
 impl FromStr for Api {
@@ -87,8 +91,10 @@ impl FromStr for Api {
 			"parity_accounts" => Ok(ParityAccounts),
 			"parity_set" => Ok(ParitySet),
 			"traces" => Ok(Traces),
+			"debug" => Ok(Debug),
 			"rpc" => Ok(Rpc),
 			"secretstore" => Ok(SecretStore),
+			"evm" => Ok(Evm),
 			api => Err(format!("Unknown api: {}", api))
 		}
 	}
@@ -170,8 +176,10 @@ fn to_modules(apis: &HashSet<Api>) -> BTreeMap<String, String> {
 			Api::ParityPubSub => ("parity_pubsub", "1.0"),
 			Api::ParitySet => ("parity_set", "1.0"),
 			Api::Traces => ("traces", "1.0"),
+			Api::Debug => ("debug", "1.0"),
 			Api::Rpc => ("rpc", "1.0"),
 			Api::SecretStore => ("secretstore", "1.0"),
+			Api::Evm => ("evm", "1.0"),
 		};
 		modules.insert(name.into(), version.into());
 	}
@@ -211,6 +219,7 @@ pub struct FullDependencies {
 	pub dapps_service: Option<Arc<DappsService>>,
 	pub dapps_address: Option<(String, u16)>,
 	pub ws_address: Option<(String, u16)>,
+	pub stratum: Option<Stratum>,
 	pub fetch: FetchClient,
 	pub remote: parity_reactor::Remote,
 }
@@ -298,6 +307,7 @@ impl FullDependencies {
 						signer,
 						self.dapps_address.clone(),
 						self.ws_address.clone(),
+						self.stratum.clone(),
 					).to_delegate());
 
 					if !for_generic_pubsub {
@@ -328,6 +338,12 @@ impl FullDependencies {
 				Api::Traces => {
 					handler.extend_with(TracesClient::new(&self.client, &self.miner).to_delegate())
 				},
+				Api::Debug => {
+					handler.extend_with(DebugClient::new(&self.client, &self.miner).to_delegate())
+				},
+				Api::Evm => {
+					handler.extend_with(EvmClient::new(&self.client).to_delegate())
+				},
 				Api::Rpc => {
 					let modules = to_modules(&apis);
 					handler.extend_with(RpcClient::new(modules).to_delegate());
@@ -377,6 +393,7 @@ pub struct LightDependencies {
 	pub on_demand: Arc<::light::on_demand::OnDemand>,
 	pub cache: Arc<Mutex<LightDataCache>>,
 	pub transaction_queue: Arc<RwLock<LightTransactionQueue>>,
+	pub gas_price_oracle: GasPriceOracle,
 	pub dapps_service: Option<Arc<DappsService>>,
 	pub dapps_address: Option<(String, u16)>,
 	pub ws_address: Option<(String, u16)>,
@@ -400,6 +417,7 @@ impl LightDependencies {
 			self.on_demand.clone(),
 			self.cache.clone(),
 			self.transaction_queue.clone(),
+			self.gas_price_oracle,
 		);
 
 		macro_rules! add_signing_methods {
@@ -437,6 +455,7 @@ impl LightDependencies {
 						self.transaction_queue.clone(),
 						self.secret_store.clone(),
 						self.cache.clone(),
+						self.gas_price_oracle,
 					);
 					handler.extend_with(Eth::to_delegate(client.clone()));
 
@@ -451,6 +470,7 @@ impl LightDependencies {
 						self.on_demand.clone(),
 						self.sync.clone(),
 						self.cache.clone(),
+						self.gas_price_oracle,
 						self.remote.clone(),
 					);
 					self.client.add_listener(
@@ -506,7 +526,14 @@ impl LightDependencies {
 					).to_delegate())
 				},
 				Api::Traces => {
-					handler.extend_with(light::TracesClient.to_delegate())
+					handler.extend_with(light::TracesClient::new(Arc::new(dispatcher.clone())).to_delegate())
+				},
+				Api::Debug => {
+					handler.extend_with(light::DebugClient.to_delegate())
+				},
+				Api::Evm => {
+					// light clients don't seal blocks.
+					warn!(target: "rpc", "The evm API is not available for light clients.");
 				},
 				Api::Rpc => {
 					let modules = to_modules(&apis);
@@ -555,12 +582,14 @@ impl ApiSet {
 			},
 			ApiSet::IpcContext => {
 				public_list.insert(Api::Traces);
+				public_list.insert(Api::Debug);
 				public_list.insert(Api::ParityPubSub);
 				public_list.insert(Api::ParityAccounts);
 				public_list
 			},
 			ApiSet::SafeContext => {
 				public_list.insert(Api::Traces);
+				public_list.insert(Api::Debug);
 				public_list.insert(Api::ParityPubSub);
 				public_list.insert(Api::ParityAccounts);
 				public_list.insert(Api::ParitySet);
@@ -569,6 +598,7 @@ impl ApiSet {
 			},
 			ApiSet::All => {
 				public_list.insert(Api::Traces);
+				public_list.insert(Api::Debug);
 				public_list.insert(Api::ParityPubSub);
 				public_list.insert(Api::ParityAccounts);
 				public_list.insert(Api::ParitySet);
@@ -603,8 +633,10 @@ mod test {
 		assert_eq!(Api::ParityAccounts, "parity_accounts".parse().unwrap());
 		assert_eq!(Api::ParitySet, "parity_set".parse().unwrap());
 		assert_eq!(Api::Traces, "traces".parse().unwrap());
+		assert_eq!(Api::Debug, "debug".parse().unwrap());
 		assert_eq!(Api::Rpc, "rpc".parse().unwrap());
 		assert_eq!(Api::SecretStore, "secretstore".parse().unwrap());
+		assert_eq!(Api::Evm, "evm".parse().unwrap());
 		assert!("rp".parse::<Api>().is_err());
 	}
 
@@ -633,7 +665,7 @@ mod test {
 			// safe
 			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::Rpc, Api::SecretStore,
 			// semi-safe
-			Api::ParityAccounts
+			Api::ParityAccounts, Api::Debug
 		].into_iter().collect();
 		assert_eq!(ApiSet::IpcContext.list_apis(), expected);
 	}
@@ -644,7 +676,7 @@ mod test {
 			// safe
 			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::Rpc, Api::SecretStore,
 			// semi-safe
-			Api::ParityAccounts,
+			Api::ParityAccounts, Api::Debug,
 			// Unsafe
 			Api::ParitySet, Api::Signer,
 		].into_iter().collect();
@@ -655,7 +687,7 @@ mod test {
 	fn test_all_apis() {
 		assert_eq!("all".parse::<ApiSet>().unwrap(), ApiSet::List(vec![
 			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::Rpc, Api::SecretStore,
-			Api::ParityAccounts,
+			Api::ParityAccounts, Api::Debug,
 			Api::ParitySet, Api::Signer,
 			Api::Personal
 		].into_iter().collect()));
@@ -665,7 +697,7 @@ mod test {
 	fn test_all_without_personal_apis() {
 		assert_eq!("personal,all,-personal".parse::<ApiSet>().unwrap(), ApiSet::List(vec![
 			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::Rpc, Api::SecretStore,
-			Api::ParityAccounts,
+			Api::ParityAccounts, Api::Debug,
 			Api::ParitySet, Api::Signer,
 		].into_iter().collect()));
 	}
@@ -23,7 +23,7 @@ use ethcore::account_provider::{AccountProvider, AccountProviderSettings};
 use ethcore::client::{Client, Mode, DatabaseCompactionProfile, VMType, BlockChainClient};
 use ethcore::ethstore::ethkey;
 use ethcore::miner::{Miner, MinerService, ExternalMiner, MinerOptions};
-use ethcore::miner::{StratumOptions, Stratum};
+use ethcore::miner::{StratumOptions, Stratum, CpuMinerOptions, CpuMiner};
 use ethcore::service::ClientService;
 use ethcore::snapshot;
 use ethcore::verification::queue::VerifierSettings;
@@ -86,6 +86,7 @@ pub struct RunCmd {
 	pub net_conf: ethsync::NetworkConfiguration,
 	pub network_id: Option<u64>,
 	pub warp_sync: bool,
+	pub fast_sync: bool,
 	pub public_node: bool,
 	pub acc_conf: AccountsConfig,
 	pub gas_pricer: GasPricerConfig,
@@ -108,6 +109,7 @@ pub struct RunCmd {
 	pub name: String,
 	pub custom_bootnodes: bool,
 	pub stratum: Option<StratumOptions>,
+	pub cpu_miner: Option<CpuMinerOptions>,
 	pub no_periodic_snapshot: bool,
 	pub check_seal: bool,
 	pub download_old_blocks: bool,
@@ -115,6 +117,7 @@ pub struct RunCmd {
 	pub serve_light: bool,
 	pub light: bool,
 	pub no_persistent_txqueue: bool,
+	pub persistent_full_txqueue: bool,
 }
 
 pub fn open_ui(ws_conf: &rpc::WsConfiguration, ui_conf: &rpc::UiConfiguration) -> Result<(), String> {
@@ -143,6 +146,7 @@ pub fn open_dapp(dapps_conf: &dapps::Configuration, rpc_conf: &rpc::HttpConfigur
 // node info fetcher for the local store.
 struct FullNodeInfo {
 	miner: Option<Arc<Miner>>, // TODO: only TXQ needed, just use that after decoupling.
+	full_txqueue: bool,
 }
 
 impl ::local_store::NodeInfo for FullNodeInfo {
@@ -159,6 +163,13 @@ impl ::local_store::NodeInfo for FullNodeInfo {
 			.filter(|tx| local_txs.contains_key(&tx.hash()))
 			.collect()
 	}
+
+	fn queued_transactions(&self) -> Vec<::ethcore::miner::QueuedTransaction> {
+		match self.miner.as_ref() {
+			Some(m) if self.full_txqueue => m.queued_transactions(),
+			_ => Vec::new(),
+		}
+	}
 }
 
 // helper for light execution.
@@ -312,6 +323,7 @@ fn execute_light(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>) ->
 		on_demand: on_demand,
 		cache: cache.clone(),
 		transaction_queue: txq,
+		gas_price_oracle: cmd.gas_pricer.oracle(),
 		dapps_service: dapps_service,
 		dapps_address: cmd.dapps_conf.address(cmd.http_conf.address()),
 		ws_address: cmd.ws_conf.address(),
@@ -458,6 +470,7 @@ pub fn execute(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>) -> R
 
 	sync_config.fork_block = spec.fork_block();
 	sync_config.warp_sync = spec.engine.supports_warp() && cmd.warp_sync;
+	sync_config.fast_sync = spec.engine.supports_warp() && cmd.fast_sync;
 	sync_config.download_old_blocks = cmd.download_old_blocks;
 	sync_config.serve_light = cmd.serve_light;
 
@@ -543,6 +556,9 @@ pub fn execute(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>) -> R
 	let client = service.client();
 	let snapshot_service = service.snapshot_service();
 
+	// the gas price oracle needs the chain to pick the initial minimal gas price.
+	miner.recalibrate_minimal_gas_price_from_chain(&*client);
+
 	// initialize the local node information store.
 	let store = {
 		let db = service.db();
@@ -550,7 +566,8 @@ pub fn execute(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>) -> R
 			miner: match cmd.no_persistent_txqueue {
 				true => None,
 				false => Some(miner.clone()),
-			}
+			},
+			full_txqueue: cmd.persistent_full_txqueue,
 		};
 
 		let store = ::local_store::create(db, ::ethcore::db::COL_NODE_INFO, node_info);
@@ -575,6 +592,27 @@ pub fn execute(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>) -> R
 			Err(e) => warn!("Error loading cached pending transactions from disk: {}", e),
 		}
 
+		// re-queue the rest of the transaction queue, dropping entries which became invalid.
+		if cmd.persistent_full_txqueue && !cmd.no_persistent_txqueue {
+			match store.queued_transactions() {
+				Ok(queued) => {
+					let total = queued.len();
+					let restored = miner.import_queued_transactions(&*client, queued)
+						.into_iter()
+						.filter(|res| match *res {
+							Ok(_) => true,
+							Err(ref e) => {
+								debug!("Dropping saved transaction: {}", e);
+								false
+							},
+						})
+						.count();
+					info!("Restored {} of {} saved queued transactions.", restored, total);
+				},
+				Err(e) => warn!("Error loading cached transaction queue from disk: {}", e),
+			}
+		}
+
 		Arc::new(store)
 	};
 
@@ -585,9 +623,18 @@ pub fn execute(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>) -> R
 	let external_miner = Arc::new(ExternalMiner::default());
 
 	// start stratum
-	if let Some(ref stratum_config) = cmd.stratum {
-		Stratum::register(stratum_config, miner.clone(), Arc::downgrade(&client))
-			.map_err(|e| format!("Stratum start error: {:?}", e))?;
+	let stratum = match cmd.stratum {
+		Some(ref stratum_config) => Some(
+			Stratum::register(stratum_config, miner.clone(), Arc::downgrade(&client))
+				.map_err(|e| format!("Stratum start error: {:?}", e))?
+		),
+		None => None,
+	};
+
+	// start the built-in CPU miner
+	if let Some(ref cpu_miner_config) = cmd.cpu_miner {
+		CpuMiner::register(cpu_miner_config, miner.clone(), Arc::downgrade(&client))
+			.map_err(|e| format!("CPU miner start error: {}", e))?;
 	}
 
 	// create sync object
@@ -680,6 +727,7 @@ pub fn execute(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>) -> R
 		dapps_service: dapps_service,
 		dapps_address: cmd.dapps_conf.address(cmd.http_conf.address()),
 		ws_address: cmd.ws_conf.address(),
+		stratum: stratum,
 		fetch: fetch.clone(),
 		remote: event_loop.remote(),
 	});
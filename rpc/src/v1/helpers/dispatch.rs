@@ -33,7 +33,7 @@ use stats::Corpus;
 use ethkey::Signature;
 use ethsync::LightSync;
 use ethcore::ids::BlockId;
-use ethcore::miner::MinerService;
+use ethcore::miner::{MinerService, GasPriceOracle};
 use ethcore::client::MiningBlockChainClient;
 use ethcore::transaction::{Action, SignedTransaction, PendingTransaction, Transaction};
 use ethcore::account_provider::AccountProvider;
@@ -167,16 +167,15 @@ impl<C: MiningBlockChainClient, M: MinerService> Dispatcher for FullDispatcher<C
 	}
 }
 
-/// Get a recent gas price corpus.
+/// Get a recent gas price corpus, sampled from the last `sample_size` non-empty blocks.
 // TODO: this could be `impl Trait`.
 pub fn fetch_gas_price_corpus(
 	sync: Arc<LightSync>,
 	client: Arc<LightChainClient>,
 	on_demand: Arc<OnDemand>,
 	cache: Arc<Mutex<LightDataCache>>,
+	sample_size: usize,
 ) -> BoxFuture<Corpus<U256>, Error> {
-	const GAS_PRICE_SAMPLE_SIZE: usize = 100;
-
 	if let Some(cached) = cache.lock().gas_price_corpus() {
 		return future::ok(cached).boxed()
 	}
@@ -187,7 +186,7 @@ pub fn fetch_gas_price_corpus(
 		// and request each of the blocks from the network.
 		let block_requests = client.ancestry_iter(BlockId::Latest)
 			.filter(|hdr| hdr.gas_used() != U256::default())
-			.take(GAS_PRICE_SAMPLE_SIZE)
+			.take(sample_size)
 			.map(|hdr| request::Body(hdr.into()))
 			.collect::<Vec<_>>();
 
@@ -204,8 +203,8 @@ pub fn fetch_gas_price_corpus(
 				})
 			})
 			.map(move |prices| {
-				// produce a corpus from the vector, cache it, and return
-				// the median as the intended gas price.
+				// produce a corpus from the vector, cache it, and return it.
+				// the intended gas price is picked from it by the caller.
 				let corpus: ::stats::Corpus<_> = prices.into();
 				cache.lock().set_gas_price_corpus(corpus.clone());
 				corpus
@@ -242,6 +241,8 @@ pub struct LightDispatcher {
 	pub cache: Arc<Mutex<LightDataCache>>,
 	/// Transaction queue.
 	pub transaction_queue: Arc<RwLock<LightTransactionQueue>>,
+	/// Gas price oracle used to pick default gas prices.
+	pub gas_price_oracle: GasPriceOracle,
 }
 
 impl LightDispatcher {
@@ -254,6 +255,7 @@ impl LightDispatcher {
 		on_demand: Arc<OnDemand>,
 		cache: Arc<Mutex<LightDataCache>>,
 		transaction_queue: Arc<RwLock<LightTransactionQueue>>,
+		gas_price_oracle: GasPriceOracle,
 	) -> Self {
 		LightDispatcher {
 			sync: sync,
@@ -261,6 +263,7 @@ impl LightDispatcher {
 			on_demand: on_demand,
 			cache: cache,
 			transaction_queue: transaction_queue,
+			gas_price_oracle: gas_price_oracle,
 		}
 	}
 
@@ -272,6 +275,7 @@ impl LightDispatcher {
 			self.client.clone(),
 			self.on_demand.clone(),
 			self.cache.clone(),
+			self.gas_price_oracle.blocks,
 		)
 	}
 
@@ -329,15 +333,19 @@ impl Dispatcher for LightDispatcher {
 		// fast path for known gas price.
 		let gas_price = match request_gas_price {
 			Some(gas_price) => future::ok(with_gas_price(gas_price)).boxed(),
-			None => fetch_gas_price_corpus(
-				self.sync.clone(),
-				self.client.clone(),
-				self.on_demand.clone(),
-				self.cache.clone()
-			).and_then(|corp| match corp.median() {
-				Some(median) => future::ok(*median),
-				None => future::ok(DEFAULT_GAS_PRICE), // fall back to default on error.
-			}).map(with_gas_price).boxed()
+			None => {
+				let oracle = self.gas_price_oracle;
+				fetch_gas_price_corpus(
+					self.sync.clone(),
+					self.client.clone(),
+					self.on_demand.clone(),
+					self.cache.clone(),
+					oracle.blocks,
+				).and_then(move |corp| match oracle.gas_price(&corp) {
+					Some(gas_price) => future::ok(gas_price),
+					None => future::ok(DEFAULT_GAS_PRICE), // fall back to default on error.
+				}).map(with_gas_price).boxed()
+			}
 		};
 
 		match (request_nonce, force_nonce) {
@@ -579,7 +587,7 @@ fn decrypt(accounts: &AccountProvider, address: Address, msg: Bytes, password: S
 pub fn default_gas_price<C, M>(client: &C, miner: &M) -> U256
 	where C: MiningBlockChainClient, M: MinerService
 {
-	client.gas_price_corpus(100).median().cloned().unwrap_or_else(|| miner.sensible_gas_price())
+	miner.gas_price_oracle().gas_price_from_chain(client).unwrap_or_else(|| miner.sensible_gas_price())
 }
 
 /// Convert RPC confirmation payload to signer confirmation payload.
@@ -20,6 +20,7 @@ use std::fmt;
 use rlp::DecoderError;
 use ethcore::error::{Error as EthcoreError, CallError, TransactionError};
 use ethcore::account_provider::{SignError as AccountError};
+use ethcore::engines::EngineError;
 use jsonrpc_core::{Error, ErrorCode, Value};
 
 mod codes {
@@ -29,6 +30,7 @@ mod codes {
 	pub const NO_AUTHOR: i64 = -32002;
 	pub const NO_NEW_WORK: i64 = -32003;
 	pub const NO_WORK_REQUIRED: i64 = -32004;
+	pub const REQUEST_TOO_LARGE: i64 = -32005;
 	pub const UNKNOWN_ERROR: i64 = -32009;
 	pub const TRANSACTION_ERROR: i64 = -32010;
 	pub const EXECUTION_ERROR: i64 = -32015;
@@ -71,6 +73,14 @@ pub fn public_unsupported(details: Option<String>) -> Error {
 	}
 }
 
+pub fn request_too_large(details: String) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::REQUEST_TOO_LARGE),
+		message: "Request exceeds the limits of this node.".into(),
+		data: Some(Value::String(details)),
+	}
+}
+
 pub fn request_not_found() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::REQUEST_NOT_FOUND),
@@ -222,6 +232,30 @@ pub fn ws_disabled() -> Error {
 	}
 }
 
+pub fn stratum_disabled() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		message: "Stratum server is disabled. This API is not available.".into(),
+		data: None,
+	}
+}
+
+pub fn dev_chain(error: String) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		message: error,
+		data: None,
+	}
+}
+
+pub fn engine(error: EngineError) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		message: format!("{}", error),
+		data: None,
+	}
+}
+
 pub fn network_disabled() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
@@ -290,6 +324,9 @@ pub fn transaction_message(error: TransactionError) -> String {
 		LimitReached => {
 			"There are too many transactions in the queue. Your transaction was dropped due to limit. Try increasing the fee.".into()
 		},
+		SenderLimitReached { limit } => {
+			format!("There are too many transactions from your account in the queue (limit: {}). Wait for some of them to be mined or try replacing one of them.", limit)
+		},
 		InsufficientGas { minimal, got } => {
 			format!("Transaction gas is too low. There is not enough gas to cover minimal cost of the transaction (minimal: {}, got: {}). Try increasing supplied gas.", minimal, got)
 		},
@@ -307,6 +344,7 @@ pub fn transaction_message(error: TransactionError) -> String {
 		SenderBanned => "Sender is banned in local queue.".into(),
 		RecipientBanned => "Recipient is banned in local queue.".into(),
 		CodeBanned => "Code is banned in local queue.".into(),
+		NotAllowed => "Transaction is not permitted.".into(),
 	}
 }
 
@@ -341,6 +379,7 @@ pub fn call(error: CallError) -> Error {
 		CallError::StateCorrupt => state_corrupt(),
 		CallError::Exceptional => exceptional(),
 		CallError::Execution(e) => execution(e),
+		CallError::BlockNotFound => unknown_block(),
 		CallError::TransactionNotFound => internal("{}, this should not be the case with eth_call, most likely a bug.", CallError::TransactionNotFound),
 	}
 }
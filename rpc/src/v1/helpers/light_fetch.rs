@@ -16,14 +16,18 @@
 
 //! Helpers for fetching blockchain data either from the light client or the network.
 
+use std::ops::Range;
 use std::sync::Arc;
 
 use ethcore::basic_account::BasicAccount;
+use ethcore::client::CallAnalytics;
 use ethcore::encoded;
 use ethcore::executed::{Executed, ExecutionError};
 use ethcore::ids::BlockId;
 use ethcore::filter::Filter as EthcoreFilter;
-use ethcore::transaction::{Action, Transaction as EthTransaction};
+use ethcore::miner::GasPriceOracle;
+use ethcore::receipt::LocalizedReceipt;
+use ethcore::transaction::{Action, Transaction as EthTransaction, LocalizedTransaction, SignedTransaction};
 
 use futures::{future, Future, BoxFuture};
 use jsonrpc_core::Error;
@@ -33,16 +37,17 @@ use light::cache::Cache;
 use light::client::LightChainClient;
 use light::cht;
 use light::on_demand::{request, OnDemand, HeaderRef, Request as OnDemandRequest, Response as OnDemandResponse};
-use light::request::Field;
+use light::request::{Field, TransactionIndexResponse};
 
 use ethsync::LightSync;
-use util::{Address, Mutex, U256};
+use util::{Address, Mutex, H256, U256};
 
-use v1::helpers::{CallRequest as CallRequestHelper, errors, dispatch};
+use v1::helpers::{CallRequest as CallRequestHelper, errors, dispatch, limit_logs};
 use v1::types::{BlockNumber, CallRequest, Log};
 
 /// Helper for fetching blockchain data either from the light client or the network
 /// as necessary.
+#[derive(Clone)]
 pub struct LightFetch {
 	/// The light client.
 	pub client: Arc<LightChainClient>,
@@ -52,11 +57,62 @@ pub struct LightFetch {
 	pub sync: Arc<LightSync>,
 	/// The light data cache.
 	pub cache: Arc<Mutex<Cache>>,
+	/// Gas price oracle used to pick default gas prices.
+	pub gas_price_oracle: GasPriceOracle,
 }
 
+/// Maximum number of blocks a single log query may span.
+const MAX_LOG_RANGE: u64 = 100_000;
+
+/// Maximum number of headers a single log query may fetch from the network.
+const MAX_LOG_HEADER_FETCHES: u64 = 1024;
+
+/// Maximum number of blocks a single log query may fetch receipts for.
+const MAX_LOG_RECEIPT_FETCHES: usize = 256;
+
+/// Number of blocks to fetch headers or receipts for in a single request batch.
+const LOG_REQUEST_BATCH: u64 = 64;
+
 /// Type alias for convenience.
 pub type ExecutionResult = Result<Executed, ExecutionError>;
 
+// request proved execution of the given transaction on top of the state of the given header.
+fn execute_tx(
+	sync: Arc<LightSync>,
+	on_demand: Arc<OnDemand>,
+	client: Arc<LightChainClient>,
+	tx: SignedTransaction,
+	hdr: encoded::Header,
+	id: BlockId,
+	analytics: CallAnalytics,
+) -> BoxFuture<ExecutionResult, Error> {
+	// TODO: get last-hashes from network.
+	let env_info = match client.env_info(id) {
+		Some(env_info) => env_info,
+		_ => return future::err(errors::unknown_block()).boxed(),
+	};
+
+	let request = request::TransactionProof {
+		tx: tx,
+		header: hdr.into(),
+		env_info: env_info,
+		engine: client.engine().clone(),
+		analytics: analytics,
+	};
+
+	let proved_future = sync.with_context(move |ctx| {
+		on_demand
+			.request(ctx, request)
+			.expect("no back-references; therefore all back-refs valid; qed")
+			.map_err(errors::on_demand_cancel).boxed()
+	});
+
+	match proved_future {
+		Some(fut) => fut.boxed(),
+		None => future::err(errors::network_disabled()).boxed(),
+	}
+}
+
 // extract the header indicated by the given `HeaderRef` from the given responses.
 // fails only if they do not correspond.
 fn extract_header(res: &[OnDemandResponse], header: HeaderRef) -> Option<encoded::Header> {
@@ -69,6 +125,42 @@ fn extract_header(res: &[OnDemandResponse], header: HeaderRef) -> Option<encoded
 	}
 }
 
+// find the transaction in the given block, checking it against the index reported by
+// the peer. the block body is already verified against the header at this point.
+fn extract_transaction(block: &encoded::Block, tx_hash: H256, index: &TransactionIndexResponse)
+	-> Result<LocalizedTransaction, Error>
+{
+	match block.view().localized_transaction_at(index.index as usize) {
+		Some(tx) if tx.hash() == tx_hash => Ok(tx),
+		_ => Err(errors::internal("Peer gave a bad transaction index", tx_hash)),
+	}
+}
+
+// check that a log query spanning blocks `from` to `to` doesn't span too many blocks.
+fn check_log_range(from: u64, to: u64) -> Result<(), Error> {
+	match to - from >= MAX_LOG_RANGE {
+		true => Err(errors::request_too_large(
+			format!("Log queries may span at most {} blocks", MAX_LOG_RANGE)
+		)),
+		false => Ok(()),
+	}
+}
+
+// get the numbers of the headers a log query starting at block `from` needs to fetch from
+// the network, given the oldest header of the queried span stored locally.
+// the genesis header is always stored, so it's never fetched.
+fn remote_log_headers(from: u64, oldest_local: u64) -> Result<Range<u64>, Error> {
+	let remote_start = ::std::cmp::max(from, 1);
+	let remote_end = ::std::cmp::max(oldest_local, remote_start);
+
+	match remote_end - remote_start > MAX_LOG_HEADER_FETCHES {
+		true => Err(errors::request_too_large(
+			format!("Log queries may fetch at most {} headers from the network", MAX_LOG_HEADER_FETCHES)
+		)),
+		false => Ok(remote_start..remote_end),
+	}
+}
+
 impl LightFetch {
 	// push the necessary requests onto the request chain to get the header by the given ID.
 	// yield a header reference which other requests can use.
@@ -105,6 +197,45 @@ impl LightFetch {
 		}
 	}
 
+	// get the index of a transaction along with the header of the block it was included in.
+	// `None` if no peer knows of the transaction or the block isn't canonical: its hash
+	// is checked against that of the block with the same number in our chain, whose header
+	// is either stored locally or proven with a CHT.
+	fn transaction_index(&self, tx_hash: H256) -> BoxFuture<Option<(TransactionIndexResponse, encoded::Header)>, Error> {
+		let maybe_future = self.sync.with_context(move |ctx| {
+			self.on_demand.request_raw(ctx, vec![request::TransactionIndex(tx_hash.into()).into()])
+				.expect("no back-references; therefore all back-references valid; qed")
+				.map_err(errors::on_demand_cancel)
+				.boxed()
+		});
+
+		let index_future = match maybe_future {
+			Some(recv) => recv,
+			None => return future::err(errors::network_disabled()).boxed(),
+		};
+
+		let fetcher = self.clone();
+		index_future.and_then(move |mut res| {
+			let index = match res.pop() {
+				Some(OnDemandResponse::TransactionIndex(index)) => index,
+				None => return future::ok(None).boxed(), // no peer knows of the transaction.
+				_ => panic!("responses correspond directly with requests in amount and type; qed"),
+			};
+
+			// blocks past our best block aren't canonical for us yet.
+			if index.num > fetcher.client.chain_info().best_block_number {
+				return future::ok(None).boxed();
+			}
+
+			fetcher.header(BlockId::Number(index.num))
+				.map(move |hdr| match hdr.hash() == index.hash {
+					true => Some((index, hdr)),
+					false => None,
+				})
+				.boxed()
+		}).boxed()
+	}
+
 	/// Get a block header from the on demand service or client, or error.
 	pub fn header(&self, id: BlockId) -> BoxFuture<encoded::Header, Error> {
 		let mut reqs = Vec::new();
@@ -157,8 +288,44 @@ impl LightFetch {
 		}
 	}
 
+	/// helper for getting an item of an account's storage at a given block.
+	/// The account's storage root is proven against the header first and the
+	/// storage item against that root.
+	pub fn storage(&self, address: Address, key: H256, id: BlockId) -> BoxFuture<H256, Error> {
+		let mut reqs = Vec::new();
+		let header_ref = match self.make_header_requests(id, &mut reqs) {
+			Ok(r) => r,
+			Err(e) => return future::err(e).boxed(),
+		};
+
+		let account_idx = reqs.len();
+		reqs.push(request::Account { header: header_ref.clone(), address: address }.into());
+		reqs.push(request::Storage {
+			header: header_ref,
+			address: address,
+			key: key,
+			storage_root: Field::back_ref(account_idx, 1),
+		}.into());
+
+		let maybe_future = self.sync.with_context(move |ctx| {
+			self.on_demand.request_raw(ctx, reqs)
+				.expect("all back-references known to be valid; qed")
+				.map(|mut res| match res.pop() {
+					Some(OnDemandResponse::Storage(value)) => value,
+					_ => panic!("responses correspond directly with requests in amount and type; qed"),
+				})
+				.map_err(errors::on_demand_cancel)
+				.boxed()
+		});
+
+		match maybe_future {
+			Some(recv) => recv,
+			None => future::err(errors::network_disabled()).boxed()
+		}
+	}
+
 	/// helper for getting proved execution.
-	pub fn proved_execution(&self, req: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<ExecutionResult, Error> {
+	pub fn proved_execution(&self, req: CallRequest, num: Trailing<BlockNumber>, analytics: CallAnalytics) -> BoxFuture<ExecutionResult, Error> {
 		const DEFAULT_GAS_PRICE: U256 = U256([0, 0, 0, 21_000_000]);
 
 		let (sync, on_demand, client) = (self.sync.clone(), self.on_demand.clone(), self.client.clone());
@@ -173,15 +340,16 @@ impl LightFetch {
 
 		let gas_price_fut = match req.gas_price {
 			Some(price) => future::ok(price).boxed(),
-			None => dispatch::fetch_gas_price_corpus(
-				self.sync.clone(),
-				self.client.clone(),
-				self.on_demand.clone(),
-				self.cache.clone(),
-			).map(|corp| match corp.median() {
-				Some(median) => *median,
-				None => DEFAULT_GAS_PRICE,
-			}).boxed()
+			None => {
+				let oracle = self.gas_price_oracle;
+				dispatch::fetch_gas_price_corpus(
+					self.sync.clone(),
+					self.client.clone(),
+					self.on_demand.clone(),
+					self.cache.clone(),
+					oracle.blocks,
+				).map(move |corp| oracle.gas_price(&corp).unwrap_or(DEFAULT_GAS_PRICE)).boxed()
+			}
 		};
 
 		// if nonce resolves, this should too since it'll be in the LRU-cache.
@@ -206,31 +374,17 @@ impl LightFetch {
 				None => Err(errors::unknown_block()),
 			})
 		}).join(header_fut).and_then(move |(tx, hdr)| {
-			// then request proved execution.
-			// TODO: get last-hashes from network.
-			let env_info = match client.env_info(id) {
-				Some(env_info) => env_info,
-				_ => return future::err(errors::unknown_block()).boxed(),
-			};
-
-			let request = request::TransactionProof {
-				tx: tx,
-				header: hdr.into(),
-				env_info: env_info,
-				engine: client.engine().clone(),
-			};
+			execute_tx(sync, on_demand, client, tx, hdr, id, analytics)
+		}).boxed()
+	}
 
-			let proved_future = sync.with_context(move |ctx| {
-				on_demand
-					.request(ctx, request)
-					.expect("no back-references; therefore all back-refs valid; qed")
-					.map_err(errors::on_demand_cancel).boxed()
-			});
+	/// helper for getting proved execution of an already-signed transaction.
+	pub fn proved_transaction_execution(&self, tx: SignedTransaction, num: Trailing<BlockNumber>, analytics: CallAnalytics) -> BoxFuture<ExecutionResult, Error> {
+		let (sync, on_demand, client) = (self.sync.clone(), self.on_demand.clone(), self.client.clone());
+		let id = num.unwrap_or_default().into();
 
-			match proved_future {
-				Some(fut) => fut.boxed(),
-				None => future::err(errors::network_disabled()).boxed(),
-			}
+		self.header(id).and_then(move |hdr| {
+			execute_tx(sync, on_demand, client, tx, hdr, id, analytics)
 		}).boxed()
 	}
 
@@ -261,62 +415,268 @@ impl LightFetch {
 		}
 	}
 
-	/// get transaction logs
-	pub fn logs(&self, filter: EthcoreFilter) -> BoxFuture<Vec<Log>, Error> {
-		use std::collections::BTreeMap;
+	/// Get a transaction by hash, along with the location it was included at.
+	/// The transaction is verified against the block's transactions root.
+	/// `None` if the transaction isn't known to be in a canonical block.
+	pub fn transaction_by_hash(&self, tx_hash: H256) -> BoxFuture<Option<LocalizedTransaction>, Error> {
+		let fetcher = self.clone();
+		self.transaction_index(tx_hash).and_then(move |index| {
+			let (index, hdr) = match index {
+				Some(index) => index,
+				None => return future::ok(None).boxed(),
+			};
+
+			let maybe_future = fetcher.sync.with_context(|ctx| {
+				fetcher.on_demand.request_raw(ctx, vec![request::Body(hdr.into()).into()])
+					.expect("no back-references; therefore all back-references valid; qed")
+					.map_err(errors::on_demand_cancel)
+					.and_then(move |mut res| match res.pop() {
+						Some(OnDemandResponse::Body(block)) =>
+							extract_transaction(&block, tx_hash, &index).map(Some),
+						_ => panic!("responses correspond directly with requests in amount and type; qed"),
+					})
+					.boxed()
+			});
+
+			match maybe_future {
+				Some(recv) => recv,
+				None => future::err(errors::network_disabled()).boxed()
+			}
+		}).boxed()
+	}
+
+	/// Get the receipt of a transaction by hash.
+	/// The transaction and receipts are verified against the block's transactions
+	/// and receipts roots respectively.
+	/// `None` if the transaction isn't known to be in a canonical block.
+	pub fn transaction_receipt(&self, tx_hash: H256) -> BoxFuture<Option<LocalizedReceipt>, Error> {
+		let fetcher = self.clone();
+		self.transaction_index(tx_hash).and_then(move |index| {
+			let (index, hdr) = match index {
+				Some(index) => index,
+				None => return future::ok(None).boxed(),
+			};
 
-		use futures::stream::{self, Stream};
+			let header_ref: HeaderRef = hdr.into();
+			let reqs = vec![
+				request::Body(header_ref.clone()).into(),
+				request::BlockReceipts(header_ref).into(),
+			];
+
+			let engine = fetcher.client.engine().clone();
+			let maybe_future = fetcher.sync.with_context(|ctx| {
+				fetcher.on_demand.request_raw(ctx, reqs)
+					.expect("no back-references; therefore all back-references valid; qed")
+					.map_err(errors::on_demand_cancel)
+					.and_then(move |mut res| {
+						let (block, receipts) = match (res.remove(0), res.remove(0)) {
+							(OnDemandResponse::Body(block), OnDemandResponse::Receipts(receipts)) => (block, receipts),
+							_ => panic!("responses correspond directly with requests in amount and type; qed"),
+						};
+
+						let tx = extract_transaction(&block, tx_hash, &index)?;
+
+						// the receipts root commits to as many receipts as there are transactions.
+						let previous_receipts = receipts.into_iter().take(tx.transaction_index + 1).collect();
+						Ok(Some(::ethcore::client::transaction_receipt(&*engine, tx, previous_receipts)))
+					})
+					.boxed()
+			});
 
+			match maybe_future {
+				Some(recv) => recv,
+				None => future::err(errors::network_disabled()).boxed()
+			}
+		}).boxed()
+	}
+
+	/// Get transaction logs.
+	///
+	/// Candidate blocks are found by checking header blooms: headers which aren't
+	/// stored locally are fetched with CHT proofs. Bodies and receipts are then fetched
+	/// only for candidate blocks and verified against their headers before being filtered.
+	/// Only the last `filter.limit` matching logs are returned.
+	pub fn logs(&self, filter: EthcoreFilter) -> BoxFuture<Vec<Log>, Error> {
 		const NO_INVALID_BACK_REFS: &'static str = "Fails only on invalid back-references; back-references here known to be valid; qed";
 
-		// early exit for "to" block before "from" block.
 		let best_number = self.client.chain_info().best_block_number;
 		let block_number = |id| match id {
 			BlockId::Earliest => Some(0),
 			BlockId::Latest | BlockId::Pending => Some(best_number),
 			BlockId::Hash(h) => self.client.block_header(BlockId::Hash(h)).map(|hdr| hdr.number()),
-			BlockId::Number(x) => Some(x),
+			BlockId::Number(x) => Some(::std::cmp::min(x, best_number)),
 		};
 
-		match (block_number(filter.to_block), block_number(filter.from_block)) {
-			(Some(to), Some(from)) if to < from => return future::ok(Vec::new()).boxed(),
-			(Some(_), Some(_)) => {},
+		// early exit for "to" block before "from" block.
+		let (from, to) = match (block_number(filter.from_block), block_number(filter.to_block)) {
+			(Some(from), Some(to)) if to < from => return future::ok(Vec::new()).boxed(),
+			(Some(from), Some(to)) => (from, to),
 			_ => return future::err(errors::unknown_block()).boxed(),
+		};
+
+		if let Err(e) = check_log_range(from, to) {
+			return future::err(e).boxed();
 		}
 
-		let maybe_future = self.sync.with_context(move |ctx| {
-			// find all headers which match the filter, and fetch the receipts for each one.
-			// match them with their numbers for easy sorting later.
-			let bit_combos = filter.bloom_possibilities();
-			let receipts_futures: Vec<_> = self.client.ancestry_iter(filter.to_block)
-				.take_while(|ref hdr| BlockId::Number(hdr.number()) != filter.from_block)
-				.take_while(|ref hdr| BlockId::Hash(hdr.hash()) != filter.from_block)
-				.filter(|ref hdr| {
-					let hdr_bloom = hdr.log_bloom();
-					bit_combos.iter().find(|&bloom| hdr_bloom & *bloom == *bloom).is_some()
-				})
-				.map(|hdr| (hdr.number(), request::BlockReceipts(hdr.into())))
-				.map(|(num, req)| self.on_demand.request(ctx, req).expect(NO_INVALID_BACK_REFS).map(move |x| (num, x)))
-				.collect();
-
-			// as the receipts come in, find logs within them which match the filter.
-			// insert them into a BTreeMap to maintain order by number and block index.
-			stream::futures_unordered(receipts_futures)
-				.fold(BTreeMap::new(), move |mut matches, (num, receipts)| {
-					for (block_index, log) in receipts.into_iter().flat_map(|r| r.logs).enumerate() {
-						if filter.matches(&log) {
-							matches.insert((num, block_index), log.into());
-						}
-					}
-					future::ok(matches)
-				}) // and then collect them into a vector.
-				.map(|matches| matches.into_iter().map(|(_, v)| v).collect())
-				.map_err(errors::on_demand_cancel)
-		});
+		// use the headers we have locally, then fetch the rest with CHT proofs.
+		let mut headers: Vec<_> = self.client.ancestry_iter(BlockId::Number(to))
+			.take_while(|hdr| hdr.number() >= from)
+			.collect();
 
-		match maybe_future {
-			Some(fut) => fut.boxed(),
-			None => future::err(errors::network_disabled()).boxed(),
+		let oldest_local = headers.last().map_or(to + 1, |hdr| hdr.number());
+		if from == 0 && oldest_local > 0 {
+			headers.push(self.client.block_header(BlockId::Number(0)).expect("genesis always stored; qed"));
 		}
+
+		let remote_headers = match remote_log_headers(from, oldest_local) {
+			Ok(range) => range,
+			Err(e) => return future::err(e).boxed(),
+		};
+		let remote_start = remote_headers.start;
+
+		let mut header_batches = Vec::new();
+		for num in remote_headers {
+			if (num - remote_start) % LOG_REQUEST_BATCH == 0 {
+				header_batches.push(Vec::new());
+			}
+
+			let req = cht::block_to_cht_number(num)
+				.and_then(|cht_num| self.client.cht_root(cht_num as usize))
+				.and_then(|root| request::HeaderProof::new(num, root));
+
+			let batch = header_batches.last_mut().expect("a batch is pushed on the first iteration; qed");
+			match req {
+				Some(req) => {
+					let hash_ref = Field::back_ref(batch.len(), 0);
+					batch.push(req.into());
+					batch.push(request::HeaderByHash(hash_ref).into());
+				}
+				None => return future::err(errors::unknown_block()).boxed(),
+			}
+		}
+
+		let (sync, on_demand) = (self.sync.clone(), self.on_demand.clone());
+		let header_futures = self.sync.with_context(|ctx| {
+			header_batches.into_iter()
+				.map(|reqs| self.on_demand.request_raw(ctx, reqs).expect(NO_INVALID_BACK_REFS))
+				.collect::<Vec<_>>()
+		});
+
+		let header_futures = match header_futures {
+			Some(futures) => futures,
+			None => return future::err(errors::network_disabled()).boxed(),
+		};
+
+		future::join_all(header_futures)
+			.map_err(errors::on_demand_cancel)
+			.and_then(move |responses| {
+				headers.extend(responses.into_iter().flat_map(|res| res).filter_map(|res| match res {
+					OnDemandResponse::HeaderByHash(hdr) => Some(hdr),
+					_ => None,
+				}));
+
+				// find all headers which match the filter, and fetch the receipts for each one.
+				let bit_combos = filter.bloom_possibilities();
+				let mut candidates: Vec<_> = headers.into_iter()
+					.filter(|hdr| {
+						let hdr_bloom = hdr.log_bloom();
+						bit_combos.iter().any(|bloom| hdr_bloom & *bloom == *bloom)
+					})
+					.collect();
+
+				if candidates.len() > MAX_LOG_RECEIPT_FETCHES {
+					return future::err(errors::request_too_large(
+						format!("Log queries may fetch receipts of at most {} blocks", MAX_LOG_RECEIPT_FETCHES)
+					)).boxed();
+				}
+
+				candidates.sort_by_key(|hdr| hdr.number());
+
+				// bodies are needed for the hashes of the transactions the logs belong to.
+				let receipt_futures = sync.with_context(|ctx| {
+					candidates.chunks(LOG_REQUEST_BATCH as usize)
+						.map(|batch| batch.iter().cloned().flat_map(|hdr| {
+							let header_ref: HeaderRef = hdr.into();
+							vec![request::Body(header_ref.clone()).into(), request::BlockReceipts(header_ref).into()]
+						}).collect())
+						.map(|reqs| on_demand.request_raw(ctx, reqs).expect(NO_INVALID_BACK_REFS))
+						.collect::<Vec<_>>()
+				});
+
+				let receipt_futures = match receipt_futures {
+					Some(futures) => futures,
+					None => return future::err(errors::network_disabled()).boxed(),
+				};
+
+				// as the receipts come in, find logs within them which match the filter.
+				future::join_all(receipt_futures)
+					.map_err(errors::on_demand_cancel)
+					.map(move |responses| {
+						let mut matches = Vec::new();
+						let mut responses = responses.into_iter().flat_map(|res| res);
+						for hdr in candidates {
+							let (block, receipts) = match (responses.next(), responses.next()) {
+								(Some(OnDemandResponse::Body(block)), Some(OnDemandResponse::Receipts(receipts))) => (block, receipts),
+								_ => panic!("responses correspond directly with requests in amount and type; qed"),
+							};
+
+							let (block_hash, block_number) = (hdr.hash(), hdr.number());
+							let transaction_hashes = block.transaction_hashes();
+							let logs = receipts.into_iter()
+								.enumerate()
+								.flat_map(|(tx_index, receipt)| receipt.logs.into_iter()
+									.enumerate()
+									.map(move |(tx_log_index, log)| (tx_index, tx_log_index, log)))
+								.enumerate();
+
+							for (log_index, (tx_index, tx_log_index, log)) in logs {
+								if !filter.matches(&log) { continue }
+
+								let mut log: Log = log.into();
+								log.block_hash = Some(block_hash.into());
+								log.block_number = Some(block_number.into());
+								log.transaction_hash = transaction_hashes.get(tx_index).map(|hash| (*hash).into());
+								log.transaction_index = Some(tx_index.into());
+								log.log_index = Some(log_index.into());
+								log.transaction_log_index = Some(tx_log_index.into());
+								log.log_type = "mined".to_owned();
+								matches.push(log);
+							}
+						}
+
+						limit_logs(matches, filter.limit)
+					})
+					.boxed()
+			})
+			.boxed()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use jsonrpc_core::ErrorCode;
+	use super::{check_log_range, remote_log_headers, MAX_LOG_RANGE, MAX_LOG_HEADER_FETCHES};
+
+	const REQUEST_TOO_LARGE: i64 = -32005;
+
+	#[test]
+	fn rejects_log_queries_spanning_too_many_blocks() {
+		assert!(check_log_range(0, MAX_LOG_RANGE - 1).is_ok());
+		assert!(check_log_range(5, 5).is_ok());
+
+		let err = check_log_range(0, MAX_LOG_RANGE).unwrap_err();
+		assert_eq!(err.code, ErrorCode::ServerError(REQUEST_TOO_LARGE));
+	}
+
+	#[test]
+	fn rejects_log_queries_fetching_too_many_headers() {
+		// genesis is never fetched.
+		assert_eq!(remote_log_headers(0, 1).unwrap(), 1..1);
+		assert_eq!(remote_log_headers(10, 20).unwrap(), 10..20);
+		assert_eq!(remote_log_headers(20, 10).unwrap(), 20..20);
+		assert_eq!(remote_log_headers(0, MAX_LOG_HEADER_FETCHES + 1).unwrap(), 1..MAX_LOG_HEADER_FETCHES + 1);
+
+		let err = remote_log_headers(0, MAX_LOG_HEADER_FETCHES + 2).unwrap_err();
+		assert_eq!(err.code, ErrorCode::ServerError(REQUEST_TOO_LARGE));
 	}
 }
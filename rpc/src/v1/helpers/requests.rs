@@ -15,6 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use util::{Address, U256, Bytes};
+use ethcore::client::StateOverride;
 use v1::types::{Origin, TransactionCondition};
 
 /// Transaction request coming from RPC
@@ -93,6 +94,8 @@ pub struct CallRequest {
 	pub data: Option<Vec<u8>>,
 	/// Nonce
 	pub nonce: Option<U256>,
+	/// State overrides applied before executing the call
+	pub state_override: StateOverride,
 }
 
 /// Confirmation object
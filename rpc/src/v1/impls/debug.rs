@@ -0,0 +1,75 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Debug api implementation.
+
+use std::sync::Arc;
+
+use ethcore::client::{MiningBlockChainClient, CallAnalytics, TransactionId};
+use ethcore::miner::MinerService;
+
+use jsonrpc_core::Error;
+use jsonrpc_macros::Trailing;
+use v1::traits::Debug;
+use v1::helpers::{errors, fake_sign, CallRequest as CRequest};
+use v1::types::{BlockNumber, CallRequest, ExecutionTrace, TraceOptions, H256};
+
+fn vm_tracing() -> CallAnalytics {
+	CallAnalytics {
+		transaction_tracing: false,
+		vm_tracing: true,
+		state_diffing: false,
+	}
+}
+
+/// Debug api implementation.
+pub struct DebugClient<C, M> {
+	client: Arc<C>,
+	miner: Arc<M>,
+}
+
+impl<C, M> DebugClient<C, M> {
+	/// Creates new Debug client.
+	pub fn new(client: &Arc<C>, miner: &Arc<M>) -> Self {
+		DebugClient {
+			client: client.clone(),
+			miner: miner.clone(),
+		}
+	}
+}
+
+impl<C, M> Debug for DebugClient<C, M> where C: MiningBlockChainClient + 'static, M: MinerService + 'static {
+	fn trace_transaction(&self, transaction_hash: H256, options: Trailing<TraceOptions>) -> Result<ExecutionTrace, Error> {
+		let options = options.unwrap_or_default();
+
+		self.client.replay(TransactionId::Hash(transaction_hash.into()), vm_tracing())
+			.map(|executed| ExecutionTrace::new(executed, &options))
+			.map_err(errors::call)
+	}
+
+	fn trace_call(&self, request: CallRequest, block: BlockNumber, options: Trailing<TraceOptions>) -> Result<ExecutionTrace, Error> {
+		let options = options.unwrap_or_default();
+
+		let request: CRequest = request.into();
+		let state_override = request.state_override.clone();
+		let signed = fake_sign::sign_call(&self.client, &self.miner, request)?;
+
+		self.client.call(&signed, block.into(), vm_tracing(), &state_override)
+			.map(|executed| ExecutionTrace::new(executed, &options))
+			.map_err(errors::call)
+	}
+}
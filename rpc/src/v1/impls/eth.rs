@@ -30,7 +30,7 @@ use util::Mutex;
 use ethash::SeedHashCompute;
 use ethcore::account_provider::{AccountProvider, DappId};
 use ethcore::block::IsBlock;
-use ethcore::client::{MiningBlockChainClient, BlockId, TransactionId, UncleId};
+use ethcore::client::{MiningBlockChainClient, ProvingBlockChainClient, BlockId, TransactionId, UncleId};
 use ethcore::ethereum::Ethash;
 use ethcore::filter::Filter as EthcoreFilter;
 use ethcore::header::{Header as BlockHeader, BlockNumber as EthBlockNumber};
@@ -43,14 +43,14 @@ use ethsync::{SyncProvider};
 use jsonrpc_core::Error;
 use jsonrpc_macros::Trailing;
 
-use v1::helpers::{errors, limit_logs, fake_sign};
+use v1::helpers::{errors, limit_logs, fake_sign, CallRequest as CRequest};
 use v1::helpers::dispatch::{Dispatcher, FullDispatcher, default_gas_price};
 use v1::helpers::block_import::is_major_importing;
 use v1::helpers::accounts::unwrap_provider;
 use v1::traits::Eth;
 use v1::types::{
 	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
-	Transaction, CallRequest, Index, Filter, Log, Receipt, Work,
+	Transaction, CallRequest, Index, Filter, Log, Receipt, Work, EthAccount, StorageProof,
 	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U256 as RpcU256,
 };
 use v1::metadata::Metadata;
@@ -271,7 +271,7 @@ fn check_known<C>(client: &C, number: BlockNumber) -> Result<(), Error> where C:
 const MAX_QUEUE_SIZE_TO_MINE_ON: usize = 4;	// because uncles go back 6.
 
 impl<C, SN: ?Sized, S: ?Sized, M, EM> Eth for EthClient<C, SN, S, M, EM> where
-	C: MiningBlockChainClient + 'static,
+	C: MiningBlockChainClient + ProvingBlockChainClient + 'static,
 	SN: SnapshotService + 'static,
 	S: SyncProvider + 'static,
 	M: MinerService + 'static,
@@ -487,6 +487,49 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM> Eth for EthClient<C, SN, S, M, EM> where
 		future::done(res).boxed()
 	}
 
+	fn proof(&self, address: RpcH160, keys: Vec<RpcH256>, num: Trailing<BlockNumber>) -> BoxFuture<EthAccount, Error> {
+		let address: Address = RpcH160::into(address);
+
+		let id = match num.unwrap_or_default() {
+			BlockNumber::Pending => return future::err(errors::unimplemented(Some("Proofs are not available for the pending block.".into()))).boxed(),
+			id => id,
+		};
+
+		try_bf!(check_known(&*self.client, id.clone()));
+
+		let id: BlockId = id.into();
+		let account_key = address.sha3();
+
+		let (account_proof, account) = match self.client.prove_account(account_key, id) {
+			Some(res) => res,
+			None => return future::err(errors::state_pruned()).boxed(),
+		};
+
+		let storage_proof = keys.into_iter().map(|key| {
+			let key: H256 = key.into();
+			match self.client.prove_storage(account_key, key.sha3(), id) {
+				Some((proof, value)) => Ok(StorageProof {
+					key: key.into(),
+					value: U256::from(value).into(),
+					proof: proof.into_iter().map(Bytes::new).collect(),
+				}),
+				None => Err(errors::state_pruned()),
+			}
+		}).collect::<Result<Vec<_>, _>>();
+
+		let res = storage_proof.map(|storage_proof| EthAccount {
+			address: address.into(),
+			balance: account.balance.into(),
+			nonce: account.nonce.into(),
+			code_hash: account.code_hash.into(),
+			storage_hash: account.storage_root.into(),
+			account_proof: account_proof.into_iter().map(Bytes::new).collect(),
+			storage_proof: storage_proof,
+		});
+
+		future::done(res).boxed()
+	}
+
 	fn block_by_hash(&self, hash: RpcH256, include_txs: bool) -> BoxFuture<Option<RichBlock>, Error> {
 		future::done(self.block(BlockId::Hash(hash.into()), include_txs)).boxed()
 	}
@@ -495,30 +538,34 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM> Eth for EthClient<C, SN, S, M, EM> where
 		future::done(self.block(num.into(), include_txs)).boxed()
 	}
 
-	fn transaction_by_hash(&self, hash: RpcH256) -> Result<Option<Transaction>, Error> {
+	fn transaction_by_hash(&self, hash: RpcH256) -> BoxFuture<Option<Transaction>, Error> {
 		let hash: H256 = hash.into();
 		let block_number = self.client.chain_info().best_block_number;
-		Ok(self.transaction(TransactionId::Hash(hash))?.or_else(|| self.miner.transaction(block_number, &hash).map(|t| Transaction::from_pending(t, block_number, self.eip86_transition))))
+		let tx = self.transaction(TransactionId::Hash(hash)).map(|tx| tx.or_else(|| {
+			self.miner.transaction(block_number, &hash)
+				.map(|t| Transaction::from_pending(t, block_number, self.eip86_transition))
+		}));
+
+		future::done(tx).boxed()
 	}
 
-	fn transaction_by_block_hash_and_index(&self, hash: RpcH256, index: Index) -> Result<Option<Transaction>, Error> {
-		self.transaction(TransactionId::Location(BlockId::Hash(hash.into()), index.value()))
+	fn transaction_by_block_hash_and_index(&self, hash: RpcH256, index: Index) -> BoxFuture<Option<Transaction>, Error> {
+		future::done(self.transaction(TransactionId::Location(BlockId::Hash(hash.into()), index.value()))).boxed()
 	}
 
-	fn transaction_by_block_number_and_index(&self, num: BlockNumber, index: Index) -> Result<Option<Transaction>, Error> {
-		self.transaction(TransactionId::Location(num.into(), index.value()))
+	fn transaction_by_block_number_and_index(&self, num: BlockNumber, index: Index) -> BoxFuture<Option<Transaction>, Error> {
+		future::done(self.transaction(TransactionId::Location(num.into(), index.value()))).boxed()
 	}
 
-	fn transaction_receipt(&self, hash: RpcH256) -> Result<Option<Receipt>, Error> {
+	fn transaction_receipt(&self, hash: RpcH256) -> BoxFuture<Option<Receipt>, Error> {
 		let best_block = self.client.chain_info().best_block_number;
 		let hash: H256 = hash.into();
-		match (self.miner.pending_receipt(best_block, &hash), self.options.allow_pending_receipt_query) {
-			(Some(receipt), true) => Ok(Some(receipt.into())),
-			_ => {
-				let receipt = self.client.transaction_receipt(TransactionId::Hash(hash));
-				Ok(receipt.map(Into::into))
-			}
-		}
+		let receipt = match (self.miner.pending_receipt(best_block, &hash), self.options.allow_pending_receipt_query) {
+			(Some(receipt), true) => Some(receipt.into()),
+			_ => self.client.transaction_receipt(TransactionId::Hash(hash)).map(Into::into),
+		};
+
+		future::ok(receipt).boxed()
 	}
 
 	fn uncle_by_block_hash_and_index(&self, hash: RpcH256, index: Index) -> Result<Option<RichBlock>, Error> {
@@ -642,15 +689,16 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM> Eth for EthClient<C, SN, S, M, EM> where
 	}
 
 	fn call(&self, request: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<Bytes, Error> {
-		let request = CallRequest::into(request);
+		let request: CRequest = request.into();
+		let state_override = request.state_override.clone();
 		let signed = match fake_sign::sign_call(&self.client, &self.miner, request) {
 			Ok(signed) => signed,
 			Err(e) => return future::err(e).boxed(),
 		};
 
 		let result = match num.unwrap_or_default() {
-			BlockNumber::Pending => self.miner.call(&*self.client, &signed, Default::default()),
-			num => self.client.call(&signed, num.into(), Default::default()),
+			BlockNumber::Pending => self.miner.call(&*self.client, &signed, Default::default(), &state_override),
+			num => self.client.call(&signed, num.into(), Default::default(), &state_override),
 		};
 
 		future::done(result
@@ -660,12 +708,13 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM> Eth for EthClient<C, SN, S, M, EM> where
 	}
 
 	fn estimate_gas(&self, request: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<RpcU256, Error> {
-		let request = CallRequest::into(request);
+		let request: CRequest = request.into();
+		let state_override = request.state_override.clone();
 		let signed = match fake_sign::sign_call(&self.client, &self.miner, request) {
 			Ok(signed) => signed,
 			Err(e) => return future::err(e).boxed(),
 		};
-		future::done(self.client.estimate_gas(&signed, num.unwrap_or_default().into())
+		future::done(self.client.estimate_gas(&signed, num.unwrap_or_default().into(), &state_override)
 			.map(Into::into)
 			.map_err(errors::call)
 		).boxed()
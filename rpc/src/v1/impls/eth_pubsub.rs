@@ -29,11 +29,12 @@ use v1::helpers::{errors, limit_logs, Subscribers};
 use v1::helpers::light_fetch::LightFetch;
 use v1::metadata::Metadata;
 use v1::traits::EthPubSub;
-use v1::types::{pubsub, RichHeader, Log};
+use v1::types::{pubsub, RichHeader, Log, LocalTransactionStatus};
 
 use ethcore::encoded;
 use ethcore::filter::Filter as EthFilter;
 use ethcore::client::{BlockChainClient, ChainNotify, BlockId};
+use ethcore::miner::{GasPriceOracle, LocalTransactionStatus as MinerLocalTransactionStatus, LocalTransactionPropagation};
 use ethsync::LightSync;
 use light::cache::Cache;
 use light::on_demand::OnDemand;
@@ -43,11 +44,17 @@ use util::{RwLock, Mutex, H256, Bytes};
 
 type Client = Sink<pubsub::Result>;
 
+/// Last seen status of a local transaction.
+type LocalTransactionState = (MinerLocalTransactionStatus, Option<LocalTransactionPropagation>);
+
 /// Eth PubSub implementation.
 pub struct EthPubSubClient<C> {
 	handler: Arc<ChainNotificationHandler<C>>,
 	heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
 	logs_subscribers: Arc<RwLock<Subscribers<(Client, EthFilter)>>>,
+	local_transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	// light clients don't track local transactions.
+	local_transactions_supported: bool,
 }
 
 impl<C> EthPubSubClient<C> {
@@ -55,15 +62,20 @@ impl<C> EthPubSubClient<C> {
 	pub fn new(client: Arc<C>, remote: Remote) -> Self {
 		let heads_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 		let logs_subscribers = Arc::new(RwLock::new(Subscribers::default()));
+		let local_transactions_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 		EthPubSubClient {
 			handler: Arc::new(ChainNotificationHandler {
 				client,
 				remote,
 				heads_subscribers: heads_subscribers.clone(),
 				logs_subscribers: logs_subscribers.clone(),
+				local_transactions_subscribers: local_transactions_subscribers.clone(),
+				local_transactions: Mutex::new(BTreeMap::new()),
 			}),
 			heads_subscribers,
 			logs_subscribers,
+			local_transactions_subscribers,
+			local_transactions_supported: true,
 		}
 	}
 
@@ -73,6 +85,7 @@ impl<C> EthPubSubClient<C> {
 		let client = Self::new(client, remote);
 		*client.heads_subscribers.write() = Subscribers::new_test();
 		*client.logs_subscribers.write() = Subscribers::new_test();
+		*client.local_transactions_subscribers.write() = Subscribers::new_test();
 		client
 	}
 
@@ -89,15 +102,19 @@ impl EthPubSubClient<LightFetch> {
 		on_demand: Arc<OnDemand>,
 		sync: Arc<LightSync>,
 		cache: Arc<Mutex<Cache>>,
+		gas_price_oracle: GasPriceOracle,
 		remote: Remote,
 	) -> Self {
 		let fetch = LightFetch {
 			client,
 			on_demand,
 			sync,
-			cache
+			cache,
+			gas_price_oracle,
 		};
-		EthPubSubClient::new(Arc::new(fetch), remote)
+		let mut client = EthPubSubClient::new(Arc::new(fetch), remote);
+		client.local_transactions_supported = false;
+		client
 	}
 }
 
@@ -107,6 +124,8 @@ pub struct ChainNotificationHandler<C> {
 	remote: Remote,
 	heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
 	logs_subscribers: Arc<RwLock<Subscribers<(Client, EthFilter)>>>,
+	local_transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	local_transactions: Mutex<BTreeMap<H256, LocalTransactionState>>,
 }
 
 impl<C> ChainNotificationHandler<C> {
@@ -195,6 +214,50 @@ impl<C: LightClient> LightChainNotify for ChainNotificationHandler<C> {
 	}
 }
 
+impl<C: BlockChainClient> ChainNotificationHandler<C> {
+	/// Notifies about local transactions which changed status since the last block.
+	fn notify_local_transactions(&self) {
+		if self.local_transactions_subscribers.read().is_empty() {
+			return;
+		}
+
+		let mut propagation = self.client.local_transactions_propagation();
+		let current = self.client.local_transactions()
+			.into_iter()
+			.map(|(hash, status)| {
+				// waiting for a rebroadcast is not reported to subscribers.
+				let propagation = propagation.remove(&hash).map(|p| LocalTransactionPropagation { due: false, ..p });
+				(hash, (status, propagation))
+			})
+			.collect::<BTreeMap<_, _>>();
+
+		let changed = {
+			let mut last = self.local_transactions.lock();
+			let changed = current.iter()
+				.filter(|&(hash, state)| last.get(hash) != Some(state))
+				.map(|(hash, state)| (*hash, state.clone()))
+				.collect::<Vec<_>>();
+			*last = current;
+			changed
+		};
+		if changed.is_empty() {
+			return;
+		}
+
+		let block_number = self.client.chain_info().best_block_number;
+		let eip86_transition = self.client.eip86_transition();
+		let changed = changed.into_iter()
+			.map(|(hash, (status, propagation))| {
+				(hash.into(), LocalTransactionStatus::from(status, propagation, block_number, eip86_transition))
+			})
+			.collect::<BTreeMap<_, _>>();
+
+		for subscriber in self.local_transactions_subscribers.read().values() {
+			Self::notify(&self.remote, subscriber, pubsub::Result::LocalTransactions(changed.clone()));
+		}
+	}
+}
+
 impl<C: BlockChainClient> ChainNotify for ChainNotificationHandler<C> {
 	fn new_blocks(
 		&self,
@@ -232,6 +295,9 @@ impl<C: BlockChainClient> ChainNotify for ChainNotificationHandler<C> {
 				log
 			}).collect()).boxed()
 		});
+
+		// Local transactions
+		self.notify_local_transactions();
 	}
 }
 
@@ -252,6 +318,12 @@ impl<C: Send + Sync + 'static> EthPubSub for EthPubSubClient<C> {
 			(pubsub::Kind::Logs, Some(pubsub::Params::Logs(filter))) => {
 				self.logs_subscribers.write().push(subscriber, filter.into());
 			},
+			(pubsub::Kind::LocalTransactions, None) if self.local_transactions_supported => {
+				self.local_transactions_subscribers.write().push(subscriber)
+			},
+			(pubsub::Kind::LocalTransactions, None) => {
+				let _ = subscriber.reject(errors::unimplemented(Some("Local transactions are not tracked by light clients.".into())));
+			},
 			_ => {
 				let _ = subscriber.reject(errors::unimplemented(None));
 			},
@@ -261,7 +333,8 @@ impl<C: Send + Sync + 'static> EthPubSub for EthPubSubClient<C> {
 	fn unsubscribe(&self, id: SubscriptionId) -> BoxFuture<bool, Error> {
 		let res = self.heads_subscribers.write().remove(&id).is_some();
 		let res2 = self.logs_subscribers.write().remove(&id).is_some();
+		let res3 = self.local_transactions_subscribers.write().remove(&id).is_some();
 
-		future::ok(res || res2).boxed()
+		future::ok(res || res2 || res3).boxed()
 	}
 }
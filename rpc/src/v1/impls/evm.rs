@@ -0,0 +1,67 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ganache-compatible development chain rpc implementation.
+
+use std::sync::Arc;
+
+use ethcore::client::DevBlockChainClient;
+use util::U256 as EthU256;
+
+use jsonrpc_core::Error;
+use v1::traits::Evm;
+use v1::helpers::errors;
+use v1::types::U256;
+
+/// Development chain rpc implementation.
+pub struct EvmClient<C> {
+	client: Arc<C>,
+}
+
+impl<C> EvmClient<C> {
+	/// Creates new EvmClient.
+	pub fn new(client: &Arc<C>) -> Self {
+		EvmClient {
+			client: client.clone(),
+		}
+	}
+}
+
+impl<C> Evm for EvmClient<C> where C: DevBlockChainClient + 'static {
+	fn mine(&self) -> Result<U256, Error> {
+		self.client.mine_block()
+			.map(|_| U256::default())
+			.map_err(errors::dev_chain)
+	}
+
+	fn increase_time(&self, seconds: u64) -> Result<u64, Error> {
+		self.client.increase_time(seconds).map_err(errors::dev_chain)
+	}
+
+	fn snapshot(&self) -> Result<U256, Error> {
+		self.client.snapshot()
+			.map(|id| (id as u64).into())
+			.map_err(errors::dev_chain)
+	}
+
+	fn revert(&self, id: U256) -> Result<bool, Error> {
+		let id: EthU256 = id.into();
+		if id > EthU256::from(usize::max_value() as u64) {
+			return Ok(false);
+		}
+		self.client.revert(id.low_u64() as usize).map_err(errors::dev_chain)
+	}
+}
@@ -35,6 +35,7 @@ use ethcore::encoded;
 use ethcore::executed::{Executed, ExecutionError};
 use ethcore::ids::BlockId;
 use ethcore::filter::Filter as EthcoreFilter;
+use ethcore::miner::GasPriceOracle;
 use ethcore::transaction::{Action, SignedTransaction, Transaction as EthTransaction};
 use ethsync::LightSync;
 use rlp::UntrustedRlp;
@@ -52,7 +53,7 @@ use v1::helpers::light_fetch::LightFetch;
 use v1::traits::Eth;
 use v1::types::{
 	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
-	Transaction, CallRequest, Index, Filter, Log, Receipt, Work,
+	Transaction, CallRequest, Index, Filter, Log, Receipt, Work, EthAccount,
 	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U256 as RpcU256,
 };
 use v1::metadata::Metadata;
@@ -70,6 +71,7 @@ pub struct EthClient {
 	accounts: Arc<AccountProvider>,
 	cache: Arc<Mutex<LightDataCache>>,
 	polls: Mutex<PollManager<PollFilter>>,
+	gas_price_oracle: GasPriceOracle,
 }
 
 impl Clone for EthClient {
@@ -83,6 +85,7 @@ impl Clone for EthClient {
 			accounts: self.accounts.clone(),
 			cache: self.cache.clone(),
 			polls: Mutex::new(PollManager::new()),
+			gas_price_oracle: self.gas_price_oracle,
 		}
 	}
 }
@@ -98,6 +101,7 @@ impl EthClient {
 		transaction_queue: Arc<RwLock<TransactionQueue>>,
 		accounts: Arc<AccountProvider>,
 		cache: Arc<Mutex<LightDataCache>>,
+		gas_price_oracle: GasPriceOracle,
 	) -> Self {
 		EthClient {
 			sync: sync,
@@ -107,6 +111,7 @@ impl EthClient {
 			accounts: accounts,
 			cache: cache,
 			polls: Mutex::new(PollManager::new()),
+			gas_price_oracle: gas_price_oracle,
 		}
 	}
 
@@ -117,6 +122,7 @@ impl EthClient {
 			on_demand: self.on_demand.clone(),
 			sync: self.sync.clone(),
 			cache: self.cache.clone(),
+			gas_price_oracle: self.gas_price_oracle,
 		}
 	}
 
@@ -248,7 +254,7 @@ impl Eth for EthClient {
 
 	fn gas_price(&self) -> Result<RpcU256, Error> {
 		Ok(self.cache.lock().gas_price_corpus()
-			.and_then(|c| c.median().cloned())
+			.and_then(|c| self.gas_price_oracle.gas_price(&c))
 			.map(RpcU256::from)
 			.unwrap_or_else(Default::default))
 	}
@@ -274,8 +280,11 @@ impl Eth for EthClient {
 			.map(|acc| acc.map_or(0.into(), |a| a.balance).into()).boxed()
 	}
 
-	fn storage_at(&self, _address: RpcH160, _key: RpcU256, _num: Trailing<BlockNumber>) -> BoxFuture<RpcH256, Error> {
-		future::err(errors::unimplemented(None)).boxed()
+	fn storage_at(&self, address: RpcH160, key: RpcU256, num: Trailing<BlockNumber>) -> BoxFuture<RpcH256, Error> {
+		let key: U256 = key.into();
+		self.fetcher().storage(address.into(), key.into(), num.unwrap_or_default().into())
+			.map(Into::into)
+			.boxed()
 	}
 
 	fn block_by_hash(&self, hash: RpcH256, include_txs: bool) -> BoxFuture<Option<RichBlock>, Error> {
@@ -359,6 +368,10 @@ impl Eth for EthClient {
 		future::err(errors::unimplemented(None)).boxed()
 	}
 
+	fn proof(&self, _address: RpcH160, _keys: Vec<RpcH256>, _num: Trailing<BlockNumber>) -> BoxFuture<EthAccount, Error> {
+		future::err(errors::light_unimplemented(None)).boxed()
+	}
+
 	fn send_raw_transaction(&self, raw: Bytes) -> Result<RpcH256, Error> {
 		let best_header = self.client.best_block_header().decode();
 
@@ -384,7 +397,11 @@ impl Eth for EthClient {
 	}
 
 	fn call(&self, req: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<Bytes, Error> {
-		self.fetcher().proved_execution(req, num).and_then(|res| {
+		if req.state_override.as_ref().map_or(false, |o| !o.is_empty()) {
+			return future::err(errors::light_unimplemented(Some("State overrides are not supported".into()))).boxed();
+		}
+
+		self.fetcher().proved_execution(req, num, Default::default()).and_then(|res| {
 			match res {
 				Ok(exec) => Ok(exec.output.into()),
 				Err(e) => Err(errors::execution(e)),
@@ -393,8 +410,12 @@ impl Eth for EthClient {
 	}
 
 	fn estimate_gas(&self, req: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<RpcU256, Error> {
+		if req.state_override.as_ref().map_or(false, |o| !o.is_empty()) {
+			return future::err(errors::light_unimplemented(Some("State overrides are not supported".into()))).boxed();
+		}
+
 		// TODO: binary chop for more accurate estimates.
-		self.fetcher().proved_execution(req, num).and_then(|res| {
+		self.fetcher().proved_execution(req, num, Default::default()).and_then(|res| {
 			match res {
 				Ok(exec) => Ok((exec.refunded + exec.gas_used).into()),
 				Err(e) => Err(errors::execution(e)),
@@ -402,20 +423,48 @@ impl Eth for EthClient {
 		}).boxed()
 	}
 
-	fn transaction_by_hash(&self, hash: RpcH256) -> Result<Option<Transaction>, Error> {
-		Err(errors::unimplemented(None))
+	fn transaction_by_hash(&self, hash: RpcH256) -> BoxFuture<Option<Transaction>, Error> {
+		let hash = hash.into();
+		let eip86_transition = self.client.eip86_transition();
+
+		// transactions still in the local queue aren't known to the network yet.
+		if let Some(tx) = self.transaction_queue.read().transaction(&hash) {
+			let best_block = self.client.chain_info().best_block_number;
+			return future::ok(Some(Transaction::from_signed(tx, best_block, eip86_transition))).boxed();
+		}
+
+		self.fetcher().transaction_by_hash(hash)
+			.map(move |tx| tx.map(|tx| Transaction::from_localized(tx, eip86_transition)))
+			.boxed()
 	}
 
-	fn transaction_by_block_hash_and_index(&self, hash: RpcH256, idx: Index) -> Result<Option<Transaction>, Error> {
-		Err(errors::unimplemented(None))
+	fn transaction_by_block_hash_and_index(&self, hash: RpcH256, idx: Index) -> BoxFuture<Option<Transaction>, Error> {
+		let eip86_transition = self.client.eip86_transition();
+		self.fetcher().block(BlockId::Hash(hash.into()))
+			.map(move |block| block.view().localized_transaction_at(idx.value())
+				.map(|tx| Transaction::from_localized(tx, eip86_transition)))
+			.boxed()
 	}
 
-	fn transaction_by_block_number_and_index(&self, num: BlockNumber, idx: Index) -> Result<Option<Transaction>, Error> {
-		Err(errors::unimplemented(None))
+	fn transaction_by_block_number_and_index(&self, num: BlockNumber, idx: Index) -> BoxFuture<Option<Transaction>, Error> {
+		let eip86_transition = self.client.eip86_transition();
+		self.fetcher().block(num.into())
+			.map(move |block| block.view().localized_transaction_at(idx.value())
+				.map(|tx| Transaction::from_localized(tx, eip86_transition)))
+			.boxed()
 	}
 
-	fn transaction_receipt(&self, hash: RpcH256) -> Result<Option<Receipt>, Error> {
-		Err(errors::unimplemented(None))
+	fn transaction_receipt(&self, hash: RpcH256) -> BoxFuture<Option<Receipt>, Error> {
+		let hash = hash.into();
+
+		// transactions still in the local queue have no receipt yet.
+		if self.transaction_queue.read().transaction(&hash).is_some() {
+			return future::ok(None).boxed();
+		}
+
+		self.fetcher().transaction_receipt(hash)
+			.map(|receipt| receipt.map(Into::into))
+			.boxed()
 	}
 
 	fn uncle_by_block_hash_and_index(&self, hash: RpcH256, idx: Index) -> Result<Option<RichBlock>, Error> {
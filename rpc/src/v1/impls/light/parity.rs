@@ -44,7 +44,7 @@ use v1::types::{
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, Header, RichHeader,
+	AccountInfo, HwAccountInfo, Header, RichHeader, StratumWorker,
 };
 
 /// Parity implementation for light client.
@@ -90,6 +90,7 @@ impl ParityClient {
 			on_demand: self.light_dispatch.on_demand.clone(),
 			sync: self.light_dispatch.sync.clone(),
 			cache: self.light_dispatch.cache.clone(),
+			gas_price_oracle: self.light_dispatch.gas_price_oracle,
 		}
 	}
 }
@@ -282,7 +283,7 @@ impl Parity for ParityClient {
 		let txq = self.light_dispatch.transaction_queue.read();
 
 		for pending in txq.ready_transactions(best_num, best_tm) {
-			map.insert(pending.hash().into(), LocalTransactionStatus::Pending);
+			map.insert(pending.hash().into(), LocalTransactionStatus::Pending { stuck: false, rebroadcasts: 0 });
 		}
 
 		for future in txq.future_transactions(best_num, best_tm) {
@@ -389,4 +390,8 @@ impl Parity for ParityClient {
 	fn ipfs_cid(&self, content: Bytes) -> Result<String, Error> {
 		ipfs::cid(content)
 	}
+
+	fn stratum_workers(&self) -> Result<BTreeMap<String, StratumWorker>, Error> {
+		Err(errors::light_unimplemented(None))
+	}
 }
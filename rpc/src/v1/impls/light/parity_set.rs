@@ -74,6 +74,14 @@ impl<F: Fetch> ParitySet for ParitySetClient<F> {
 		Err(errors::light_unimplemented(None))
 	}
 
+	fn clique_propose(&self, _address: H160, _authorize: bool) -> Result<bool, Error> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn clique_discard(&self, _address: H160) -> Result<bool, Error> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn set_transactions_limit(&self, _limit: usize) -> Result<bool, Error> {
 		Err(errors::light_unimplemented(None))
 	}
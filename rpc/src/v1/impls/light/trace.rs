@@ -16,15 +16,47 @@
 
 //! Traces api implementation.
 
+use std::sync::Arc;
+
+use futures::{future, Future, BoxFuture};
 use jsonrpc_core::Error;
 use jsonrpc_macros::Trailing;
+use v1::impls::traces::{to_call_analytics, decode_raw_transaction};
 use v1::traits::Traces;
 use v1::helpers::errors;
+use v1::helpers::dispatch::LightDispatcher;
+use v1::helpers::light_fetch::{LightFetch, ExecutionResult};
 use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, H256};
 
 /// Traces api implementation.
-// TODO: all calling APIs should be possible w. proved remote TX execution.
-pub struct TracesClient;
+// TODO: replaying transactions should be possible w. proved remote TX execution.
+pub struct TracesClient {
+	light_dispatch: Arc<LightDispatcher>,
+}
+
+impl TracesClient {
+	/// Creates new `TracesClient`.
+	pub fn new(light_dispatch: Arc<LightDispatcher>) -> Self {
+		TracesClient {
+			light_dispatch: light_dispatch,
+		}
+	}
+
+	/// Create a light blockchain data fetcher.
+	fn fetcher(&self) -> LightFetch {
+		LightFetch {
+			client: self.light_dispatch.client.clone(),
+			on_demand: self.light_dispatch.on_demand.clone(),
+			sync: self.light_dispatch.sync.clone(),
+			cache: self.light_dispatch.cache.clone(),
+			gas_price_oracle: self.light_dispatch.gas_price_oracle,
+		}
+	}
+}
+
+fn to_trace_results(res: ExecutionResult) -> Result<TraceResults, Error> {
+	res.map(TraceResults::from).map_err(errors::execution)
+}
 
 impl Traces for TracesClient {
 	fn filter(&self, _filter: TraceFilter) -> Result<Option<Vec<LocalizedTrace>>, Error> {
@@ -43,15 +75,36 @@ impl Traces for TracesClient {
 		Err(errors::light_unimplemented(None))
 	}
 
-	fn call(&self, _request: CallRequest, _flags: Vec<String>, _block: Trailing<BlockNumber>) -> Result<TraceResults, Error> {
-		Err(errors::light_unimplemented(None))
+	fn call(&self, request: CallRequest, flags: Vec<String>, block: Trailing<BlockNumber>) -> BoxFuture<TraceResults, Error> {
+		if request.state_override.as_ref().map_or(false, |o| !o.is_empty()) {
+			return future::err(errors::light_unimplemented(Some("State overrides are not supported".into()))).boxed();
+		}
+
+		self.fetcher().proved_execution(request, block, to_call_analytics(flags))
+			.and_then(to_trace_results)
+			.boxed()
 	}
 
-	fn raw_transaction(&self, _raw_transaction: Bytes, _flags: Vec<String>, _block: Trailing<BlockNumber>) -> Result<TraceResults, Error> {
+	fn call_many(&self, _requests: Vec<(CallRequest, Vec<String>)>, _block: Trailing<BlockNumber>) -> Result<Vec<TraceResults>, Error> {
 		Err(errors::light_unimplemented(None))
 	}
 
+	fn raw_transaction(&self, raw_transaction: Bytes, flags: Vec<String>, block: Trailing<BlockNumber>) -> BoxFuture<TraceResults, Error> {
+		let signed = match decode_raw_transaction(raw_transaction) {
+			Ok(signed) => signed,
+			Err(e) => return future::err(e).boxed(),
+		};
+
+		self.fetcher().proved_transaction_execution(signed, block, to_call_analytics(flags))
+			.and_then(to_trace_results)
+			.boxed()
+	}
+
 	fn replay_transaction(&self, _transaction_hash: H256, _flags: Vec<String>) -> Result<TraceResults, Error> {
 		Err(errors::light_unimplemented(None))
 	}
+
+	fn replay_block_transactions(&self, _block_number: BlockNumber, _flags: Vec<String>) -> Result<Vec<TraceResults>, Error> {
+		Err(errors::light_unimplemented(None))
+	}
 }
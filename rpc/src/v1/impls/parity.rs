@@ -29,7 +29,7 @@ use ethkey::{Brain, Generator};
 use ethstore::random_phrase;
 use ethsync::{SyncProvider, ManageNetwork};
 use ethcore::ids::BlockId;
-use ethcore::miner::MinerService;
+use ethcore::miner::{MinerService, Stratum};
 use ethcore::client::{MiningBlockChainClient};
 use ethcore::mode::Mode;
 use ethcore::account_provider::AccountProvider;
@@ -48,7 +48,7 @@ use v1::types::{
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, RichHeader
+	AccountInfo, HwAccountInfo, RichHeader, StratumWorker,
 };
 
 /// Parity implementation.
@@ -69,6 +69,7 @@ pub struct ParityClient<C, M, S: ?Sized, U> where
 	signer: Option<Arc<SignerService>>,
 	dapps_address: Option<(String, u16)>,
 	ws_address: Option<(String, u16)>,
+	stratum: Option<Stratum>,
 	eip86_transition: u64,
 }
 
@@ -91,6 +92,7 @@ impl<C, M, S: ?Sized, U> ParityClient<C, M, S, U> where
 		signer: Option<Arc<SignerService>>,
 		dapps_address: Option<(String, u16)>,
 		ws_address: Option<(String, u16)>,
+		stratum: Option<Stratum>,
 	) -> Self {
 		ParityClient {
 			client: client.clone(),
@@ -104,6 +106,7 @@ impl<C, M, S: ?Sized, U> ParityClient<C, M, S, U> where
 			signer: signer,
 			dapps_address: dapps_address,
 			ws_address: ws_address,
+			stratum: stratum,
 			eip86_transition: client.eip86_transition(),
 		}
 	}
@@ -313,10 +316,14 @@ impl<C, M, S: ?Sized, U> Parity for ParityClient<C, M, S, U> where
 		}
 
 		let transactions = self.miner.local_transactions();
+		let mut propagation = self.miner.local_transactions_propagation();
 		let block_number = self.client.chain_info().best_block_number;
 		Ok(transactions
 		   .into_iter()
-		   .map(|(hash, status)| (hash.into(), LocalTransactionStatus::from(status, block_number, self.eip86_transition)))
+		   .map(|(hash, status)| {
+			   let propagation = propagation.remove(&hash);
+			   (hash.into(), LocalTransactionStatus::from(status, propagation, block_number, self.eip86_transition))
+		   })
 		   .collect()
 		)
 	}
@@ -409,4 +416,9 @@ impl<C, M, S: ?Sized, U> Parity for ParityClient<C, M, S, U> where
 	fn ipfs_cid(&self, content: Bytes) -> Result<String, Error> {
 		ipfs::cid(content)
 	}
+
+	fn stratum_workers(&self) -> Result<BTreeMap<String, StratumWorker>, Error> {
+		let stratum = self.stratum.as_ref().ok_or_else(errors::stratum_disabled)?;
+		Ok(stratum.worker_stats().into_iter().map(|(worker, stats)| (worker, stats.into())).collect())
+	}
 }
@@ -105,6 +105,16 @@ impl<C, M, U, F> ParitySet for ParitySetClient<C, M, U, F> where
 		Ok(true)
 	}
 
+	fn clique_propose(&self, address: H160, authorize: bool) -> Result<bool, Error> {
+		self.miner.propose_signer(address.into(), authorize).map_err(errors::engine)?;
+		Ok(true)
+	}
+
+	fn clique_discard(&self, address: H160) -> Result<bool, Error> {
+		self.miner.discard_signer_proposal(&address.into()).map_err(errors::engine)?;
+		Ok(true)
+	}
+
 	fn set_transactions_limit(&self, limit: usize) -> Result<bool, Error> {
 		self.miner.set_transactions_limit(limit);
 		Ok(true)
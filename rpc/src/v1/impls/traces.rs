@@ -23,13 +23,15 @@ use ethcore::client::{MiningBlockChainClient, CallAnalytics, TransactionId, Trac
 use ethcore::miner::MinerService;
 use ethcore::transaction::SignedTransaction;
 
+use futures::{future, Future, BoxFuture};
 use jsonrpc_core::Error;
 use jsonrpc_macros::Trailing;
 use v1::traits::Traces;
-use v1::helpers::{errors, fake_sign};
+use v1::helpers::{errors, fake_sign, CallRequest as CRequest};
 use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, H256};
 
-fn to_call_analytics(flags: Vec<String>) -> CallAnalytics {
+/// Convert the tracing flags given over RPC into call analytics.
+pub fn to_call_analytics(flags: Vec<String>) -> CallAnalytics {
 	CallAnalytics {
 		transaction_tracing: flags.contains(&("trace".to_owned())),
 		vm_tracing: flags.contains(&("vmTrace".to_owned())),
@@ -37,6 +39,12 @@ fn to_call_analytics(flags: Vec<String>) -> CallAnalytics {
 	}
 }
 
+/// Decode and check the signature of a raw transaction given over RPC.
+pub fn decode_raw_transaction(raw_transaction: Bytes) -> Result<SignedTransaction, Error> {
+	let tx = UntrustedRlp::new(&raw_transaction.into_vec()).as_val().map_err(|e| errors::invalid_params("Transaction is not valid RLP", e))?;
+	SignedTransaction::new(tx).map_err(errors::transaction)
+}
+
 /// Traces api implementation.
 pub struct TracesClient<C, M> {
 	client: Arc<C>,
@@ -79,26 +87,53 @@ impl<C, M> Traces for TracesClient<C, M> where C: MiningBlockChainClient + 'stat
 			.map(LocalizedTrace::from))
 	}
 
-	fn call(&self, request: CallRequest, flags: Vec<String>, block: Trailing<BlockNumber>) -> Result<TraceResults, Error> {
+	fn call(&self, request: CallRequest, flags: Vec<String>, block: Trailing<BlockNumber>) -> BoxFuture<TraceResults, Error> {
 		let block = block.unwrap_or_default();
 
-		let request = CallRequest::into(request);
-		let signed = fake_sign::sign_call(&self.client, &self.miner, request)?;
+		let request: CRequest = request.into();
+		let state_override = request.state_override.clone();
+		let signed = match fake_sign::sign_call(&self.client, &self.miner, request) {
+			Ok(signed) => signed,
+			Err(e) => return future::err(e).boxed(),
+		};
 
-		self.client.call(&signed, block.into(), to_call_analytics(flags))
+		future::done(self.client.call(&signed, block.into(), to_call_analytics(flags), &state_override)
 			.map(TraceResults::from)
 			.map_err(errors::call)
+		).boxed()
+	}
+
+	fn call_many(&self, requests: Vec<(CallRequest, Vec<String>)>, block: Trailing<BlockNumber>) -> Result<Vec<TraceResults>, Error> {
+		let block = block.unwrap_or_default();
+
+		let requests = requests.into_iter()
+			.map(|(request, flags)| {
+				let request: CRequest = request.into();
+				if !request.state_override.is_empty() {
+					return Err(errors::unimplemented(Some("State overrides are not supported by trace_callMany".into())));
+				}
+				let signed = fake_sign::sign_call(&self.client, &self.miner, request)?;
+				Ok((signed, to_call_analytics(flags)))
+			})
+			.collect::<Result<Vec<_>, Error>>()?;
+
+		self.client.call_many(requests, block.into())
+			.map(|results| results.into_iter().map(TraceResults::from).collect())
+			.map_err(errors::call)
 	}
 
-	fn raw_transaction(&self, raw_transaction: Bytes, flags: Vec<String>, block: Trailing<BlockNumber>) -> Result<TraceResults, Error> {
+	fn raw_transaction(&self, raw_transaction: Bytes, flags: Vec<String>, block: Trailing<BlockNumber>) -> BoxFuture<TraceResults, Error> {
 		let block = block.unwrap_or_default();
 
-		let tx = UntrustedRlp::new(&raw_transaction.into_vec()).as_val().map_err(|e| errors::invalid_params("Transaction is not valid RLP", e))?;
-		let signed = SignedTransaction::new(tx).map_err(errors::transaction)?;
+		let signed = match decode_raw_transaction(raw_transaction) {
+			Ok(signed) => signed,
+			Err(e) => return future::err(e).boxed(),
+		};
 
-		self.client.call(&signed, block.into(), to_call_analytics(flags))
+		future::done(self.client.call(&signed, block.into(), to_call_analytics(flags), &Default::default())
 			.map(TraceResults::from)
 			.map_err(errors::call)
+		).boxed()
 	}
 
 	fn replay_transaction(&self, transaction_hash: H256, flags: Vec<String>) -> Result<TraceResults, Error> {
@@ -106,4 +141,10 @@ impl<C, M> Traces for TracesClient<C, M> where C: MiningBlockChainClient + 'stat
 			.map(TraceResults::from)
 			.map_err(errors::call)
 	}
+
+	fn replay_block_transactions(&self, block_number: BlockNumber, flags: Vec<String>) -> Result<Vec<TraceResults>, Error> {
+		self.client.replay_block_transactions(block_number.into(), to_call_analytics(flags))
+			.map(|results| results.into_iter().map(TraceResults::from).collect())
+			.map_err(errors::call)
+	}
 }
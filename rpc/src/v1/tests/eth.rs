@@ -19,6 +19,8 @@ use std::env;
 use std::sync::Arc;
 use std::time::Duration;
 
+use serde_json;
+
 use ethcore::client::{BlockChainClient, Client, ClientConfig};
 use ethcore::ids::BlockId;
 use ethcore::spec::{Genesis, Spec};
@@ -70,6 +72,10 @@ fn miner_service(spec: &Spec, accounts: Arc<AccountProvider>) -> Arc<Miner> {
 			work_queue_size: 50,
 			enable_resubmission: true,
 			refuse_service_transactions: false,
+			tx_queue_min_gas_price_bump: None,
+			tx_queue_per_sender: usize::max_value(),
+			tx_queue_no_local_limit: false,
+			tx_rebroadcast_period: 10,
 		},
 		GasPricer::new_fixed(20_000_000_000u64.into()),
 		&spec,
@@ -195,6 +201,26 @@ fn eth_get_balance() {
 	assert_eq!(tester.handler.handle_request_sync(req_new_acc).unwrap(), res_new_acc);
 }
 
+#[test]
+fn eth_get_proof() {
+	let chain = extract_chain!("BlockchainTests/bcWalletTest", "wallet2outOf3txs");
+	let tester = EthTester::from_chain(&chain);
+
+	let req_proof = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_getProof",
+		"params": ["0xaaaf5374fce5edbc8e2a8697c15331677e6ebaaa", ["0x0000000000000000000000000000000000000000000000000000000000000000"], "latest"],
+		"id": 1
+	}"#;
+
+	let res: serde_json::Value = serde_json::from_str(&tester.handler.handle_request_sync(req_proof).unwrap()).unwrap();
+	let account = &res["result"];
+	assert_eq!(account["balance"], "0x9");
+	assert!(!account["accountProof"].as_array().unwrap().is_empty());
+	assert_eq!(account["storageProof"].as_array().unwrap().len(), 1);
+	assert_eq!(account["storageProof"][0]["value"], "0x0");
+}
+
 #[test]
 fn eth_block_number() {
 	let chain = extract_chain!("BlockchainTests/bcRPC_API_Test");
@@ -20,13 +20,14 @@ use std::collections::hash_map::Entry;
 use util::{Address, H256, Bytes, U256};
 use util::standard::*;
 use ethcore::error::{Error, CallError};
-use ethcore::client::{MiningBlockChainClient, Executed, CallAnalytics};
+use ethcore::client::{MiningBlockChainClient, Executed, CallAnalytics, StateOverride};
 use ethcore::block::{ClosedBlock, IsBlock};
 use ethcore::header::BlockNumber;
 use ethcore::transaction::{UnverifiedTransaction, SignedTransaction, PendingTransaction};
 use ethcore::receipt::{Receipt, RichReceipt};
-use ethcore::miner::{MinerService, MinerStatus, TransactionImportResult, LocalTransactionStatus};
+use ethcore::miner::{MinerService, MinerStatus, TransactionImportResult, LocalTransactionStatus, LocalTransactionPropagation};
 use ethcore::account_provider::SignError as AccountError;
+use ethcore::engines::EngineError;
 
 /// Test miner service.
 pub struct TestMinerService {
@@ -38,12 +39,16 @@ pub struct TestMinerService {
 	pub pending_transactions: Mutex<HashMap<H256, SignedTransaction>>,
 	/// Pre-existed local transactions
 	pub local_transactions: Mutex<BTreeMap<H256, LocalTransactionStatus>>,
+	/// Pre-existed propagation details of local transactions
+	pub local_transactions_propagation: Mutex<BTreeMap<H256, LocalTransactionPropagation>>,
 	/// Pre-existed pending receipts
 	pub pending_receipts: Mutex<BTreeMap<H256, Receipt>>,
 	/// Last nonces.
 	pub last_nonces: RwLock<HashMap<Address, U256>>,
 	/// Password held by Engine.
 	pub password: RwLock<String>,
+	/// Signer votes cast by the Engine.
+	pub signer_proposals: RwLock<HashMap<Address, bool>>,
 
 	min_gas_price: RwLock<U256>,
 	gas_range_target: RwLock<(U256, U256)>,
@@ -60,12 +65,14 @@ impl Default for TestMinerService {
 			latest_closed_block: Mutex::new(None),
 			pending_transactions: Mutex::new(HashMap::new()),
 			local_transactions: Mutex::new(BTreeMap::new()),
+			local_transactions_propagation: Mutex::new(BTreeMap::new()),
 			pending_receipts: Mutex::new(BTreeMap::new()),
 			last_nonces: RwLock::new(HashMap::new()),
 			min_gas_price: RwLock::new(U256::from(20_000_000)),
 			gas_range_target: RwLock::new((U256::from(12345), U256::from(54321))),
 			author: RwLock::new(Address::zero()),
 			password: RwLock::new(String::new()),
+			signer_proposals: RwLock::new(HashMap::new()),
 			extra_data: RwLock::new(vec![1, 2, 3, 4]),
 			limit: RwLock::new(1024),
 			tx_gas_limit: RwLock::new(!U256::zero()),
@@ -110,6 +117,16 @@ impl MinerService for TestMinerService {
 		Ok(())
 	}
 
+	fn propose_signer(&self, address: Address, authorize: bool) -> Result<(), EngineError> {
+		self.signer_proposals.write().insert(address, authorize);
+		Ok(())
+	}
+
+	fn discard_signer_proposal(&self, address: &Address) -> Result<(), EngineError> {
+		self.signer_proposals.write().remove(address);
+		Ok(())
+	}
+
 	fn set_extra_data(&self, extra_data: Bytes) {
 		*self.extra_data.write() = extra_data;
 	}
@@ -238,6 +255,14 @@ impl MinerService for TestMinerService {
 		self.local_transactions.lock().iter().map(|(hash, stats)| (*hash, stats.clone())).collect()
 	}
 
+	fn local_transactions_propagation(&self) -> BTreeMap<H256, LocalTransactionPropagation> {
+		self.local_transactions_propagation.lock().clone()
+	}
+
+	fn mark_local_transactions_rebroadcast(&self, _hashes: &[H256], _best_block: BlockNumber) {
+		unimplemented!();
+	}
+
 	fn ready_transactions(&self, _best_block: BlockNumber, _best_timestamp: u64) -> Vec<PendingTransaction> {
 		self.pending_transactions.lock().values().cloned().map(Into::into).collect()
 	}
@@ -288,7 +313,7 @@ impl MinerService for TestMinerService {
 			.unwrap_or(Some(U256::default()))
 	}
 
-	fn call(&self, _chain: &MiningBlockChainClient, _t: &SignedTransaction, _analytics: CallAnalytics) -> Result<Executed, CallError> {
+	fn call(&self, _chain: &MiningBlockChainClient, _t: &SignedTransaction, _analytics: CallAnalytics, _state_override: &StateOverride) -> Result<Executed, CallError> {
 		unimplemented!();
 	}
 
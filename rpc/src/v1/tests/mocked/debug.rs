@@ -0,0 +1,107 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+use std::sync::Arc;
+
+use ethcore::executed::{Executed, CallError};
+use ethcore::trace::{VMTrace, VMOperation, VMExecutedOperation};
+use ethcore::client::TestBlockChainClient;
+
+use jsonrpc_core::IoHandler;
+use v1::tests::helpers::{TestMinerService};
+use v1::{Debug, DebugClient};
+
+struct Tester {
+	client: Arc<TestBlockChainClient>,
+	_miner: Arc<TestMinerService>,
+	io: IoHandler,
+}
+
+fn io() -> Tester {
+	let client = Arc::new(TestBlockChainClient::new());
+	*client.execution_result.write() = Some(Ok(Executed {
+		exception: None,
+		gas: 30_000.into(),
+		gas_used: 21_006.into(),
+		refunded: 0.into(),
+		cumulative_gas_used: 21_006.into(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![],
+		trace: vec![],
+		vm_trace: Some(VMTrace {
+			parent_step: 0,
+			code: vec![0x60, 0x01, 0x60, 0x02, 0x01, 0x00],
+			operations: vec![(0, 0x60, 3, 1), (2, 0x60, 3, 2), (4, 0x01, 3, 3)].into_iter()
+				.map(|(pc, instruction, gas_cost, push): (usize, u8, u64, u64)| VMOperation {
+					pc: pc,
+					instruction: instruction,
+					gas_cost: gas_cost.into(),
+					executed: Some(VMExecutedOperation {
+						gas_used: (100 - 3 * (pc as u64 / 2 + 1)).into(),
+						stack_push: vec![push.into()],
+						mem_diff: None,
+						store_diff: None,
+					}),
+				})
+				.collect(),
+			subs: vec![],
+		}),
+		state_diff: None,
+	}));
+	let miner = Arc::new(TestMinerService::default());
+	let debug = DebugClient::new(&client, &miner);
+	let mut io = IoHandler::new();
+	io.extend_with(debug.to_delegate());
+
+	Tester {
+		client: client,
+		_miner: miner,
+		io: io,
+	}
+}
+
+#[test]
+fn rpc_debug_trace_transaction() {
+	let tester = io();
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceTransaction","params":["0x0000000000000000000000000000000000000000000000000000000000000005", {"disableMemory": true, "disableStorage": true}],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"failed":false,"gas":21006,"returnValue":"","structLogs":[{"depth":1,"gas":100,"gasCost":3,"op":"PUSH1","pc":0,"stack":[]},{"depth":1,"gas":97,"gasCost":3,"op":"PUSH1","pc":2,"stack":["0000000000000000000000000000000000000000000000000000000000000001"]},{"depth":1,"gas":94,"gasCost":3,"op":"ADD","pc":4,"stack":["0000000000000000000000000000000000000000000000000000000000000001","0000000000000000000000000000000000000000000000000000000000000002"]}]},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_debug_trace_call_limit() {
+	let tester = io();
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceCall","params":[{}, "latest", {"disableStack": true, "disableMemory": true, "disableStorage": true, "limit": 1}],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"failed":false,"gas":21006,"returnValue":"","structLogs":[{"depth":1,"gas":100,"gasCost":3,"op":"PUSH1","pc":0}]},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_debug_trace_call_state_pruned() {
+	let tester = io();
+	*tester.client.execution_result.write() = Some(Err(CallError::StatePruned));
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceCall","params":[{}, "latest"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not supported because your node is running with state pruning. Run with --pruning=archive."},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
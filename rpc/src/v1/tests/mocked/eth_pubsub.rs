@@ -22,7 +22,10 @@ use jsonrpc_pubsub::Session;
 
 use v1::{EthPubSub, EthPubSubClient, Metadata};
 
-use ethcore::client::{TestBlockChainClient, EachBlockWith, ChainNotify};
+use ethcore::client::{TestBlockChainClient, EachBlockWith, ChainNotify, BlockChainClient};
+use ethcore::miner::MinerService;
+use ethcore::transaction::{Transaction, Action};
+use ethkey::{Generator, Random};
 use parity_reactor::EventLoop;
 
 #[test]
@@ -150,6 +153,110 @@ fn should_subscribe_to_logs() {
 	assert_eq!(res, None);
 }
 
+#[test]
+fn should_subscribe_to_local_transactions() {
+	// given
+	let el = EventLoop::spawn();
+	let mut client = TestBlockChainClient::new();
+	client.add_blocks(1, EachBlockWith::Nothing);
+	let tx = Transaction {
+		action: Action::Create,
+		value: 0.into(),
+		data: Vec::new(),
+		gas: 100_000.into(),
+		gas_price: 20_000_000_000u64.into(),
+		nonce: 0.into(),
+	}.sign(Random.generate().unwrap().secret(), None);
+	client.set_balance(tx.sender(), 10_000_000_000_000_000_000u64.into());
+	client.miner.import_own_transaction(&client, tx.into()).unwrap();
+	let best_hash = client.chain_info().best_block_hash;
+
+	let pubsub = EthPubSubClient::new_test(Arc::new(client), el.remote());
+	let handler = pubsub.handler();
+	let pubsub = pubsub.to_delegate();
+
+	let mut io = MetaIoHandler::default();
+	io.extend_with(pubsub);
+
+	let mut metadata = Metadata::default();
+	let (sender, receiver) = futures::sync::mpsc::channel(8);
+	metadata.session = Some(Arc::new(Session::new(sender)));
+
+	// Subscribe
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["localTransactions"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x416d77337e24399d","id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+
+	// Check notifications
+	handler.new_blocks(vec![], vec![], vec![best_hash], vec![], vec![], vec![], 0);
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	let res = res.unwrap();
+	assert!(res.contains(r#""status":"pending","stuck":false,"rebroadcasts":0"#), "Unexpected notification: {}", res);
+	assert!(res.contains(r#""subscription":"0x416d77337e24399d""#));
+
+	// And unsubscribe
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_unsubscribe", "params": ["0x416d77337e24399d"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata), Some(response.to_owned()));
+
+	let (res, _receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(res, None);
+}
+
+#[test]
+fn should_notify_local_transactions_changed_since_the_last_block() {
+	// given
+	let el = EventLoop::spawn();
+	let client = TestBlockChainClient::new();
+	client.add_blocks(1, EachBlockWith::Nothing);
+	let client = Arc::new(client);
+	let import = |nonce: u64| {
+		let tx = Transaction {
+			action: Action::Create,
+			value: 0.into(),
+			data: Vec::new(),
+			gas: 100_000.into(),
+			gas_price: 20_000_000_000u64.into(),
+			nonce: nonce.into(),
+		}.sign(Random.generate().unwrap().secret(), None);
+		client.set_balance(tx.sender(), 10_000_000_000_000_000_000u64.into());
+		client.miner.import_own_transaction(&*client, tx.clone().into()).unwrap();
+		format!(r#""0x{}""#, tx.hash().hex())
+	};
+	let first = import(0);
+	let best_hash = client.chain_info().best_block_hash;
+
+	let pubsub = EthPubSubClient::new_test(client.clone(), el.remote());
+	let handler = pubsub.handler();
+	let pubsub = pubsub.to_delegate();
+
+	let mut io = MetaIoHandler::default();
+	io.extend_with(pubsub);
+
+	let mut metadata = Metadata::default();
+	let (sender, receiver) = futures::sync::mpsc::channel(8);
+	metadata.session = Some(Arc::new(Session::new(sender)));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["localTransactions"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x416d77337e24399d","id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+
+	handler.new_blocks(vec![], vec![], vec![best_hash], vec![], vec![], vec![], 0);
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	assert!(res.unwrap().contains(&first));
+
+	// when
+	let second = import(0);
+	handler.new_blocks(vec![], vec![], vec![best_hash], vec![], vec![], vec![], 0);
+
+	// then
+	// statuses are checked once per block, and only the changed ones are notified.
+	let (res, _receiver) = receiver.into_future().wait().unwrap();
+	let res = res.unwrap();
+	assert!(res.contains(&second), "Unexpected notification: {}", res);
+	assert!(!res.contains(&first), "Unexpected notification: {}", res);
+}
+
 #[test]
 fn should_return_unimplemented() {
 	// given
@@ -0,0 +1,112 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethcore::client::DevBlockChainClient;
+use util::Mutex;
+
+use jsonrpc_core::IoHandler;
+use v1::{Evm, EvmClient};
+
+#[derive(Default)]
+struct TestDevClient {
+	best_block: Mutex<u64>,
+	time_offset: Mutex<u64>,
+	snapshots: Mutex<Vec<(u64, u64)>>,
+}
+
+impl DevBlockChainClient for TestDevClient {
+	fn mine_block(&self) -> Result<(), String> {
+		*self.best_block.lock() += 1;
+		Ok(())
+	}
+
+	fn increase_time(&self, seconds: u64) -> Result<u64, String> {
+		let mut offset = self.time_offset.lock();
+		*offset += seconds;
+		Ok(*offset)
+	}
+
+	fn snapshot(&self) -> Result<usize, String> {
+		let mut snapshots = self.snapshots.lock();
+		snapshots.push((*self.best_block.lock(), *self.time_offset.lock()));
+		Ok(snapshots.len())
+	}
+
+	fn revert(&self, id: usize) -> Result<bool, String> {
+		let mut snapshots = self.snapshots.lock();
+		if id == 0 || id > snapshots.len() {
+			return Ok(false);
+		}
+		let (best_block, time_offset) = snapshots[id - 1];
+		*self.best_block.lock() = best_block;
+		*self.time_offset.lock() = time_offset;
+		snapshots.truncate(id - 1);
+		Ok(true)
+	}
+}
+
+fn io() -> (Arc<TestDevClient>, IoHandler) {
+	let client = Arc::new(TestDevClient::default());
+	let mut io = IoHandler::new();
+	io.extend_with(EvmClient::new(&client).to_delegate());
+	(client, io)
+}
+
+#[test]
+fn rpc_evm_mine() {
+	let (client, io) = io();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "evm_mine", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(*client.best_block.lock(), 1);
+}
+
+#[test]
+fn rpc_evm_increase_time() {
+	let (_client, io) = io();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "evm_increaseTime", "params": [60], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":60,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let response = r#"{"jsonrpc":"2.0","result":120,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_evm_snapshot_and_revert() {
+	let (client, io) = io();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "evm_snapshot", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x1","id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	client.mine_block().unwrap();
+	client.increase_time(10).unwrap();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "evm_revert", "params": ["0x1"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(*client.best_block.lock(), 0);
+	assert_eq!(*client.time_offset.lock(), 0);
+
+	let response = r#"{"jsonrpc":"2.0","result":false,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
@@ -17,8 +17,10 @@
 //! RPC mocked tests. Most of these test that the RPC server is serializing and forwarding
 //! method calls properly.
 
+mod debug;
 mod eth;
 mod eth_pubsub;
+mod evm;
 mod manage_network;
 mod net;
 mod parity;
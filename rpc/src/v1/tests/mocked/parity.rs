@@ -20,7 +20,7 @@ use util::Address;
 use ethsync::ManageNetwork;
 use ethcore::account_provider::AccountProvider;
 use ethcore::client::{TestBlockChainClient};
-use ethcore::miner::LocalTransactionStatus;
+use ethcore::miner::{LocalTransactionStatus, LocalTransactionPropagation};
 use ethstore::ethkey::{Generator, Random};
 
 use jsonrpc_core::IoHandler;
@@ -86,6 +86,7 @@ impl Dependencies {
 			signer,
 			self.dapps_address.clone(),
 			self.ws_address.clone(),
+			None,
 		)
 	}
 
@@ -403,6 +404,17 @@ fn rpc_parity_ws_address() {
 	assert_eq!(io2.handle_request_sync(request), Some(response2.to_owned()));
 }
 
+#[test]
+fn rpc_parity_stratum_workers() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_stratumWorkers", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"Stratum server is disabled. This API is not available."},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_parity_dapps_address() {
 	// given
@@ -460,9 +472,15 @@ fn rpc_parity_local_transactions() {
 	let io = deps.default_client();
 	deps.miner.local_transactions.lock().insert(10.into(), LocalTransactionStatus::Pending);
 	deps.miner.local_transactions.lock().insert(15.into(), LocalTransactionStatus::Future);
+	deps.miner.local_transactions_propagation.lock().insert(10.into(), LocalTransactionPropagation {
+		broadcast_at: 5,
+		rebroadcasts: 2,
+		stuck: true,
+		due: false,
+	});
 
 	let request = r#"{"jsonrpc": "2.0", "method": "parity_localTransactions", "params":[], "id": 1}"#;
-	let response = r#"{"jsonrpc":"2.0","result":{"0x000000000000000000000000000000000000000000000000000000000000000a":{"status":"pending"},"0x000000000000000000000000000000000000000000000000000000000000000f":{"status":"future"}},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"0x000000000000000000000000000000000000000000000000000000000000000a":{"status":"pending","stuck":true,"rebroadcasts":2},"0x000000000000000000000000000000000000000000000000000000000000000f":{"status":"future"}},"id":1}"#;
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
@@ -178,6 +178,26 @@ fn rpc_parity_set_engine_signer() {
 	assert_eq!(*miner.password.read(), "password".to_string());
 }
 
+#[test]
+fn rpc_parity_clique_propose_and_discard() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client(&client, &miner, &updater, &network).to_delegate());
+	let address = Address::from_str("cd1722f3947def4cf144679da39c4c32bdc35681").unwrap();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_cliquePropose", "params":["0xcd1722f3947def4cf144679da39c4c32bdc35681", true], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(miner.signer_proposals.read().get(&address), Some(&true));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_cliqueDiscard", "params":["0xcd1722f3947def4cf144679da39c4c32bdc35681"], "id": 1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert!(miner.signer_proposals.read().is_empty());
+}
+
 
 #[test]
 fn rpc_parity_set_transactions_limit() {
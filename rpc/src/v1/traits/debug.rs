@@ -0,0 +1,35 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Geth-compatible debug rpc interface.
+
+use jsonrpc_core::Error;
+use jsonrpc_macros::Trailing;
+use v1::types::{BlockNumber, CallRequest, ExecutionTrace, TraceOptions, H256};
+
+build_rpc_trait! {
+	/// Geth-compatible debug rpc interface.
+	pub trait Debug {
+		/// Replays the transaction with the given hash and returns struct logs of its execution.
+		#[rpc(name = "debug_traceTransaction")]
+		fn trace_transaction(&self, H256, Trailing<TraceOptions>) -> Result<ExecutionTrace, Error>;
+
+		/// Executes the given call on top of the given block and returns struct logs of its execution.
+		#[rpc(name = "debug_traceCall")]
+		fn trace_call(&self, CallRequest, BlockNumber, Trailing<TraceOptions>) -> Result<ExecutionTrace, Error>;
+	}
+}
@@ -20,7 +20,7 @@ use jsonrpc_macros::Trailing;
 
 use futures::BoxFuture;
 
-use v1::types::{RichBlock, BlockNumber, Bytes, CallRequest, Filter, FilterChanges, Index};
+use v1::types::{RichBlock, BlockNumber, Bytes, CallRequest, EthAccount, Filter, FilterChanges, Index};
 use v1::types::{Log, Receipt, SyncStatus, Transaction, Work};
 use v1::types::{H64, H160, H256, U256};
 
@@ -101,6 +101,10 @@ build_rpc_trait! {
 		#[rpc(async, name = "eth_getCode")]
 		fn code_at(&self, H160, Trailing<BlockNumber>) -> BoxFuture<Bytes, Error>;
 
+		/// Returns the account and storage values of the given account including the merkle proofs.
+		#[rpc(async, name = "eth_getProof")]
+		fn proof(&self, H160, Vec<H256>, Trailing<BlockNumber>) -> BoxFuture<EthAccount, Error>;
+
 		/// Sends signed transaction, returning its hash.
 		#[rpc(name = "eth_sendRawTransaction")]
 		fn send_raw_transaction(&self, Bytes) -> Result<H256, Error>;
@@ -118,20 +122,20 @@ build_rpc_trait! {
 		fn estimate_gas(&self, CallRequest, Trailing<BlockNumber>) -> BoxFuture<U256, Error>;
 
 		/// Get transaction by its hash.
-		#[rpc(name = "eth_getTransactionByHash")]
-		fn transaction_by_hash(&self, H256) -> Result<Option<Transaction>, Error>;
+		#[rpc(async, name = "eth_getTransactionByHash")]
+		fn transaction_by_hash(&self, H256) -> BoxFuture<Option<Transaction>, Error>;
 
 		/// Returns transaction at given block hash and index.
-		#[rpc(name = "eth_getTransactionByBlockHashAndIndex")]
-		fn transaction_by_block_hash_and_index(&self, H256, Index) -> Result<Option<Transaction>, Error>;
+		#[rpc(async, name = "eth_getTransactionByBlockHashAndIndex")]
+		fn transaction_by_block_hash_and_index(&self, H256, Index) -> BoxFuture<Option<Transaction>, Error>;
 
 		/// Returns transaction by given block number and index.
-		#[rpc(name = "eth_getTransactionByBlockNumberAndIndex")]
-		fn transaction_by_block_number_and_index(&self, BlockNumber, Index) -> Result<Option<Transaction>, Error>;
+		#[rpc(async, name = "eth_getTransactionByBlockNumberAndIndex")]
+		fn transaction_by_block_number_and_index(&self, BlockNumber, Index) -> BoxFuture<Option<Transaction>, Error>;
 
 		/// Returns transaction receipt.
-		#[rpc(name = "eth_getTransactionReceipt")]
-		fn transaction_receipt(&self, H256) -> Result<Option<Receipt>, Error>;
+		#[rpc(async, name = "eth_getTransactionReceipt")]
+		fn transaction_receipt(&self, H256) -> BoxFuture<Option<Receipt>, Error>;
 
 		/// Returns an uncles at given block and index.
 		#[rpc(name = "eth_getUncleByBlockHashAndIndex")]
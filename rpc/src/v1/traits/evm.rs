@@ -0,0 +1,43 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ganache-compatible development chain rpc interface.
+
+use jsonrpc_core::Error;
+use v1::types::U256;
+
+build_rpc_trait! {
+	/// Ganache-compatible development chain rpc interface.
+	pub trait Evm {
+		/// Seals a new block right away, even if there are no pending transactions.
+		#[rpc(name = "evm_mine")]
+		fn mine(&self) -> Result<U256, Error>;
+
+		/// Moves the timestamps of new blocks the given number of seconds forward.
+		/// Returns the total time adjustment in seconds.
+		#[rpc(name = "evm_increaseTime")]
+		fn increase_time(&self, u64) -> Result<u64, Error>;
+
+		/// Records the current state of the chain. Returns the snapshot id.
+		#[rpc(name = "evm_snapshot")]
+		fn snapshot(&self) -> Result<U256, Error>;
+
+		/// Rolls the chain back to the snapshot with the given id.
+		/// The snapshot and all later ones are dropped.
+		#[rpc(name = "evm_revert")]
+		fn revert(&self, U256) -> Result<bool, Error>;
+	}
+}
@@ -17,9 +17,11 @@
 //! Ethereum rpc interfaces.
 
 pub mod web3;
+pub mod debug;
 pub mod eth;
 pub mod eth_pubsub;
 pub mod eth_signing;
+pub mod evm;
 pub mod net;
 pub mod parity;
 pub mod parity_accounts;
@@ -33,9 +35,11 @@ pub mod rpc;
 pub mod secretstore;
 
 pub use self::web3::Web3;
+pub use self::debug::Debug;
 pub use self::eth::{Eth, EthFilter};
 pub use self::eth_pubsub::EthPubSub;
 pub use self::eth_signing::EthSigning;
+pub use self::evm::Evm;
 pub use self::net::Net;
 pub use self::parity::Parity;
 pub use self::parity_accounts::ParityAccounts;
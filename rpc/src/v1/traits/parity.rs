@@ -28,7 +28,7 @@ use v1::types::{
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, RichHeader,
+	AccountInfo, HwAccountInfo, RichHeader, StratumWorker,
 };
 
 build_rpc_trait! {
@@ -203,5 +203,9 @@ build_rpc_trait! {
 		/// Get IPFS CIDv0 given protobuf encoded bytes.
 		#[rpc(name = "parity_cidV0")]
 		fn ipfs_cid(&self, Bytes) -> Result<String, Error>;
+
+		/// Returns share statistics of the workers authorized by the Stratum server.
+		#[rpc(name = "parity_stratumWorkers")]
+		fn stratum_workers(&self) -> Result<BTreeMap<String, StratumWorker>, Error>;
 	}
 }
@@ -48,6 +48,14 @@ build_rpc_trait! {
 		#[rpc(name = "parity_setEngineSigner")]
 		fn set_engine_signer(&self, H160, String) -> Result<bool, Error>;
 
+		/// Votes to add (`true`) or remove (`false`) a signer in the blocks we seal (Clique).
+		#[rpc(name = "parity_cliquePropose")]
+		fn clique_propose(&self, H160, bool) -> Result<bool, Error>;
+
+		/// Stops voting on a signer (Clique).
+		#[rpc(name = "parity_cliqueDiscard")]
+		fn clique_discard(&self, H160) -> Result<bool, Error>;
+
 		/// Sets the limits for transaction queue.
 		#[rpc(name = "parity_setTransactionsLimit")]
 		fn set_transactions_limit(&self, usize) -> Result<bool, Error>;
@@ -18,6 +18,7 @@
 
 use jsonrpc_core::Error;
 use jsonrpc_macros::Trailing;
+use futures::BoxFuture;
 use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, H256};
 
 build_rpc_trait! {
@@ -40,15 +41,23 @@ build_rpc_trait! {
 		fn block_traces(&self, BlockNumber) -> Result<Option<Vec<LocalizedTrace>>, Error>;
 
 		/// Executes the given call and returns a number of possible traces for it.
-		#[rpc(name = "trace_call")]
-		fn call(&self, CallRequest, Vec<String>, Trailing<BlockNumber>) -> Result<TraceResults, Error>;
+		#[rpc(async, name = "trace_call")]
+		fn call(&self, CallRequest, Vec<String>, Trailing<BlockNumber>) -> BoxFuture<TraceResults, Error>;
+
+		/// Executes all given calls, each on top of the state left by the previous one, and returns a number of possible traces for each of them.
+		#[rpc(name = "trace_callMany")]
+		fn call_many(&self, Vec<(CallRequest, Vec<String>)>, Trailing<BlockNumber>) -> Result<Vec<TraceResults>, Error>;
 
 		/// Executes the given raw transaction and returns a number of possible traces for it.
-		#[rpc(name = "trace_rawTransaction")]
-		fn raw_transaction(&self, Bytes, Vec<String>, Trailing<BlockNumber>) -> Result<TraceResults, Error>;
+		#[rpc(async, name = "trace_rawTransaction")]
+		fn raw_transaction(&self, Bytes, Vec<String>, Trailing<BlockNumber>) -> BoxFuture<TraceResults, Error>;
 
 		/// Executes the transaction with the given hash and returns a number of possible traces for it.
 		#[rpc(name = "trace_replayTransaction")]
 		fn replay_transaction(&self, H256, Vec<String>) -> Result<TraceResults, Error>;
+
+		/// Executes all the transactions of the given block and returns a number of possible traces for each of them.
+		#[rpc(name = "trace_replayBlockTransactions")]
+		fn replay_block_transactions(&self, BlockNumber, Vec<String>) -> Result<Vec<TraceResults>, Error>;
 	}
 }
@@ -0,0 +1,78 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+use v1::types::{Bytes, H160, H256, U256};
+
+/// Account state together with merkle proofs of its inclusion (used by `eth_getProof`).
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct EthAccount {
+	/// Address of the account.
+	pub address: H160,
+	/// Account balance.
+	pub balance: U256,
+	/// Account nonce.
+	pub nonce: U256,
+	/// Hash of the account code.
+	#[serde(rename="codeHash")]
+	pub code_hash: H256,
+	/// Root of the account storage trie.
+	#[serde(rename="storageHash")]
+	pub storage_hash: H256,
+	/// Trie nodes (in order from the state root) proving the account.
+	#[serde(rename="accountProof")]
+	pub account_proof: Vec<Bytes>,
+	/// Proofs of the requested storage entries.
+	#[serde(rename="storageProof")]
+	pub storage_proof: Vec<StorageProof>,
+}
+
+/// A single storage entry together with its merkle proof.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct StorageProof {
+	/// Storage key.
+	pub key: H256,
+	/// Value stored under the key.
+	pub value: U256,
+	/// Trie nodes (in order from the storage root) proving the value.
+	pub proof: Vec<Bytes>,
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use super::{EthAccount, StorageProof};
+
+	#[test]
+	fn eth_account_serialization() {
+		let account = EthAccount {
+			address: 5.into(),
+			balance: 1.into(),
+			nonce: 2.into(),
+			code_hash: 3.into(),
+			storage_hash: 4.into(),
+			account_proof: vec![vec![0x80].into()],
+			storage_proof: vec![StorageProof {
+				key: 6.into(),
+				value: 7.into(),
+				proof: vec![],
+			}],
+		};
+
+		let serialized = serde_json::to_string(&account).unwrap();
+		assert_eq!(serialized, r#"{"address":"0x0000000000000000000000000000000000000005","balance":"0x1","nonce":"0x2","codeHash":"0x0000000000000000000000000000000000000000000000000000000000000003","storageHash":"0x0000000000000000000000000000000000000000000000000000000000000004","accountProof":["0x80"],"storageProof":[{"key":"0x0000000000000000000000000000000000000000000000000000000000000006","value":"0x7","proof":[]}]}"#);
+	}
+}
@@ -15,7 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use v1::helpers::CallRequest as Request;
-use v1::types::{Bytes, H160, U256};
+use v1::types::{Bytes, H160, U256, StateOverride};
 
 /// Call request
 #[derive(Debug, Default, PartialEq, Deserialize)]
@@ -36,6 +36,9 @@ pub struct CallRequest {
 	pub data: Option<Bytes>,
 	/// Nonce
 	pub nonce: Option<U256>,
+	/// State overrides applied before executing the call
+	#[serde(rename="stateOverride")]
+	pub state_override: Option<StateOverride>,
 }
 
 impl Into<Request> for CallRequest {
@@ -48,6 +51,7 @@ impl Into<Request> for CallRequest {
 			value: self.value.map(Into::into),
 			data: self.data.map(Into::into),
 			nonce: self.nonce.map(Into::into),
+			state_override: self.state_override.map(Into::into).unwrap_or_default(),
 		}
 	}
 }
@@ -57,6 +61,8 @@ mod tests {
 	use std::str::FromStr;
 	use rustc_hex::FromHex;
 	use serde_json;
+	use util::Address;
+	use v1::helpers::CallRequest as Request;
 	use v1::types::{U256, H160};
 	use super::CallRequest;
 
@@ -81,6 +87,7 @@ mod tests {
 			value: Some(U256::from(3)),
 			data: Some(vec![0x12, 0x34, 0x56].into()),
 			nonce: Some(U256::from(4)),
+			state_override: None,
 		});
 	}
 
@@ -103,7 +110,8 @@ mod tests {
 			gas: Some(U256::from_str("76c0").unwrap()),
 			value: Some(U256::from_str("9184e72a").unwrap()),
 			data: Some("d46e8dd67c5d32be8d46e8dd67c5d32be8058bb8eb970870f072445675058bb8eb970870f072445675".from_hex().unwrap().into()),
-			nonce: None
+			nonce: None,
+			state_override: None,
 		});
 	}
 
@@ -120,6 +128,23 @@ mod tests {
 			value: None,
 			data: None,
 			nonce: None,
+			state_override: None,
 		});
 	}
+
+	#[test]
+	fn call_request_deserialize_state_override() {
+		let s = r#"{
+			"to":"0x0000000000000000000000000000000000000002",
+			"stateOverride":{
+				"0x0000000000000000000000000000000000000001":{"balance":"0x10"}
+			}
+		}"#;
+		let deserialized: CallRequest = serde_json::from_str(s).unwrap();
+		let request: Request = deserialized.into();
+
+		assert_eq!(request.to, Some(Address::from(2)));
+		assert_eq!(request.state_override.len(), 1);
+		assert_eq!(request.state_override[&Address::from(1)].balance, Some(0x10.into()));
+	}
 }
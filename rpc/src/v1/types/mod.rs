@@ -18,6 +18,7 @@
 //! RPC types
 
 mod account_info;
+mod account_proof;
 mod block;
 mod block_number;
 mod bytes;
@@ -35,6 +36,9 @@ mod node_kind;
 mod provenance;
 mod receipt;
 mod rpc_settings;
+mod state_override;
+mod stratum;
+mod struct_log;
 mod sync;
 mod trace;
 mod trace_filter;
@@ -47,6 +51,7 @@ mod work;
 pub mod pubsub;
 
 pub use self::account_info::{AccountInfo, ExtAccountInfo, HwAccountInfo};
+pub use self::account_proof::{EthAccount, StorageProof};
 pub use self::bytes::Bytes;
 pub use self::block::{RichBlock, Block, BlockTransactions, Header, RichHeader, Rich};
 pub use self::block_number::BlockNumber;
@@ -67,6 +72,9 @@ pub use self::node_kind::{NodeKind, Availability, Capability};
 pub use self::provenance::{Origin, DappId};
 pub use self::receipt::Receipt;
 pub use self::rpc_settings::RpcSettings;
+pub use self::state_override::{AccountOverride, StateOverride};
+pub use self::stratum::StratumWorker;
+pub use self::struct_log::{ExecutionTrace, StructLog, TraceOptions};
 pub use self::sync::{
 	SyncStatus, SyncInfo, Peers, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
 	TransactionStats, ChainStatus, EthProtocolInfo, PipProtocolInfo,
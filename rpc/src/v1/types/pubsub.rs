@@ -16,18 +16,21 @@
 
 //! Pub-Sub types.
 
+use std::collections::BTreeMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::Error;
 use serde_json::{Value, from_value};
-use v1::types::{RichHeader, Filter, Log};
+use v1::types::{RichHeader, Filter, Log, H256, LocalTransactionStatus};
 
 /// Subscription result.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Result {
 	/// New block header.
 	Header(RichHeader),
 	/// Logs
 	Logs(Vec<Log>),
+	/// Local transactions which status changed.
+	LocalTransactions(BTreeMap<H256, LocalTransactionStatus>),
 }
 
 impl Serialize for Result {
@@ -37,6 +40,7 @@ impl Serialize for Result {
 		match *self {
 			Result::Header(ref header) => header.serialize(serializer),
 			Result::Logs(ref logs) => logs.serialize(serializer),
+			Result::LocalTransactions(ref transactions) => transactions.serialize(serializer),
 		}
 	}
 }
@@ -57,6 +61,11 @@ pub enum Kind {
 	/// Node syncing status subscription.
 	#[serde(rename="syncing")]
 	Syncing,
+	/// Local transactions status changes subscription (Parity extension).
+	/// Statuses are checked on every new block: the ones which changed since are notified together.
+	/// Not available on light clients.
+	#[serde(rename="localTransactions")]
+	LocalTransactions,
 }
 
 /// Subscription kind.
@@ -101,6 +110,7 @@ mod tests {
 		assert_eq!(serde_json::from_str::<Kind>(r#""logs""#).unwrap(), Kind::Logs);
 		assert_eq!(serde_json::from_str::<Kind>(r#""newPendingTransactions""#).unwrap(), Kind::NewPendingTransactions);
 		assert_eq!(serde_json::from_str::<Kind>(r#""syncing""#).unwrap(), Kind::Syncing);
+		assert_eq!(serde_json::from_str::<Kind>(r#""localTransactions""#).unwrap(), Kind::LocalTransactions);
 	}
 
 	#[test]
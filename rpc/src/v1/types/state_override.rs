@@ -0,0 +1,125 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State overrides applied before executing a call.
+
+use std::collections::BTreeMap;
+use ethcore::client::{AccountOverride as EthAccountOverride, StateOverride as EthStateOverride};
+use v1::types::{Bytes, H160, H256, U256};
+
+/// Changes applied to a single account before executing a call.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountOverride {
+	/// Balance
+	pub balance: Option<U256>,
+	/// Nonce
+	pub nonce: Option<U256>,
+	/// Code
+	pub code: Option<Bytes>,
+	/// Storage replacing the whole storage of the account
+	pub state: Option<BTreeMap<H256, H256>>,
+	/// Storage slots to modify
+	#[serde(rename="stateDiff")]
+	pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+impl Into<EthAccountOverride> for AccountOverride {
+	fn into(self) -> EthAccountOverride {
+		fn storage(s: BTreeMap<H256, H256>) -> BTreeMap<::util::H256, ::util::H256> {
+			s.into_iter().map(|(k, v)| (k.into(), v.into())).collect()
+		}
+
+		EthAccountOverride {
+			balance: self.balance.map(Into::into),
+			nonce: self.nonce.map(Into::into),
+			code: self.code.map(Into::into),
+			state: self.state.map(storage),
+			state_diff: self.state_diff.map(storage),
+		}
+	}
+}
+
+/// Changes applied to the state before executing a call, keyed by account address.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct StateOverride(BTreeMap<H160, AccountOverride>);
+
+impl StateOverride {
+	/// Whether there is nothing to override.
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+}
+
+impl Into<EthStateOverride> for StateOverride {
+	fn into(self) -> EthStateOverride {
+		self.0.into_iter().map(|(address, o)| (address.into(), o.into())).collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeMap;
+	use serde_json;
+	use util::Address;
+	use ethcore::client::StateOverride as EthStateOverride;
+	use super::{AccountOverride, StateOverride};
+
+	#[test]
+	fn state_override_deserialize() {
+		let s = r#"{
+			"0x0000000000000000000000000000000000000001": {
+				"balance": "0x10",
+				"nonce": "0x2",
+				"code": "0x6000",
+				"stateDiff": {
+					"0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000005"
+				}
+			},
+			"0x0000000000000000000000000000000000000002": {
+				"state": {}
+			}
+		}"#;
+		let deserialized: StateOverride = serde_json::from_str(s).unwrap();
+
+		let mut state_diff = BTreeMap::new();
+		state_diff.insert(1.into(), 5.into());
+		let mut expected = BTreeMap::new();
+		expected.insert(1.into(), AccountOverride {
+			balance: Some(0x10.into()),
+			nonce: Some(2.into()),
+			code: Some(vec![0x60, 0x00].into()),
+			state: None,
+			state_diff: Some(state_diff),
+		});
+		expected.insert(2.into(), AccountOverride {
+			state: Some(BTreeMap::new()),
+			..Default::default()
+		});
+		assert_eq!(deserialized, StateOverride(expected));
+
+		let converted: EthStateOverride = deserialized.into();
+		assert_eq!(converted.len(), 2);
+		assert_eq!(converted[&Address::from(1)].balance, Some(0x10.into()));
+		assert_eq!(converted[&Address::from(2)].state, Some(BTreeMap::new()));
+	}
+
+	#[test]
+	fn state_override_deserialize_rejects_unknown_fields() {
+		let s = r#"{"0x0000000000000000000000000000000000000001": {"storage": {}}}"#;
+		assert!(serde_json::from_str::<StateOverride>(s).is_err());
+	}
+}
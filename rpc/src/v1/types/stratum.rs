@@ -0,0 +1,61 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use ethcore::miner::StratumWorkerStats;
+use v1::types::U256;
+
+/// Share statistics of a Stratum worker
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct StratumWorker {
+	/// Number of accepted shares
+	pub accepted: u64,
+	/// Number of shares for outdated work
+	pub stale: u64,
+	/// Number of malformed shares or shares below the share difficulty
+	pub invalid: u64,
+	/// Hashrate estimated from the recently accepted shares
+	pub hashrate: U256,
+}
+
+impl From<StratumWorkerStats> for StratumWorker {
+	fn from(stats: StratumWorkerStats) -> Self {
+		StratumWorker {
+			accepted: stats.accepted,
+			stale: stats.stale,
+			invalid: stats.invalid,
+			hashrate: stats.hashrate.into(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use super::StratumWorker;
+
+	#[test]
+	fn stratum_worker_serialization() {
+		let worker = StratumWorker {
+			accepted: 10,
+			stale: 1,
+			invalid: 2,
+			hashrate: 1000.into(),
+		};
+
+		let serialized = serde_json::to_string(&worker).unwrap();
+		assert_eq!(serialized, r#"{"accepted":10,"stale":1,"invalid":2,"hashrate":"0x3e8"}"#);
+	}
+}
@@ -0,0 +1,294 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Geth-compatible struct-log traces.
+
+use std::cmp;
+use std::collections::BTreeMap;
+use rustc_hex::ToHex;
+use ethcore::client::Executed;
+use ethcore::evm::INSTRUCTIONS;
+use ethcore::evm::instructions::SLOAD;
+use ethcore::trace::VMTrace;
+use util::{H256, U256};
+
+/// Struct-log tracing options (used by `debug_traceTransaction` and `debug_traceCall`).
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TraceOptions {
+	/// Don't include the stack in struct logs.
+	#[serde(rename="disableStack")]
+	pub disable_stack: Option<bool>,
+	/// Don't include the memory in struct logs.
+	#[serde(rename="disableMemory")]
+	pub disable_memory: Option<bool>,
+	/// Don't include the storage in struct logs.
+	#[serde(rename="disableStorage")]
+	pub disable_storage: Option<bool>,
+	/// Maximal number of struct logs returned. `0` or missing means no limit.
+	pub limit: Option<usize>,
+}
+
+/// A single executed operation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StructLog {
+	/// Program counter.
+	pub pc: usize,
+	/// Opcode mnemonic.
+	pub op: &'static str,
+	/// Gas remaining before the operation.
+	pub gas: u64,
+	/// Gas cost of the operation.
+	#[serde(rename="gasCost")]
+	pub gas_cost: u64,
+	/// Call depth (starts with 1).
+	pub depth: usize,
+	/// Stack before the operation, bottom first.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub stack: Option<Vec<String>>,
+	/// Memory before the operation, in 32-byte words.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub memory: Option<Vec<String>>,
+	/// Storage of the executing contract accessed so far.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub storage: Option<BTreeMap<String, String>>,
+}
+
+/// Struct-log trace of a transaction.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExecutionTrace {
+	/// Gas used by the transaction.
+	pub gas: u64,
+	/// Whether the execution failed.
+	pub failed: bool,
+	/// Output of the transaction.
+	#[serde(rename="returnValue")]
+	pub return_value: String,
+	/// Executed operations.
+	#[serde(rename="structLogs")]
+	pub struct_logs: Vec<StructLog>,
+}
+
+impl ExecutionTrace {
+	/// Create a struct-log trace out of execution results which contain a VM trace.
+	pub fn new(executed: Executed, options: &TraceOptions) -> Self {
+		let mut logger = StructLogger {
+			options: options,
+			limit: options.limit.and_then(|l| if l == 0 { None } else { Some(l) }),
+			logs: Vec::new(),
+		};
+
+		if let Some(ref trace) = executed.vm_trace {
+			logger.walk(trace, 1);
+		}
+
+		ExecutionTrace {
+			gas: executed.gas_used.low_u64(),
+			failed: executed.exception.is_some(),
+			return_value: executed.output.to_hex(),
+			struct_logs: logger.logs,
+		}
+	}
+}
+
+// Rebuilds full stack, memory and storage from the diff-based `VMTrace`.
+struct StructLogger<'a> {
+	options: &'a TraceOptions,
+	limit: Option<usize>,
+	logs: Vec<StructLog>,
+}
+
+impl<'a> StructLogger<'a> {
+	fn is_full(&self) -> bool {
+		self.limit.map_or(false, |limit| self.logs.len() >= limit)
+	}
+
+	// Walk the operations of a single frame, descending into subtraces.
+	// Returns `false` when the output limit has been reached.
+	fn walk(&mut self, trace: &VMTrace, depth: usize) -> bool {
+		let mut stack: Vec<U256> = Vec::new();
+		let mut memory: Vec<u8> = Vec::new();
+		let mut storage: BTreeMap<H256, H256> = BTreeMap::new();
+		let mut subs = trace.subs.iter().peekable();
+
+		// gas before the first operation is not recorded; infer it from its result.
+		let mut gas = trace.operations.first().map_or_else(U256::zero, |op| {
+			op.executed.as_ref().map_or(op.gas_cost, |ex| ex.gas_used + op.gas_cost)
+		});
+
+		for (step, op) in trace.operations.iter().enumerate() {
+			if self.is_full() {
+				return false;
+			}
+
+			let info = &INSTRUCTIONS[op.instruction as usize];
+			self.logs.push(StructLog {
+				pc: op.pc,
+				op: info.name,
+				gas: gas.low_u64(),
+				gas_cost: op.gas_cost.low_u64(),
+				depth: depth,
+				stack: match self.options.disable_stack {
+					Some(true) => None,
+					_ => Some(stack.iter().map(|v| H256::from(*v).to_hex()).collect()),
+				},
+				memory: match self.options.disable_memory {
+					Some(true) => None,
+					_ => Some(memory.chunks(32).map(|w| w.to_hex()).collect()),
+				},
+				storage: match self.options.disable_storage {
+					Some(true) => None,
+					_ => Some(storage.iter().map(|(k, v)| (k.to_hex(), v.to_hex())).collect()),
+				},
+			});
+
+			while subs.peek().map_or(false, |sub| sub.parent_step == step) {
+				let sub = subs.next().expect("peeked just above; qed");
+				if !self.walk(sub, depth + 1) {
+					return false;
+				}
+			}
+
+			let ex = match op.executed {
+				Some(ref ex) => ex,
+				// the operation failed; execution of this frame stops here.
+				None => break,
+			};
+
+			let loaded_key = match op.instruction {
+				SLOAD => stack.last().cloned(),
+				_ => None,
+			};
+
+			let remaining = stack.len() - cmp::min(info.args, stack.len());
+			stack.truncate(remaining);
+			stack.extend(ex.stack_push.iter().cloned());
+
+			if let Some(ref diff) = ex.mem_diff {
+				let end = diff.offset + diff.data.len();
+				if memory.len() < end {
+					memory.resize((end + 31) / 32 * 32, 0);
+				}
+				memory[diff.offset..end].copy_from_slice(&diff.data);
+			}
+
+			if let Some(ref diff) = ex.store_diff {
+				storage.insert(diff.location.into(), diff.value.into());
+			}
+
+			if let (Some(key), Some(value)) = (loaded_key, ex.stack_push.first()) {
+				storage.insert(key.into(), (*value).into());
+			}
+
+			gas = ex.gas_used;
+		}
+
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethcore::trace::{VMTrace, VMOperation, VMExecutedOperation, StorageDiff};
+	use ethcore::client::Executed;
+	use super::{ExecutionTrace, TraceOptions};
+
+	fn op(pc: usize, instruction: u8, gas_cost: u64, gas_left: u64, push: Vec<u64>) -> VMOperation {
+		VMOperation {
+			pc: pc,
+			instruction: instruction,
+			gas_cost: gas_cost.into(),
+			executed: Some(VMExecutedOperation {
+				gas_used: gas_left.into(),
+				stack_push: push.into_iter().map(Into::into).collect(),
+				mem_diff: None,
+				store_diff: None,
+			}),
+		}
+	}
+
+	// PUSH1 0x2a PUSH1 0 SSTORE STOP
+	fn executed() -> Executed {
+		let mut ops = vec![
+			op(0, 0x60, 3, 97, vec![0x2a]),
+			op(2, 0x60, 3, 94, vec![0]),
+			op(4, 0x55, 20000, 74, vec![]),
+		];
+		ops[2].executed.as_mut().unwrap().store_diff = Some(StorageDiff { location: 0.into(), value: 0x2a.into() });
+		ops.push(op(5, 0x00, 0, 74, vec![]));
+
+		Executed {
+			exception: None,
+			gas: 100.into(),
+			gas_used: 26.into(),
+			refunded: 0.into(),
+			cumulative_gas_used: 26.into(),
+			logs: vec![],
+			contracts_created: vec![],
+			output: vec![0x01],
+			trace: vec![],
+			vm_trace: Some(VMTrace {
+				parent_step: 0,
+				code: vec![],
+				operations: ops,
+				subs: vec![],
+			}),
+			state_diff: None,
+		}
+	}
+
+	#[test]
+	fn should_rebuild_stack_and_storage() {
+		let trace = ExecutionTrace::new(executed(), &Default::default());
+		let logs = &trace.struct_logs;
+
+		assert_eq!(logs.len(), 4);
+		assert_eq!(logs[0].gas, 100);
+		assert_eq!(logs[2].op, "SSTORE");
+		assert_eq!(logs[2].gas, 94);
+		assert_eq!(logs[2].stack.as_ref().unwrap().len(), 2);
+		assert_eq!(logs[3].stack.as_ref().unwrap().len(), 0);
+		assert_eq!(logs[3].storage.as_ref().unwrap().get("0000000000000000000000000000000000000000000000000000000000000000").unwrap(), "000000000000000000000000000000000000000000000000000000000000002a");
+	}
+
+	#[test]
+	fn should_respect_options() {
+		let options = TraceOptions {
+			disable_stack: Some(true),
+			disable_memory: Some(true),
+			disable_storage: Some(true),
+			limit: Some(1),
+		};
+		let trace = ExecutionTrace::new(executed(), &options);
+
+		let serialized = serde_json::to_string(&trace).unwrap();
+		assert_eq!(serialized, r#"{"gas":26,"failed":false,"returnValue":"01","structLogs":[{"pc":0,"op":"PUSH1","gas":100,"gasCost":3,"depth":1}]}"#);
+	}
+
+	#[test]
+	fn should_deserialize_options() {
+		let s = r#"{"disableStorage":true,"limit":10}"#;
+		let deserialized: TraceOptions = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized, TraceOptions {
+			disable_stack: None,
+			disable_memory: None,
+			disable_storage: Some(true),
+			limit: Some(10),
+		});
+	}
+}
@@ -75,10 +75,15 @@ pub struct Transaction {
 }
 
 /// Local Transaction Status
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum LocalTransactionStatus {
 	/// Transaction is pending
-	Pending,
+	Pending {
+		/// Gas price is below the recent inclusion gas price, the transaction is unlikely to be mined.
+		stuck: bool,
+		/// Number of times the transaction was broadcast again because it was not mined.
+		rebroadcasts: usize,
+	},
 	/// Transaction is in future part of the queue
 	Future,
 	/// Transaction is already mined.
@@ -102,7 +107,8 @@ impl Serialize for LocalTransactionStatus {
 		use self::LocalTransactionStatus::*;
 
 		let elems = match *self {
-			Pending | Future => 1,
+			Future => 1,
+			Pending { .. } => 3,
 			Mined(..) | Dropped(..) | Invalid(..) | Canceled(..) => 2,
 			Rejected(..) => 3,
 			Replaced(..) => 4,
@@ -113,7 +119,11 @@ impl Serialize for LocalTransactionStatus {
 
 		let mut struc = serializer.serialize_struct("LocalTransactionStatus", elems)?;
 		match *self {
-			Pending => struc.serialize_field(status, "pending")?,
+			Pending { ref stuck, ref rebroadcasts } => {
+				struc.serialize_field(status, "pending")?;
+				struc.serialize_field("stuck", stuck)?;
+				struc.serialize_field("rebroadcasts", rebroadcasts)?;
+			},
 			Future => struc.serialize_field(status, "future")?,
 			Mined(ref tx) => {
 				struc.serialize_field(status, "mined")?;
@@ -249,10 +259,19 @@ impl Transaction {
 
 impl LocalTransactionStatus {
 	/// Convert `LocalTransactionStatus` into RPC `LocalTransactionStatus`.
-	pub fn from(s: miner::LocalTransactionStatus, block_number: u64, eip86_transition: u64) -> Self {
+	/// `propagation` details are only relevant for pending transactions.
+	pub fn from(
+		s: miner::LocalTransactionStatus,
+		propagation: Option<miner::LocalTransactionPropagation>,
+		block_number: u64,
+		eip86_transition: u64,
+	) -> Self {
 		use ethcore::miner::LocalTransactionStatus::*;
 		match s {
-			Pending => LocalTransactionStatus::Pending,
+			Pending => {
+				let propagation = propagation.unwrap_or_default();
+				LocalTransactionStatus::Pending { stuck: propagation.stuck, rebroadcasts: propagation.rebroadcasts }
+			},
 			Future => LocalTransactionStatus::Future,
 			Mined(tx) => LocalTransactionStatus::Mined(Transaction::from_signed(tx, block_number, eip86_transition)),
 			Dropped(tx) => LocalTransactionStatus::Dropped(Transaction::from_signed(tx, block_number, eip86_transition)),
@@ -279,7 +298,7 @@ mod tests {
 	#[test]
 	fn test_local_transaction_status_serialize() {
 		let tx_ser = serde_json::to_string(&Transaction::default()).unwrap();
-		let status1 = LocalTransactionStatus::Pending;
+		let status1 = LocalTransactionStatus::Pending { stuck: true, rebroadcasts: 2 };
 		let status2 = LocalTransactionStatus::Future;
 		let status3 = LocalTransactionStatus::Mined(Transaction::default());
 		let status4 = LocalTransactionStatus::Dropped(Transaction::default());
@@ -289,7 +308,7 @@ mod tests {
 
 		assert_eq!(
 			serde_json::to_string(&status1).unwrap(),
-			r#"{"status":"pending"}"#
+			r#"{"status":"pending","stuck":true,"rebroadcasts":2}"#
 		);
 		assert_eq!(
 			serde_json::to_string(&status2).unwrap(),
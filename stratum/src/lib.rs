@@ -40,7 +40,7 @@ mod traits {
 }
 
 pub use traits::{
-	JobDispatcher, PushWorkHandler, Error, ServiceConfiguration,
+	JobDispatcher, PushWorkHandler, Error, ServiceConfiguration, Work, ShareResult,
 	RemoteWorkHandler, RemoteJobDispatcher,
 };
 
@@ -53,13 +53,27 @@ use jsonrpc_macros::IoDelegate;
 use std::sync::Arc;
 
 use std::net::SocketAddr;
-use std::collections::{HashSet, HashMap};
-use util::{H256, Hashable, RwLock, RwLockReadGuard};
+use std::cmp;
+use std::collections::{HashSet, HashMap, BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+use util::{H256, U256, Hashable, RwLock, RwLockReadGuard, clean_0x};
 
 type RpcResult = BoxFuture<jsonrpc_core::Value, jsonrpc_core::Error>;
 
 const NOTIFY_COUNTER_INITIAL: u32 = 16;
 
+/// Protocol announced in `mining.subscribe` by EthereumStratum/1.0.0 (NiceHash) miners
+const ETHEREUM_STRATUM: &'static str = "EthereumStratum/1.0.0";
+
+/// Number of EthereumStratum/1.0.0 jobs kept to tell stale shares from unknown ones
+const RECENT_JOBS: usize = 8;
+
+/// Accepted shares older than that don't count towards the hashrate estimate
+const HASHRATE_WINDOW_SECS: u64 = 600;
+
+/// EthereumStratum/1.0.0 sessions which haven't authorized a worker for that long are dropped
+const UNAUTHORIZED_SESSION_SECS: u64 = 60;
+
 struct StratumRpc {
 	stratum: RwLock<Option<Arc<Stratum>>>,
 }
@@ -106,6 +120,78 @@ impl From<SocketAddr> for SocketMetadata {
 	}
 }
 
+/// Share statistics of an authorized worker
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkerStats {
+	/// Number of accepted shares
+	pub accepted: u64,
+	/// Number of shares for outdated work
+	pub stale: u64,
+	/// Number of malformed shares or shares below the share difficulty
+	pub invalid: u64,
+	/// Hashrate (hashes per second) estimated from the recently accepted shares
+	pub hashrate: U256,
+}
+
+struct WorkerRecord {
+	stats: WorkerStats,
+	since: Instant,
+	/// Last time the worker was authorized or submitted a share
+	last_active: Instant,
+	/// Accepted shares with known difficulty, oldest first
+	shares: VecDeque<(Instant, U256)>,
+}
+
+impl WorkerRecord {
+	fn new() -> Self {
+		WorkerRecord {
+			stats: WorkerStats::default(),
+			since: Instant::now(),
+			last_active: Instant::now(),
+			shares: VecDeque::new(),
+		}
+	}
+
+	fn record(&mut self, result: ShareResult, difficulty: Option<U256>) {
+		self.last_active = Instant::now();
+		match result {
+			ShareResult::Block | ShareResult::Accepted => {
+				self.stats.accepted += 1;
+				if let Some(difficulty) = difficulty {
+					self.shares.push_back((Instant::now(), difficulty));
+				}
+			},
+			ShareResult::Stale => self.stats.stale += 1,
+			ShareResult::Invalid => self.stats.invalid += 1,
+		}
+	}
+
+	fn stats(&mut self) -> WorkerStats {
+		let window = Duration::from_secs(HASHRATE_WINDOW_SECS);
+		while self.shares.front().map_or(false, |&(time, _)| time.elapsed() > window) {
+			self.shares.pop_front();
+		}
+
+		// a share of difficulty `d` takes `d` hashes on average
+		let hashes = self.shares.iter().fold(U256::zero(), |acc, &(_, difficulty)| acc + difficulty);
+		let span = cmp::max(cmp::min(self.since.elapsed(), window).as_secs(), 1);
+		WorkerStats {
+			hashrate: hashes / U256::from(span),
+			..self.stats.clone()
+		}
+	}
+}
+
+/// EthereumStratum/1.0.0 session of a subscribed client
+struct Session {
+	/// Leading bytes of the nonces searched by the session
+	extranonce: u16,
+	/// Share difficulty last sent to the session
+	difficulty: Option<U256>,
+	/// Time of the subscription
+	since: Instant,
+}
+
 pub struct PeerMetaExtractor;
 
 impl MetaExtractor<SocketMetadata> for PeerMetaExtractor {
@@ -124,6 +210,16 @@ pub struct Stratum {
 	dispatcher: Arc<JobDispatcher>,
 	/// Authorized workers (socket - worker_id)
 	workers: Arc<RwLock<HashMap<SocketAddr, String>>>,
+	/// Share statistics of the authorized workers (worker_id - stats)
+	stats: RwLock<HashMap<String, WorkerRecord>>,
+	/// Clients speaking EthereumStratum/1.0.0
+	sessions: RwLock<HashMap<SocketAddr, Session>>,
+	/// Extranonce of the next EthereumStratum/1.0.0 session
+	next_extranonce: RwLock<u16>,
+	/// Recent EthereumStratum/1.0.0 jobs, most recent first
+	jobs: RwLock<VecDeque<Work>>,
+	/// Share difficulty of EthereumStratum/1.0.0 workers, block difficulty if not set
+	share_difficulty: Option<U256>,
 	/// Secret if any
 	secret: Option<H256>,
 	/// Dispatch notify couinter
@@ -143,6 +239,7 @@ impl Stratum {
 		addr: &SocketAddr,
 		dispatcher: Arc<JobDispatcher>,
 		secret: Option<H256>,
+		share_difficulty: Option<U256>,
 	) -> Result<Arc<Stratum>, Error> {
 
 		let rpc = Arc::new(StratumRpc {
@@ -167,6 +264,11 @@ impl Stratum {
 			job_que: RwLock::new(HashSet::new()),
 			dispatcher: dispatcher,
 			workers: Arc::new(RwLock::new(HashMap::new())),
+			stats: RwLock::new(HashMap::new()),
+			sessions: RwLock::new(HashMap::new()),
+			next_extranonce: RwLock::new(0),
+			jobs: RwLock::new(VecDeque::new()),
+			share_difficulty: share_difficulty,
 			secret: secret,
 			notify_counter: RwLock::new(NOTIFY_COUNTER_INITIAL),
 		});
@@ -182,7 +284,13 @@ impl Stratum {
 		}
 	}
 
-	fn submit(&self, params: Params, _meta: SocketMetadata) -> RpcResult {
+	fn submit(&self, params: Params, meta: SocketMetadata) -> RpcResult {
+		let worker = self.workers.read().get(meta.addr()).cloned();
+		if self.sessions.read().contains_key(meta.addr()) {
+			let accepted = self.submit_share(params, meta.addr(), worker);
+			return future::ok(to_value(accepted).expect("Booleans are serializable; qed")).boxed();
+		}
+
 		future::ok(match params {
 			Params::Array(vals) => {
 				// first two elements are service messages (worker_id & job_id)
@@ -190,11 +298,13 @@ impl Stratum {
 					.filter_map(|val| match val { &Value::String(ref str) => Some(str.to_owned()), _ => None })
 					.collect::<Vec<String>>()) {
 						Ok(()) => {
+							self.record_share(&worker, ShareResult::Accepted, None);
 							self.update_peers();
 							to_value(true)
 						},
 						Err(submit_err) => {
 							warn!("Error while submitting share: {:?}", submit_err);
+							self.record_share(&worker, ShareResult::Invalid, None);
 							to_value(false)
 						}
 					}
@@ -206,10 +316,182 @@ impl Stratum {
 		}.expect("Only true/false is returned and it's always serializable; qed")).boxed()
 	}
 
-	fn subscribe(&self, _params: Params, meta: SocketMetadata) -> RpcResult {
+	fn submit_share(&self, params: Params, addr: &SocketAddr, worker: Option<String>) -> bool {
+		let extranonce = match (self.sessions.read().get(addr), worker.is_some()) {
+			(Some(session), true) => session.extranonce,
+			_ => {
+				trace!(target: "stratum", "Share from unauthorized peer {}", addr);
+				return false;
+			},
+		};
+
+		// `[worker_id, job_id, nonce]`, the nonce without the extranonce
+		let (job_id, nonce) = match params.parse::<(String, String, String)>() {
+			Ok((_, job_id, nonce)) => (job_id, nonce),
+			Err(_) => {
+				trace!(target: "stratum", "Invalid share format {:?}", params);
+				self.record_share(&worker, ShareResult::Invalid, None);
+				return false;
+			},
+		};
+
+		let job = {
+			let jobs = self.jobs.read();
+			jobs.iter().enumerate()
+				.find(|&(_, work)| work.pow_hash.hex() == clean_0x(&job_id))
+				.map(|(age, work)| (age, work.clone()))
+		};
+
+		let (result, difficulty) = match (job, ethereum_stratum_nonce(extranonce, &nonce)) {
+			(_, None) => (ShareResult::Invalid, None),
+			(None, _) => (ShareResult::Stale, None),
+			(Some((age, work)), Some(nonce)) => {
+				let difficulty = self.share_difficulty(&work);
+				match self.dispatcher.submit_share(work, nonce, difficulty_to_boundary(&difficulty)) {
+					// an outdated job can still be sealed, but its shares don't help the current one
+					ShareResult::Accepted if age > 0 => (ShareResult::Stale, None),
+					result => (result, Some(difficulty)),
+				}
+			},
+		};
+
+		trace!(target: "stratum", "Share of job {} from {}: {:?}", job_id, addr, result);
+		self.record_share(&worker, result, difficulty);
+		match result {
+			ShareResult::Block => {
+				self.update_peers();
+				true
+			},
+			ShareResult::Accepted => true,
+			ShareResult::Stale | ShareResult::Invalid => false,
+		}
+	}
+
+	fn record_share(&self, worker: &Option<String>, result: ShareResult, difficulty: Option<U256>) {
+		if let Some(ref worker) = *worker {
+			self.stats.write().entry(worker.clone())
+				.or_insert_with(WorkerRecord::new)
+				.record(result, difficulty);
+		}
+	}
+
+	fn share_difficulty(&self, work: &Work) -> U256 {
+		match self.share_difficulty {
+			Some(difficulty) if difficulty < work.difficulty => difficulty,
+			_ => work.difficulty,
+		}
+	}
+
+	/// Record the current EthereumStratum/1.0.0 job. Returns it only if it changed,
+	/// along with whether the previous jobs should be abandoned.
+	fn update_work(&self) -> Option<(Work, bool)> {
+		let work = match self.dispatcher.work() {
+			Some(work) => work,
+			None => return None,
+		};
+
+		let mut jobs = self.jobs.write();
+		let clean = match jobs.front() {
+			Some(last) if *last == work => return None,
+			Some(last) => last.number != work.number,
+			None => true,
+		};
+		jobs.push_front(work.clone());
+		jobs.truncate(RECENT_JOBS);
+		Some((work, clean))
+	}
+
+	/// Push a job to an EthereumStratum/1.0.0 session, preceded by the share difficulty if it changed.
+	fn push_job(&self, addr: &SocketAddr, work: &Work, clean: bool) -> Result<(), PushMessageError> {
+		let difficulty = self.share_difficulty(work);
+		let difficulty_changed = match self.sessions.write().get_mut(addr) {
+			Some(session) => {
+				let changed = session.difficulty != Some(difficulty);
+				session.difficulty = Some(difficulty);
+				changed
+			},
+			None => return Ok(()),
+		};
+
+		if difficulty_changed {
+			self.tcp_dispatcher.push_message(addr, format!(
+				"{{ \"id\": null, \"method\": \"mining.set_difficulty\", \"params\": [{}] }}",
+				stratum_difficulty(&difficulty)
+			))?;
+		}
+		self.tcp_dispatcher.push_message(addr, format!(
+			"{{ \"id\": null, \"method\": \"mining.notify\", \"params\": [\"{}\", \"{}\", \"{}\", {}] }}",
+			work.pow_hash.hex(), work.seed_hash.hex(), work.pow_hash.hex(), clean
+		))
+	}
+
+	/// Push the current job to the authorized EthereumStratum/1.0.0 workers if it changed.
+	/// Returns the peers that are no longer connected.
+	fn push_ethereum_stratum_work(&self) -> HashSet<SocketAddr> {
+		let mut hup_peers = HashSet::new();
+		let (work, clean) = match self.update_work() {
+			Some(work) => work,
+			None => return hup_peers,
+		};
+
+		let authorized = self.workers.read().keys().cloned().collect::<Vec<_>>();
+		let addrs = {
+			let sessions = self.sessions.read();
+			authorized.into_iter().filter(|addr| sessions.contains_key(addr)).collect::<Vec<_>>()
+		};
+		trace!(target: "stratum", "pushing job {} for {} EthereumStratum workers", work.pow_hash, addrs.len());
+		for addr in addrs {
+			match self.push_job(&addr, &work, clean) {
+				Err(PushMessageError::NoSuchPeer) => {
+					trace!(target: "stratum", "Worker no longer connected: {}", &addr);
+					hup_peers.insert(addr);
+				},
+				Err(e) => {
+					warn!(target: "stratum", "Unexpected transport error: {:?}", e);
+				},
+				Ok(_) => { },
+			}
+		}
+		hup_peers
+	}
+
+	fn subscribe(&self, params: Params, meta: SocketMetadata) -> RpcResult {
 		use std::str::FromStr;
 
 		self.subscribers.write().push(meta.addr().clone());
+		let ethereum_stratum = match params {
+			Params::Array(ref vals) => vals.get(1).and_then(|val| val.as_str()) == Some(ETHEREUM_STRATUM),
+			_ => false,
+		};
+
+		if ethereum_stratum {
+			let extranonce = {
+				let mut sessions = self.sessions.write();
+				sessions.remove(meta.addr());
+				let extranonce = match self.allocate_extranonce(&sessions) {
+					Some(extranonce) => extranonce,
+					None => {
+						warn!(target: "stratum", "No extranonce left for the EthereumStratum subscription from {:?}", meta.addr());
+						return future::err(jsonrpc_core::Error::internal_error()).boxed();
+					},
+				};
+				sessions.insert(meta.addr().clone(), Session { extranonce: extranonce, difficulty: None, since: Instant::now() });
+				extranonce
+			};
+			trace!(target: "stratum", "EthereumStratum subscription request from {:?} (extranonce {:04x})", meta.addr(), extranonce);
+
+			// the first job is pushed once the worker is authorized
+			let extranonce = format!("{:04x}", extranonce);
+			return future::ok(Value::Array(vec![
+				Value::Array(vec![
+					Value::String("mining.notify".into()),
+					Value::String(extranonce.clone()),
+					Value::String(ETHEREUM_STRATUM.into()),
+				]),
+				Value::String(extranonce),
+			])).boxed();
+		}
+
 		self.job_que.write().insert(meta.addr().clone());
 		trace!(target: "stratum", "Subscription request from {:?}", meta.addr());
 
@@ -234,11 +516,58 @@ impl Stratum {
 				}
 			}
 			trace!(target: "stratum", "New worker #{} registered", worker_id);
+			self.stats.write().entry(worker_id.clone()).or_insert_with(WorkerRecord::new).last_active = Instant::now();
 			self.workers.write().insert(meta.addr().clone(), worker_id);
+
+			if self.sessions.read().contains_key(meta.addr()) {
+				self.update_work();
+				let work = self.jobs.read().front().cloned();
+				if let Some(work) = work {
+					if let Err(e) = self.push_job(meta.addr(), &work, true) {
+						warn!(target: "stratum", "Failed to push the job to {}: {:?}", meta.addr(), e);
+					}
+				}
+			}
 			to_value(true)
 		}).map(|v| v.expect("Only true/false is returned and it's always serializable; qed"))).boxed()
 	}
 
+	/// Allocate an extranonce not used by any of the given sessions, `None` if all of them are.
+	fn allocate_extranonce(&self, sessions: &HashMap<SocketAddr, Session>) -> Option<u16> {
+		let in_use = sessions.values().map(|session| session.extranonce).collect::<HashSet<_>>();
+		let mut next = self.next_extranonce.write();
+		for _ in 0..(::std::u16::MAX as u32 + 1) {
+			let extranonce = *next;
+			*next = next.wrapping_add(1);
+			if !in_use.contains(&extranonce) {
+				return Some(extranonce);
+			}
+		}
+		None
+	}
+
+	/// Drop EthereumStratum/1.0.0 sessions which didn't authorize a worker in time and the
+	/// statistics of workers which are neither connected nor have submitted shares recently.
+	/// Authorized sessions are dropped once pushing a job to them finds them disconnected.
+	fn prune(&self, now: Instant) {
+		let workers = self.workers.read();
+		self.sessions.write().retain(|addr, session| {
+			workers.contains_key(addr) || now.duration_since(session.since) < Duration::from_secs(UNAUTHORIZED_SESSION_SECS)
+		});
+
+		let connected = workers.values().collect::<HashSet<_>>();
+		self.stats.write().retain(|worker, record| {
+			connected.contains(worker) || now.duration_since(record.last_active) < Duration::from_secs(HASHRATE_WINDOW_SECS)
+		});
+	}
+
+	/// Share statistics of the authorized workers.
+	pub fn worker_stats(&self) -> BTreeMap<String, WorkerStats> {
+		self.stats.write().iter_mut()
+			.map(|(worker, record)| (worker.clone(), record.stats()))
+			.collect()
+	}
+
 	pub fn subscribers(&self) -> RwLockReadGuard<Vec<SocketAddr>> {
 		self.subscribers.read()
 	}
@@ -251,12 +580,15 @@ impl Stratum {
 				|json| self.tcp_dispatcher.push_message(&socket_addr, json.to_owned())
 			);
 		}
+		self.prune(Instant::now());
 	}
 }
 
 impl PushWorkHandler for Stratum {
 	fn push_work_all(&self, payload: String) -> Result<(), Error> {
-		let hup_peers = {
+		let mut hup_peers = {
+			// EthereumStratum/1.0.0 sessions get their own jobs
+			let ethereum_stratum = self.sessions.read().keys().cloned().collect::<HashSet<_>>();
 			let workers = self.workers.read();
 			let next_request_id = {
 				let mut counter = self.notify_counter.write();
@@ -268,7 +600,7 @@ impl PushWorkHandler for Stratum {
 			let mut hup_peers = HashSet::with_capacity(0); // most of the cases won't be needed, hence avoid allocation
 			let workers_msg = format!("{{ \"id\": {}, \"method\": \"mining.notify\", \"params\": {} }}", next_request_id, payload);
 			trace!(target: "stratum", "pushing work for {} workers (payload: '{}')", workers.len(), &workers_msg);
-			for (ref addr, _) in workers.iter() {
+			for (ref addr, _) in workers.iter().filter(|&(addr, _)| !ethereum_stratum.contains(addr)) {
 				trace!(target: "stratum", "pusing work to {}", addr);
 				match self.tcp_dispatcher.push_message(addr, workers_msg.clone()) {
 					Err(PushMessageError::NoSuchPeer) => {
@@ -283,11 +615,17 @@ impl PushWorkHandler for Stratum {
 			}
 			hup_peers
 		};
+		hup_peers.extend(self.push_ethereum_stratum_work());
 
 		if !hup_peers.is_empty() {
-			let mut workers = self.workers.write();
-			for hup_peer in hup_peers { workers.remove(&hup_peer); }
+			{
+				let mut workers = self.workers.write();
+				for hup_peer in &hup_peers { workers.remove(hup_peer); }
+			}
+			let mut sessions = self.sessions.write();
+			for hup_peer in &hup_peers { sessions.remove(hup_peer); }
 		}
+		self.prune(Instant::now());
 
 		Ok(())
 	}
@@ -316,6 +654,36 @@ impl PushWorkHandler for Stratum {
 	}
 }
 
+/// Ethash boundary of the given difficulty.
+fn difficulty_to_boundary(difficulty: &U256) -> H256 {
+	if *difficulty <= U256::one() {
+		U256::max_value().into()
+	} else {
+		(((U256::one() << 255) / *difficulty) << 1).into()
+	}
+}
+
+/// EthereumStratum/1.0.0 difficulty, difficulty 1 standing for 2^32 hashes.
+fn stratum_difficulty(difficulty: &U256) -> f64 {
+	difficulty.0.iter().rev().fold(0f64, |acc, &limb| acc * 18446744073709551616f64 + limb as f64) / 4294967296f64
+}
+
+/// Full nonce of an EthereumStratum/1.0.0 share. Miners submit only the bytes following the extranonce,
+/// but the full nonce is accepted as well.
+fn ethereum_stratum_nonce(extranonce: u16, nonce: &str) -> Option<u64> {
+	let nonce = clean_0x(nonce);
+	let value = match u64::from_str_radix(nonce, 16) {
+		Ok(value) => value,
+		Err(_) => return None,
+	};
+
+	match nonce.len() {
+		12 => Some(((extranonce as u64) << 48) | value),
+		16 if value >> 48 == extranonce as u64 => Some(value),
+		_ => None,
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -362,7 +730,7 @@ mod tests {
 
 	#[test]
 	fn can_be_started() {
-		let stratum = Stratum::start(&SocketAddr::from_str("127.0.0.1:19980").unwrap(), Arc::new(VoidManager), None);
+		let stratum = Stratum::start(&SocketAddr::from_str("127.0.0.1:19980").unwrap(), Arc::new(VoidManager), None, None);
 		assert!(stratum.is_ok());
 	}
 
@@ -371,7 +739,7 @@ mod tests {
 		init_log();
 
 		let addr = SocketAddr::from_str("127.0.0.1:19985").unwrap();
-		let stratum = Stratum::start(&addr, Arc::new(VoidManager), None).unwrap();
+		let stratum = Stratum::start(&addr, Arc::new(VoidManager), None, None).unwrap();
 		let request = r#"{"jsonrpc": "2.0", "method": "mining.subscribe", "params": [], "id": 1}"#;
 		dummy_request(&addr, request);
 		assert_eq!(1, stratum.subscribers.read().len());
@@ -416,7 +784,7 @@ mod tests {
 	#[test]
 	fn receives_initial_paylaod() {
 		let addr = SocketAddr::from_str("127.0.0.1:19975").unwrap();
-		Stratum::start(&addr, DummyManager::new(), None).expect("There should be no error starting stratum");
+		Stratum::start(&addr, DummyManager::new(), None, None).expect("There should be no error starting stratum");
 		let request = r#"{"jsonrpc": "2.0", "method": "mining.subscribe", "params": [], "id": 2}"#;
 
 		let response = String::from_utf8(dummy_request(&addr, request)).unwrap();
@@ -430,7 +798,8 @@ mod tests {
 		let stratum = Stratum::start(
 			&addr,
 			Arc::new(DummyManager::build().of_initial(r#"["dummy autorize payload"]"#)),
-			None
+			None,
+			None,
 		).expect("There should be no error starting stratum");
 
 		let request = r#"{"jsonrpc": "2.0", "method": "mining.authorize", "params": ["miner1", ""], "id": 1}"#;
@@ -448,7 +817,8 @@ mod tests {
 		let stratum = Stratum::start(
 			&addr,
 			Arc::new(DummyManager::build().of_initial(r#"["dummy autorize payload"]"#)),
-			None
+			None,
+			None,
 		).expect("There should be no error starting stratum");
 
 		let mut auth_request =
@@ -497,4 +867,122 @@ mod tests {
 			"{ \"id\": 17, \"method\": \"mining.notify\", \"params\": { \"00040008\", \"100500\" } }\n",
 			response);
 	}
+
+	struct ShareManager;
+
+	impl JobDispatcher for ShareManager {
+		fn submit(&self, _payload: Vec<String>) -> Result<(), Error> {
+			Ok(())
+		}
+
+		fn work(&self) -> Option<Work> {
+			Some(Work {
+				pow_hash: H256::from(1),
+				seed_hash: H256::from(2),
+				difficulty: U256::from(1_000_000),
+				number: 1,
+			})
+		}
+
+		fn submit_share(&self, _work: Work, nonce: u64, _share_target: H256) -> ShareResult {
+			// only even nonces meet the share difficulty
+			match nonce & 1 {
+				0 => ShareResult::Accepted,
+				_ => ShareResult::Invalid,
+			}
+		}
+	}
+
+	#[test]
+	fn tracks_ethereum_stratum_shares() {
+		let stratum = Stratum::start(
+			&SocketAddr::from_str("127.0.0.1:19960").unwrap(),
+			Arc::new(ShareManager),
+			None,
+			Some(U256::from(1000)),
+		).expect("There should be no error starting stratum");
+		let meta = SocketMetadata::from(SocketAddr::from_str("127.0.0.1:30000").unwrap());
+
+		let subscription = stratum.subscribe(
+			Params::Array(vec![Value::String("miner/1.0".into()), Value::String(ETHEREUM_STRATUM.into())]),
+			meta.clone()
+		).wait().unwrap();
+		assert_eq!(subscription, Value::from_str(r#"[["mining.notify","0000","EthereumStratum/1.0.0"],"0000"]"#).unwrap());
+
+		let authorization = stratum.authorize(
+			Params::Array(vec![Value::String("miner1".into()), Value::String("".into())]),
+			meta.clone()
+		).wait().unwrap();
+		assert_eq!(authorization, Value::Bool(true));
+
+		let submit = |job: String, nonce: &str| stratum.submit(
+			Params::Array(vec![Value::String("miner1".into()), Value::String(job), Value::String(nonce.into())]),
+			meta.clone()
+		).wait().unwrap();
+		let job = H256::from(1).hex();
+
+		assert_eq!(submit(job.clone(), "000000000002"), Value::Bool(true));
+		assert_eq!(submit(job.clone(), "0000000000000004"), Value::Bool(true));
+		assert_eq!(submit(job.clone(), "000000000003"), Value::Bool(false));
+		assert_eq!(submit(job.clone(), "0001000000000004"), Value::Bool(false));
+		assert_eq!(submit(H256::from(3).hex(), "000000000002"), Value::Bool(false));
+
+		let stats = stratum.worker_stats();
+		assert_eq!(stats.len(), 1);
+		assert_eq!(stats["miner1"].accepted, 2);
+		assert_eq!(stats["miner1"].invalid, 2);
+		assert_eq!(stats["miner1"].stale, 1);
+		assert!(stats["miner1"].hashrate > U256::zero());
+	}
+
+	#[test]
+	fn prunes_ethereum_stratum_sessions_and_stats() {
+		let stratum = Stratum::start(
+			&SocketAddr::from_str("127.0.0.1:19961").unwrap(),
+			Arc::new(ShareManager),
+			None,
+			None,
+		).expect("There should be no error starting stratum");
+		let subscribe = |addr: &str| stratum.subscribe(
+			Params::Array(vec![Value::String("miner/1.0".into()), Value::String(ETHEREUM_STRATUM.into())]),
+			SocketMetadata::from(SocketAddr::from_str(addr).unwrap())
+		).wait().unwrap();
+
+		// extranonces in use are skipped once the counter wraps
+		subscribe("127.0.0.1:30000");
+		*stratum.next_extranonce.write() = 0;
+		assert_eq!(subscribe("127.0.0.1:30001"), Value::from_str(r#"[["mining.notify","0001","EthereumStratum/1.0.0"],"0001"]"#).unwrap());
+
+		let authorization = stratum.authorize(
+			Params::Array(vec![Value::String("miner1".into()), Value::String("".into())]),
+			SocketMetadata::from(SocketAddr::from_str("127.0.0.1:30000").unwrap())
+		).wait().unwrap();
+		assert_eq!(authorization, Value::Bool(true));
+		stratum.stats.write().insert("gone".into(), WorkerRecord::new());
+
+		stratum.prune(Instant::now());
+		assert_eq!(stratum.sessions.read().len(), 2);
+		assert_eq!(stratum.stats.read().len(), 2);
+
+		// the unauthorized session and the statistics of the disconnected worker expire
+		stratum.prune(Instant::now() + Duration::from_secs(HASHRATE_WINDOW_SECS));
+		assert_eq!(stratum.sessions.read().keys().collect::<Vec<_>>(), vec![&SocketAddr::from_str("127.0.0.1:30000").unwrap()]);
+		assert_eq!(stratum.stats.read().keys().collect::<Vec<_>>(), vec!["miner1"]);
+	}
+
+	#[test]
+	fn ethereum_stratum_nonce_is_prefixed_with_extranonce() {
+		assert_eq!(ethereum_stratum_nonce(0xabcd, "000000000001"), Some(0xabcd000000000001));
+		assert_eq!(ethereum_stratum_nonce(0xabcd, "0xabcd00000000ffff"), Some(0xabcd00000000ffff));
+		assert_eq!(ethereum_stratum_nonce(0xabcd, "abce00000000ffff"), None);
+		assert_eq!(ethereum_stratum_nonce(0xabcd, "0001"), None);
+		assert_eq!(ethereum_stratum_nonce(0xabcd, "zz0000000001"), None);
+	}
+
+	#[test]
+	fn ethereum_stratum_difficulty() {
+		assert_eq!(stratum_difficulty(&U256::from(1u64 << 32)), 1f64);
+		assert_eq!(stratum_difficulty(&U256::from(1u64 << 31)), 0.5f64);
+		assert_eq!(stratum_difficulty(&(U256::from(1) << 96)), 18446744073709551616f64);
+	}
 }
@@ -16,7 +16,7 @@
 
 use std;
 use std::error::Error as StdError;
-use util::H256;
+use util::{H256, U256};
 use ipc::IpcConfig;
 use jsonrpc_tcp_server::PushMessageError;
 
@@ -42,6 +42,34 @@ impl From<PushMessageError> for Error {
 	}
 }
 
+/// Mining job of the EthereumStratum/1.0.0 dialect
+#[binary]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Work {
+	/// Hash of the header without the seal
+	pub pow_hash: H256,
+	/// Seed hash of the Ethash epoch
+	pub seed_hash: H256,
+	/// Block difficulty
+	pub difficulty: U256,
+	/// Block number
+	pub number: u64,
+}
+
+/// Outcome of a submitted share
+#[binary]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShareResult {
+	/// Share met the block difficulty and the block was sealed
+	Block,
+	/// Share met the share difficulty
+	Accepted,
+	/// Share is for work that is outdated or can no longer be sealed
+	Stale,
+	/// Share is malformed, doesn't meet the share difficulty or sealed a block which was rejected
+	Invalid,
+}
+
 /// Interface that can provide pow/blockchain-specific responses for the clients
 #[ipc(client_ident="RemoteJobDispatcher")]
 pub trait JobDispatcher: Send + Sync {
@@ -53,6 +81,10 @@ pub trait JobDispatcher: Send + Sync {
 	fn job(&self) -> Option<String> { None }
 	// miner job result
 	fn submit(&self, payload: Vec<String>) -> Result<(), Error>;
+	// current job for the EthereumStratum/1.0.0 dialect
+	fn work(&self) -> Option<Work> { None }
+	// verify the nonce of a share against `share_target`, sealing the block if it meets the block difficulty
+	fn submit_share(&self, _work: Work, _nonce: u64, _share_target: H256) -> ShareResult { ShareResult::Invalid }
 }
 
 /// Interface that can handle requests to push job for workers
@@ -71,6 +103,7 @@ pub struct ServiceConfiguration {
 	pub listen_addr: String,
 	pub port: u16,
 	pub secret: Option<H256>,
+	pub share_difficulty: Option<U256>,
 }
 
 impl IpcConfig for PushWorkHandler { }
@@ -62,6 +62,8 @@ pub struct SyncConfig {
 	pub fork_block: Option<(BlockNumber, H256)>,
 	/// Enable snapshot sync
 	pub warp_sync: bool,
+	/// Enable fast sync of the state at a recent block
+	pub fast_sync: bool,
 	/// Enable light client server.
 	pub serve_light: bool,
 }
@@ -76,6 +78,7 @@ impl Default for SyncConfig {
 			light_subprotocol_name: LIGHT_PROTOCOL,
 			fork_block: None,
 			warp_sync: false,
+			fast_sync: false,
 			serve_light: false,
 		}
 	}
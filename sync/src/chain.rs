@@ -103,6 +103,7 @@ use super::SyncConfig;
 use block_sync::{BlockDownloader, BlockRequest, BlockDownloaderImportError as DownloaderImportError, DownloadAction};
 use rand::Rng;
 use snapshot::{Snapshot, ChunkType};
+use state_sync::{StateDownload, Pivot};
 use api::{EthProtocolInfo as PeerInfoDigest, WARP_SYNC_PROTOCOL_ID};
 use transactions_stats::{TransactionsStats, Stats as TransactionStats};
 
@@ -133,6 +134,18 @@ const MAX_TRANSACTIONS_TO_PROPAGATE: usize = 64;
 // Min number of blocks to be behind for a snapshot sync
 const SNAPSHOT_RESTORE_THRESHOLD: BlockNumber = 100000;
 const SNAPSHOT_MIN_PEERS: usize = 3;
+// Number of blocks between the fast sync pivot and the head of the chain.
+const FAST_SYNC_PIVOT_DISTANCE: BlockNumber = 64;
+// Number of peers which must agree on the fast sync pivot and its total difficulty.
+const FAST_SYNC_MIN_PEERS: usize = 3;
+// Max number of headers to look for the fast sync pivot in when confirming it with a peer.
+const MAX_PIVOT_CONFIRMATION_HEADERS: BlockNumber = FAST_SYNC_PIVOT_DISTANCE * 2;
+// Max number of state nodes to request in a single packet.
+const MAX_NODE_DATA_TO_REQUEST: usize = 384;
+// Max number of queued state nodes to look up in the local database before a request.
+const MAX_LOCAL_NODES_TO_IMPORT: usize = 4096;
+// Number of node data responses to process between saving the fast sync progress.
+const FAST_SYNC_PERSIST_INTERVAL: usize = 256;
 
 const STATUS_PACKET: u8 = 0x00;
 const NEW_BLOCK_HASHES_PACKET: u8 = 0x01;
@@ -168,6 +181,7 @@ const RECEIPTS_TIMEOUT_SEC: u64 = 10;
 const FORK_HEADER_TIMEOUT_SEC: u64 = 3;
 const SNAPSHOT_MANIFEST_TIMEOUT_SEC: u64 = 5;
 const SNAPSHOT_DATA_TIMEOUT_SEC: u64 = 120;
+const NODE_DATA_TIMEOUT_SEC: u64 = 10;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 /// Sync state
@@ -180,6 +194,10 @@ pub enum SyncState {
 	SnapshotData,
 	/// Waiting for snapshot restoration progress.
 	SnapshotWaiting,
+	/// Downloading the fast sync pivot block
+	FastSyncPivot,
+	/// Downloading the state at the fast sync pivot block
+	FastSyncState,
 	/// Downloading new blocks
 	Blocks,
 	/// Initial chain sync complete. Waiting for new packets
@@ -258,6 +276,10 @@ enum PeerAsking {
 	BlockReceipts,
 	SnapshotManifest,
 	SnapshotData,
+	PivotHeaders,
+	PivotBody,
+	PivotReceipts,
+	NodeData,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
@@ -299,6 +321,8 @@ struct PeerInfo {
 	asking_hash: Option<H256>,
 	/// Holds requested snapshot chunk hash if any.
 	asking_snapshot_data: Option<H256>,
+	/// Holds requested state node hashes if any.
+	asking_node_data: Vec<H256>,
 	/// Request timestamp
 	ask_time: u64,
 	/// Holds a set of transactions recently sent to this peer to avoid spamming.
@@ -327,6 +351,7 @@ impl PeerInfo {
 	fn reset_asking(&mut self) {
 		self.asking_blocks.clear();
 		self.asking_hash = None;
+		self.asking_node_data.clear();
 		// mark any pending requests as expired
 		if self.asking != PeerAsking::Nothing && self.is_allowed() {
 			self.expired = true;
@@ -381,6 +406,16 @@ pub struct ChainSync {
 	download_old_blocks: bool,
 	/// Enable warp sync.
 	enable_warp_sync: bool,
+	/// Fast sync pivot and state downloader.
+	state_sync: StateDownload,
+	/// Peers which agree on the fast sync pivot.
+	pivot_confirmations: HashSet<PeerId>,
+	/// Whether the state nodes downloaded for a previous fast sync pivot may be reused.
+	reuse_state_nodes: bool,
+	/// Node data responses processed since the fast sync progress was last saved.
+	node_data_since_persist: usize,
+	/// Enable fast sync.
+	enable_fast_sync: bool,
 }
 
 type RlpResponseResult = Result<Option<(PacketId, RlpStream)>, PacketDecodeError>;
@@ -390,7 +425,7 @@ impl ChainSync {
 	pub fn new(config: SyncConfig, chain: &BlockChainClient) -> ChainSync {
 		let chain_info = chain.chain_info();
 		let mut sync = ChainSync {
-			state: if config.warp_sync { SyncState::WaitingPeers } else { SyncState::Idle },
+			state: if config.warp_sync || config.fast_sync { SyncState::WaitingPeers } else { SyncState::Idle },
 			starting_block: chain.chain_info().best_block_number,
 			highest_block: None,
 			peers: HashMap::new(),
@@ -406,6 +441,11 @@ impl ChainSync {
 			sync_start_time: None,
 			transactions_stats: TransactionsStats::default(),
 			enable_warp_sync: config.warp_sync,
+			state_sync: StateDownload::new(),
+			pivot_confirmations: HashSet::new(),
+			reuse_state_nodes: false,
+			node_data_since_persist: 0,
+			enable_fast_sync: config.fast_sync,
 		};
 		sync.update_targets(chain);
 		sync
@@ -460,6 +500,9 @@ impl ChainSync {
 
 	/// Abort all sync activity
 	pub fn abort(&mut self, io: &mut SyncIo) {
+		if self.state_sync.is_downloading_state() {
+			self.persist_fast_sync(io);
+		}
 		self.reset_and_continue(io);
 		self.peers.clear();
 	}
@@ -471,6 +514,7 @@ impl ChainSync {
 		let chain_info = io.chain().chain_info();
 		for (_, ref mut p) in &mut self.peers {
 			if p.block_set != Some(BlockSet::OldBlocks) {
+				self.state_sync.clear_node_download(&p.asking_node_data);
 				p.reset_asking();
 				if p.difficulty.is_none() {
 					// assume peer has up to date difficulty
@@ -478,7 +522,15 @@ impl ChainSync {
 				}
 			}
 		}
-		self.state = SyncState::Idle;
+		self.state = if self.state_sync.is_downloading_state() {
+			SyncState::FastSyncState
+		} else if self.state == SyncState::FastSyncPivot {
+			self.state_sync.clear();
+			self.pivot_confirmations.clear();
+			SyncState::WaitingPeers
+		} else {
+			SyncState::Idle
+		};
 		// Reactivate peers only if some progress has been made
 		// since the last sync round of if starting fresh.
 		self.active_peers = self.peers.keys().cloned().collect();
@@ -550,13 +602,160 @@ impl ChainSync {
 				trace!(target: "sync", "Starting unconfirmed snapshot sync {:?} with {:?}", hash, peers);
 				self.start_snapshot_sync(io, peers);
 			}
-		} else if timeout {
+		} else if timeout && !self.enable_fast_sync {
 			trace!(target: "sync", "No snapshots found, starting full sync");
 			self.state = SyncState::Idle;
 			self.continue_sync(io);
 		}
 	}
 
+	fn maybe_start_fast_sync(&mut self, io: &mut SyncIo) {
+		if !self.enable_fast_sync || self.state != SyncState::WaitingPeers {
+			return;
+		}
+		if io.chain().chain_info().best_block_number != 0 {
+			debug!(target: "sync", "Database is not empty, fast sync disabled");
+			self.enable_fast_sync = false;
+			if !self.enable_warp_sync {
+				self.state = SyncState::Idle;
+				self.continue_sync(io);
+			}
+			return;
+		}
+
+		if self.state_sync.pivot().is_none() {
+			if let Some(progress) = io.chain().fast_sync_progress() {
+				match StateDownload::from_rlp(&progress) {
+					Ok(download) => {
+						info!(target: "sync", "Resuming fast sync at block #{}", download.pivot().map_or(0, |p| p.number()));
+						self.state_sync = download;
+						self.state = SyncState::FastSyncState;
+						self.continue_sync(io);
+						return;
+					},
+					Err(e) => {
+						warn!(target: "sync", "Error restoring fast sync progress: {:?}", e);
+						io.chain().set_fast_sync_progress(None);
+					},
+				}
+			}
+		}
+
+		// wait for more peers to choose the pivot from and give warp sync a chance.
+		let timeout = self.sync_start_time.map_or(false, |t| ((time::precise_time_ns() - t) / 1_000_000_000) > WAIT_PEERS_TIMEOUT_SEC);
+		if !timeout {
+			return;
+		}
+
+		// the pivot is taken from the best peer and confirmed by the others.
+		let peers = self.fast_sync_pivot_peers();
+		if peers.len() < FAST_SYNC_MIN_PEERS {
+			trace!(target: "sync", "Waiting for more peers to start fast sync: {} of {}", peers.len(), FAST_SYNC_MIN_PEERS);
+			return;
+		}
+		let best_peer = peers.into_iter()
+			.max_by_key(|&(_, d)| d)
+			.map(|(id, _)| id)
+			.expect("there are at least FAST_SYNC_MIN_PEERS peers; qed");
+
+		trace!(target: "sync", "Starting fast sync with {}", best_peer);
+		self.state = SyncState::FastSyncPivot;
+		self.pivot_confirmations.clear();
+		// the pivot is verified against its parent, so ask for one more header.
+		self.request_pivot_headers(io, best_peer, FAST_SYNC_PIVOT_DISTANCE + 2);
+	}
+
+	/// Idle peers able to choose or confirm the fast sync pivot, with their total difficulty.
+	fn fast_sync_pivot_peers(&self) -> Vec<(PeerId, U256)> {
+		self.peers.iter()
+			.filter(|&(id, p)| self.active_peers.contains(id) && p.can_sync() && p.asking == PeerAsking::Nothing && p.protocol_version != PROTOCOL_VERSION_62)
+			.filter_map(|(id, p)| p.difficulty.map(|d| (*id, d)))
+			.collect()
+	}
+
+	/// Ask the other peers for the headers down from their best block to check that they agree on the pivot.
+	fn request_pivot_confirmations(&mut self, io: &mut SyncIo) {
+		let peers: Vec<PeerId> = self.fast_sync_pivot_peers().into_iter()
+			.map(|(id, _)| id)
+			.filter(|id| !self.pivot_confirmations.contains(id))
+			.collect();
+		for peer_id in peers {
+			self.request_pivot_headers(io, peer_id, MAX_PIVOT_CONFIRMATION_HEADERS);
+		}
+	}
+
+	/// Drop the fast sync pivot some peers disagree on and choose a new one with other peers.
+	fn reject_fast_sync_pivot(&mut self, io: &mut SyncIo, peer_id: PeerId) {
+		debug!(target: "sync", "{}: Disagrees on the fast sync pivot, choosing a new pivot", peer_id);
+		// it is not known which side is right, so none of them is used for the next pivot.
+		let confirmations: Vec<PeerId> = self.pivot_confirmations.drain().collect();
+		for p in confirmations {
+			self.deactivate_peer(io, p);
+		}
+		self.deactivate_peer(io, peer_id);
+		self.state_sync.clear();
+		self.state = SyncState::WaitingPeers;
+	}
+
+	/// Called once the whole state at the pivot block has been downloaded.
+	fn complete_fast_sync(&mut self, io: &mut SyncIo) {
+		let (block, receipts, parent_total_difficulty, number) = {
+			let pivot = self.state_sync.pivot().expect("fast sync is complete only when the pivot is set; qed");
+			(
+				pivot.block().expect("state is downloaded after the pivot body; qed"),
+				pivot.receipts.clone().expect("state is downloaded after the pivot receipts; qed"),
+				pivot.parent_total_difficulty,
+				pivot.number(),
+			)
+		};
+		match io.chain().import_fast_sync_pivot(block, receipts, parent_total_difficulty) {
+			Ok(hash) => info!(target: "sync", "Fast sync complete at block #{} ({})", number, hash),
+			Err(e) => warn!(target: "sync", "Error importing fast sync pivot block: {:?}, falling back to full sync", e),
+		}
+		self.enable_fast_sync = false;
+		self.state_sync.clear();
+		self.reuse_state_nodes = false;
+		self.node_data_since_persist = 0;
+		io.chain().set_fast_sync_progress(None);
+		self.restart(io);
+	}
+
+	/// Choose a new fast sync pivot when no peer serves the state of the current one anymore,
+	/// which happens once the peers prune it. Every peer able to serve node data has to return
+	/// nothing, as block numbers announced by peers are not verified during fast sync.
+	/// The nodes downloaded so far are reused for the new pivot.
+	fn maybe_refresh_fast_sync_pivot(&mut self, io: &mut SyncIo) {
+		if self.state != SyncState::FastSyncState || self.state_sync.is_complete() {
+			return;
+		}
+		let serving_peers: Vec<PeerId> = self.peers.iter()
+			.filter(|&(id, p)| p.can_sync() && self.serves_node_data(&*io, *id))
+			.map(|(id, _)| *id)
+			.collect();
+		let stalled = !serving_peers.is_empty()
+			&& !serving_peers.iter().any(|id| self.active_peers.contains(id))
+			&& !self.peers.values().any(|p| p.asking == PeerAsking::NodeData);
+		if !stalled {
+			return;
+		}
+
+		info!(target: "sync", "State at block #{} is no longer served, choosing a new fast sync pivot", self.state_sync.pivot().map_or(0, |p| p.number()));
+		self.state_sync.clear();
+		self.pivot_confirmations.clear();
+		self.reuse_state_nodes = true;
+		self.node_data_since_persist = 0;
+		io.chain().set_fast_sync_progress(None);
+		self.active_peers = self.peers.keys().cloned().collect();
+		self.state = SyncState::WaitingPeers;
+		self.maybe_start_fast_sync(io);
+	}
+
+	/// Save the state download progress so that it can be resumed after a restart.
+	fn persist_fast_sync(&mut self, io: &mut SyncIo) {
+		self.node_data_since_persist = 0;
+		io.chain().set_fast_sync_progress(self.state_sync.to_rlp());
+	}
+
 	fn start_snapshot_sync(&mut self, io: &mut SyncIo, peers: &[PeerId]) {
 		if !self.snapshot.have_manifest() {
 			for p in peers {
@@ -617,6 +816,7 @@ impl ChainSync {
 			expired: false,
 			confirmation: if self.fork_block.is_none() { ForkConfirmation::Confirmed } else { ForkConfirmation::Unconfirmed },
 			asking_snapshot_data: None,
+			asking_node_data: Vec::new(),
 			snapshot_hash: if warp_protocol { Some(r.val_at(5)?) } else { None },
 			snapshot_number: if warp_protocol { Some(r.val_at(6)?) } else { None },
 			block_set: None,
@@ -700,6 +900,9 @@ impl ChainSync {
 			self.sync_peer(io, peer_id, false);
 			return Ok(());
 		}
+		if self.peers.get(&peer_id).map_or(false, |p| p.asking == PeerAsking::PivotHeaders) {
+			return self.on_fast_sync_pivot_headers(io, peer_id, r);
+		}
 
 		self.clear_peer_download(peer_id);
 		let expected_hash = self.peers.get(&peer_id).and_then(|p| p.asking_hash);
@@ -771,6 +974,9 @@ impl ChainSync {
 
 	/// Called by peer once it has new block bodies
 	fn on_peer_block_bodies(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
+		if self.peers.get(&peer_id).map_or(false, |p| p.asking == PeerAsking::PivotBody) {
+			return self.on_fast_sync_pivot_body(io, peer_id, r);
+		}
 		self.clear_peer_download(peer_id);
 		let block_set = self.peers.get(&peer_id).and_then(|p| p.block_set).unwrap_or(BlockSet::NewBlocks);
 		if !self.reset_peer_asking(peer_id, PeerAsking::BlockBodies) {
@@ -825,6 +1031,9 @@ impl ChainSync {
 
 	/// Called by peer once it has new block receipts
 	fn on_peer_block_receipts(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
+		if self.peers.get(&peer_id).map_or(false, |p| p.asking == PeerAsking::PivotReceipts) {
+			return self.on_fast_sync_pivot_receipts(io, peer_id, r);
+		}
 		self.clear_peer_download(peer_id);
 		let block_set = self.peers.get(&peer_id).and_then(|p| p.block_set).unwrap_or(BlockSet::NewBlocks);
 		if !self.reset_peer_asking(peer_id, PeerAsking::BlockReceipts) {
@@ -877,6 +1086,212 @@ impl ChainSync {
 		Ok(())
 	}
 
+	/// Called by peer once it has sent the headers leading to the fast sync pivot block
+	fn on_fast_sync_pivot_headers(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
+		let (expected_hash, peer_difficulty) = match self.peers.get(&peer_id) {
+			Some(peer) => (peer.asking_hash, peer.difficulty),
+			None => return Ok(()),
+		};
+		if !self.reset_peer_asking(peer_id, PeerAsking::PivotHeaders) || self.state != SyncState::FastSyncPivot {
+			trace!(target: "sync", "{}: Ignored unexpected pivot headers", peer_id);
+			self.continue_sync(io);
+			return Ok(());
+		}
+		trace!(target: "sync", "{} -> BlockHeaders ({} entries), fast sync pivot", peer_id, r.item_count()?);
+
+		let headers: Vec<BlockHeader> = match r.as_list() {
+			Ok(headers) => headers,
+			Err(e) => {
+				debug!(target: "sync", "{}: Invalid pivot headers: {:?}", peer_id, e);
+				io.disable_peer(peer_id);
+				self.continue_sync(io);
+				return Ok(());
+			}
+		};
+
+		// headers go from the peer's best block down.
+		let linked = headers.first().map_or(false, |h| Some(h.hash()) == expected_hash)
+			&& headers.windows(2).all(|w| *w[0].parent_hash() == w[1].hash() && w[0].number() == w[1].number() + 1);
+		let peer_difficulty = match peer_difficulty {
+			Some(td) if linked => td,
+			_ => {
+				debug!(target: "sync", "{}: Pivot headers do not match the peer status", peer_id);
+				io.disable_peer(peer_id);
+				self.continue_sync(io);
+				return Ok(());
+			}
+		};
+
+		match self.state_sync.pivot().map(|p| (p.hash(), p.parent_total_difficulty)) {
+			None => self.choose_fast_sync_pivot(io, peer_id, headers, peer_difficulty),
+			Some((pivot_hash, parent_total_difficulty)) => self.confirm_fast_sync_pivot(io, peer_id, headers, peer_difficulty, pivot_hash, parent_total_difficulty),
+		}
+		Ok(())
+	}
+
+	/// Take the fast sync pivot from the headers down from the best block of a peer.
+	fn choose_fast_sync_pivot(&mut self, io: &mut SyncIo, peer_id: PeerId, mut headers: Vec<BlockHeader>, peer_difficulty: U256) {
+		let pivot_index = FAST_SYNC_PIVOT_DISTANCE as usize;
+		if headers.len() < pivot_index + 2 {
+			info!(target: "sync", "Chain is too short for fast sync, starting full sync");
+			self.enable_fast_sync = false;
+			self.state = SyncState::Idle;
+			self.continue_sync(io);
+			return;
+		}
+		headers.truncate(pivot_index + 2);
+
+		// the total difficulty is only trusted once other peers confirm it.
+		let parent_total_difficulty = verify_header_chain(io.chain(), &headers)
+			.map_err(|e| debug!(target: "sync", "{}: Invalid pivot headers: {:?}", peer_id, e))
+			.ok()
+			.and_then(|_| total_difficulty_before(peer_difficulty, &headers[..pivot_index + 1]));
+		let parent_total_difficulty = match parent_total_difficulty {
+			Some(td) => td,
+			None => {
+				io.disable_peer(peer_id);
+				self.continue_sync(io);
+				return;
+			}
+		};
+
+		headers.truncate(pivot_index + 1);
+		let pivot = headers.pop().expect("headers has more than FAST_SYNC_PIVOT_DISTANCE items; qed");
+		trace!(target: "sync", "{}: Fast sync pivot #{} ({}), waiting for confirmations", peer_id, pivot.number(), pivot.hash());
+		self.state_sync.reset_to(Pivot::new(pivot, parent_total_difficulty));
+		self.pivot_confirmations.clear();
+		self.pivot_confirmations.insert(peer_id);
+		self.request_pivot_confirmations(io);
+		self.continue_sync(io);
+	}
+
+	/// Check that the headers down from the best block of a peer lead to the chosen fast sync pivot
+	/// with the same total difficulty. Starts downloading the pivot block once enough peers agree.
+	fn confirm_fast_sync_pivot(&mut self, io: &mut SyncIo, peer_id: PeerId, headers: Vec<BlockHeader>, peer_difficulty: U256, pivot_hash: H256, parent_total_difficulty: U256) {
+		if self.pivot_confirmations.len() >= FAST_SYNC_MIN_PEERS {
+			// already confirmed, the pivot block is being downloaded.
+			self.continue_sync(io);
+			return;
+		}
+
+		// a peer far ahead or on another fork can neither confirm nor reject the pivot.
+		let pivot_index = match headers.iter().position(|h| h.hash() == pivot_hash) {
+			Some(index) => index,
+			None => {
+				trace!(target: "sync", "{}: Fast sync pivot not found in the peer chain", peer_id);
+				self.continue_sync(io);
+				return;
+			}
+		};
+
+		let agrees = verify_header_chain(io.chain(), &headers[..pivot_index + 1]).is_ok()
+			&& total_difficulty_before(peer_difficulty, &headers[..pivot_index + 1]) == Some(parent_total_difficulty);
+		if !agrees {
+			self.reject_fast_sync_pivot(io, peer_id);
+			self.continue_sync(io);
+			return;
+		}
+
+		self.pivot_confirmations.insert(peer_id);
+		trace!(target: "sync", "{}: Confirmed the fast sync pivot, {} of {} confirmations", peer_id, self.pivot_confirmations.len(), FAST_SYNC_MIN_PEERS);
+		if self.pivot_confirmations.len() >= FAST_SYNC_MIN_PEERS {
+			self.request_pivot_body(io, peer_id, pivot_hash);
+		} else {
+			self.continue_sync(io);
+		}
+	}
+
+	/// Called by peer once it has sent the fast sync pivot block body
+	fn on_fast_sync_pivot_body(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
+		if !self.reset_peer_asking(peer_id, PeerAsking::PivotBody) || self.state != SyncState::FastSyncPivot {
+			trace!(target: "sync", "{}: Ignored unexpected pivot body", peer_id);
+			self.continue_sync(io);
+			return Ok(());
+		}
+		let item_count = r.item_count()?;
+		trace!(target: "sync", "{} -> BlockBodies ({} entries), fast sync pivot", peer_id, item_count);
+		let pivot_hash = self.state_sync.pivot().map(|p| p.hash()).expect("pivot body is requested only when the pivot is set; qed");
+		if item_count != 1 || !self.state_sync.set_body(r.at(0)?.as_raw().to_vec()) {
+			debug!(target: "sync", "{}: Invalid pivot body", peer_id);
+			io.disable_peer(peer_id);
+			self.continue_sync(io);
+			return Ok(());
+		}
+		self.request_pivot_receipts(io, peer_id, pivot_hash);
+		Ok(())
+	}
+
+	/// Called by peer once it has sent the fast sync pivot block receipts
+	fn on_fast_sync_pivot_receipts(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
+		if !self.reset_peer_asking(peer_id, PeerAsking::PivotReceipts) || self.state != SyncState::FastSyncPivot {
+			trace!(target: "sync", "{}: Ignored unexpected pivot receipts", peer_id);
+			self.continue_sync(io);
+			return Ok(());
+		}
+		let item_count = r.item_count()?;
+		trace!(target: "sync", "{} -> BlockReceipts ({} entries), fast sync pivot", peer_id, item_count);
+		if item_count != 1 || !self.state_sync.set_receipts(r.at(0)?.as_raw().to_vec()) {
+			debug!(target: "sync", "{}: Invalid pivot receipts", peer_id);
+			io.disable_peer(peer_id);
+			self.continue_sync(io);
+			return Ok(());
+		}
+		info!(target: "sync", "Downloading state at block #{}", self.state_sync.pivot().map_or(0, |p| p.number()));
+		self.state = SyncState::FastSyncState;
+		self.persist_fast_sync(io);
+		if self.state_sync.is_complete() {
+			self.complete_fast_sync(io);
+		} else {
+			self.continue_sync(io);
+		}
+		Ok(())
+	}
+
+	/// Called by peer once it has sent the requested state nodes
+	fn on_peer_node_data(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
+		self.clear_peer_download(peer_id);
+		if !self.reset_peer_asking(peer_id, PeerAsking::NodeData) || self.state != SyncState::FastSyncState {
+			trace!(target: "sync", "{}: Ignored unexpected node data", peer_id);
+			self.continue_sync(io);
+			return Ok(());
+		}
+		let item_count = r.item_count()?;
+		trace!(target: "sync", "{} -> NodeData ({} entries)", peer_id, item_count);
+		if item_count == 0 {
+			self.deactivate_peer(io, peer_id);
+			self.continue_sync(io);
+			return Ok(());
+		}
+
+		match self.state_sync.import_nodes(r.as_list()?) {
+			Ok(imported) => {
+				if !imported.nodes.is_empty() {
+					io.chain().import_state_nodes(imported.nodes, imported.accounts);
+				}
+			},
+			Err(e) => {
+				debug!(target: "sync", "{}: Invalid node data: {:?}", peer_id, e);
+				io.disable_peer(peer_id);
+				self.deactivate_peer(io, peer_id);
+				self.continue_sync(io);
+				return Ok(());
+			}
+		}
+		trace!(target: "sync", "State download: {} nodes done, {} pending", self.state_sync.done_nodes(), self.state_sync.pending_nodes());
+
+		if self.state_sync.is_complete() {
+			self.complete_fast_sync(io);
+			return Ok(());
+		}
+		self.node_data_since_persist += 1;
+		if self.node_data_since_persist >= FAST_SYNC_PERSIST_INTERVAL {
+			self.persist_fast_sync(io);
+		}
+		self.sync_peer(io, peer_id, false);
+		self.continue_sync(io);
+		Ok(())
+	}
+
 	/// Called by peer once it has new block bodies
 	#[cfg_attr(feature="dev", allow(cyclomatic_complexity))]
 	fn on_peer_new_block(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
@@ -1154,6 +1569,7 @@ impl ChainSync {
 
 	/// Resume downloading
 	fn continue_sync(&mut self, io: &mut SyncIo) {
+		self.maybe_refresh_fast_sync_pivot(io);
 		let mut peers: Vec<(PeerId, U256, u8)> = self.peers.iter().filter_map(|(k, p)|
 			if p.can_sync() { Some((*k, p.difficulty.unwrap_or_else(U256::zero), p.protocol_version)) } else { None }).collect();
 		random::new().shuffle(&mut peers); //TODO: sort by rating
@@ -1165,7 +1581,18 @@ impl ChainSync {
 				self.sync_peer(io, p, false);
 			}
 		}
-		if (self.state != SyncState::WaitingPeers && self.state != SyncState::SnapshotWaiting && self.state != SyncState::Waiting && self.state != SyncState::Idle)
+		if self.state == SyncState::FastSyncPivot && !self.peers.values().any(|p| match p.asking {
+			PeerAsking::PivotHeaders | PeerAsking::PivotBody | PeerAsking::PivotReceipts => true,
+			_ => false,
+		}) {
+			trace!(target: "sync", "Fast sync pivot download failed, choosing a new pivot");
+			self.state_sync.clear();
+			self.pivot_confirmations.clear();
+			self.state = SyncState::WaitingPeers;
+		}
+
+		if (self.state != SyncState::WaitingPeers && self.state != SyncState::SnapshotWaiting && self.state != SyncState::Waiting && self.state != SyncState::Idle
+			&& self.state != SyncState::FastSyncPivot && self.state != SyncState::FastSyncState)
 			&& !self.peers.values().any(|p| p.asking != PeerAsking::Nothing && p.block_set != Some(BlockSet::OldBlocks) && p.can_sync()) {
 
 			self.complete_sync(io);
@@ -1220,6 +1647,7 @@ impl ChainSync {
 				SyncState::WaitingPeers => {
 					trace!(target: "sync", "Checking snapshot sync: {} vs {}", peer_snapshot_number, chain_info.best_block_number);
 					self.maybe_start_snapshot_sync(io);
+					self.maybe_start_fast_sync(io);
 				},
 				SyncState::Idle | SyncState::Blocks | SyncState::NewBlocks => {
 					if io.chain().queue_info().is_full() {
@@ -1258,7 +1686,10 @@ impl ChainSync {
 						self.request_snapshot_data(io, peer_id);
 					}
 				},
-				SyncState::SnapshotManifest | //already downloading from other peer
+				SyncState::FastSyncState => {
+					self.request_node_data(io, peer_id);
+				},
+				SyncState::SnapshotManifest | SyncState::FastSyncPivot | //already downloading from other peer
 					SyncState::Waiting | SyncState::SnapshotWaiting => ()
 			}
 		} else {
@@ -1293,6 +1724,46 @@ impl ChainSync {
 		}
 	}
 
+	/// Whether a peer can serve the state nodes for fast sync.
+	fn serves_node_data(&self, io: &SyncIo, peer_id: PeerId) -> bool {
+		// eth/62 peers do not serve node data.
+		self.peers.get(&peer_id).map_or(false, |p| p.protocol_version != PROTOCOL_VERSION_62)
+			&& serves_state_nodes(&io.peer_info(peer_id))
+	}
+
+	/// Find some state nodes to download for a peer.
+	fn request_node_data(&mut self, io: &mut SyncIo, peer_id: PeerId) {
+		if !self.serves_node_data(io, peer_id) {
+			return;
+		}
+		if self.reuse_state_nodes {
+			{
+				let chain = io.chain();
+				match self.state_sync.import_local_nodes(MAX_LOCAL_NODES_TO_IMPORT, |address_hash, hash| chain.state_node(address_hash, hash)) {
+					Ok(ref imported) if imported.nodes.is_empty() => {},
+					Ok(imported) => chain.import_state_nodes(imported.nodes, imported.accounts),
+					Err(e) => warn!(target: "sync", "Invalid local state nodes, downloading them again: {:?}", e),
+				}
+			}
+			if self.state_sync.is_complete() {
+				self.complete_fast_sync(io);
+				return;
+			}
+		}
+		let hashes = self.state_sync.needed_nodes(MAX_NODE_DATA_TO_REQUEST);
+		if hashes.is_empty() {
+			return;
+		}
+		trace!(target: "sync", "{} <- GetNodeData: {} entries", peer_id, hashes.len());
+		let mut rlp = RlpStream::new_list(hashes.len());
+		for h in &hashes {
+			rlp.append(h);
+		}
+		self.send_request(io, peer_id, PeerAsking::NodeData, GET_NODE_DATA_PACKET, rlp.out());
+		let peer = self.peers.get_mut(&peer_id).expect("peer_id may originate either from on_packet, where it is already validated or from enumerating self.peers. qed");
+		peer.asking_node_data = hashes;
+	}
+
 	/// Clear all blocks/headers marked as being downloaded by a peer.
 	fn clear_peer_download(&mut self, peer_id: PeerId) {
 		if let Some(ref mut peer) = self.peers.get_mut(&peer_id) {
@@ -1322,6 +1793,10 @@ impl ChainSync {
 						self.snapshot.clear_chunk_download(&hash);
 					}
 				},
+				PeerAsking::NodeData => {
+					self.state_sync.clear_node_download(&peer.asking_node_data);
+					peer.asking_node_data.clear();
+				},
 				_ => (),
 			}
 		}
@@ -1374,6 +1849,39 @@ impl ChainSync {
 		self.send_request(sync, peer_id, PeerAsking::ForkHeader, GET_BLOCK_HEADERS_PACKET, rlp.out());
 	}
 
+	/// Request `count` headers from a peer's best block down, to choose or confirm the fast sync pivot.
+	fn request_pivot_headers(&mut self, sync: &mut SyncIo, peer_id: PeerId, count: BlockNumber) {
+		let latest_hash = match self.peers.get(&peer_id) {
+			Some(peer) => peer.latest_hash,
+			None => return,
+		};
+		trace!(target: "sync", "{} <- GetBlockHeaders: fast sync pivot from {}", peer_id, latest_hash);
+		let mut rlp = RlpStream::new_list(4);
+		rlp.append(&latest_hash);
+		rlp.append(&count);
+		rlp.append(&0u32);
+		rlp.append(&1u32);
+		self.send_request(sync, peer_id, PeerAsking::PivotHeaders, GET_BLOCK_HEADERS_PACKET, rlp.out());
+		let peer = self.peers.get_mut(&peer_id).expect("peer_id may originate either from on_packet, where it is already validated or from enumerating self.peers. qed");
+		peer.asking_hash = Some(latest_hash);
+	}
+
+	/// Request the fast sync pivot block body from a peer.
+	fn request_pivot_body(&mut self, sync: &mut SyncIo, peer_id: PeerId, hash: H256) {
+		trace!(target: "sync", "{} <- GetBlockBodies: fast sync pivot {}", peer_id, hash);
+		let mut rlp = RlpStream::new_list(1);
+		rlp.append(&hash);
+		self.send_request(sync, peer_id, PeerAsking::PivotBody, GET_BLOCK_BODIES_PACKET, rlp.out());
+	}
+
+	/// Request the fast sync pivot block receipts from a peer.
+	fn request_pivot_receipts(&mut self, sync: &mut SyncIo, peer_id: PeerId, hash: H256) {
+		trace!(target: "sync", "{} <- GetReceipts: fast sync pivot {}", peer_id, hash);
+		let mut rlp = RlpStream::new_list(1);
+		rlp.append(&hash);
+		self.send_request(sync, peer_id, PeerAsking::PivotReceipts, GET_RECEIPTS_PACKET, rlp.out());
+	}
+
 	/// Request snapshot manifest from a peer.
 	fn request_snapshot_manifest(&mut self, sync: &mut SyncIo, peer_id: PeerId) {
 		trace!(target: "sync", "{} <- GetSnapshotManifest", peer_id);
@@ -1769,6 +2277,7 @@ impl ChainSync {
 			NEW_BLOCK_HASHES_PACKET => self.on_peer_new_hashes(io, peer, &rlp),
 			SNAPSHOT_MANIFEST_PACKET => self.on_snapshot_manifest(io, peer, &rlp),
 			SNAPSHOT_DATA_PACKET => self.on_snapshot_data(io, peer, &rlp),
+			NODE_DATA_PACKET => self.on_peer_node_data(io, peer, &rlp),
 			_ => {
 				debug!(target: "sync", "{}: Unknown packet {}", peer, packet_id);
 				Ok(())
@@ -1793,6 +2302,10 @@ impl ChainSync {
 				PeerAsking::ForkHeader => elapsed > FORK_HEADER_TIMEOUT_SEC,
 				PeerAsking::SnapshotManifest => elapsed > SNAPSHOT_MANIFEST_TIMEOUT_SEC,
 				PeerAsking::SnapshotData => elapsed > SNAPSHOT_DATA_TIMEOUT_SEC,
+				PeerAsking::PivotHeaders => elapsed > HEADERS_TIMEOUT_SEC,
+				PeerAsking::PivotBody => elapsed > BODIES_TIMEOUT_SEC,
+				PeerAsking::PivotReceipts => elapsed > RECEIPTS_TIMEOUT_SEC,
+				PeerAsking::NodeData => elapsed > NODE_DATA_TIMEOUT_SEC,
 			};
 			if timeout {
 				trace!(target:"sync", "Timeout {}", peer_id);
@@ -1982,7 +2495,9 @@ impl ChainSync {
 			return 0;
 		}
 
+		// transactions might no longer be permitted since they were queued.
 		let (transactions, service_transactions): (Vec<_>, Vec<_>) = transactions.into_iter()
+			.filter(|tx| io.chain().transaction_allowed(&tx.transaction))
 			.partition(|tx| !tx.transaction.gas_price.is_zero());
 
 		// usual transactions could be propagated to all peers
@@ -2158,6 +2673,7 @@ impl ChainSync {
 	/// Maintain other peers. Send out any new blocks and transactions
 	pub fn maintain_sync(&mut self, io: &mut SyncIo) {
 		self.maybe_start_snapshot_sync(io);
+		self.maybe_start_fast_sync(io);
 		self.check_resume(io);
 	}
 
@@ -2183,6 +2699,22 @@ impl ChainSync {
 			self.peers.values_mut().nth(peer).map(|mut peer_info|
 				peer_info.last_sent_transactions.clear()
 			);
+
+			// Local transactions which were not mined for a while are sent to all peers again.
+			let rebroadcast = io.chain().local_transactions_propagation()
+				.into_iter()
+				.filter(|&(_, ref propagation)| propagation.due)
+				.map(|(hash, _)| hash)
+				.collect::<Vec<_>>();
+			if !rebroadcast.is_empty() {
+				trace!(target: "sync", "Re-broadcasting {} local transactions to all peers.", rebroadcast.len());
+				for peer_info in self.peers.values_mut() {
+					for hash in &rebroadcast {
+						peer_info.last_sent_transactions.remove(hash);
+					}
+				}
+				io.chain().mark_local_transactions_rebroadcast(&rebroadcast);
+			}
 		}
 	}
 
@@ -2203,12 +2735,37 @@ impl ChainSync {
 	}
 }
 
+/// Verify the seals of headers ordered from the newest down, each against the next one.
+/// The last header is only used as the parent.
+fn verify_header_chain(chain: &BlockChainClient, headers: &[BlockHeader]) -> Result<(), ::ethcore::error::Error> {
+	for w in headers.windows(2) {
+		chain.verify_header_with_parent(&w[0], &w[1])?;
+	}
+	Ok(())
+}
+
+/// Total difficulty of the chain before the given headers, given the total difficulty including them.
+fn total_difficulty_before(total_difficulty: U256, headers: &[BlockHeader]) -> Option<U256> {
+	headers.iter().fold(Some(total_difficulty), |td, h| td.and_then(|td| match td.overflowing_sub(*h.difficulty()) {
+		(_, true) => None,
+		(td, false) => Some(td),
+	}))
+}
+
+// Parity client string prefix
+const PARITY_CLIENT_ID_PREFIX: &'static str = "Parity/v";
+
+/// Checks if peer is able to serve all the state nodes by hash. Parity stores the nodes of
+/// storage tries and code under keys combined with the account address hash, which are not
+/// known to `GetNodeData`, so only other clients (e.g. geth) are used for fast sync.
+fn serves_state_nodes(client_id: &str) -> bool {
+	!client_id.starts_with(PARITY_CLIENT_ID_PREFIX)
+}
+
 /// Checks if peer is able to process service transactions
 fn accepts_service_transaction(client_id: &str) -> bool {
 	// Parity versions starting from this will accept service-transactions
 	const SERVICE_TRANSACTIONS_VERSION: (u32, u32) = (1u32, 6u32);
-	// Parity client string prefix
-	const PARITY_CLIENT_ID_PREFIX: &'static str = "Parity/v";
 
 	if !client_id.starts_with(PARITY_CLIENT_ID_PREFIX) {
 		return false;
@@ -2226,8 +2783,8 @@ mod tests {
 	use network::PeerId;
 	use tests::helpers::*;
 	use tests::snapshot::TestSnapshotService;
-	use util::{U256, Address, RwLock};
-	use util::sha3::Hashable;
+	use util::{U256, Address, RwLock, HashDB, MemoryDB, TrieMut, SecTrieDBMut};
+	use util::sha3::{Hashable, SHA3_NULL_RLP, SHA3_EMPTY};
 	use util::hash::H256;
 	use util::bytes::Bytes;
 	use rlp::{Rlp, RlpStream, UntrustedRlp};
@@ -2237,8 +2794,9 @@ mod tests {
 	use ethkey;
 	use ethcore::header::*;
 	use ethcore::client::*;
-	use ethcore::transaction::UnverifiedTransaction;
+	use ethcore::transaction::{UnverifiedTransaction, Transaction, Action};
 	use ethcore::miner::MinerService;
+	use ethkey::Generator;
 
 	fn get_dummy_block(order: u32, parent_hash: H256) -> Bytes {
 		let mut header = Header::new();
@@ -2485,6 +3043,7 @@ mod tests {
 				snapshot_number: None,
 				snapshot_hash: None,
 				asking_snapshot_data: None,
+				asking_node_data: Vec::new(),
 				block_set: None,
 			});
 
@@ -2724,6 +3283,49 @@ mod tests {
 		assert_eq!(0x02, queue.read()[1].packet_id);
 	}
 
+	#[test]
+	fn rebroadcasts_local_transactions_to_all_peers() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		let tx = Transaction {
+			action: Action::Create,
+			value: 0.into(),
+			data: Vec::new(),
+			gas: 100_000.into(),
+			gas_price: 20_000_000_000u64.into(),
+			nonce: 0.into(),
+		}.sign(ethkey::Random.generate().unwrap().secret(), None);
+		client.set_balance(tx.sender(), 10_000_000_000_000_000_000u64.into());
+		client.miner.import_own_transaction(&client, tx.into()).unwrap();
+		client.miner.chain_new_blocks(&client, &[], &[], &[], &[]);
+
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(1), &client);
+		insert_dummy_peer(&mut sync, 1, client.block_hash_delta_minus(1));
+		insert_dummy_peer(&mut sync, 2, client.block_hash_delta_minus(1));
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let peer_count = {
+			let mut io = TestIo::new(&mut client, &ss, &queue, None);
+			sync.propagate_new_transactions(&mut io)
+		};
+
+		// not mined for 10 blocks
+		client.add_blocks(10, EachBlockWith::Nothing);
+		client.miner.chain_new_blocks(&client, &[], &[], &[], &[]);
+		assert_eq!(client.local_transactions_propagation().values().map(|p| p.rebroadcasts).collect::<Vec<_>>(), vec![0]);
+		let best_hash = client.chain_info().best_block_hash;
+		let peer_count2 = {
+			let mut io = TestIo::new(&mut client, &ss, &queue, None);
+			sync.chain_new_blocks(&mut io, &[best_hash], &[], &[best_hash], &[], &[], &[]);
+			sync.propagate_new_transactions(&mut io)
+		};
+
+		assert_eq!(3, peer_count);
+		assert_eq!(3, peer_count2);
+		// counted as rebroadcast only once it was sent again.
+		assert_eq!(client.local_transactions_propagation().values().map(|p| p.rebroadcasts).collect::<Vec<_>>(), vec![1]);
+	}
+
 	#[test]
 	fn should_maintain_transations_propagation_stats() {
 		let mut client = TestBlockChainClient::new();
@@ -3023,4 +3625,196 @@ mod tests {
 		assert_eq!(status.transactions_in_pending_queue, 0);
 		assert_eq!(status.transactions_in_future_queue, 0);
 	}
+
+	fn fast_sync(client: &TestBlockChainClient) -> ChainSync {
+		let mut config = SyncConfig::default();
+		config.fast_sync = true;
+		let mut sync = ChainSync::new(config, client);
+		// do not wait for warp sync peers.
+		sync.sync_start_time = Some(0);
+		sync
+	}
+
+	fn insert_fast_sync_peer(sync: &mut ChainSync, peer_id: PeerId, remote: &TestBlockChainClient) {
+		let chain_info = remote.chain_info();
+		insert_dummy_peer(sync, peer_id, chain_info.best_block_hash);
+		let peer = sync.peers.get_mut(&peer_id).unwrap();
+		peer.protocol_version = 63;
+		peer.difficulty = Some(chain_info.total_difficulty);
+		sync.active_peers.insert(peer_id);
+	}
+
+	// `count` headers down from the best block of `remote`.
+	fn pivot_headers_rlp(remote: &TestBlockChainClient, count: u64) -> Bytes {
+		let best = remote.chain_info().best_block_number;
+		let mut rlp = RlpStream::new_list(count as usize);
+		for n in (best + 1 - count..best + 1).rev() {
+			rlp.append_raw(&remote.block_header(BlockId::Number(n)).unwrap().into_inner(), 1);
+		}
+		rlp.out()
+	}
+
+	fn peers_asking(sync: &ChainSync, asking: PeerAsking) -> Vec<PeerId> {
+		let mut peers: Vec<PeerId> = sync.peers.iter().filter(|&(_, p)| p.asking == asking).map(|(id, _)| *id).collect();
+		peers.sort();
+		peers
+	}
+
+	// pivot block with an empty body and no receipts, waiting for the state at `state_root`.
+	fn fast_sync_state(sync: &mut ChainSync, state_root: H256) {
+		let mut header = Header::default();
+		header.set_number(100);
+		header.set_state_root(state_root);
+		sync.state_sync.reset_to(Pivot::new(header, 12345.into()));
+		let mut empty_body = RlpStream::new_list(2);
+		empty_body.begin_list(0);
+		empty_body.begin_list(0);
+		assert!(sync.state_sync.set_body(empty_body.out()));
+		assert!(sync.state_sync.set_receipts(RlpStream::new_list(0).out()));
+		sync.state = SyncState::FastSyncState;
+	}
+
+	fn start_fast_sync_pivot(sync: &mut ChainSync, io: &mut SyncIo, remote: &TestBlockChainClient) -> (PeerId, Vec<PeerId>) {
+		sync.maybe_start_fast_sync(io);
+		assert_eq!(sync.state, SyncState::FastSyncPivot);
+		let chooser = peers_asking(sync, PeerAsking::PivotHeaders);
+		assert_eq!(chooser.len(), 1);
+		let chooser = chooser[0];
+
+		let headers = pivot_headers_rlp(remote, FAST_SYNC_PIVOT_DISTANCE + 2);
+		sync.on_fast_sync_pivot_headers(io, chooser, &UntrustedRlp::new(&headers)).unwrap();
+		assert_eq!(sync.state_sync.pivot().map(|p| p.hash()), remote.block_hash(BlockId::Number(100 - FAST_SYNC_PIVOT_DISTANCE)));
+		(chooser, peers_asking(sync, PeerAsking::PivotHeaders))
+	}
+
+	#[test]
+	fn chooses_fast_sync_pivot_confirmed_by_peers() {
+		let mut remote = TestBlockChainClient::new();
+		remote.add_blocks(100, EachBlockWith::Nothing);
+		let client = TestBlockChainClient::new();
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&client, &ss, &queue, None);
+		let mut sync = fast_sync(&client);
+
+		insert_fast_sync_peer(&mut sync, 1, &remote);
+		insert_fast_sync_peer(&mut sync, 2, &remote);
+		sync.maybe_start_fast_sync(&mut io);
+		assert_eq!(sync.state, SyncState::WaitingPeers);
+		assert!(peers_asking(&sync, PeerAsking::PivotHeaders).is_empty());
+
+		insert_fast_sync_peer(&mut sync, 3, &remote);
+		let (chooser, confirmers) = start_fast_sync_pivot(&mut sync, &mut io, &remote);
+		assert_eq!(confirmers.len(), 2);
+		assert!(!confirmers.contains(&chooser));
+
+		let headers = pivot_headers_rlp(&remote, 100);
+		sync.on_fast_sync_pivot_headers(&mut io, confirmers[0], &UntrustedRlp::new(&headers)).unwrap();
+		assert!(peers_asking(&sync, PeerAsking::PivotBody).is_empty());
+		sync.on_fast_sync_pivot_headers(&mut io, confirmers[1], &UntrustedRlp::new(&headers)).unwrap();
+
+		assert_eq!(sync.state, SyncState::FastSyncPivot);
+		assert_eq!(peers_asking(&sync, PeerAsking::PivotBody), vec![confirmers[1]]);
+	}
+
+	#[test]
+	fn rejects_fast_sync_pivot_peers_disagree_on() {
+		let mut remote = TestBlockChainClient::new();
+		remote.add_blocks(100, EachBlockWith::Nothing);
+		let client = TestBlockChainClient::new();
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&client, &ss, &queue, None);
+		let mut sync = fast_sync(&client);
+		for peer_id in 1..4 {
+			insert_fast_sync_peer(&mut sync, peer_id, &remote);
+		}
+		let (chooser, confirmers) = start_fast_sync_pivot(&mut sync, &mut io, &remote);
+
+		// the same headers with a different total difficulty.
+		let dissenter = confirmers[0];
+		sync.peers.get_mut(&dissenter).unwrap().difficulty = Some(remote.chain_info().total_difficulty + U256::from(1));
+		let headers = pivot_headers_rlp(&remote, 100);
+		sync.on_fast_sync_pivot_headers(&mut io, dissenter, &UntrustedRlp::new(&headers)).unwrap();
+
+		assert_eq!(sync.state, SyncState::WaitingPeers);
+		assert!(sync.state_sync.pivot().is_none());
+		assert!(!sync.active_peers.contains(&chooser));
+		assert!(!sync.active_peers.contains(&dissenter));
+		assert!(sync.active_peers.contains(&confirmers[1]));
+	}
+
+	#[test]
+	fn refreshes_fast_sync_pivot_once_state_is_not_served() {
+		let client = TestBlockChainClient::new();
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&client, &ss, &queue, None);
+		io.peers_info.insert(3, "Parity/v1.7.0-beta".to_owned());
+		let mut sync = fast_sync(&client);
+		for peer_id in 1..4 {
+			insert_dummy_peer(&mut sync, peer_id, H256::random());
+			sync.peers.get_mut(&peer_id).unwrap().protocol_version = 63;
+			sync.active_peers.insert(peer_id);
+		}
+		fast_sync_state(&mut sync, H256::random());
+
+		// parity peers are not asked for state nodes.
+		sync.request_node_data(&mut io, 3);
+		assert!(peers_asking(&sync, PeerAsking::NodeData).is_empty());
+
+		sync.continue_sync(&mut io);
+		let first = peers_asking(&sync, PeerAsking::NodeData);
+		assert_eq!(first.len(), 1);
+		assert!(first[0] != 3);
+
+		let no_nodes = RlpStream::new_list(0).out();
+		sync.on_peer_node_data(&mut io, first[0], &UntrustedRlp::new(&no_nodes)).unwrap();
+		assert!(!sync.active_peers.contains(&first[0]));
+		assert_eq!(sync.state, SyncState::FastSyncState);
+		let second = peers_asking(&sync, PeerAsking::NodeData);
+		assert_eq!(second, vec![3 - first[0]]);
+
+		sync.on_peer_node_data(&mut io, second[0], &UntrustedRlp::new(&no_nodes)).unwrap();
+		assert_eq!(sync.state, SyncState::WaitingPeers);
+		assert!(sync.state_sync.pivot().is_none());
+		assert!(sync.reuse_state_nodes);
+		assert!(client.fast_sync_progress.read().is_none());
+		assert_eq!(sync.active_peers.len(), 3);
+	}
+
+	#[test]
+	fn completes_fast_sync_once_state_is_downloaded() {
+		let mut db = MemoryDB::new();
+		let mut state_root = H256::new();
+		{
+			let mut account = RlpStream::new_list(4);
+			account.append(&U256::zero()).append(&U256::from(1000)).append(&SHA3_NULL_RLP).append(&SHA3_EMPTY);
+			let mut trie = SecTrieDBMut::new(&mut db, &mut state_root);
+			trie.insert(&Address::random(), &account.out()).unwrap();
+		}
+		let root_node = db.get(&state_root).unwrap().to_vec();
+
+		let client = TestBlockChainClient::new();
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&client, &ss, &queue, None);
+		let mut sync = fast_sync(&client);
+		insert_dummy_peer(&mut sync, 1, H256::random());
+		sync.peers.get_mut(&1).unwrap().protocol_version = 63;
+		sync.active_peers.insert(1);
+		fast_sync_state(&mut sync, state_root);
+
+		sync.continue_sync(&mut io);
+		assert_eq!(peers_asking(&sync, PeerAsking::NodeData), vec![1]);
+		let mut nodes = RlpStream::new_list(1);
+		nodes.append(&root_node);
+		sync.on_peer_node_data(&mut io, 1, &UntrustedRlp::new(&nodes.out())).unwrap();
+
+		assert!(client.state_nodes.read().contains_key(&state_root));
+		assert!(client.fast_sync_pivot.read().is_some());
+		assert!(client.fast_sync_progress.read().is_none());
+		assert!(!sync.enable_fast_sync);
+		assert!(sync.state_sync.pivot().is_none());
+	}
 }
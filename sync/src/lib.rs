@@ -57,6 +57,7 @@ mod blocks;
 mod block_sync;
 mod sync_io;
 mod snapshot;
+mod state_sync;
 mod transactions_stats;
 
 pub mod light_sync;
@@ -0,0 +1,712 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State download for fast sync.
+//!
+//! The state trie at a pivot block is downloaded node by node with `GetNodeData`,
+//! starting from the state root. Every received node is checked against the hash it was
+//! requested by and decoded to find the nodes it references, down to account storage
+//! tries and code.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use ethcore::header::{BlockNumber, Header as BlockHeader};
+use rlp::{RlpStream, UntrustedRlp, Prototype, DecoderError};
+use util::{Bytes, H256, U256, Hashable};
+use util::sha3::{SHA3_NULL_RLP, SHA3_EMPTY};
+use util::triehash::ordered_trie_root;
+
+/// Trie a state node belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+	/// Node of the account trie.
+	Account,
+	/// Node of the storage trie of the account with given address hash.
+	Storage(H256),
+	/// Code of the account with given address hash.
+	Code(H256),
+}
+
+impl NodeKind {
+	/// Address hash the node is stored under, if it belongs to an account.
+	pub fn address_hash(&self) -> Option<H256> {
+		match *self {
+			NodeKind::Account => None,
+			NodeKind::Storage(hash) | NodeKind::Code(hash) => Some(hash),
+		}
+	}
+}
+
+// a place in the state a node is needed at.
+#[derive(Debug, Clone, PartialEq)]
+struct PendingNode {
+	kind: NodeKind,
+	// nibbles of the key leading to the node from the root of its trie.
+	path: Vec<u8>,
+}
+
+/// Block the state is downloaded at.
+#[derive(Debug, Clone)]
+pub struct Pivot {
+	/// Block header.
+	pub header: BlockHeader,
+	/// Block body RLP, once downloaded.
+	pub body: Option<Bytes>,
+	/// Block receipts RLP, once downloaded.
+	pub receipts: Option<Bytes>,
+	/// Total difficulty of the parent block.
+	pub parent_total_difficulty: U256,
+}
+
+impl Pivot {
+	/// Create a new pivot with a header and the total difficulty of its parent.
+	pub fn new(header: BlockHeader, parent_total_difficulty: U256) -> Self {
+		Pivot {
+			header: header,
+			body: None,
+			receipts: None,
+			parent_total_difficulty: parent_total_difficulty,
+		}
+	}
+
+	/// Pivot block hash.
+	pub fn hash(&self) -> H256 {
+		self.header.hash()
+	}
+
+	/// Pivot block number.
+	pub fn number(&self) -> BlockNumber {
+		self.header.number()
+	}
+
+	/// Full block RLP. Only available once the body is downloaded.
+	pub fn block(&self) -> Option<Bytes> {
+		self.body.as_ref().map(|body| {
+			let body = UntrustedRlp::new(body);
+			let mut stream = RlpStream::new_list(3);
+			stream.append(&self.header);
+			stream.append_raw(body.at(0).expect("body checked on download; qed").as_raw(), 1);
+			stream.append_raw(body.at(1).expect("body checked on download; qed").as_raw(), 1);
+			stream.out()
+		})
+	}
+
+	// check that a block body belongs to the pivot header.
+	fn check_body(&self, body: &UntrustedRlp) -> Result<bool, DecoderError> {
+		let transactions = body.at(0)?;
+		let uncles = body.at(1)?;
+		let transactions_root = ordered_trie_root(transactions.iter().map(|r| r.as_raw().to_vec()));
+		Ok(transactions_root == *self.header.transactions_root() && uncles.as_raw().sha3() == *self.header.uncles_hash())
+	}
+
+	// check that block receipts belong to the pivot header.
+	fn check_receipts(&self, receipts: &UntrustedRlp) -> Result<bool, DecoderError> {
+		if !receipts.is_list() {
+			return Err(DecoderError::RlpExpectedToBeList);
+		}
+		let receipts_root = ordered_trie_root(receipts.iter().map(|r| r.as_raw().to_vec()));
+		Ok(receipts_root == *self.header.receipts_root())
+	}
+}
+
+/// State nodes ready to be written to the database.
+#[derive(Debug, Default)]
+pub struct ImportedNodes {
+	/// Node data along with the address hash of the account it belongs to, if any.
+	pub nodes: Vec<(Option<H256>, Bytes)>,
+	/// Address hashes of all the accounts found in the nodes.
+	pub accounts: Vec<H256>,
+}
+
+/// Fast sync pivot and state download progress.
+pub struct StateDownload {
+	pivot: Option<Pivot>,
+	/// Nodes not received yet, with all the places in the state they are needed at.
+	pending: HashMap<H256, Vec<PendingNode>>,
+	/// Pending nodes which are not being downloaded.
+	queue: VecDeque<H256>,
+	/// Pending nodes known to be missing from the local database.
+	missing: VecDeque<H256>,
+	downloading: HashSet<H256>,
+	done: usize,
+}
+
+impl StateDownload {
+	/// Create a new instance.
+	pub fn new() -> StateDownload {
+		StateDownload {
+			pivot: None,
+			pending: HashMap::new(),
+			queue: VecDeque::new(),
+			missing: VecDeque::new(),
+			downloading: HashSet::new(),
+			done: 0,
+		}
+	}
+
+	/// Clear everything.
+	pub fn clear(&mut self) {
+		self.pivot = None;
+		self.pending.clear();
+		self.queue.clear();
+		self.missing.clear();
+		self.downloading.clear();
+		self.done = 0;
+	}
+
+	/// Start over with a new pivot block.
+	pub fn reset_to(&mut self, pivot: Pivot) {
+		self.clear();
+		self.pivot = Some(pivot);
+	}
+
+	/// Get the pivot block, if chosen.
+	pub fn pivot(&self) -> Option<&Pivot> {
+		self.pivot.as_ref()
+	}
+
+	/// Set the pivot block body. Returns `false` if it does not match the header.
+	pub fn set_body(&mut self, body: Bytes) -> bool {
+		let pivot = match self.pivot {
+			Some(ref mut pivot) => pivot,
+			None => return false,
+		};
+		match pivot.check_body(&UntrustedRlp::new(&body)) {
+			Ok(true) => {
+				pivot.body = Some(body);
+				true
+			},
+			_ => false,
+		}
+	}
+
+	/// Set the pivot block receipts and start downloading the state.
+	/// Returns `false` if they do not match the header.
+	pub fn set_receipts(&mut self, receipts: Bytes) -> bool {
+		let state_root = {
+			let pivot = match self.pivot {
+				Some(ref mut pivot) => pivot,
+				None => return false,
+			};
+			match pivot.check_receipts(&UntrustedRlp::new(&receipts)) {
+				Ok(true) => pivot.receipts = Some(receipts),
+				_ => return false,
+			}
+			*pivot.header.state_root()
+		};
+
+		if state_root != SHA3_NULL_RLP {
+			self.add_pending(state_root, PendingNode { kind: NodeKind::Account, path: Vec::new() });
+		}
+		true
+	}
+
+	/// Whether the pivot block is fully downloaded and the state download has started.
+	pub fn is_downloading_state(&self) -> bool {
+		self.pivot.as_ref().map_or(false, |p| p.body.is_some() && p.receipts.is_some())
+	}
+
+	/// Whether the whole state has been downloaded.
+	pub fn is_complete(&self) -> bool {
+		self.is_downloading_state() && self.pending.is_empty()
+	}
+
+	/// Number of nodes downloaded so far.
+	pub fn done_nodes(&self) -> usize {
+		self.done
+	}
+
+	/// Number of nodes known to be missing.
+	pub fn pending_nodes(&self) -> usize {
+		self.pending.len()
+	}
+
+	/// Find up to `count` nodes to download and mark them as being downloaded.
+	pub fn needed_nodes(&mut self, count: usize) -> Vec<H256> {
+		let mut needed = Vec::new();
+		while needed.len() < count {
+			match self.missing.pop_front().or_else(|| self.queue.pop_front()) {
+				Some(hash) => {
+					// may have been received from another peer after being re-queued.
+					if self.pending.contains_key(&hash) && self.downloading.insert(hash) {
+						needed.push(hash);
+					}
+				},
+				None => break,
+			}
+		}
+		needed
+	}
+
+	/// Import queued nodes which are already in the local database, such as the nodes downloaded
+	/// for a previous pivot. Up to `count` queued nodes are looked up with `lookup`, given the
+	/// address hash the node is stored under. Returns the imported nodes missing from the database.
+	pub fn import_local_nodes<F>(&mut self, count: usize, lookup: F) -> Result<ImportedNodes, DecoderError>
+		where F: Fn(Option<H256>, &H256) -> Option<Bytes>
+	{
+		let mut found = Vec::new();
+		let mut found_hashes = Vec::new();
+		for _ in 0..count {
+			let hash = match self.queue.pop_front() {
+				Some(hash) => hash,
+				None => break,
+			};
+			// may have been received from another peer after being re-queued.
+			let address_hash = match self.pending.get(&hash) {
+				Some(places) if !self.downloading.contains(&hash) => places[0].kind.address_hash(),
+				_ => continue,
+			};
+			match lookup(address_hash, &hash) {
+				Some(data) => {
+					found.push(data);
+					found_hashes.push(hash);
+				},
+				None => self.missing.push_back(hash),
+			}
+		}
+
+		let mut imported = match self.import_nodes(found) {
+			Ok(imported) => imported,
+			Err(e) => {
+				self.missing.extend(found_hashes);
+				return Err(e);
+			}
+		};
+		// an identical node may still be missing under another account.
+		imported.nodes.retain(|&(address_hash, ref data)| lookup(address_hash, &data.sha3()).is_none());
+		Ok(imported)
+	}
+
+	/// Return nodes requested from a peer which have not been received to the queue.
+	pub fn clear_node_download(&mut self, hashes: &[H256]) {
+		for hash in hashes {
+			if self.downloading.remove(hash) && self.pending.contains_key(hash) {
+				self.queue.push_back(*hash);
+			}
+		}
+	}
+
+	/// Import node data received from a peer. Nodes which were not requested are ignored.
+	/// Fails if a requested node could not be decoded, in which case nothing is imported.
+	pub fn import_nodes(&mut self, nodes: Vec<Bytes>) -> Result<ImportedNodes, DecoderError> {
+		let mut decoded = Vec::new();
+		for data in nodes {
+			let hash = data.sha3();
+			let places = match self.pending.get(&hash) {
+				Some(places) => places.clone(),
+				None => {
+					trace!(target: "sync", "Ignored unexpected node data {}", hash);
+					continue;
+				}
+			};
+
+			let mut children = Vec::new();
+			let mut accounts = Vec::new();
+			for place in &places {
+				match place.kind {
+					NodeKind::Code(_) => {},
+					kind => node_children(kind, &place.path, &UntrustedRlp::new(&data), &mut children, &mut accounts)?,
+				}
+			}
+			decoded.push((hash, data, places, children, accounts));
+		}
+
+		let mut imported = ImportedNodes::default();
+		for (hash, data, places, children, accounts) in decoded {
+			// the same node could have been sent twice in one response.
+			if self.pending.remove(&hash).is_none() {
+				continue;
+			}
+			self.downloading.remove(&hash);
+			self.done += 1;
+
+			for (child, place) in children {
+				self.add_pending(child, place);
+			}
+			imported.accounts.extend(accounts);
+
+			// an identical node may be stored under several accounts.
+			let mut kinds: Vec<_> = places.into_iter().map(|p| p.kind.address_hash()).collect();
+			kinds.sort();
+			kinds.dedup();
+			let last = kinds.pop().expect("pending nodes are always needed at one place at least; qed");
+			for address_hash in kinds {
+				imported.nodes.push((address_hash, data.clone()));
+			}
+			imported.nodes.push((last, data));
+		}
+		Ok(imported)
+	}
+
+	/// Serialize the download progress. Only the state download can be resumed,
+	/// so there is nothing to persist before the pivot block is downloaded.
+	pub fn to_rlp(&self) -> Option<Bytes> {
+		let pivot = match self.pivot {
+			Some(ref pivot) if self.is_downloading_state() => pivot,
+			_ => return None,
+		};
+
+		let mut stream = RlpStream::new_list(6);
+		stream.append(&pivot.header);
+		stream.append_raw(pivot.body.as_ref().expect("checked by is_downloading_state; qed"), 1);
+		stream.append_raw(pivot.receipts.as_ref().expect("checked by is_downloading_state; qed"), 1);
+		stream.append(&pivot.parent_total_difficulty);
+		stream.append(&(self.done as u64));
+
+		let places = self.pending.values().map(Vec::len).sum();
+		stream.begin_list(places);
+		for (hash, places) in &self.pending {
+			for place in places {
+				let (kind, address_hash) = match place.kind {
+					NodeKind::Account => (0u8, H256::new()),
+					NodeKind::Storage(hash) => (1u8, hash),
+					NodeKind::Code(hash) => (2u8, hash),
+				};
+				stream.begin_list(4)
+					.append(hash)
+					.append(&kind)
+					.append(&address_hash)
+					.append(&place.path);
+			}
+		}
+		Some(stream.out())
+	}
+
+	/// Restore the download progress serialized with `to_rlp`.
+	pub fn from_rlp(data: &[u8]) -> Result<StateDownload, DecoderError> {
+		let rlp = UntrustedRlp::new(data);
+		let mut pivot = Pivot::new(rlp.val_at(0)?, rlp.val_at(3)?);
+		pivot.body = Some(rlp.at(1)?.as_raw().to_vec());
+		pivot.receipts = Some(rlp.at(2)?.as_raw().to_vec());
+
+		let mut download = StateDownload::new();
+		download.pivot = Some(pivot);
+		download.done = rlp.val_at::<u64>(4)? as usize;
+		for item in rlp.at(5)?.iter() {
+			let hash = item.val_at(0)?;
+			let address_hash = item.val_at(2)?;
+			let kind = match item.val_at::<u8>(1)? {
+				0 => NodeKind::Account,
+				1 => NodeKind::Storage(address_hash),
+				2 => NodeKind::Code(address_hash),
+				_ => return Err(DecoderError::Custom("Unknown state node kind")),
+			};
+			download.add_pending(hash, PendingNode { kind: kind, path: item.val_at(3)? });
+		}
+		Ok(download)
+	}
+
+	fn add_pending(&mut self, hash: H256, place: PendingNode) {
+		let places = self.pending.entry(hash).or_insert_with(Vec::new);
+		if places.is_empty() {
+			self.queue.push_back(hash);
+		}
+		places.push(place);
+	}
+}
+
+// decode nibbles of a hex-prefix encoded partial key and whether it ends at a leaf.
+fn decode_partial_key(encoded: &[u8]) -> Result<(Vec<u8>, bool), DecoderError> {
+	let first = match encoded.first() {
+		Some(first) => *first,
+		None => return Err(DecoderError::Custom("Empty partial key")),
+	};
+
+	let is_leaf = first & 0x20 != 0;
+	let mut nibbles = Vec::with_capacity(encoded.len() * 2);
+	if first & 0x10 != 0 {
+		nibbles.push(first & 0x0f);
+	}
+	for byte in &encoded[1..] {
+		nibbles.push(byte >> 4);
+		nibbles.push(byte & 0x0f);
+	}
+	Ok((nibbles, is_leaf))
+}
+
+// find nodes referenced by the given trie node.
+fn node_children(
+	kind: NodeKind,
+	path: &[u8],
+	node: &UntrustedRlp,
+	children: &mut Vec<(H256, PendingNode)>,
+	accounts: &mut Vec<H256>,
+) -> Result<(), DecoderError> {
+	match node.prototype()? {
+		Prototype::List(2) => {
+			let (partial, is_leaf) = decode_partial_key(node.at(0)?.data()?)?;
+			let mut path = path.to_vec();
+			path.extend(partial);
+			if !is_leaf {
+				child_reference(kind, path, &node.at(1)?, children, accounts)
+			} else if kind == NodeKind::Account {
+				account_children(path, node.at(1)?.data()?, children, accounts)
+			} else {
+				Ok(())
+			}
+		},
+		Prototype::List(17) => {
+			for i in 0..16 {
+				let mut child_path = path.to_vec();
+				child_path.push(i as u8);
+				child_reference(kind, child_path, &node.at(i)?, children, accounts)?;
+			}
+			Ok(())
+		},
+		Prototype::Data(0) => Ok(()),
+		_ => Err(DecoderError::Custom("Invalid trie node")),
+	}
+}
+
+// follow a reference to a child node, which is either a hash or the node itself.
+fn child_reference(
+	kind: NodeKind,
+	path: Vec<u8>,
+	reference: &UntrustedRlp,
+	children: &mut Vec<(H256, PendingNode)>,
+	accounts: &mut Vec<H256>,
+) -> Result<(), DecoderError> {
+	if reference.is_empty() {
+		Ok(())
+	} else if reference.is_list() {
+		node_children(kind, &path, reference, children, accounts)
+	} else {
+		children.push((reference.as_val()?, PendingNode { kind: kind, path: path }));
+		Ok(())
+	}
+}
+
+// find the storage trie and code of an account stored at the given path.
+fn account_children(
+	path: Vec<u8>,
+	account: &[u8],
+	children: &mut Vec<(H256, PendingNode)>,
+	accounts: &mut Vec<H256>,
+) -> Result<(), DecoderError> {
+	if path.len() != 64 {
+		return Err(DecoderError::Custom("Invalid account key length"));
+	}
+
+	let mut address_hash = H256::new();
+	for (byte, nibbles) in address_hash.iter_mut().zip(path.chunks(2)) {
+		*byte = (nibbles[0] << 4) | nibbles[1];
+	}
+
+	let account = UntrustedRlp::new(account);
+	let storage_root: H256 = account.val_at(2)?;
+	let code_hash: H256 = account.val_at(3)?;
+
+	if storage_root != SHA3_NULL_RLP {
+		children.push((storage_root, PendingNode { kind: NodeKind::Storage(address_hash), path: Vec::new() }));
+	}
+	if code_hash != SHA3_EMPTY {
+		children.push((code_hash, PendingNode { kind: NodeKind::Code(address_hash), path: Vec::new() }));
+	}
+	accounts.push(address_hash);
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rlp;
+	use util::{Address, HashDB, MemoryDB, TrieMut, SecTrieDBMut};
+
+	fn account_rlp(nonce: u64, storage_root: H256, code_hash: H256) -> Bytes {
+		let mut stream = RlpStream::new_list(4);
+		stream.append(&U256::from(nonce));
+		stream.append(&U256::from(1000));
+		stream.append(&storage_root);
+		stream.append(&code_hash);
+		stream.out()
+	}
+
+	// build a state with plain accounts, an account with storage and an account with code.
+	fn test_state(db: &mut MemoryDB) -> (H256, Vec<Address>) {
+		let addresses: Vec<Address> = (0..50).map(|_| Address::random()).collect();
+
+		let mut storage_root = H256::new();
+		{
+			let mut trie = SecTrieDBMut::new(db, &mut storage_root);
+			for i in 0..100u64 {
+				trie.insert(&H256::from(i), &rlp::encode(&U256::from(i + 1))).unwrap();
+			}
+		}
+		let code_hash = db.insert(b"some contract code");
+
+		let mut state_root = H256::new();
+		{
+			let mut trie = SecTrieDBMut::new(db, &mut state_root);
+			for (i, address) in addresses.iter().enumerate() {
+				let account = match i {
+					0 => account_rlp(0, storage_root, SHA3_EMPTY),
+					1 => account_rlp(1, SHA3_NULL_RLP, code_hash),
+					_ => account_rlp(i as u64, SHA3_NULL_RLP, SHA3_EMPTY),
+				};
+				trie.insert(address, &account).unwrap();
+			}
+		}
+		(state_root, addresses)
+	}
+
+	fn start_download(state_root: H256) -> StateDownload {
+		let mut header = BlockHeader::default();
+		header.set_number(100);
+		header.set_state_root(state_root);
+
+		let mut download = StateDownload::new();
+		download.reset_to(Pivot::new(header, 12345.into()));
+		let mut empty_body = RlpStream::new_list(2);
+		empty_body.begin_list(0);
+		empty_body.begin_list(0);
+		assert!(download.set_body(empty_body.out()));
+		assert!(download.set_receipts(RlpStream::new_list(0).out()));
+		download
+	}
+
+	fn serve(download: &mut StateDownload, db: &MemoryDB, count: usize) -> ImportedNodes {
+		let hashes = download.needed_nodes(count);
+		let nodes = hashes.iter().map(|h| db.get(h).unwrap().to_vec()).collect();
+		download.import_nodes(nodes).unwrap()
+	}
+
+	#[test]
+	fn downloads_whole_state() {
+		let mut db = MemoryDB::new();
+		let (state_root, addresses) = test_state(&mut db);
+		let mut download = start_download(state_root);
+
+		let mut imported = ImportedNodes::default();
+		while !download.is_complete() {
+			let batch = serve(&mut download, &db, 16);
+			assert!(!batch.nodes.is_empty());
+			imported.nodes.extend(batch.nodes);
+			imported.accounts.extend(batch.accounts);
+		}
+
+		assert_eq!(download.pending_nodes(), 0);
+		assert_eq!(download.done_nodes(), imported.nodes.len());
+		assert_eq!(imported.accounts.len(), addresses.len());
+		for address in &addresses {
+			assert!(imported.accounts.contains(&address.sha3()));
+		}
+
+		let contract = addresses[1].sha3();
+		assert!(imported.nodes.iter().any(|&(ref a, ref n)| a == &Some(contract) && &n[..] == b"some contract code"));
+		let with_storage = addresses[0].sha3();
+		assert!(imported.nodes.iter().filter(|&&(ref a, _)| a == &Some(with_storage)).count() > 1);
+		assert!(imported.nodes.iter().any(|&(ref a, ref n)| a.is_none() && n.sha3() == state_root));
+	}
+
+	#[test]
+	fn ignores_unrequested_data() {
+		let mut db = MemoryDB::new();
+		let (state_root, _) = test_state(&mut db);
+		let mut download = start_download(state_root);
+
+		let imported = download.import_nodes(vec![b"unexpected".to_vec()]).unwrap();
+		assert!(imported.nodes.is_empty());
+		assert_eq!(download.pending_nodes(), 1);
+
+		// received nodes are not requested again after the request is cleared.
+		let hashes = download.needed_nodes(16);
+		assert_eq!(hashes, vec![state_root]);
+		download.clear_node_download(&hashes);
+		serve(&mut download, &db, 16);
+		download.clear_node_download(&hashes);
+		assert!(!download.needed_nodes(1024).contains(&state_root));
+	}
+
+	#[test]
+	fn resumes_from_persisted_progress() {
+		let mut db = MemoryDB::new();
+		let (state_root, _) = test_state(&mut db);
+		let mut download = start_download(state_root);
+		for _ in 0..3 {
+			serve(&mut download, &db, 4);
+		}
+
+		// nodes being downloaded are persisted as pending.
+		let requested = download.needed_nodes(2);
+		let progress = download.to_rlp().unwrap();
+		let mut restored = StateDownload::from_rlp(&progress).unwrap();
+		assert_eq!(restored.pending_nodes(), download.pending_nodes());
+		assert_eq!(restored.done_nodes(), download.done_nodes());
+		assert_eq!(restored.pivot().unwrap().hash(), download.pivot().unwrap().hash());
+		assert!(restored.pending.contains_key(&requested[0]));
+
+		while !restored.is_complete() {
+			serve(&mut restored, &db, 16);
+		}
+	}
+
+	#[test]
+	fn rejects_mismatching_pivot_data() {
+		let mut header = BlockHeader::default();
+		header.set_number(100);
+		let mut download = StateDownload::new();
+		download.reset_to(Pivot::new(header, 0.into()));
+
+		let mut body = RlpStream::new_list(2);
+		body.begin_list(0);
+		body.begin_list(1).append_raw(&BlockHeader::default().rlp(::ethcore::header::Seal::With), 1);
+		assert!(!download.set_body(body.out()));
+		assert!(!download.set_body(b"garbage".to_vec()));
+		assert!(download.to_rlp().is_none());
+		assert!(!download.is_downloading_state());
+	}
+
+	#[test]
+	fn reuses_nodes_of_previous_pivot() {
+		let mut db = MemoryDB::new();
+		let (state_root, _) = test_state(&mut db);
+		let mut download = start_download(state_root);
+		let mut local = HashMap::new();
+		while !download.is_complete() {
+			for (address_hash, node) in serve(&mut download, &db, 16).nodes {
+				local.insert((address_hash, node.sha3()), node);
+			}
+		}
+
+		// the state of the new pivot has one more account.
+		let mut new_root = state_root;
+		{
+			let mut trie = SecTrieDBMut::from_existing(&mut db, &mut new_root).unwrap();
+			trie.insert(&Address::random(), &account_rlp(0, SHA3_NULL_RLP, SHA3_EMPTY)).unwrap();
+		}
+		let lookup = |address_hash: Option<H256>, hash: &H256| local.get(&(address_hash, *hash)).cloned();
+		let mut download = start_download(new_root);
+		let mut fetched = 0;
+		while !download.is_complete() {
+			let imported = download.import_local_nodes(1024, &lookup).unwrap();
+			assert!(imported.nodes.iter().all(|&(address_hash, ref node)| lookup(address_hash, &node.sha3()).is_none()));
+
+			let missing: Vec<H256> = download.missing.drain(..).collect();
+			fetched += missing.len();
+			download.import_nodes(missing.iter().map(|h| db.get(h).unwrap().to_vec()).collect()).unwrap();
+		}
+
+		// only the nodes leading to the new account are downloaded again.
+		assert!(fetched > 0 && fetched < 10);
+	}
+
+	#[test]
+	fn empty_state_completes_immediately() {
+		let download = start_download(SHA3_NULL_RLP);
+		assert!(download.is_complete());
+		assert!(download.pivot().unwrap().block().is_some());
+	}
+}
@@ -51,6 +51,16 @@ impl<T: Ord> Corpus<T> {
 		self.0.get(self.0.len() / 2)
 	}
 
+	/// Get the element at the given percentile (0-100), if it exists.
+	/// Percentiles above 100 are treated as 100.
+	pub fn percentile(&self, percentile: usize) -> Option<&T> {
+		if self.0.is_empty() {
+			return None;
+		}
+		let index = ::std::cmp::min(percentile.saturating_mul(self.0.len()) / 100, self.0.len() - 1);
+		self.0.get(index)
+	}
+
 	/// Whether the corpus is empty.
 	pub fn is_empty(&self) -> bool {
 		self.0.is_empty()
@@ -121,7 +131,23 @@ impl<T: Ord + Copy + ::std::fmt::Display> Histogram<T>
 
 #[cfg(test)]
 mod tests {
-	use super::Histogram;
+	use super::{Corpus, Histogram};
+
+	#[test]
+	fn check_percentile() {
+		let corpus: Corpus<usize> = vec![9, 3, 7, 1, 5, 2, 8, 4, 6, 10].into();
+		assert_eq!(corpus.percentile(0), Some(&1));
+		assert_eq!(corpus.percentile(10), Some(&2));
+		assert_eq!(corpus.percentile(50), corpus.median());
+		assert_eq!(corpus.percentile(99), Some(&10));
+		assert_eq!(corpus.percentile(100), Some(&10));
+		assert_eq!(corpus.percentile(1000), Some(&10));
+	}
+
+	#[test]
+	fn no_percentile_of_empty_corpus() {
+		assert!(Corpus::<usize>::from(vec![]).percentile(50).is_none());
+	}
 
 	#[test]
 	fn check_histogram() {